@@ -23,6 +23,11 @@ pub struct Backend {
 }
 
 impl Backend {
+    /// Create a backend for the delivery service at `ds_url`.
+    pub fn new(ds_url: Url) -> Self {
+        Self { ds_url }
+    }
+
     /// Register a new client with the server.
     pub fn register_client(
         &self,
@@ -136,10 +141,10 @@ impl Backend {
     }
 
     /// Reset the DS.
-    pub fn reset_server(&self) {
+    pub fn reset_server(&self) -> Result<(), String> {
         let mut url = self.ds_url.clone();
         url.set_path("reset");
-        get(&url).unwrap();
+        get(&url).map(|_| ())
     }
 }
 
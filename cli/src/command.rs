@@ -0,0 +1,397 @@
+//! # Non-interactive command line interface
+//!
+//! Every subcommand loads the state of the given user from its database,
+//! executes a single operation against the DS, and prints the result as JSON
+//! on stdout. All changes to the state are stored right away.
+//! The process exits with `0` on success, `1` if the command failed, and `2`
+//! for invalid arguments.
+//!
+//! The `batch` subcommand executes a scenario file with one command per line,
+//! e.g.
+//!
+//! ```text
+//! # Comments and empty lines are ignored.
+//! register --user alice
+//! register --user bob
+//! create-group --user alice demo
+//! invite --user alice --group demo bob
+//! recv --user bob
+//! send --user bob --group demo "Hi alice"
+//! recv --user alice
+//! ```
+//!
+//! It prints one JSON object per command and stops at the first failure.
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+use url::Url;
+
+use super::{backend::Backend, user::User};
+
+/// The exit code for failed commands.
+pub const EXIT_FAILURE: i32 = 1;
+
+#[derive(Debug, Parser)]
+#[command(name = "cli", about = "OpenMLS proof-of-concept client")]
+pub struct Cli {
+    /// The URL of the delivery service [default: http://localhost:8080].
+    #[arg(long, global = true)]
+    pub ds_url: Option<Url>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// The URL of the delivery service to use.
+    pub fn ds_url(&self) -> Url {
+        self.ds_url
+            .clone()
+            .unwrap_or_else(|| Url::parse("http://localhost:8080").unwrap())
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the interactive shell. This is the default.
+    Repl,
+    /// Reset the DS.
+    Reset,
+    /// Register a new client with two key packages.
+    Register {
+        #[arg(short, long)]
+        user: String,
+    },
+    /// Create a new key package and publish it.
+    CreateKp {
+        #[arg(short, long)]
+        user: String,
+    },
+    /// Create a new group.
+    CreateGroup {
+        #[arg(short, long)]
+        user: String,
+        group: String,
+    },
+    /// Invite a client to a group.
+    Invite {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+        member: String,
+    },
+    /// Remove a client from a group.
+    Remove {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+        member: String,
+    },
+    /// Send a message to a group.
+    Send {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+        message: String,
+    },
+    /// Receive and process all new messages and print the application
+    /// messages.
+    Recv {
+        #[arg(short, long)]
+        user: String,
+        /// Only print messages for this group.
+        #[arg(short, long)]
+        group: Option<String>,
+    },
+    /// Update the own leaf in a group with fresh key material.
+    SelfUpdate {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+    },
+    /// Ask the other members to remove the user from a group.
+    Leave {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+    },
+    /// List the pending proposals of a group.
+    Proposals {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+    },
+    /// Commit to all pending proposals of a group.
+    Commit {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+    },
+    /// List the members of a group.
+    Members {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+    },
+    /// List the groups of the user.
+    Groups {
+        #[arg(short, long)]
+        user: String,
+    },
+    /// Show the epoch authenticator of a group.
+    EpochAuthenticator {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+    },
+    /// Write a GroupInfo for external joins to a file.
+    ExportGroupInfo {
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long)]
+        group: String,
+        file: PathBuf,
+    },
+    /// Join a group with an external commit using a GroupInfo file.
+    JoinExternal {
+        #[arg(short, long)]
+        user: String,
+        file: PathBuf,
+    },
+    /// Replace the signature key of the user in all groups.
+    RotateCredential {
+        #[arg(short, long)]
+        user: String,
+    },
+    /// Execute a scenario file with one command per line.
+    Batch { file: PathBuf },
+}
+
+/// Load the state of `user_name` and connect it to the DS at `ds_url`.
+fn load_user(user_name: &str, ds_url: &Url) -> Result<User, String> {
+    let mut user = User::load(user_name.to_string())
+        .map_err(|e| format!("Error loading client {user_name}: {e}"))?;
+    user.set_backend(Backend::new(ds_url.clone()));
+    Ok(user)
+}
+
+/// Load `user_name` and run `f` on it.
+fn with_user(
+    user_name: &str,
+    ds_url: &Url,
+    f: impl FnOnce(&mut User) -> Result<Value, String>,
+) -> Result<Value, String> {
+    let mut user = load_user(user_name, ds_url)?;
+    f(&mut user)
+}
+
+/// Execute a single non-interactive command and return its JSON result.
+///
+/// Note that `Repl` and `Batch` are not handled here.
+pub fn execute(command: Command, ds_url: &Url) -> Result<Value, String> {
+    match command {
+        Command::Repl | Command::Batch { .. } => {
+            Err("This command can't be executed here.".to_string())
+        }
+        Command::Reset => {
+            Backend::new(ds_url.clone()).reset_server()?;
+            Ok(json!({}))
+        }
+        Command::Register { user: user_name } => {
+            let mut user = User::new(user_name.clone())?;
+            user.set_backend(Backend::new(ds_url.clone()));
+            user.add_key_package();
+            user.add_key_package();
+            user.register()?;
+            Ok(json!({ "user": user_name }))
+        }
+        Command::CreateKp { user } => with_user(&user, ds_url, |user| {
+            user.create_kp()?;
+            Ok(json!({}))
+        }),
+        Command::CreateGroup { user, group } => with_user(&user, ds_url, |user| {
+            user.create_group(group.clone())?;
+            Ok(json!({ "group": group }))
+        }),
+        Command::Invite {
+            user,
+            group,
+            member,
+        } => with_user(&user, ds_url, |user| {
+            // Make sure we know about clients that registered recently.
+            user.update_clients();
+            user.invite(member.clone(), group.clone())?;
+            Ok(json!({ "group": group, "member": member }))
+        }),
+        Command::Remove {
+            user,
+            group,
+            member,
+        } => with_user(&user, ds_url, |user| {
+            user.remove(member.clone(), group.clone())?;
+            Ok(json!({ "group": group, "member": member }))
+        }),
+        Command::Send {
+            user,
+            group,
+            message,
+        } => with_user(&user, ds_url, |user| {
+            user.send_msg(&message, group.clone())?;
+            Ok(json!({ "group": group }))
+        }),
+        Command::Recv { user, group } => with_user(&user, ds_url, |user| {
+            let messages = user.update(group)?;
+            Ok(json!({ "messages": messages }))
+        }),
+        Command::SelfUpdate { user, group } => with_user(&user, ds_url, |user| {
+            user.self_update(group.clone())?;
+            Ok(json!({ "group": group }))
+        }),
+        Command::Leave { user, group } => with_user(&user, ds_url, |user| {
+            user.leave_group(group.clone())?;
+            Ok(json!({ "group": group }))
+        }),
+        Command::Proposals { user, group } => with_user(&user, ds_url, |user| {
+            let proposals = user.pending_proposals(group)?;
+            Ok(json!({ "proposals": proposals }))
+        }),
+        Command::Commit { user, group } => with_user(&user, ds_url, |user| {
+            user.commit_pending_proposals(group.clone())?;
+            Ok(json!({ "group": group }))
+        }),
+        Command::Members { user, group } => with_user(&user, ds_url, |user| {
+            let members = user.members(group)?;
+            Ok(json!({ "members": members }))
+        }),
+        Command::Groups { user } => with_user(&user, ds_url, |user| {
+            Ok(json!({ "groups": user.group_summaries() }))
+        }),
+        Command::EpochAuthenticator { user, group } => with_user(&user, ds_url, |user| {
+            let epoch_authenticator = user.epoch_authenticator(group)?;
+            Ok(json!({ "epoch_authenticator": epoch_authenticator }))
+        }),
+        Command::ExportGroupInfo { user, group, file } => with_user(&user, ds_url, |user| {
+            let group_info = user.export_group_info(group.clone())?;
+            std::fs::write(&file, group_info)
+                .map_err(|e| format!("Error writing {}: {e}", file.display()))?;
+            Ok(json!({ "group": group, "file": file }))
+        }),
+        Command::JoinExternal { user, file } => with_user(&user, ds_url, |user| {
+            let group_info = std::fs::read(&file)
+                .map_err(|e| format!("Error reading {}: {e}", file.display()))?;
+            let group = user.join_by_external_commit(&group_info)?;
+            Ok(json!({ "group": group }))
+        }),
+        Command::RotateCredential { user } => with_user(&user, ds_url, |user| {
+            user.rotate_credential()?;
+            Ok(json!({}))
+        }),
+    }
+}
+
+/// Split a line of a scenario file into arguments.
+/// Arguments are separated by whitespace. Double quotes group words into a
+/// single argument.
+fn split_args(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if quoted {
+        return Err("Unterminated quote".to_string());
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Execute all commands in the scenario `file`.
+/// The result of each command is printed as a single JSON line. Execution stops
+/// at the first failing command.
+pub fn run_batch(file: &Path, ds_url: &Url) -> Result<(), String> {
+    let scenario = std::fs::read_to_string(file)
+        .map_err(|e| format!("Error reading scenario {}: {e}", file.display()))?;
+
+    for (line_number, line) in scenario.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_number = line_number + 1;
+
+        let args = split_args(line).map_err(|e| format!("Line {line_number}: {e}"))?;
+        let cli = Cli::try_parse_from(std::iter::once("cli".to_string()).chain(args))
+            .map_err(|e| format!("Line {line_number}: {e}"))?;
+        // Commands use the DS of the batch unless they set their own.
+        let ds_url = cli.ds_url.as_ref().unwrap_or(ds_url);
+        let command = match cli.command {
+            Some(Command::Repl) | Some(Command::Batch { .. }) | None => {
+                return Err(format!(
+                    "Line {line_number}: Command not allowed in a batch"
+                ))
+            }
+            Some(command) => command,
+        };
+
+        let result = execute(command, ds_url);
+        print_result(Some((line_number, line)), &result);
+        result?;
+    }
+    Ok(())
+}
+
+/// Print the result of a command as JSON on stdout.
+pub fn print_result(line: Option<(usize, &str)>, result: &Result<Value, String>) {
+    let mut output = match result {
+        Ok(value) => json!({ "ok": true, "result": value }),
+        Err(e) => json!({ "ok": false, "error": e }),
+    };
+    if let Some((line_number, command)) = line {
+        output["line"] = json!(line_number);
+        output["command"] = json!(command);
+    }
+    println!("{output}");
+}
+
+#[test]
+fn split_scenario_lines() {
+    assert_eq!(
+        split_args(r#"send --user bob --group demo "Hi alice""#).unwrap(),
+        vec!["send", "--user", "bob", "--group", "demo", "Hi alice"]
+    );
+    assert_eq!(
+        split_args(r#"create-group -u alice """#).unwrap(),
+        vec!["create-group", "-u", "alice", ""]
+    );
+    assert!(split_args(r#"send "unterminated"#).is_err());
+}
@@ -1,17 +1,13 @@
 use std::collections::HashMap;
 
+use ds_lib::messages::AuthToken;
 use openmls::prelude::*;
 use openmls_basic_credential::SignatureKeyPair;
 use openmls_traits::OpenMlsProvider;
 
-use super::{openmls_rust_persistent_crypto::OpenMlsRustPersistentCrypto, serialize_any_hashmap};
+use super::{openmls_rust_persistent_crypto::OpenMlsRustPersistentCrypto, persistence};
 
-#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Identity {
-    #[serde(
-        serialize_with = "serialize_any_hashmap::serialize_hashmap",
-        deserialize_with = "serialize_any_hashmap::deserialize_hashmap"
-    )]
     pub(crate) kp: HashMap<Vec<u8>, KeyPackage>,
     pub(crate) credential_with_key: CredentialWithKey,
     pub(crate) signer: SignatureKeyPair,
@@ -40,21 +36,47 @@ impl Identity {
             )
             .unwrap();
 
+        let hash_ref = key_package
+            .key_package()
+            .hash_ref(crypto.crypto())
+            .unwrap()
+            .as_slice()
+            .to_vec();
+        persistence::store_key_package(crypto.connection(), &hash_ref, key_package.key_package())
+            .unwrap();
+
         Self {
-            kp: HashMap::from([(
-                key_package
-                    .key_package()
-                    .hash_ref(crypto.crypto())
-                    .unwrap()
-                    .as_slice()
-                    .to_vec(),
-                key_package.key_package().clone(),
-            )]),
+            kp: HashMap::from([(hash_ref, key_package.key_package().clone())]),
             credential_with_key,
             signer: signature_keys,
         }
     }
 
+    /// Load the identity stored in the database of `crypto`.
+    pub(crate) fn load(
+        ciphersuite: Ciphersuite,
+        crypto: &OpenMlsRustPersistentCrypto,
+    ) -> Result<(Self, Option<AuthToken>), String> {
+        let (credential_with_key, auth_token) = persistence::load_identity(crypto.connection())?
+            .ok_or("No client stored in the database".to_string())?;
+        let signer = SignatureKeyPair::read(
+            crypto.storage(),
+            credential_with_key.signature_key.as_slice(),
+            ciphersuite.signature_algorithm(),
+        )
+        .ok_or("Signature key not found in the database".to_string())?;
+        let kp = persistence::load_key_packages(crypto.connection())?;
+
+        Ok((
+            Self {
+                kp,
+                credential_with_key,
+                signer,
+            },
+            auth_token,
+        ))
+    }
+
     /// Create an additional key package using the credential_with_key/signer bound to this identity
     pub fn add_key_package(
         &mut self,
@@ -70,18 +92,30 @@ impl Identity {
             )
             .unwrap();
 
-        self.kp.insert(
-            key_package
-                .key_package()
-                .hash_ref(crypto.crypto())
-                .unwrap()
-                .as_slice()
-                .to_vec(),
-            key_package.key_package().clone(),
-        );
+        let hash_ref = key_package
+            .key_package()
+            .hash_ref(crypto.crypto())
+            .unwrap()
+            .as_slice()
+            .to_vec();
+        persistence::store_key_package(crypto.connection(), &hash_ref, key_package.key_package())
+            .unwrap();
+        self.kp.insert(hash_ref, key_package.key_package().clone());
         key_package.key_package().clone()
     }
 
+    /// Forget the key package with the given hash reference after it was used.
+    pub fn remove_key_package(
+        &mut self,
+        hash_ref: &[u8],
+        crypto: &OpenMlsRustPersistentCrypto,
+    ) -> Result<(), String> {
+        if self.kp.remove(hash_ref).is_some() {
+            persistence::delete_key_package(crypto.connection(), hash_ref)?;
+        }
+        Ok(())
+    }
+
     /// Get the plain identity as byte vector.
     pub fn identity(&self) -> &[u8] {
         self.credential_with_key.credential.serialized_content()
@@ -1,16 +1,17 @@
-// #[macro_use]
-// extern crate clap;
-// use clap::App;
-
 use std::io::{stdin, stdout, StdoutLock, Write};
+use std::process::ExitCode;
+
+use clap::Parser;
 use termion::input::TermRead;
+use url::Url;
 
 mod backend;
+mod command;
 mod conversation;
 mod identity;
 mod networking;
 mod openmls_rust_persistent_crypto;
-mod serialize_any_hashmap;
+mod persistence;
 mod user;
 
 const HELP: &str = "
@@ -18,16 +19,26 @@ const HELP: &str = "
 >>>     - update                                update the client state
 >>>     - reset                                 reset the server
 >>>     - register {client name}                register a new client
->>>     - save {client name}                    serialize and save the client state
->>>     - load {client name}                    load and deserialize the client state as a new client
->>>     - autosave                              enable automatic save of the current client state upon each update
+>>>     - load {client name}                    load the stored state of a client
 >>>     - create kp                             create a new key package
 >>>     - create group {group name}             create a new group
+>>>     - groups                                list all groups
+>>>     - join external {file}                  join a group with an external commit using the group info in {file}
+>>>     - rotate credential                     replace the signature key in all groups
 >>>     - group {group name}                    group operations
 >>>         - send {message}                    send message to group
 >>>         - invite {client name}              invite a user to the group
+>>>         - remove {client name}              remove a user from the group
+>>>         - leave                             ask the other members to remove you from the group
+>>>         - self update                       update your own key material in the group
+>>>         - members                           list the members of the group
+>>>         - epoch authenticator               show the epoch authenticator of the group
+>>>         - proposals                         list the pending proposals
+>>>         - commit                            commit to all pending proposals
+>>>         - export group info {file}          write a group info for external joins to {file}
 >>>         - read                              read messages sent to the group (max 100)
 >>>         - update                            update the client state
+>>>         - exit                              leave the group operations
 
 ";
 
@@ -45,9 +56,35 @@ fn update(client: &mut user::User, group_id: Option<String>, stdout: &mut Stdout
     stdout.write_all(b"\n").unwrap();
 }
 
-fn main() {
+fn main() -> ExitCode {
     pretty_env_logger::init();
 
+    let cli = command::Cli::parse();
+    let ds_url = cli.ds_url();
+    let result = match cli.command {
+        None | Some(command::Command::Repl) => {
+            repl(ds_url);
+            Ok(())
+        }
+        Some(command::Command::Batch { file }) => command::run_batch(&file, &ds_url),
+        Some(command) => {
+            let result = command::execute(command, &ds_url);
+            command::print_result(None, &result);
+            result.map(|_| ())
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            log::error!("{e}");
+            ExitCode::from(command::EXIT_FAILURE as u8)
+        }
+    }
+}
+
+/// Run the interactive shell.
+fn repl(ds_url: Url) {
     let stdout = stdout();
     let mut stdout = stdout.lock();
     let stdin = stdin();
@@ -63,22 +100,41 @@ fn main() {
         let op = stdin.read_line().unwrap().unwrap();
 
         // Register a client.
-        // There's no persistence. So once the client app stops you have to
-        // register a new client.
+        // The state of the client is stored in a database and can be loaded
+        // again with `load` after the client app stops.
         if let Some(client_name) = op.strip_prefix("register ") {
-            client = Some(user::User::new(client_name.to_string()));
-            client.as_mut().unwrap().add_key_package();
-            client.as_mut().unwrap().add_key_package();
-            client.as_mut().unwrap().register();
-            stdout
-                .write_all(format!("registered new client {client_name}\n\n").as_bytes())
-                .unwrap();
+            let mut new_client = match user::User::new(client_name.to_string()) {
+                Ok(new_client) => new_client,
+                Err(e) => {
+                    stdout
+                        .write_all(
+                            format!("Error creating client {client_name} : {e}\n\n").as_bytes(),
+                        )
+                        .unwrap();
+                    continue;
+                }
+            };
+            new_client.set_backend(backend::Backend::new(ds_url.clone()));
+            new_client.add_key_package();
+            new_client.add_key_package();
+            match new_client.register() {
+                Ok(()) => stdout
+                    .write_all(format!("registered new client {client_name}\n\n").as_bytes())
+                    .unwrap(),
+                Err(e) => stdout
+                    .write_all(
+                        format!("Error registering client {client_name} : {e}\n\n").as_bytes(),
+                    )
+                    .unwrap(),
+            }
+            client = Some(new_client);
             continue;
         }
 
         if let Some(client_name) = op.strip_prefix("load ") {
             match user::User::load(client_name.to_string()) {
-                Ok(user) => {
+                Ok(mut user) => {
+                    user.set_backend(backend::Backend::new(ds_url.clone()));
                     client = Some(user);
                     stdout
                         .write_all(format!("recovered client {client_name}\n\n").as_bytes())
@@ -96,42 +152,12 @@ fn main() {
         // Create a new KeyPackage.
         if op == "create kp" {
             if let Some(client) = &mut client {
-                client.create_kp();
-                stdout
-                    .write_all(b" >>> New key package created\n\n")
-                    .unwrap();
-            } else {
-                stdout
-                    .write_all(b" >>> No client to update :(\n\n")
-                    .unwrap();
-            }
-            continue;
-        }
-
-        // Save the current client state.
-        if op == "save" {
-            if let Some(client) = &mut client {
-                client.save();
-                let name = &client.identity.borrow().identity_as_string();
-                stdout
-                    .write_all(format!(" >>> client {name} state saved\n\n").as_bytes())
-                    .unwrap();
-            } else {
-                stdout
-                    .write_all(b" >>> No client to update :(\n\n")
-                    .unwrap();
-            }
-            continue;
-        }
-
-        // Enable automatic saving of the client state.
-        if op == "autosave" {
-            if let Some(client) = &mut client {
-                client.enable_auto_save();
-                let name = &client.identity.borrow().identity_as_string();
-                stdout
-                    .write_all(format!(" >>> autosave enabled for client {name} \n\n").as_bytes())
-                    .unwrap();
+                match client.create_kp() {
+                    Ok(()) => stdout
+                        .write_all(b" >>> New key package created\n\n")
+                        .unwrap(),
+                    Err(e) => println!("Error sending new key package: {e:?}"),
+                }
             } else {
                 stdout
                     .write_all(b" >>> No client to update :(\n\n")
@@ -143,10 +169,14 @@ fn main() {
         // Create a new group.
         if let Some(group_name) = op.strip_prefix("create group ") {
             if let Some(client) = &mut client {
-                client.create_group(group_name.to_string());
-                stdout
-                    .write_all(format!(" >>> Created group {group_name} :)\n\n").as_bytes())
-                    .unwrap();
+                match client.create_group(group_name.to_string()) {
+                    Ok(()) => stdout
+                        .write_all(format!(" >>> Created group {group_name} :)\n\n").as_bytes())
+                        .unwrap(),
+                    Err(e) => stdout
+                        .write_all(format!(" >>> {e} :(\n\n").as_bytes())
+                        .unwrap(),
+                }
             } else {
                 stdout
                     .write_all(b" >>> No client to create a group :(\n\n")
@@ -229,6 +259,116 @@ fn main() {
                         continue;
                     }
 
+                    // Ask the other members to remove us.
+                    if op2 == "leave" {
+                        match client.leave_group(group_name.to_string()) {
+                            Ok(()) => stdout
+                                .write_all(
+                                    format!("requested to leave {group_name}\n\n").as_bytes(),
+                                )
+                                .unwrap(),
+                            Err(e) => println!("Error leaving group: {e}"),
+                        }
+                        continue;
+                    }
+
+                    // Update the own key material.
+                    if op2 == "self update" {
+                        match client.self_update(group_name.to_string()) {
+                            Ok(()) => stdout
+                                .write_all(
+                                    format!("updated own leaf in {group_name}\n\n").as_bytes(),
+                                )
+                                .unwrap(),
+                            Err(e) => println!("Error updating own leaf: {e}"),
+                        }
+                        continue;
+                    }
+
+                    // List the group members.
+                    if op2 == "members" {
+                        match client.members(group_name.to_string()) {
+                            Ok(members) => {
+                                for member in members {
+                                    stdout
+                                        .write_all(
+                                            format!(
+                                                "    {}: {} ({})\n",
+                                                member.index, member.identity, member.signature_key
+                                            )
+                                            .as_bytes(),
+                                        )
+                                        .unwrap();
+                                }
+                                stdout.write_all(b"\n").unwrap();
+                            }
+                            Err(e) => println!("Error listing members: {e}"),
+                        }
+                        continue;
+                    }
+
+                    // Show the epoch authenticator.
+                    if op2 == "epoch authenticator" {
+                        match client.epoch_authenticator(group_name.to_string()) {
+                            Ok(authenticator) => stdout
+                                .write_all(format!("    {authenticator}\n\n").as_bytes())
+                                .unwrap(),
+                            Err(e) => println!("Error getting epoch authenticator: {e}"),
+                        }
+                        continue;
+                    }
+
+                    // List the pending proposals.
+                    if op2 == "proposals" {
+                        match client.pending_proposals(group_name.to_string()) {
+                            Ok(proposals) => {
+                                for proposal in proposals {
+                                    stdout
+                                        .write_all(
+                                            format!(
+                                                "    {} from {}\n",
+                                                proposal.proposal_type, proposal.sender
+                                            )
+                                            .as_bytes(),
+                                        )
+                                        .unwrap();
+                                }
+                                stdout.write_all(b"\n").unwrap();
+                            }
+                            Err(e) => println!("Error listing proposals: {e}"),
+                        }
+                        continue;
+                    }
+
+                    // Commit to the pending proposals.
+                    if op2 == "commit" {
+                        match client.commit_pending_proposals(group_name.to_string()) {
+                            Ok(()) => stdout
+                                .write_all(
+                                    format!("committed pending proposals in {group_name}\n\n")
+                                        .as_bytes(),
+                                )
+                                .unwrap(),
+                            Err(e) => println!("Error committing proposals: {e}"),
+                        }
+                        continue;
+                    }
+
+                    // Export a group info for external joins.
+                    if let Some(file) = op2.strip_prefix("export group info ") {
+                        match client.export_group_info(group_name.to_string()).and_then(
+                            |group_info| {
+                                std::fs::write(file, group_info).map_err(|e| e.to_string())
+                            },
+                        ) {
+                            Ok(()) => stdout
+                                .write_all(format!("wrote group info to {file}\n\n").as_bytes())
+                                .unwrap(),
+                            Err(e) => println!("Error exporting group info: {e}"),
+                        }
+                        continue;
+                    }
+
                     // Exit group.
                     if op2 == "exit" {
                         stdout.write_all(b" >>> Leaving group \n\n").unwrap();
@@ -245,6 +385,58 @@ fn main() {
             continue;
         }
 
+        // List all groups.
+        if op == "groups" {
+            if let Some(client) = &mut client {
+                for group in client.group_summaries() {
+                    stdout
+                        .write_all(
+                            format!(
+                                "    {} (epoch {}, {} members)\n",
+                                group.name, group.epoch, group.members
+                            )
+                            .as_bytes(),
+                        )
+                        .unwrap();
+                }
+                stdout.write_all(b"\n").unwrap();
+            } else {
+                stdout.write_all(b" >>> No client :(\n\n").unwrap();
+            }
+            continue;
+        }
+
+        // Join a group with an external commit.
+        if let Some(file) = op.strip_prefix("join external ") {
+            if let Some(client) = &mut client {
+                match std::fs::read(file)
+                    .map_err(|e| e.to_string())
+                    .and_then(|group_info| client.join_by_external_commit(&group_info))
+                {
+                    Ok(group_name) => stdout
+                        .write_all(format!(" >>> Joined group {group_name} :)\n\n").as_bytes())
+                        .unwrap(),
+                    Err(e) => println!("Error joining group: {e}"),
+                }
+            } else {
+                stdout.write_all(b" >>> No client :(\n\n").unwrap();
+            }
+            continue;
+        }
+
+        // Replace the signature key in all groups.
+        if op == "rotate credential" {
+            if let Some(client) = &mut client {
+                match client.rotate_credential() {
+                    Ok(()) => stdout.write_all(b" >>> Rotated credential :)\n\n").unwrap(),
+                    Err(e) => println!("Error rotating credential: {e}"),
+                }
+            } else {
+                stdout.write_all(b" >>> No client :(\n\n").unwrap();
+            }
+            continue;
+        }
+
         // Update the client state.
         if op == "update" {
             if let Some(client) = &mut client {
@@ -259,9 +451,13 @@ fn main() {
 
         // Reset the server and client.
         if op == "reset" {
-            backend::Backend::default().reset_server();
-            client = None;
-            stdout.write_all(b" >>> Reset server :)\n\n").unwrap();
+            match backend::Backend::new(ds_url.clone()).reset_server() {
+                Ok(()) => {
+                    client = None;
+                    stdout.write_all(b" >>> Reset server :)\n\n").unwrap();
+                }
+                Err(e) => println!("Error resetting server: {e}"),
+            }
             continue;
         }
 
@@ -281,20 +477,20 @@ fn main() {
 #[ignore]
 fn basic_test() {
     // Reset the server before doing anything for testing.
-    backend::Backend::default().reset_server();
+    backend::Backend::default().reset_server().unwrap();
 
     const MESSAGE_1: &str = "Thanks for adding me Client1.";
     const MESSAGE_2: &str = "Welcome Client3.";
     const MESSAGE_3: &str = "Thanks so much for the warm welcome! 😊";
 
     // Create one client
-    let mut client_1 = user::User::new("Client1".to_string());
+    let mut client_1 = user::User::new("Client1".to_string()).unwrap();
 
     // Create another client
-    let mut client_2 = user::User::new("Client2".to_string());
+    let mut client_2 = user::User::new("Client2".to_string()).unwrap();
 
     // Create another client
-    let mut client_3 = user::User::new("Client3".to_string());
+    let mut client_3 = user::User::new("Client3".to_string()).unwrap();
 
     // Update the clients to know about the other clients.
     client_1.update(None).unwrap();
@@ -302,7 +498,9 @@ fn basic_test() {
     client_3.update(None).unwrap();
 
     // Client 1 creates a group.
-    client_1.create_group("MLS Discussions".to_string());
+    client_1
+        .create_group("MLS Discussions".to_string())
+        .unwrap();
 
     // Client 1 adds Client 2 to the group.
     client_1
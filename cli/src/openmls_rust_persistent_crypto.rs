@@ -1,21 +1,43 @@
-//! # OpenMLS Default Crypto Provider
+//! # OpenMLS Persistent Crypto Provider
 //!
 //! This is an implementation of the [`OpenMlsProvider`] trait to use with
-//! OpenMLS.
+//! OpenMLS. It uses the [`RustCrypto`] provider and stores all state in an
+//! SQLite database with the [`SqliteStorageProvider`].
 
-use openmls_rust_crypto::{MemoryStorage, RustCrypto};
+use std::{path::Path, rc::Rc};
+
+use openmls_rust_crypto::RustCrypto;
+use openmls_sqlite_storage::{Codec, Connection, SqliteStorageProvider};
 use openmls_traits::OpenMlsProvider;
 
-#[derive(Default, Debug)]
+/// The codec used to store values in the database.
+#[derive(Default)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    type Error = serde_json::Error;
+
+    fn to_vec<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, Self::Error> {
+        serde_json::to_vec(value)
+    }
+
+    fn from_slice<T: serde::de::DeserializeOwned>(slice: &[u8]) -> Result<T, Self::Error> {
+        serde_json::from_slice(slice)
+    }
+}
+
 pub struct OpenMlsRustPersistentCrypto {
     crypto: RustCrypto,
-    storage: MemoryStorage,
+    storage: SqliteStorageProvider<JsonCodec, Rc<Connection>>,
+    // The connection is shared with the storage provider such that the client
+    // can keep its own state in the same database.
+    connection: Rc<Connection>,
 }
 
 impl OpenMlsProvider for OpenMlsRustPersistentCrypto {
     type CryptoProvider = RustCrypto;
     type RandProvider = RustCrypto;
-    type StorageProvider = MemoryStorage;
+    type StorageProvider = SqliteStorageProvider<JsonCodec, Rc<Connection>>;
 
     fn crypto(&self) -> &Self::CryptoProvider {
         &self.crypto
@@ -31,11 +53,26 @@ impl OpenMlsProvider for OpenMlsRustPersistentCrypto {
 }
 
 impl OpenMlsRustPersistentCrypto {
-    pub fn save_keystore(&self, user_name: String) -> Result<(), String> {
-        self.storage.save(user_name)
+    /// Open the database at `path` and create the tables of the storage
+    /// provider if necessary.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut connection = Connection::open(path)
+            .map_err(|e| format!("Error opening database {}: {e}", path.display()))?;
+        SqliteStorageProvider::<JsonCodec, &mut Connection>::new(&mut connection)
+            .initialize()
+            .map_err(|e| format!("Error initializing database: {e}"))?;
+
+        let connection = Rc::new(connection);
+        Ok(Self {
+            crypto: RustCrypto::default(),
+            storage: SqliteStorageProvider::new(connection.clone()),
+            connection,
+        })
     }
 
-    pub fn load_keystore(&mut self, user_name: String) -> Result<(), String> {
-        self.storage.load(user_name)
+    /// Get the database connection for state that is not handled by the
+    /// storage provider.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
     }
 }
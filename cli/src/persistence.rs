@@ -0,0 +1,239 @@
+//! # Client state in SQLite
+//!
+//! The CLI keeps everything in one SQLite database per user. The OpenMLS state
+//! is stored by the [`SqliteStorageProvider`] of the
+//! [`OpenMlsRustPersistentCrypto`] provider. The state of the client
+//! application lives in the tables created here, next to it.
+//!
+//! All changes are written right away such that the state survives crashes.
+//!
+//! [`SqliteStorageProvider`]: openmls_sqlite_storage::SqliteStorageProvider
+//! [`OpenMlsRustPersistentCrypto`]: super::openmls_rust_persistent_crypto::OpenMlsRustPersistentCrypto
+
+use std::{collections::HashMap, path::PathBuf};
+
+use ds_lib::messages::AuthToken;
+use openmls::prelude::{CredentialWithKey, KeyPackage};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::conversation::{Conversation, ConversationMessage};
+
+/// Get the path of the database of `user_name`.
+pub fn db_path(user_name: &str) -> PathBuf {
+    std::env::temp_dir().join("openmls_cli_".to_owned() + user_name + ".sqlite")
+}
+
+/// Create the tables of the client application if they don't exist.
+pub fn create_tables(connection: &Connection) -> Result<(), String> {
+    connection
+        .execute_batch(
+            "CREATE TABLE IF NOT EXISTS cli_identity (
+                id INTEGER PRIMARY KEY CHECK (id = 0),
+                credential_with_key BLOB NOT NULL,
+                auth_token BLOB
+            );
+            CREATE TABLE IF NOT EXISTS cli_key_packages (
+                hash_ref BLOB PRIMARY KEY,
+                key_package BLOB NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS cli_contacts (
+                id BLOB PRIMARY KEY
+            );
+            CREATE TABLE IF NOT EXISTS cli_groups (
+                name TEXT PRIMARY KEY
+            );
+            CREATE TABLE IF NOT EXISTS cli_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                group_name TEXT NOT NULL,
+                author TEXT NOT NULL,
+                message TEXT NOT NULL
+            );",
+        )
+        .map_err(|e| format!("Error creating tables: {e}"))
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(value).map_err(|e| format!("Error encoding value: {e}"))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    serde_json::from_slice(bytes).map_err(|e| format!("Error decoding value: {e}"))
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("Database error: {e}")
+}
+
+/// Store the credential and auth token of the user.
+pub fn store_identity(
+    connection: &Connection,
+    credential_with_key: &CredentialWithKey,
+    auth_token: Option<&AuthToken>,
+) -> Result<(), String> {
+    let auth_token = auth_token.map(to_json).transpose()?;
+    connection
+        .execute(
+            "INSERT OR REPLACE INTO cli_identity (id, credential_with_key, auth_token)
+            VALUES (0, ?1, ?2)",
+            params![to_json(credential_with_key)?, auth_token],
+        )
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Load the credential and auth token of the user.
+/// Returns `None` if there's no user in the database.
+pub fn load_identity(
+    connection: &Connection,
+) -> Result<Option<(CredentialWithKey, Option<AuthToken>)>, String> {
+    let row = connection
+        .query_row(
+            "SELECT credential_with_key, auth_token FROM cli_identity WHERE id = 0",
+            [],
+            |row| Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, Option<Vec<u8>>>(1)?)),
+        )
+        .optional()
+        .map_err(db_error)?;
+    let Some((credential_with_key, auth_token)) = row else {
+        return Ok(None);
+    };
+    let auth_token = auth_token.map(|bytes| from_json(&bytes)).transpose()?;
+    Ok(Some((from_json(&credential_with_key)?, auth_token)))
+}
+
+/// Store a key package of the user under its hash reference.
+pub fn store_key_package(
+    connection: &Connection,
+    hash_ref: &[u8],
+    key_package: &KeyPackage,
+) -> Result<(), String> {
+    connection
+        .execute(
+            "INSERT OR REPLACE INTO cli_key_packages (hash_ref, key_package) VALUES (?1, ?2)",
+            params![hash_ref, to_json(key_package)?],
+        )
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Delete a used key package of the user.
+pub fn delete_key_package(connection: &Connection, hash_ref: &[u8]) -> Result<(), String> {
+    connection
+        .execute(
+            "DELETE FROM cli_key_packages WHERE hash_ref = ?1",
+            params![hash_ref],
+        )
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Load all key packages of the user by their hash reference.
+pub fn load_key_packages(connection: &Connection) -> Result<HashMap<Vec<u8>, KeyPackage>, String> {
+    let mut statement = connection
+        .prepare("SELECT hash_ref, key_package FROM cli_key_packages")
+        .map_err(db_error)?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })
+        .map_err(db_error)?;
+    let mut key_packages = HashMap::new();
+    for row in rows {
+        let (hash_ref, key_package) = row.map_err(db_error)?;
+        key_packages.insert(hash_ref, from_json(&key_package)?);
+    }
+    Ok(key_packages)
+}
+
+/// Store a contact.
+pub fn store_contact(connection: &Connection, id: &[u8]) -> Result<(), String> {
+    connection
+        .execute(
+            "INSERT OR IGNORE INTO cli_contacts (id) VALUES (?1)",
+            params![id],
+        )
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Load the ids of all contacts.
+pub fn load_contacts(connection: &Connection) -> Result<Vec<Vec<u8>>, String> {
+    let mut statement = connection
+        .prepare("SELECT id FROM cli_contacts")
+        .map_err(db_error)?;
+    let rows = statement
+        .query_map([], |row| row.get::<_, Vec<u8>>(0))
+        .map_err(db_error)?;
+    rows.collect::<Result<_, _>>().map_err(db_error)
+}
+
+/// Store the name of a group the user is a member of.
+pub fn store_group(connection: &Connection, name: &str) -> Result<(), String> {
+    connection
+        .execute(
+            "INSERT OR IGNORE INTO cli_groups (name) VALUES (?1)",
+            params![name],
+        )
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Delete a group the user is not a member of anymore, and its messages.
+pub fn delete_group(connection: &Connection, name: &str) -> Result<(), String> {
+    connection
+        .execute("DELETE FROM cli_groups WHERE name = ?1", params![name])
+        .map_err(db_error)?;
+    connection
+        .execute(
+            "DELETE FROM cli_messages WHERE group_name = ?1",
+            params![name],
+        )
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Load the names of all groups of the user.
+pub fn load_groups(connection: &Connection) -> Result<Vec<String>, String> {
+    let mut statement = connection
+        .prepare("SELECT name FROM cli_groups")
+        .map_err(db_error)?;
+    let rows = statement
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(db_error)?;
+    rows.collect::<Result<_, _>>().map_err(db_error)
+}
+
+/// Append a message to the conversation of a group.
+pub fn store_message(
+    connection: &Connection,
+    group_name: &str,
+    message: &ConversationMessage,
+) -> Result<(), String> {
+    connection
+        .execute(
+            "INSERT INTO cli_messages (group_name, author, message) VALUES (?1, ?2, ?3)",
+            params![group_name, message.author, message.message],
+        )
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Load the conversation of a group.
+pub fn load_conversation(
+    connection: &Connection,
+    group_name: &str,
+) -> Result<Conversation, String> {
+    let mut statement = connection
+        .prepare("SELECT author, message FROM cli_messages WHERE group_name = ?1 ORDER BY id")
+        .map_err(db_error)?;
+    let rows = statement
+        .query_map(params![group_name], |row| {
+            Ok(ConversationMessage::new(row.get(1)?, row.get(0)?))
+        })
+        .map_err(db_error)?;
+    let mut conversation = Conversation::default();
+    for message in rows {
+        conversation.add(message.map_err(db_error)?);
+    }
+    Ok(conversation)
+}
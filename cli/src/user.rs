@@ -1,24 +1,19 @@
 use std::borrow::Borrow;
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
 use std::{cell::RefCell, collections::HashMap, str};
 
 use ds_lib::messages::AuthToken;
 use ds_lib::{ClientKeyPackages, GroupMessage};
 use openmls::prelude::{tls_codec::*, *};
+use openmls_basic_credential::SignatureKeyPair;
 use openmls_traits::OpenMlsProvider;
 
 use super::{
     backend::Backend, conversation::Conversation, conversation::ConversationMessage,
-    identity::Identity, openmls_rust_persistent_crypto::OpenMlsRustPersistentCrypto,
-    serialize_any_hashmap,
+    identity::Identity, openmls_rust_persistent_crypto::OpenMlsRustPersistentCrypto, persistence,
 };
 
 const CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
 
-#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Contact {
     id: Vec<u8>,
 }
@@ -29,28 +24,50 @@ impl Contact {
     }
 }
 
+/// A member of a group as shown to the user.
+#[derive(Debug, serde::Serialize)]
+pub struct MemberInfo {
+    pub index: u32,
+    pub identity: String,
+    pub signature_key: String,
+}
+
+/// A group of the user as shown to the user.
+#[derive(Debug, serde::Serialize)]
+pub struct GroupInfoSummary {
+    pub name: String,
+    pub epoch: u64,
+    pub members: usize,
+}
+
+/// A pending proposal as shown to the user.
+#[derive(Debug, serde::Serialize)]
+pub struct PendingProposal {
+    pub proposal_type: String,
+    pub sender: String,
+}
+
+/// Encode `bytes` as lower case hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 pub struct Group {
     group_name: String,
     conversation: Conversation,
     mls_group: RefCell<MlsGroup>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+/// A client of the DS.
+///
+/// The whole state of the user is stored in an SQLite database (see
+/// [`persistence`]) and updated with every change.
 pub struct User {
-    #[serde(
-        serialize_with = "serialize_any_hashmap::serialize_hashmap",
-        deserialize_with = "serialize_any_hashmap::deserialize_hashmap"
-    )]
     pub(crate) contacts: HashMap<Vec<u8>, Contact>,
-    #[serde(skip)]
     pub(crate) groups: RefCell<HashMap<String, Group>>,
-    group_list: HashSet<String>,
     pub(crate) identity: RefCell<Identity>,
-    #[serde(skip)]
     backend: Backend,
-    #[serde(skip)]
     provider: OpenMlsRustPersistentCrypto,
-    autosave_enabled: bool,
     auth_token: Option<AuthToken>,
 }
 
@@ -62,110 +79,93 @@ pub enum PostUpdateActions {
 
 impl User {
     /// Create a new user with the given name and a fresh set of credentials.
-    pub fn new(username: String) -> Self {
-        let crypto = OpenMlsRustPersistentCrypto::default();
-        let out = Self {
+    ///
+    /// Any state stored for a previous client with the same name is deleted.
+    pub fn new(username: String) -> Result<Self, String> {
+        let path = persistence::db_path(&username);
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Error deleting old state {}: {e}", path.display()))?;
+        }
+        let crypto = OpenMlsRustPersistentCrypto::open(&path)?;
+        persistence::create_tables(crypto.connection())?;
+
+        let identity = Identity::new(CIPHERSUITE, &crypto, username.as_bytes());
+        persistence::store_identity(crypto.connection(), &identity.credential_with_key, None)?;
+
+        Ok(Self {
             groups: RefCell::new(HashMap::new()),
-            group_list: HashSet::new(),
             contacts: HashMap::new(),
-            identity: RefCell::new(Identity::new(CIPHERSUITE, &crypto, username.as_bytes())),
+            identity: RefCell::new(identity),
             backend: Backend::default(),
             provider: crypto,
-            autosave_enabled: false,
             auth_token: None,
-        };
-        out
-    }
-
-    fn get_file_path(user_name: &str) -> PathBuf {
-        openmls_memory_storage::persistence::get_file_path(
-            &("openmls_cli_".to_owned() + user_name + ".json"),
-        )
-    }
-
-    fn load_from_file(input_file: &File) -> Result<Self, String> {
-        // Prepare file reader.
-        let reader = BufReader::new(input_file);
-
-        // Read the JSON contents of the file as an instance of `User`.
-        match serde_json::from_reader::<BufReader<&File>, User>(reader) {
-            Ok(user) => Ok(user),
-            Err(e) => Result::Err(e.to_string()),
-        }
+        })
     }
 
+    /// Load the user with the given name from its database.
+    ///
+    /// The groups are loaded from the storage provider with [`MlsGroup::load`].
     pub fn load(user_name: String) -> Result<Self, String> {
-        let input_path = User::get_file_path(&user_name);
-
-        match File::open(input_path) {
-            Err(e) => {
-                log::error!("Error loading user state: {:?}", e.to_string());
-                Err(e.to_string())
-            }
-            Ok(input_file) => {
-                let user_result = User::load_from_file(&input_file);
-
-                if user_result.is_ok() {
-                    let mut user = user_result.ok().unwrap();
-                    match user.provider.load_keystore(user_name) {
-                        Ok(_) => {
-                            let groups = user.groups.get_mut();
-                            for group_name in &user.group_list {
-                                let mlsgroup = MlsGroup::load(
-                                    user.provider.storage(),
-                                    &GroupId::from_slice(group_name.as_bytes()),
-                                );
-                                let grp = Group {
-                                    mls_group: RefCell::new(mlsgroup.unwrap().unwrap()),
-                                    group_name: group_name.clone(),
-                                    conversation: Conversation::default(),
-                                };
-                                groups.insert(group_name.clone(), grp);
-                            }
-                            Ok(user)
-                        }
-                        Err(e) => Err(e),
-                    }
-                } else {
-                    user_result
-                }
-            }
+        let path = persistence::db_path(&user_name);
+        if !path.exists() {
+            return Err(format!("No state stored for client {user_name}"));
         }
-    }
+        let crypto = OpenMlsRustPersistentCrypto::open(&path)?;
+        persistence::create_tables(crypto.connection())?;
 
-    fn save_to_file(&self, output_file: &File) {
-        let writer = BufWriter::new(output_file);
-        match serde_json::to_writer_pretty(writer, &self) {
-            Ok(()) => log::info!("User serialized"),
-            Err(e) => log::error!("Error serializing user: {:?}", e.to_string()),
-        }
-    }
+        let (identity, auth_token) = Identity::load(CIPHERSUITE, &crypto)?;
 
-    pub fn save(&mut self) {
-        let output_path = User::get_file_path(&self.identity.borrow().identity_as_string());
-        match File::create(output_path) {
-            Err(e) => log::error!("Error saving user state: {:?}", e.to_string()),
-            Ok(output_file) => {
-                self.save_to_file(&output_file);
+        let contacts = persistence::load_contacts(crypto.connection())?
+            .into_iter()
+            .map(|id| (id.clone(), Contact { id }))
+            .collect();
 
-                match self.provider.save_keystore(self.username()) {
-                    Ok(_) => log::info!("User state saved"),
-                    Err(e) => log::error!("Error saving user state : {:?}", e.to_string()),
-                }
-            }
+        let mut groups = HashMap::new();
+        for group_name in persistence::load_groups(crypto.connection())? {
+            let mls_group = MlsGroup::load(
+                crypto.storage(),
+                &GroupId::from_slice(group_name.as_bytes()),
+            )
+            .map_err(|e| format!("Error loading group {group_name}: {e}"))?
+            .ok_or_else(|| format!("Group {group_name} not found in the storage"))?;
+            let group = Group {
+                group_name: group_name.clone(),
+                conversation: persistence::load_conversation(crypto.connection(), &group_name)?,
+                mls_group: RefCell::new(mls_group),
+            };
+            groups.insert(group_name, group);
         }
-    }
 
-    pub fn enable_auto_save(&mut self) {
-        self.autosave_enabled = true;
+        Ok(Self {
+            groups: RefCell::new(groups),
+            contacts,
+            identity: RefCell::new(identity),
+            backend: Backend::default(),
+            provider: crypto,
+            auth_token,
+        })
     }
 
-    fn autosave(&mut self) {
-        if self.autosave_enabled {
-            self.save();
+    /// Record a new group of the user.
+    fn insert_group(&self, group: Group) -> Result<(), String> {
+        persistence::store_group(self.provider.connection(), &group.group_name)?;
+        match self
+            .groups
+            .borrow_mut()
+            .insert(group.group_name.clone(), group)
+        {
+            Some(old) => Err(format!("Overrode the group {:?}", old.group_name)),
+            None => Ok(()),
         }
     }
 
+    /// Forget a group the user is not a member of anymore.
+    fn remove_group(&self, group_name: &str) -> Result<(), String> {
+        self.groups.borrow_mut().remove(group_name);
+        persistence::delete_group(self.provider.connection(), group_name)
+    }
+
     /// Add a key package to the user identity and return the pair [key package
     /// hash ref , key package]
     pub fn add_key_package(&self) -> (Vec<u8>, KeyPackage) {
@@ -207,16 +207,24 @@ impl User {
         Vec::from_iter(kpgs)
     }
 
-    pub fn register(&mut self) {
+    pub fn register(&mut self) -> Result<(), String> {
         match self.backend.register_client(self.key_packages()) {
             Ok(token) => {
                 log::debug!("Created new user: {:?}", self.username());
                 self.set_auth_token(token)
             }
-            Err(e) => log::error!("Error creating user: {:?}", e),
+            Err(e) => {
+                log::error!("Error creating user: {:?}", e);
+                Err(e)
+            }
         }
     }
 
+    /// Use the delivery service at `backend` instead of the default one.
+    pub fn set_backend(&mut self, backend: Backend) {
+        self.backend = backend;
+    }
+
     /// Get a list of clients in the group to send messages to.
     fn recipients(&self, group: &Group) -> Vec<Vec<u8>> {
         let mut recipients = Vec::new();
@@ -269,7 +277,7 @@ impl User {
     }
 
     /// Create a new key package and publish it to the delivery server
-    pub fn create_kp(&self) {
+    pub fn create_kp(&self) -> Result<(), String> {
         let kp = self.add_key_package();
         let ckp = ClientKeyPackages(
             vec![kp]
@@ -279,10 +287,7 @@ impl User {
                 .into(),
         );
 
-        match self.backend.publish_key_packages(self, ckp) {
-            Ok(()) => (),
-            Err(e) => println!("Error sending new key package: {e:?}"),
-        };
+        self.backend.publish_key_packages(self, ckp)
     }
 
     /// Send an application message to the group.
@@ -305,10 +310,7 @@ impl User {
 
         let msg = GroupMessage::new(message_out.into(), &self.recipients(group));
         log::debug!(" >>> send: {:?}", msg);
-        match self.backend.send_msg(&msg) {
-            Ok(()) => (),
-            Err(e) => println!("Error sending group message: {e:?}"),
-        }
+        self.backend.send_msg(&msg)?;
 
         // XXX: Need to update the client's local view of the conversation to include
         // the message they sent.
@@ -318,7 +320,7 @@ impl User {
 
     /// Update the user clients list.
     /// It updates the contacts with all the clients known by the server
-    fn update_clients(&mut self) {
+    pub(crate) fn update_clients(&mut self) {
         match self.backend.list_clients() {
             Ok(mut v) => {
                 for client_id in v.drain(..) {
@@ -326,16 +328,23 @@ impl User {
                         "update::Processing client for contact {:?}",
                         str::from_utf8(&client_id).unwrap()
                     );
-                    if client_id != self.identity.borrow().identity()
-                        && self
-                            .contacts
-                            .insert(
-                                client_id.clone(),
-                                Contact {
-                                    id: client_id.clone(),
-                                },
-                            )
-                            .is_some()
+                    if client_id == self.identity.borrow().identity() {
+                        continue;
+                    }
+                    if let Err(e) =
+                        persistence::store_contact(self.provider.connection(), &client_id)
+                    {
+                        log::error!("update_clients::Error storing contact: {e}");
+                    }
+                    if self
+                        .contacts
+                        .insert(
+                            client_id.clone(),
+                            Contact {
+                                id: client_id.clone(),
+                            },
+                        )
+                        .is_some()
                     {
                         log::debug!(
                             "update::added client to contact {:?}",
@@ -434,6 +443,11 @@ impl User {
                         .clone(),
                     String::from_utf8(sender_name).unwrap(),
                 );
+                persistence::store_message(
+                    self.provider.connection(),
+                    &group.group_name,
+                    &conversation_message,
+                )?;
                 group.conversation.add(conversation_message.clone());
                 if group_name.is_none() || group_name.clone().unwrap() == group.group_name {
                     Some(conversation_message)
@@ -441,8 +455,11 @@ impl User {
                     None
                 }
             }
-            ProcessedMessageContent::ProposalMessage(_proposal_ptr) => {
-                // intentionally left blank.
+            ProcessedMessageContent::ProposalMessage(proposal_ptr) => {
+                // Keep the proposal until someone commits to it.
+                mls_group
+                    .store_pending_proposal(self.provider.storage(), *proposal_ptr)
+                    .map_err(|e| format!("{e:?}"))?;
                 None
             }
             ProcessedMessageContent::ExternalJoinProposalMessage(_external_proposal_ptr) => {
@@ -507,10 +524,7 @@ impl User {
                             if post_update_actions == PostUpdateActions::Remove {
                                 match group_id_option {
                                     Some(gid) => {
-                                        let mut grps = self.groups.borrow_mut();
-                                        grps.remove_entry(str::from_utf8(gid.as_slice()).unwrap());
-                                        self.group_list
-                                            .remove(str::from_utf8(gid.as_slice()).unwrap());
+                                        self.remove_group(str::from_utf8(gid.as_slice()).unwrap())?;
                                     }
                                     None => log::debug!(
                                         "update::Error post update remove must have a group id"
@@ -538,14 +552,15 @@ impl User {
 
         self.update_clients();
 
-        self.autosave();
-
         Ok(messages_out)
     }
 
     /// Create a group with the given name.
-    pub fn create_group(&mut self, name: String) {
+    pub fn create_group(&mut self, name: String) -> Result<(), String> {
         log::debug!("{} creates group {}", self.username(), name);
+        if self.groups.borrow().contains_key(&name) {
+            return Err(format!("Group '{name}' existed already"));
+        }
         let group_id = name.as_bytes();
 
         // NOTE: Since the DS currently doesn't distribute copies of the group's ratchet
@@ -561,7 +576,7 @@ impl User {
             GroupId::from_slice(group_id),
             self.identity.borrow().credential_with_key.clone(),
         )
-        .expect("Failed to create MlsGroup");
+        .map_err(|e| format!("Failed to create MlsGroup - {e}"))?;
 
         let group = Group {
             group_name: name.clone(),
@@ -569,13 +584,7 @@ impl User {
             mls_group: RefCell::new(mls_group),
         };
 
-        if self.groups.borrow().contains_key(&name) {
-            panic!("Group '{}' existed already", name);
-        }
-
-        self.groups.borrow_mut().insert(name, group);
-
-        self.autosave();
+        self.insert_group(group)
     }
 
     /// Invite user with the given name to the group.
@@ -621,7 +630,7 @@ impl User {
             .mls_group
             .borrow_mut()
             .merge_pending_commit(&self.provider)
-            .expect("error merging pending commit");
+            .map_err(|e| format!("Failed to merge pending commit - {e}"))?;
 
         // Finally, send Welcome to the joiner.
         log::trace!("Sending welcome");
@@ -629,10 +638,6 @@ impl User {
             .send_welcome(&welcome)
             .expect("Error sending Welcome message");
 
-        drop(groups);
-
-        self.autosave();
-
         Ok(())
     }
 
@@ -674,26 +679,376 @@ impl User {
             .mls_group
             .borrow_mut()
             .merge_pending_commit(&self.provider)
-            .expect("error merging pending commit");
+            .map_err(|e| format!("Failed to merge pending commit - {e}"))?;
+
+        Ok(())
+    }
+
+    /// Send a handshake message to all other members of the group.
+    fn send_to_group(&self, group: &Group, message: MlsMessageOut) -> Result<(), String> {
+        let msg = GroupMessage::new(message.into(), &self.recipients(group));
+        self.backend.send_msg(&msg)
+    }
+
+    /// Update the own leaf in the group with fresh key material.
+    pub fn self_update(&mut self, group_name: String) -> Result<(), String> {
+        let groups = self.groups.borrow();
+        let group = match groups.get(&group_name) {
+            Some(g) => g,
+            None => return Err(format!("No group with name {group_name} known.")),
+        };
+
+        let bundle = group
+            .mls_group
+            .borrow_mut()
+            .self_update(
+                &self.provider,
+                &self.identity.borrow().signer,
+                LeafNodeParameters::default(),
+            )
+            .map_err(|e| format!("Failed to update own leaf - {e}"))?;
+
+        log::trace!("Sending commit");
+        self.send_to_group(group, bundle.commit().clone())?;
+
+        group
+            .mls_group
+            .borrow_mut()
+            .merge_pending_commit(&self.provider)
+            .map_err(|e| format!("Failed to merge pending commit - {e}"))?;
+
+        Ok(())
+    }
+
+    /// Ask the other members to remove us from the group.
+    ///
+    /// This sends a SelfRemove proposal if the group's wire format policy
+    /// allows it and a Remove proposal otherwise. The group is deleted locally
+    /// when the commit covering the proposal arrives.
+    pub fn leave_group(&mut self, group_name: String) -> Result<(), String> {
+        let groups = self.groups.borrow();
+        let group = match groups.get(&group_name) {
+            Some(g) => g,
+            None => return Err(format!("No group with name {group_name} known.")),
+        };
+
+        let identity = self.identity.borrow();
+        let mut mls_group = group.mls_group.borrow_mut();
+        let proposal =
+            match mls_group.leave_group_via_self_remove(&self.provider, &identity.signer) {
+                Err(LeaveGroupError::CannotSelfRemoveWithPureCiphertext) => {
+                    mls_group.leave_group(&self.provider, &identity.signer)
+                }
+                result => result,
+            }
+            .map_err(|e| format!("Failed to leave group - {e}"))?;
+        drop(mls_group);
+        drop(identity);
+
+        log::trace!("Sending proposal");
+        self.send_to_group(group, proposal)
+    }
+
+    /// List the proposals in the group that are waiting for a commit.
+    pub fn pending_proposals(&self, group_name: String) -> Result<Vec<PendingProposal>, String> {
+        let groups = self.groups.borrow();
+        let group = match groups.get(&group_name) {
+            Some(g) => g,
+            None => return Err(format!("No group with name {group_name} known.")),
+        };
+
+        let mls_group = group.mls_group.borrow();
+        let proposals = mls_group
+            .pending_proposals()
+            .map(|queued_proposal| PendingProposal {
+                proposal_type: format!("{:?}", queued_proposal.proposal().proposal_type()),
+                sender: match queued_proposal.sender() {
+                    Sender::Member(index) => mls_group
+                        .member(*index)
+                        .and_then(|credential| BasicCredential::try_from(credential.clone()).ok())
+                        .map(|credential| String::from_utf8_lossy(credential.identity()).into())
+                        .unwrap_or_else(|| format!("member {index}")),
+                    other => format!("{other:?}"),
+                },
+            })
+            .collect();
+        Ok(proposals)
+    }
+
+    /// Commit to all pending proposals in the group.
+    pub fn commit_pending_proposals(&mut self, group_name: String) -> Result<(), String> {
+        let groups = self.groups.borrow();
+        let group = match groups.get(&group_name) {
+            Some(g) => g,
+            None => return Err(format!("No group with name {group_name} known.")),
+        };
+
+        let (commit, welcome, _group_info) = group
+            .mls_group
+            .borrow_mut()
+            .commit_to_pending_proposals(&self.provider, &self.identity.borrow().signer)
+            .map_err(|e| format!("Failed to commit to pending proposals - {e}"))?;
+
+        log::trace!("Sending commit");
+        self.send_to_group(group, commit)?;
+
+        group
+            .mls_group
+            .borrow_mut()
+            .merge_pending_commit(&self.provider)
+            .map_err(|e| format!("Failed to merge pending commit - {e}"))?;
+
+        if let Some(welcome) = welcome {
+            log::trace!("Sending welcome");
+            self.backend.send_welcome(&welcome)?;
+        }
+
+        Ok(())
+    }
+
+    /// List the members of the group.
+    pub fn members(&self, group_name: String) -> Result<Vec<MemberInfo>, String> {
+        let groups = self.groups.borrow();
+        let group = match groups.get(&group_name) {
+            Some(g) => g,
+            None => return Err(format!("No group with name {group_name} known.")),
+        };
+
+        let members = group
+            .mls_group
+            .borrow()
+            .members()
+            .map(|member| MemberInfo {
+                index: member.index.u32(),
+                identity: BasicCredential::try_from(member.credential)
+                    .map(|credential| String::from_utf8_lossy(credential.identity()).into())
+                    .unwrap_or_default(),
+                signature_key: to_hex(&member.signature_key),
+            })
+            .collect();
+        Ok(members)
+    }
+
+    /// List all groups of the user.
+    pub fn group_summaries(&self) -> Vec<GroupInfoSummary> {
+        let groups = self.groups.borrow();
+        let mut summaries = groups
+            .values()
+            .map(|group| {
+                let mls_group = group.mls_group.borrow();
+                GroupInfoSummary {
+                    name: group.group_name.clone(),
+                    epoch: mls_group.epoch().as_u64(),
+                    members: mls_group.members().count(),
+                }
+            })
+            .collect::<Vec<_>>();
+        summaries.sort_by(|a, b| a.name.cmp(&b.name));
+        summaries
+    }
+
+    /// Get the epoch authenticator of the current epoch as hex string.
+    /// Members in the same epoch can compare it out of band.
+    pub fn epoch_authenticator(&self, group_name: String) -> Result<String, String> {
+        let groups = self.groups.borrow();
+        let group = match groups.get(&group_name) {
+            Some(g) => g,
+            None => return Err(format!("No group with name {group_name} known.")),
+        };
+
+        let authenticator = to_hex(group.mls_group.borrow().epoch_authenticator().as_slice());
+        Ok(authenticator)
+    }
+
+    /// Export a serialized GroupInfo, including the ratchet tree, that lets
+    /// other clients join the group with an external commit.
+    pub fn export_group_info(&self, group_name: String) -> Result<Vec<u8>, String> {
+        let groups = self.groups.borrow();
+        let group = match groups.get(&group_name) {
+            Some(g) => g,
+            None => return Err(format!("No group with name {group_name} known.")),
+        };
+
+        let group_info = group
+            .mls_group
+            .borrow()
+            .export_group_info(self.provider.crypto(), &self.identity.borrow().signer, true)
+            .map_err(|e| format!("Failed to export group info - {e}"))?;
+        group_info
+            .tls_serialize_detached()
+            .map_err(|e| format!("Failed to encode group info - {e}"))
+    }
+
+    /// Join a group with an external commit for the serialized `group_info`.
+    /// Returns the name of the group.
+    pub fn join_by_external_commit(&mut self, group_info: &[u8]) -> Result<String, String> {
+        let group_info = MlsMessageIn::tls_deserialize_exact(group_info)
+            .map_err(|e| format!("Invalid group info - {e}"))?
+            .into_verifiable_group_info()
+            .ok_or("Not a group info message".to_string())?;
+
+        let group_name = String::from_utf8(group_info.group_id().to_vec())
+            .map_err(|_| "Invalid group name".to_string())?;
+        if self.groups.borrow().contains_key(&group_name) {
+            return Err(format!("Group '{group_name}' existed already"));
+        }
+
+        // NOTE: Since the DS currently doesn't distribute copies of the group's ratchet
+        // tree, we need to include the ratchet_tree_extension.
+        let group_config = MlsGroupJoinConfig::builder()
+            .use_ratchet_tree_extension(true)
+            .build();
+        let (mls_group, commit, _group_info) = MlsGroup::join_by_external_commit(
+            &self.provider,
+            &self.identity.borrow().signer,
+            None,
+            group_info,
+            &group_config,
+            None,
+            None,
+            &[],
+            self.identity.borrow().credential_with_key.clone(),
+        )
+        .map_err(|e| format!("Failed to join group - {e}"))?;
+
+        let group = Group {
+            group_name: group_name.clone(),
+            conversation: Conversation::default(),
+            mls_group: RefCell::new(mls_group),
+        };
+
+        // Make sure we know all members to send them the commit.
+        self.update_clients();
+        log::trace!("Sending commit");
+        self.send_to_group(&group, commit)?;
+
+        group
+            .mls_group
+            .borrow_mut()
+            .merge_pending_commit(&self.provider)
+            .map_err(|e| format!("Failed to merge pending commit - {e}"))?;
+
+        self.insert_group(group)?;
+
+        Ok(group_name)
+    }
+
+    /// Replace the signature key of the user in all groups.
+    ///
+    /// The new key is stored as the user's identity before the first group
+    /// switches to it. If the commit can't be sent to a group, it is discarded
+    /// and the group keeps the old key until the rotation is repeated.
+    ///
+    /// Key packages that were published before still carry the old key and
+    /// should be replaced with new ones.
+    pub fn rotate_credential(&mut self) -> Result<(), String> {
+        let new_signer = SignatureKeyPair::new(CIPHERSUITE.signature_algorithm())
+            .map_err(|e| format!("Failed to create signature key - {e:?}"))?;
+        new_signer
+            .store(self.provider.storage())
+            .map_err(|e| format!("Failed to store signature key - {e:?}"))?;
+        let new_credential_with_key = CredentialWithKey {
+            credential: self
+                .identity
+                .borrow()
+                .credential_with_key
+                .credential
+                .clone(),
+            signature_key: new_signer.to_public_vec().into(),
+        };
+
+        // Groups are signed with the key of the user's leaf, which differs
+        // from the user's key in groups left over from a failed rotation.
+        // Their keys are still in the storage.
+        let mut old_signers = vec![];
+        for group in self.groups.borrow().values() {
+            let mls_group = group.mls_group.borrow();
+            let Some(own_leaf) = mls_group.own_leaf_node() else {
+                continue;
+            };
+            let old_signer = SignatureKeyPair::read(
+                self.provider.storage(),
+                own_leaf.signature_key().as_slice(),
+                CIPHERSUITE.signature_algorithm(),
+            )
+            .ok_or_else(|| {
+                format!(
+                    "Signature key of group {} not found in the database",
+                    group.group_name
+                )
+            })?;
+            old_signers.push((group.group_name.clone(), old_signer));
+        }
 
-        drop(groups);
+        persistence::store_identity(
+            self.provider.connection(),
+            &new_credential_with_key,
+            self.auth_token.as_ref(),
+        )?;
+        {
+            let mut identity = self.identity.borrow_mut();
+            identity.signer = new_signer;
+            identity.credential_with_key = new_credential_with_key.clone();
+        }
 
-        self.autosave();
+        let identity = self.identity.borrow();
+        let groups = self.groups.borrow();
+        let mut failed_groups = vec![];
+        for (group_name, old_signer) in old_signers {
+            let group = &groups[&group_name];
+            let bundle = group
+                .mls_group
+                .borrow_mut()
+                .self_update_with_new_signer(
+                    &self.provider,
+                    &old_signer,
+                    NewSignerBundle {
+                        signer: &identity.signer,
+                        credential_with_key: new_credential_with_key.clone(),
+                    },
+                    LeafNodeParameters::default(),
+                )
+                .map_err(|e| format!("Failed to rotate credential - {e}"))?;
+
+            log::trace!("Sending commit");
+            if let Err(e) = self.send_to_group(group, bundle.commit().clone()) {
+                log::error!("Failed to send commit to group {group_name} - {e}");
+                group
+                    .mls_group
+                    .borrow_mut()
+                    .clear_pending_commit(self.provider.storage())
+                    .map_err(|e| format!("Failed to clear pending commit - {e:?}"))?;
+                failed_groups.push(group_name);
+                continue;
+            }
+
+            group
+                .mls_group
+                .borrow_mut()
+                .merge_pending_commit(&self.provider)
+                .map_err(|e| format!("Failed to merge pending commit - {e}"))?;
+        }
+
+        if !failed_groups.is_empty() {
+            return Err(format!(
+                "Failed to rotate credential in groups {} - rotate again to retry",
+                failed_groups.join(", ")
+            ));
+        }
 
         Ok(())
     }
 
     /// Join a group with the provided welcome message.
-    fn join_group(&self, welcome: Welcome) -> Result<(), String> {
+    fn join_group(&mut self, welcome: Welcome) -> Result<(), String> {
         log::debug!("{} joining group ...", self.username());
 
         let mut ident = self.identity.borrow_mut();
         for secret in welcome.secrets().iter() {
             let key_package_hash = &secret.new_member();
-            if ident.kp.contains_key(key_package_hash.as_slice()) {
-                ident.kp.remove(key_package_hash.as_slice());
-            }
+            ident.remove_key_package(key_package_hash.as_slice(), &self.provider)?;
         }
+        drop(ident);
         // NOTE: Since the DS currently doesn't distribute copies of the group's ratchet
         // tree, we need to include the ratchet_tree_extension.
         let group_config = MlsGroupJoinConfig::builder()
@@ -717,18 +1072,21 @@ impl User {
 
         log::trace!("   {}", group_name);
 
-        match self.groups.borrow_mut().insert(group_name, group) {
-            Some(old) => Err(format!("Overrode the group {:?}", old.group_name)),
-            None => Ok(()),
-        }
+        self.insert_group(group)
     }
 
     pub(crate) fn username(&self) -> String {
         self.identity.borrow().identity_as_string()
     }
 
-    pub(super) fn set_auth_token(&mut self, token: AuthToken) {
+    pub(super) fn set_auth_token(&mut self, token: AuthToken) -> Result<(), String> {
+        persistence::store_identity(
+            self.provider.connection(),
+            &self.identity.borrow().credential_with_key,
+            Some(&token),
+        )?;
         self.auth_token = Some(token);
+        Ok(())
     }
 
     pub(super) fn auth_token(&self) -> Option<&AuthToken> {
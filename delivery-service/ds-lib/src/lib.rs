@@ -4,6 +4,9 @@
 //! the OpenMLS DS.
 //!
 //! Clients are represented by the `ClientInfo` struct.
+//!
+//! Client identifiers can carry a domain part (`alice@example.com`) that
+//! names the DS the client is registered with. See [`client_domain`].
 
 pub mod messages;
 
@@ -13,6 +16,24 @@ use messages::AuthToken;
 use openmls::prelude::tls_codec::*;
 use openmls::prelude::*;
 
+/// The separator between the local part and the domain part of a client
+/// identifier.
+pub const DOMAIN_SEPARATOR: u8 = b'@';
+
+/// Get the domain part of a client identifier, i.e. everything after the last
+/// [`DOMAIN_SEPARATOR`].
+/// Returns `None` if the identifier has no (or an empty) domain part. Such
+/// clients always belong to the DS they are registered with.
+pub fn client_domain(client_id: &[u8]) -> Option<&[u8]> {
+    let separator = client_id.iter().rposition(|&b| b == DOMAIN_SEPARATOR)?;
+    let domain = &client_id[separator + 1..];
+    if domain.is_empty() {
+        None
+    } else {
+        Some(domain)
+    }
+}
+
 /// Information about a client.
 /// To register a new client create a new `ClientInfo` and send it to
 /// `/clients/register`.
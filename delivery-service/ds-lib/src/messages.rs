@@ -2,7 +2,7 @@ use crate::tls_codec::{self, TlsDeserialize, TlsSerialize, TlsSize};
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 
-use crate::ClientKeyPackages;
+use crate::{ClientKeyPackages, GroupMessage};
 
 #[derive(
     Debug, Clone, TlsSize, TlsSerialize, TlsDeserialize, PartialEq, Serialize, Deserialize,
@@ -48,3 +48,14 @@ pub struct PublishKeyPackagesRequest {
 pub struct RecvMessageRequest {
     pub auth_token: AuthToken,
 }
+
+/// A group message forwarded from one DS to another.
+///
+/// The `group_message` only lists the recipients that are registered with the
+/// receiving DS. `owner_domain` is the domain of the DS that sequences
+/// handshake messages for the group.
+#[derive(Debug, TlsSize, TlsSerialize, TlsDeserialize)]
+pub struct FederatedGroupMessage {
+    pub owner_domain: Vec<u8>,
+    pub group_message: GroupMessage,
+}
@@ -41,3 +41,14 @@ fn test_client_info() {
         .unwrap();
     assert_eq!(client_data.tls_serialize_detached().unwrap(), client_data2);
 }
+
+#[test]
+fn test_client_domain() {
+    assert_eq!(
+        client_domain(b"alice@example.com"),
+        Some(&b"example.com"[..])
+    );
+    assert_eq!(client_domain(b"a@b@example.com"), Some(&b"example.com"[..]));
+    assert_eq!(client_domain(b"alice"), None);
+    assert_eq!(client_domain(b"alice@"), None);
+}
@@ -0,0 +1,292 @@
+//! # Federation between delivery services.
+//!
+//! Several DS instances, each responsible for one domain, can be federated.
+//! A client identifier with a domain part (`alice@a.example`) belongs to the DS
+//! for that domain. Identifiers without a domain part are always local.
+//!
+//! * Group messages and Welcome messages for clients on another domain are
+//!   forwarded to the DS of that domain (`/federation/message` and
+//!   `/federation/welcome`).
+//! * Key packages of clients on another domain are fetched from their DS.
+//! * Every group has a single owning DS that sequences handshake messages for
+//!   all members. The owner is the DS of the group's creator: it receives the
+//!   first handshake message of the group in epoch 0, where the creator is the
+//!   only member. All other DSs forward handshake messages to the owner, which
+//!   checks the epoch and distributes them along with its domain. A DS that
+//!   doesn't know the owner of a group asks its peers
+//!   (`/federation/owner/{group_id}`).
+//!
+//! Every peer shares a secret token with this DS. Requests between DS
+//! instances carry the domain of the sending DS in the
+//! [`FEDERATION_DOMAIN_HEADER`] and the token of that peer as bearer token.
+//! The `/federation/*` endpoints reject requests that don't authenticate as a
+//! configured peer.
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use ds_lib::{client_domain, messages::FederatedGroupMessage, GroupMessage};
+use openmls::prelude::{
+    tls_codec::{self, Deserialize, Serialize},
+    KeyPackageIn, MlsMessageIn,
+};
+
+/// The header carrying the domain of the DS that sends a federation request.
+pub const FEDERATION_DOMAIN_HEADER: &str = "X-Federation-Domain";
+
+/// A peer DS.
+#[derive(Clone)]
+pub struct Peer {
+    /// The base URL of the peer, e.g. `http://127.0.0.1:8081`.
+    pub url: String,
+    /// The secret token shared with the peer. It authenticates requests in
+    /// both directions.
+    pub token: String,
+}
+
+impl std::fmt::Debug for Peer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Don't leak the token into logs.
+        f.debug_struct("Peer")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Errors when talking to another DS.
+#[derive(Debug)]
+pub enum FederationError {
+    /// There's no peer configured for the domain.
+    UnknownPeer(String),
+    /// The request to the peer failed.
+    Network(reqwest::Error),
+    /// Encoding the request or decoding the response failed.
+    Codec(tls_codec::Error),
+    /// The peer answered with a status that is not `200 OK`.
+    Status(u16),
+}
+
+impl FederationError {
+    /// Turn the error into the response the DS sends back to its client.
+    /// Status codes of the peer are passed through.
+    pub fn into_response(self) -> actix_web::HttpResponse {
+        log::error!("Federation error: {:?}", self);
+        match self {
+            FederationError::UnknownPeer(_) => actix_web::HttpResponse::NotFound().finish(),
+            FederationError::Status(status) => {
+                match actix_web::http::StatusCode::from_u16(status) {
+                    Ok(status) => actix_web::HttpResponse::build(status).finish(),
+                    Err(_) => actix_web::HttpResponse::BadGateway().finish(),
+                }
+            }
+            FederationError::Network(_) | FederationError::Codec(_) => {
+                actix_web::HttpResponse::BadGateway().finish()
+            }
+        }
+    }
+}
+
+/// The federation configuration of a DS.
+/// The default is a DS without a domain that treats all clients as local.
+#[derive(Debug, Default)]
+pub struct Federation {
+    domain: Option<String>,
+    // (domain, peer)
+    peers: HashMap<String, Peer>,
+    client: reqwest::Client,
+}
+
+impl Federation {
+    /// Create a new federation configuration for the DS of `domain` with a
+    /// map of peer domains to their DS.
+    pub fn new(domain: String, peers: HashMap<String, Peer>) -> Self {
+        Self {
+            domain: Some(domain),
+            peers,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// The domain of this DS, if it is federated.
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
+    /// Get the domain of the DS responsible for `client_id`.
+    /// Returns `None` if the client is local to this DS.
+    pub fn remote_domain<'a>(&self, client_id: &'a [u8]) -> Option<&'a str> {
+        let own_domain = self.domain.as_deref()?;
+        let domain = std::str::from_utf8(client_domain(client_id)?).ok()?;
+        if domain == own_domain {
+            None
+        } else {
+            Some(domain)
+        }
+    }
+
+    /// Authenticate a request from another DS.
+    /// Returns the domain of the peer if the request carries the token of a
+    /// configured peer, and `None` otherwise.
+    pub fn authenticate(&self, req: &actix_web::HttpRequest) -> Option<&str> {
+        let domain = req.headers().get(FEDERATION_DOMAIN_HEADER)?.to_str().ok()?;
+        let token = req
+            .headers()
+            .get(actix_web::http::header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")?;
+        let (domain, peer) = self.peers.get_key_value(domain)?;
+        if constant_time_eq(peer.token.as_bytes(), token.as_bytes()) {
+            Some(domain)
+        } else {
+            None
+        }
+    }
+
+    /// Build a request to the peer for `domain`, authenticated with the token
+    /// shared with it.
+    fn request(
+        &self,
+        method: reqwest::Method,
+        domain: &str,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder, FederationError> {
+        let peer = self
+            .peers
+            .get(domain)
+            .ok_or_else(|| FederationError::UnknownPeer(domain.to_owned()))?;
+        let url = format!("{}{}", peer.url.trim_end_matches('/'), path);
+        Ok(self
+            .client
+            .request(method, url)
+            .header(
+                FEDERATION_DOMAIN_HEADER,
+                self.domain.as_deref().unwrap_or_default(),
+            )
+            .bearer_auth(&peer.token))
+    }
+
+    async fn get(&self, domain: &str, path: &str) -> Result<Vec<u8>, FederationError> {
+        let response = self
+            .request(reqwest::Method::GET, domain, path)?
+            .send()
+            .await
+            .map_err(FederationError::Network)?;
+        read_response(response).await
+    }
+
+    async fn post(&self, domain: &str, path: &str, body: Vec<u8>) -> Result<(), FederationError> {
+        let response = self
+            .request(reqwest::Method::POST, domain, path)?
+            .body(body)
+            .send()
+            .await
+            .map_err(FederationError::Network)?;
+        read_response(response).await.map(|_| ())
+    }
+
+    /// Fetch the serialized `ClientKeyPackages` of a remote client.
+    pub async fn fetch_key_packages(
+        &self,
+        domain: &str,
+        client_id: &[u8],
+    ) -> Result<Vec<u8>, FederationError> {
+        let path = "/clients/key_packages/".to_owned()
+            + &base64::engine::general_purpose::URL_SAFE.encode(client_id);
+        self.get(domain, &path).await
+    }
+
+    /// Consume a key package of a remote client on its DS.
+    pub async fn consume_key_package(
+        &self,
+        domain: &str,
+        client_id: &[u8],
+    ) -> Result<KeyPackageIn, FederationError> {
+        let path = "/clients/key_package/".to_owned()
+            + &base64::engine::general_purpose::URL_SAFE.encode(client_id);
+        let bytes = self.get(domain, &path).await?;
+        KeyPackageIn::tls_deserialize_exact(bytes).map_err(FederationError::Codec)
+    }
+
+    /// Forward a group message for clients of `domain` to their DS.
+    pub async fn forward_message(
+        &self,
+        domain: &str,
+        owner_domain: &str,
+        group_message: GroupMessage,
+    ) -> Result<(), FederationError> {
+        let msg = FederatedGroupMessage {
+            owner_domain: owner_domain.as_bytes().to_vec(),
+            group_message,
+        };
+        let body = msg
+            .tls_serialize_detached()
+            .map_err(FederationError::Codec)?;
+        self.post(domain, "/federation/message", body).await
+    }
+
+    /// Forward a Welcome message to the DS of `domain`, which holds the
+    /// reservation for the key package it was created for.
+    pub async fn forward_welcome(
+        &self,
+        domain: &str,
+        welcome: &MlsMessageIn,
+    ) -> Result<(), FederationError> {
+        let body = welcome
+            .tls_serialize_detached()
+            .map_err(FederationError::Codec)?;
+        self.post(domain, "/federation/welcome", body).await
+    }
+
+    /// Ask the peers for the domain of the DS owning the group with
+    /// `group_id`. Returns `None` if no peer knows the owner.
+    pub async fn lookup_group_owner(
+        &self,
+        group_id: &[u8],
+    ) -> Result<Option<String>, FederationError> {
+        let path = "/federation/owner/".to_owned()
+            + &base64::engine::general_purpose::URL_SAFE.encode(group_id);
+        let mut domains: Vec<&String> = self.peers.keys().collect();
+        domains.sort();
+        for domain in domains {
+            match self.get(domain, &path).await {
+                Ok(owner_domain) => {
+                    if let Ok(owner_domain) = String::from_utf8(owner_domain) {
+                        return Ok(Some(owner_domain));
+                    }
+                }
+                Err(FederationError::Status(404)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Hand a handshake message to the DS owning the group for sequencing.
+    /// The owner distributes it to all recipients, including the ones on this
+    /// DS.
+    pub async fn forward_to_owner(
+        &self,
+        owner_domain: &str,
+        group_message: &GroupMessage,
+    ) -> Result<(), FederationError> {
+        let body = group_message
+            .tls_serialize_detached()
+            .map_err(FederationError::Codec)?;
+        self.post(owner_domain, "/federation/handshake", body).await
+    }
+}
+
+/// Compare two byte strings in time independent of their content.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+async fn read_response(response: reqwest::Response) -> Result<Vec<u8>, FederationError> {
+    // Everything but 200 (including 204 No Content) is an error here.
+    if response.status() != reqwest::StatusCode::OK {
+        return Err(FederationError::Status(response.status().as_u16()));
+    }
+    let bytes = response.bytes().await.map_err(FederationError::Network)?;
+    Ok(bytes.to_vec())
+}
@@ -29,6 +29,9 @@
 //!
 //! The DS returns a list of messages queued for the client in all groups they
 //! are part of.
+//!
+//! Multiple DS instances can be federated, where each DS is responsible for one
+//! domain. See the [`federation`] module for details.
 
 use actix_web::{get, post, web, web::Payload, App, HttpRequest, HttpServer, Responder};
 use base64::Engine;
@@ -40,12 +43,16 @@ use tls_codec::{Deserialize, Serialize, TlsSliceU16, TlsVecU32};
 
 use ds_lib::{
     messages::{
-        PublishKeyPackagesRequest, RecvMessageRequest, RegisterClientRequest,
-        RegisterClientSuccessResponse,
+        FederatedGroupMessage, PublishKeyPackagesRequest, RecvMessageRequest,
+        RegisterClientRequest, RegisterClientSuccessResponse,
     },
     *,
 };
 use openmls::prelude::*;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+mod federation;
+use federation::{Federation, Peer};
 
 #[cfg(test)]
 mod test;
@@ -59,6 +66,24 @@ pub struct DsData {
 
     // (group_id, epoch)
     groups: Mutex<HashMap<Vec<u8>, u64>>,
+
+    // (group_id, domain of the owning DS)
+    group_owners: Mutex<HashMap<Vec<u8>, String>>,
+
+    // (key_package_hash, domain) of key packages reserved on other DSs
+    remote_reservations: Mutex<HashMap<Vec<u8>, String>>,
+
+    federation: Federation,
+}
+
+impl DsData {
+    /// Create the state for a federated DS.
+    pub fn federated(federation: Federation) -> Self {
+        Self {
+            federation,
+            ..Default::default()
+        }
+    }
 }
 
 macro_rules! unwrap_item {
@@ -159,25 +184,38 @@ async fn reset(req: HttpRequest, data: web::Data<DsData>) -> impl Responder {
     log::debug!("Resetting server");
     let mut clients = unwrap_data!(data.clients.lock());
     let mut groups = unwrap_data!(data.groups.lock());
+    let mut group_owners = unwrap_data!(data.group_owners.lock());
+    let mut remote_reservations = unwrap_data!(data.remote_reservations.lock());
     clients.clear();
     groups.clear();
+    group_owners.clear();
+    remote_reservations.clear();
     actix_web::HttpResponse::Ok().finish()
 }
 
 /// Get the list of key packages for a given client `{id}`.
 /// This returns a serialised vector of `ClientKeyPackages` (see the `ds-lib`
 /// for details).
+/// Key packages of clients on another domain are fetched from their DS.
 #[get("/clients/key_packages/{id}")]
 async fn get_key_packages(path: web::Path<String>, data: web::Data<DsData>) -> impl Responder {
-    let clients = unwrap_data!(data.clients.lock());
-
     let id = match base64::engine::general_purpose::URL_SAFE.decode(path.into_inner()) {
         Ok(v) => v,
         Err(_) => return actix_web::HttpResponse::BadRequest().finish(),
     };
 
+    if let Some(domain) = data.federation.remote_domain(&id) {
+        log::debug!("Fetching key packages for {:?} from {}", id, domain);
+        return match data.federation.fetch_key_packages(domain, &id).await {
+            Ok(bytes) => actix_web::HttpResponse::Ok().body(bytes),
+            Err(e) => e.into_response(),
+        };
+    }
+
     log::debug!("Getting key packages for {:?}", id);
 
+    let clients = unwrap_data!(data.clients.lock());
+
     let client = match clients.get(&id) {
         Some(c) => c,
         None => return actix_web::HttpResponse::NoContent().finish(),
@@ -246,16 +284,40 @@ async fn publish_key_packages(
 /// Consume a key package for a given client `{id}`.
 /// This returns a serialised `KeyPackage` (see the `ds-lib`
 /// for details).
+/// Key packages of clients on another domain are consumed on their DS. This DS
+/// remembers the reservation to forward the Welcome message later.
 #[get("/clients/key_package/{id}")]
 async fn consume_key_package(path: web::Path<String>, data: web::Data<DsData>) -> impl Responder {
-    let mut clients = unwrap_data!(data.clients.lock());
-
     let id = match base64::engine::general_purpose::URL_SAFE.decode(path.into_inner()) {
         Ok(v) => v,
         Err(_) => return actix_web::HttpResponse::BadRequest().finish(),
     };
+
+    if let Some(domain) = data.federation.remote_domain(&id) {
+        log::debug!("Consuming key package for {:?} on {}", id, domain);
+        let key_package_in = match data.federation.consume_key_package(domain, &id).await {
+            Ok(kp) => kp,
+            Err(e) => return e.into_response(),
+        };
+        let crypto = OpenMlsRustCrypto::default();
+        let key_package_hash = match key_package_in
+            .clone()
+            .validate(crypto.crypto(), ProtocolVersion::Mls10)
+            .map(|kp| kp.hash_ref(crypto.crypto()))
+        {
+            Ok(Ok(hash)) => hash,
+            _ => return actix_web::HttpResponse::BadGateway().finish(),
+        };
+        let mut remote_reservations = unwrap_data!(data.remote_reservations.lock());
+        remote_reservations.insert(key_package_hash.as_slice().to_vec(), domain.to_owned());
+        return actix_web::HttpResponse::Ok()
+            .body(unwrap_data!(key_package_in.tls_serialize_detached()));
+    }
+
     log::debug!("Consuming key package for {:?}", id);
 
+    let mut clients = unwrap_data!(data.clients.lock());
+
     let key_package = match clients.get_mut(&id) {
         Some(c) => match c.consume_kp() {
             Ok(kp) => kp,
@@ -270,9 +332,44 @@ async fn consume_key_package(path: web::Path<String>, data: web::Data<DsData>) -
     actix_web::HttpResponse::Ok().body(unwrap_data!(key_package.tls_serialize_detached()))
 }
 
+/// Store a Welcome message for all local clients with a reserved key package
+/// in it.
+/// Returns whether the message was stored for any client, and the domains of
+/// the DSs holding the remaining reservations.
+fn queue_welcome(
+    data: &DsData,
+    welcome_msg: &MlsMessageIn,
+    welcome: &Welcome,
+) -> Result<(bool, Vec<String>), ()> {
+    let mut clients = data.clients.lock().map_err(|_| ())?;
+    let mut remote_reservations = data.remote_reservations.lock().map_err(|_| ())?;
+
+    let mut queued = false;
+    let mut remote_domains = Vec::new();
+    for secret in welcome.secrets().iter() {
+        let key_package_hash = secret.new_member();
+        let key_package_hash = key_package_hash.as_slice();
+        if let Some(client) = clients.values_mut().find_map(|client| {
+            client
+                .reserved_key_pkg_hash
+                .remove(key_package_hash)
+                .then_some(client)
+        }) {
+            client.welcome_queue.push(welcome_msg.clone());
+            queued = true;
+        } else if let Some(domain) = remote_reservations.remove(key_package_hash) {
+            if !remote_domains.contains(&domain) {
+                remote_domains.push(domain);
+            }
+        }
+    }
+    Ok((queued, remote_domains))
+}
+
 /// Send a welcome message to a client.
 /// This takes a serialised `Welcome` message and stores the message for all
 /// clients in the welcome message.
+/// Welcome messages for clients on another domain are forwarded to their DS.
 #[post("/send/welcome")]
 async fn send_welcome(mut body: Payload, data: web::Data<DsData>) -> impl Responder {
     let mut bytes = web::BytesMut::new();
@@ -280,26 +377,142 @@ async fn send_welcome(mut body: Payload, data: web::Data<DsData>) -> impl Respon
         bytes.extend_from_slice(&unwrap_item!(item));
     }
     let welcome_msg = unwrap_data!(MlsMessageIn::tls_deserialize(&mut &bytes[..]));
-    let welcome = welcome_msg.clone().into_welcome().unwrap();
+    let welcome = unwrap_data!(welcome_msg.clone().into_welcome().ok_or(()));
     log::debug!("Storing welcome message: {:?}", welcome_msg);
 
-    let mut clients = unwrap_data!(data.clients.lock());
-    for secret in welcome.secrets().iter() {
-        let key_package_hash = &secret.new_member();
-        for (_client_name, client) in clients.iter_mut() {
-            match client
-                .reserved_key_pkg_hash
-                .take(key_package_hash.as_slice())
-            {
-                Some(_kp_hash) => {
-                    client.welcome_queue.push(welcome_msg);
-                    return actix_web::HttpResponse::Ok().finish();
-                }
-                None => continue,
-            };
+    let (mut delivered, remote_domains) =
+        unwrap_data!(queue_welcome(&data, &welcome_msg, &welcome));
+    for domain in remote_domains {
+        if let Err(e) = data.federation.forward_welcome(&domain, &welcome_msg).await {
+            return e.into_response();
         }
+        delivered = true;
+    }
+
+    if delivered {
+        actix_web::HttpResponse::Ok().finish()
+    } else {
+        actix_web::HttpResponse::NoContent().finish()
     }
-    actix_web::HttpResponse::NoContent().finish()
+}
+
+/// Check the epoch of a handshake message against the latest epoch this DS has
+/// seen for the group and update it.
+/// Returns `false` if the message has an earlier epoch.
+fn sequence_handshake_message(data: &DsData, protocol_msg: &ProtocolMessage) -> Result<bool, ()> {
+    let mut groups = data.groups.lock().map_err(|_| ())?;
+    let epoch = protocol_msg.epoch().as_u64();
+    let group_id = protocol_msg.group_id().as_slice();
+    if let Some(&group_epoch) = groups.get(group_id) {
+        if group_epoch > epoch {
+            return Ok(false);
+        }
+        // Update server state to the latest epoch.
+        let old_value = groups.insert(group_id.to_vec(), epoch);
+        if old_value.is_none() {
+            return Err(());
+        }
+    } else {
+        // We haven't seen this group_id yet. Store it.
+        let old_value = groups.insert(group_id.to_vec(), epoch);
+        if old_value.is_some() {
+            return Err(());
+        }
+    }
+    Ok(true)
+}
+
+/// Recipients on this DS.
+type LocalRecipients = Vec<Vec<u8>>;
+
+/// Recipients on other domains, by the domain of their DS.
+type RemoteRecipients = HashMap<String, Vec<Vec<u8>>>;
+
+/// Split the recipients of a message into local recipients and recipients on
+/// other domains, by the domain of their DS.
+/// Returns `None` if a local recipient doesn't exist.
+fn split_recipients(
+    data: &DsData,
+    recipients: &[TlsByteVecU32],
+) -> Result<Option<(LocalRecipients, RemoteRecipients)>, ()> {
+    let clients = data.clients.lock().map_err(|_| ())?;
+    let mut local_recipients = LocalRecipients::new();
+    let mut remote_recipients = RemoteRecipients::new();
+    for recipient in recipients.iter() {
+        let recipient = recipient.as_slice();
+        if let Some(domain) = data.federation.remote_domain(recipient) {
+            remote_recipients
+                .entry(domain.to_owned())
+                .or_default()
+                .push(recipient.to_vec());
+        } else if clients.contains_key(recipient) {
+            local_recipients.push(recipient.to_vec());
+        } else {
+            return Ok(None);
+        }
+    }
+    Ok(Some((local_recipients, remote_recipients)))
+}
+
+/// Store a message for all local recipients.
+fn queue_message(data: &DsData, msg: &MlsMessageIn, recipients: &[Vec<u8>]) -> Result<(), ()> {
+    let mut clients = data.clients.lock().map_err(|_| ())?;
+    for recipient in recipients {
+        if let Some(client) = clients.get_mut(recipient) {
+            client.msgs.push(msg.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Get the domain of the DS owning the group of a handshake message sent by
+/// a local client.
+/// This DS owns the group if the message is from epoch 0, where the sender
+/// created the group and is its only member. The owner of other groups this DS
+/// doesn't know yet is looked up at the peers.
+/// Returns `None` if the owner is unknown.
+async fn group_owner(
+    data: &DsData,
+    own_domain: &str,
+    protocol_msg: &ProtocolMessage,
+) -> Result<Option<String>, actix_web::HttpResponse> {
+    let group_id = protocol_msg.group_id().as_slice();
+    let recorded_owner = {
+        let mut group_owners = data
+            .group_owners
+            .lock()
+            .map_err(|_| actix_web::HttpResponse::InternalServerError().finish())?;
+        match group_owners.get(group_id) {
+            Some(owner_domain) => Some(owner_domain.clone()),
+            None if protocol_msg.epoch().as_u64() == 0 => {
+                group_owners.insert(group_id.to_vec(), own_domain.to_owned());
+                Some(own_domain.to_owned())
+            }
+            None => None,
+        }
+    };
+    if recorded_owner.is_some() {
+        return Ok(recorded_owner);
+    }
+
+    let Some(owner_domain) = data
+        .federation
+        .lookup_group_owner(group_id)
+        .await
+        .map_err(|e| e.into_response())?
+    else {
+        return Ok(None);
+    };
+    let mut group_owners = data
+        .group_owners
+        .lock()
+        .map_err(|_| actix_web::HttpResponse::InternalServerError().finish())?;
+    Ok(Some(
+        group_owners
+            .entry(group_id.to_vec())
+            .or_insert(owner_domain)
+            .clone(),
+    ))
 }
 
 /// Send an MLS message to a set of clients (group).
@@ -308,6 +521,10 @@ async fn send_welcome(mut body: Payload, data: web::Data<DsData>) -> impl Respon
 /// If a handshake message is sent with an epoch smaller or equal to another
 /// handshake message this DS has seen, a 409 is returned and the message is not
 /// processed.
+///
+/// When federated, handshake messages for groups owned by another DS are
+/// forwarded to the owner for sequencing. The owner distributes them.
+/// Messages for recipients on another domain are forwarded to their DS.
 #[post("/send/message")]
 async fn msg_send(mut body: Payload, data: web::Data<DsData>) -> impl Responder {
     let mut bytes = web::BytesMut::new();
@@ -317,47 +534,244 @@ async fn msg_send(mut body: Payload, data: web::Data<DsData>) -> impl Responder
     let group_msg = unwrap_data!(GroupMessage::tls_deserialize(&mut &bytes[..]));
     log::debug!("Storing group message: {:?}", group_msg);
 
-    let mut clients = unwrap_data!(data.clients.lock());
-    let mut groups = unwrap_data!(data.groups.lock());
+    let protocol_msg: ProtocolMessage = unwrap_data!(group_msg.msg.clone().try_into());
+    if let Some(own_domain) = data.federation.domain() {
+        if protocol_msg.is_handshake_message() {
+            let owner_domain = match group_owner(&data, own_domain, &protocol_msg).await {
+                Ok(Some(owner_domain)) => owner_domain,
+                Ok(None) => return actix_web::HttpResponse::NotFound().finish(),
+                Err(response) => return response,
+            };
+            if owner_domain != own_domain {
+                log::debug!("Forwarding handshake message to {}", owner_domain);
+                return match data
+                    .federation
+                    .forward_to_owner(&owner_domain, &group_msg)
+                    .await
+                {
+                    Ok(()) => actix_web::HttpResponse::Ok().finish(),
+                    Err(e) => e.into_response(),
+                };
+            }
+        }
+    }
+
+    distribute_group_message(&data, group_msg, protocol_msg).await
+}
+
+/// Receive a handshake message forwarded by another DS for a group owned by
+/// this DS.
+/// This takes a serialised `GroupMessage` and sequences and distributes it like
+/// `/send/message`. Only authenticated peers may use this endpoint.
+/// A 409 is returned if this DS doesn't own the group.
+#[post("/federation/handshake")]
+async fn federation_handshake(
+    req: HttpRequest,
+    mut body: Payload,
+    data: web::Data<DsData>,
+) -> impl Responder {
+    let Some(peer) = data.federation.authenticate(&req) else {
+        return actix_web::HttpResponse::Unauthorized().finish();
+    };
+    let mut bytes = web::BytesMut::new();
+    while let Some(item) = body.next().await {
+        bytes.extend_from_slice(&unwrap_item!(item));
+    }
+    let group_msg = unwrap_data!(GroupMessage::tls_deserialize(&mut &bytes[..]));
+    log::debug!("Sequencing group message from {}: {:?}", peer, group_msg);
 
-    let protocol_msg: ProtocolMessage = group_msg.msg.clone().try_into().unwrap();
+    let protocol_msg: ProtocolMessage = unwrap_data!(group_msg.msg.clone().try_into());
+    if !protocol_msg.is_handshake_message() {
+        return actix_web::HttpResponse::BadRequest().finish();
+    }
+    let is_owner = {
+        let group_owners = unwrap_data!(data.group_owners.lock());
+        group_owners
+            .get(protocol_msg.group_id().as_slice())
+            .is_some_and(|owner_domain| Some(owner_domain.as_str()) == data.federation.domain())
+    };
+    if !is_owner {
+        return actix_web::HttpResponse::Conflict().finish();
+    }
 
+    distribute_group_message(&data, group_msg, protocol_msg).await
+}
+
+/// Tell another DS the domain of the DS owning the group `{group_id}`.
+/// This returns the domain as UTF-8 bytes, or a 404 if the owner is unknown.
+/// Only authenticated peers may use this endpoint.
+#[get("/federation/owner/{group_id}")]
+async fn federation_owner(
+    req: HttpRequest,
+    path: web::Path<String>,
+    data: web::Data<DsData>,
+) -> impl Responder {
+    if data.federation.authenticate(&req).is_none() {
+        return actix_web::HttpResponse::Unauthorized().finish();
+    }
+    let group_id = match base64::engine::general_purpose::URL_SAFE.decode(path.into_inner()) {
+        Ok(group_id) => group_id,
+        Err(_) => return actix_web::HttpResponse::BadRequest().finish(),
+    };
+    let group_owners = unwrap_data!(data.group_owners.lock());
+    match group_owners.get(&group_id) {
+        Some(owner_domain) => actix_web::HttpResponse::Ok().body(owner_domain.clone()),
+        None => actix_web::HttpResponse::NotFound().finish(),
+    }
+}
+
+/// Sequence a group message if it is a handshake message and distribute it to
+/// all recipients. Remote recipients get the message through their DS, along
+/// with the domain of the group's owner. The message is stored for local
+/// recipients only once it was forwarded, so that a retry after a failed
+/// forward doesn't deliver it twice.
+async fn distribute_group_message(
+    data: &DsData,
+    group_msg: GroupMessage,
+    protocol_msg: ProtocolMessage,
+) -> actix_web::HttpResponse {
     // Reject any handshake message that has an earlier epoch than the one we know
     // about.
     // XXX: There's no test for this block in here right now because it's pretty
     //      painful to test in the current setting. This should get tested through
     //      the client and maybe later with the MlsGroup API.
-    if protocol_msg.is_handshake_message() {
-        let epoch = protocol_msg.epoch().as_u64();
+    if protocol_msg.is_handshake_message()
+        && !unwrap_data!(sequence_handshake_message(data, &protocol_msg))
+    {
+        return actix_web::HttpResponse::Conflict().finish();
+    }
+
+    let (local_recipients, remote_recipients) =
+        match unwrap_data!(split_recipients(data, group_msg.recipients.as_slice())) {
+            Some(recipients) => recipients,
+            None => return actix_web::HttpResponse::NotFound().finish(),
+        };
+
+    let owner_domain = {
+        let group_owners = unwrap_data!(data.group_owners.lock());
+        match group_owners.get(protocol_msg.group_id().as_slice()) {
+            Some(owner_domain) => owner_domain.clone(),
+            None => String::new(),
+        }
+    };
+    for (domain, recipients) in remote_recipients {
+        let forwarded_msg = GroupMessage::new(group_msg.msg.clone(), &recipients);
+        if let Err(e) = data
+            .federation
+            .forward_message(&domain, &owner_domain, forwarded_msg)
+            .await
+        {
+            return e.into_response();
+        }
+    }
+
+    unwrap_data!(queue_message(data, &group_msg.msg, &local_recipients));
+    actix_web::HttpResponse::Ok().finish()
+}
+
+/// Receive a group message forwarded by another DS.
+/// This takes a serialised `FederatedGroupMessage` (see the `ds-lib` for
+/// details) and stores the message for each client in the recipient list.
+/// All recipients have to be local to this DS. Only authenticated peers may
+/// use this endpoint.
+///
+/// Handshake messages are distributed by the owner of the group, so their
+/// `owner_domain` has to be the domain of the sending peer. A 409 is returned
+/// if the `owner_domain` disagrees with the owner this DS knows.
+#[post("/federation/message")]
+async fn federation_msg(
+    req: HttpRequest,
+    mut body: Payload,
+    data: web::Data<DsData>,
+) -> impl Responder {
+    let Some(peer) = data.federation.authenticate(&req) else {
+        return actix_web::HttpResponse::Unauthorized().finish();
+    };
+    let mut bytes = web::BytesMut::new();
+    while let Some(item) = body.next().await {
+        bytes.extend_from_slice(&unwrap_item!(item));
+    }
+    let msg = match FederatedGroupMessage::tls_deserialize(&mut &bytes[..]) {
+        Ok(msg) => msg,
+        Err(_) => {
+            log::error!("Invalid payload for /federation/message\n{:?}", bytes);
+            return actix_web::HttpResponse::BadRequest().finish();
+        }
+    };
+    let owner_domain = match String::from_utf8(msg.owner_domain) {
+        Ok(domain) => domain,
+        Err(_) => return actix_web::HttpResponse::BadRequest().finish(),
+    };
+    let group_msg = msg.group_message;
+    log::debug!("Storing federated group message: {:?}", group_msg);
+
+    let protocol_msg: ProtocolMessage = unwrap_data!(group_msg.msg.clone().try_into());
+    if protocol_msg.is_handshake_message() && owner_domain != peer {
+        return actix_web::HttpResponse::BadRequest().finish();
+    }
+    if !owner_domain.is_empty() {
+        let mut group_owners = unwrap_data!(data.group_owners.lock());
         let group_id = protocol_msg.group_id().as_slice();
-        if let Some(&group_epoch) = groups.get(group_id) {
-            if group_epoch > epoch {
+        match group_owners.get(group_id) {
+            Some(recorded_owner) if *recorded_owner != owner_domain => {
+                log::error!(
+                    "Owner {} of forwarded message disagrees with owner {}",
+                    owner_domain,
+                    recorded_owner
+                );
                 return actix_web::HttpResponse::Conflict().finish();
             }
-            // Update server state to the latest epoch.
-            let old_value = groups.insert(group_id.to_vec(), epoch);
-            if old_value.is_none() {
-                return actix_web::HttpResponse::InternalServerError().finish();
-            }
-        } else {
-            // We haven't seen this group_id yet. Store it.
-            let old_value = groups.insert(group_id.to_vec(), epoch);
-            if old_value.is_some() {
-                return actix_web::HttpResponse::InternalServerError().finish();
+            Some(_) => (),
+            None if protocol_msg.is_handshake_message() => {
+                group_owners.insert(group_id.to_vec(), owner_domain);
             }
+            None => (),
         }
     }
 
-    for recipient in group_msg.recipients.iter() {
-        let client = match clients.get_mut(recipient.as_slice()) {
-            Some(client) => client,
+    let local_recipients =
+        match unwrap_data!(split_recipients(&data, group_msg.recipients.as_slice())) {
+            // Forwarded messages are never forwarded again.
+            Some((local_recipients, remote_recipients)) if remote_recipients.is_empty() => {
+                local_recipients
+            }
+            Some(_) => return actix_web::HttpResponse::BadRequest().finish(),
             None => return actix_web::HttpResponse::NotFound().finish(),
         };
-        client.msgs.push(group_msg.msg.clone());
-    }
+    unwrap_data!(queue_message(&data, &group_msg.msg, &local_recipients));
     actix_web::HttpResponse::Ok().finish()
 }
 
+/// Receive a Welcome message forwarded by another DS.
+/// This takes a serialised `Welcome` message and stores the message for all
+/// local clients in the welcome message. Only authenticated peers may use this
+/// endpoint.
+#[post("/federation/welcome")]
+async fn federation_welcome(
+    req: HttpRequest,
+    mut body: Payload,
+    data: web::Data<DsData>,
+) -> impl Responder {
+    if data.federation.authenticate(&req).is_none() {
+        return actix_web::HttpResponse::Unauthorized().finish();
+    }
+    let mut bytes = web::BytesMut::new();
+    while let Some(item) = body.next().await {
+        bytes.extend_from_slice(&unwrap_item!(item));
+    }
+    let welcome_msg = unwrap_data!(MlsMessageIn::tls_deserialize(&mut &bytes[..]));
+    let welcome = match welcome_msg.clone().into_welcome() {
+        Some(welcome) => welcome,
+        None => return actix_web::HttpResponse::BadRequest().finish(),
+    };
+    log::debug!("Storing federated welcome message: {:?}", welcome_msg);
+
+    match unwrap_data!(queue_welcome(&data, &welcome_msg, &welcome)) {
+        (true, _) => actix_web::HttpResponse::Ok().finish(),
+        (false, _) => actix_web::HttpResponse::NoContent().finish(),
+    }
+}
+
 /// Receive all messages stored for the client `{id}`.
 /// This returns a serialised vector of `Message`s (see the `ds-lib` for
 /// details) the DS has stored for the given client.
@@ -417,6 +831,23 @@ async fn msg_recv(
     }
 }
 
+/// Register all DS endpoints.
+fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(register_client)
+        .service(list_clients)
+        .service(publish_key_packages)
+        .service(get_key_packages)
+        .service(consume_key_package)
+        .service(send_welcome)
+        .service(msg_recv)
+        .service(msg_send)
+        .service(federation_msg)
+        .service(federation_handshake)
+        .service(federation_owner)
+        .service(federation_welcome)
+        .service(reset);
+}
+
 // === Main function driving the DS ===
 
 #[actix_web::main]
@@ -433,12 +864,69 @@ async fn main() -> std::io::Result<()> {
                 .short('p')
                 .long("port")
                 .value_name("port")
+                .value_parser(clap::value_parser!(u16))
                 .help("Sets a custom port number"),
         )
+        .arg(
+            clap::Arg::new("domain")
+                .short('d')
+                .long("domain")
+                .value_name("domain")
+                .help("Enables federation with this DS serving the given domain"),
+        )
+        .arg(
+            clap::Arg::new("peer")
+                .long("peer")
+                .value_name("domain=url")
+                .action(clap::ArgAction::Append)
+                .requires("domain")
+                .help("Adds the DS of another domain, e.g. b.example=http://127.0.0.1:8081"),
+        )
+        .arg(
+            clap::Arg::new("peer-token")
+                .long("peer-token")
+                .value_name("domain=token")
+                .action(clap::ArgAction::Append)
+                .requires("peer")
+                .help("Sets the secret token shared with the DS of another domain"),
+        )
         .get_matches();
 
     // The data this app operates on.
-    let data = web::Data::new(DsData::default());
+    let data = match matches.get_one::<String>("domain") {
+        Some(domain) => {
+            let invalid_input =
+                |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+            let mut tokens = HashMap::new();
+            for token in matches.get_many::<String>("peer-token").unwrap_or_default() {
+                let Some((peer_domain, token)) = token.split_once('=') else {
+                    return Err(invalid_input(format!(
+                        "invalid peer token {token}, expected domain=token"
+                    )));
+                };
+                tokens.insert(peer_domain.to_owned(), token.to_owned());
+            }
+            let mut peers = HashMap::new();
+            for peer in matches.get_many::<String>("peer").unwrap_or_default() {
+                let Some((peer_domain, url)) = peer.split_once('=') else {
+                    return Err(invalid_input(format!(
+                        "invalid peer {peer}, expected domain=url"
+                    )));
+                };
+                let Some(token) = tokens.remove(peer_domain) else {
+                    return Err(invalid_input(format!(
+                        "missing --peer-token for peer {peer_domain}"
+                    )));
+                };
+                let url = url.to_owned();
+                peers.insert(peer_domain.to_owned(), Peer { url, token });
+            }
+            log::info!("Serving domain {} with peers {:?}", domain, peers);
+            DsData::federated(Federation::new(domain.clone(), peers))
+        }
+        None => DsData::default(),
+    };
+    let data = web::Data::new(data);
 
     // Set default port or use port provided on the command line.
     let port = matches.get_one("port").unwrap_or(&8080u16);
@@ -448,20 +936,8 @@ async fn main() -> std::io::Result<()> {
     log::info!("Listening on: {}", addr);
 
     // Start the server.
-    HttpServer::new(move || {
-        App::new()
-            .app_data(data.clone())
-            .service(register_client)
-            .service(list_clients)
-            .service(publish_key_packages)
-            .service(get_key_packages)
-            .service(consume_key_package)
-            .service(send_welcome)
-            .service(msg_recv)
-            .service(msg_send)
-            .service(reset)
-    })
-    .bind(addr)?
-    .run()
-    .await
+    HttpServer::new(move || App::new().app_data(data.clone()).configure(configure))
+        .bind(addr)?
+        .run()
+        .await
 }
@@ -1,5 +1,6 @@
 use super::*;
 use actix_web::{body::MessageBody, http::StatusCode, test, web, web::Bytes, App};
+use ds_lib::messages::AuthToken;
 use openmls_basic_credential::SignatureKeyPair;
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use openmls_traits::types::SignatureScheme;
@@ -439,3 +440,512 @@ async fn test_group() {
         panic!("Expected application message");
     }
 }
+
+/// The token shared between the federated DSs in the tests.
+const PEER_TOKEN: &str = "federation test token";
+
+/// Start a federated DS for `domain` on `listener` in the background.
+fn start_federated_ds(listener: std::net::TcpListener, domain: &str, peers: &[(&str, String)]) {
+    let peers = peers
+        .iter()
+        .map(|(domain, url)| {
+            let peer = Peer {
+                url: url.clone(),
+                token: PEER_TOKEN.to_owned(),
+            };
+            (domain.to_string(), peer)
+        })
+        .collect();
+    let data = web::Data::new(DsData::federated(Federation::new(domain.to_owned(), peers)));
+    let server = HttpServer::new(move || App::new().app_data(data.clone()).configure(configure))
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+    actix_rt::spawn(server);
+}
+
+/// Register a new client with two key packages at the DS at `url`.
+async fn register_federated_client(
+    client: &reqwest::Client,
+    url: &str,
+    identity: &str,
+    crypto: &OpenMlsRustCrypto,
+) -> (CredentialWithKey, SignatureKeyPair, AuthToken) {
+    let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    let (credential_with_key, signer) =
+        generate_credential(identity.into(), SignatureScheme::from(ciphersuite));
+    let key_packages = (0..2)
+        .map(|_| {
+            let key_package = generate_key_package(
+                ciphersuite,
+                credential_with_key.clone(),
+                Extensions::empty(),
+                crypto,
+                &signer,
+            );
+            (
+                key_package
+                    .key_package()
+                    .hash_ref(crypto.crypto())
+                    .unwrap()
+                    .as_slice()
+                    .to_vec()
+                    .into(),
+                KeyPackageIn::from(key_package),
+            )
+        })
+        .collect::<Vec<(TlsByteVecU8, KeyPackageIn)>>();
+    let body = RegisterClientRequest {
+        key_packages: ClientKeyPackages(key_packages.into()),
+    };
+    let response = client
+        .post(format!("{url}/clients/register"))
+        .body(body.tls_serialize_detached().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response =
+        RegisterClientSuccessResponse::tls_deserialize_exact(response.bytes().await.unwrap())
+            .unwrap();
+
+    (credential_with_key, signer, response.auth_token)
+}
+
+/// Send a group message to the DS at `url` and return the response status.
+async fn send_federated_message(
+    client: &reqwest::Client,
+    url: &str,
+    msg: MlsMessageOut,
+    recipients: &[&str],
+) -> reqwest::StatusCode {
+    let recipients = recipients
+        .iter()
+        .map(|r| r.as_bytes().to_vec())
+        .collect::<Vec<_>>();
+    let msg = GroupMessage::new(msg.into(), &recipients);
+    client
+        .post(format!("{url}/send/message"))
+        .body(msg.tls_serialize_detached().unwrap())
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+/// Receive all messages for `identity` from the DS at `url`.
+async fn recv_federated_messages(
+    client: &reqwest::Client,
+    url: &str,
+    identity: &str,
+    auth_token: &AuthToken,
+) -> Vec<MlsMessageIn> {
+    let body = RecvMessageRequest {
+        auth_token: auth_token.clone(),
+    };
+    let response = client
+        .get(format!(
+            "{url}/recv/{}",
+            base64::engine::general_purpose::URL_SAFE.encode(identity)
+        ))
+        .body(body.tls_serialize_detached().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    TlsVecU16::<MlsMessageIn>::tls_deserialize_exact(response.bytes().await.unwrap())
+        .expect("Invalid message list")
+        .into_vec()
+}
+
+#[actix_rt::test]
+async fn test_federation() {
+    let crypto = &OpenMlsRustCrypto::default();
+    let mls_group_create_config = MlsGroupCreateConfig::default();
+    let client = reqwest::Client::new();
+
+    // Run a DS for a.example and one for b.example.
+    let listener_a = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let listener_b = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url_a = format!("http://{}", listener_a.local_addr().unwrap());
+    let url_b = format!("http://{}", listener_b.local_addr().unwrap());
+    start_federated_ds(listener_a, "a.example", &[("b.example", url_b.clone())]);
+    start_federated_ds(listener_b, "b.example", &[("a.example", url_a.clone())]);
+
+    let alice = "alice@a.example";
+    let bob = "bob@b.example";
+    let (alice_credential, alice_signer, alice_token) =
+        register_federated_client(&client, &url_a, alice, crypto).await;
+    let (_bob_credential, bob_signer, bob_token) =
+        register_federated_client(&client, &url_b, bob, crypto).await;
+
+    // Alice fetches Bob's key package through her own DS.
+    let response = client
+        .get(format!(
+            "{url_a}/clients/key_package/{}",
+            base64::engine::general_purpose::URL_SAFE.encode(bob)
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let bob_key_package =
+        KeyPackageIn::tls_deserialize_exact(response.bytes().await.unwrap()).unwrap();
+
+    // Alice creates a group on a.example and adds Bob.
+    let mut alice_group = MlsGroup::new(
+        crypto,
+        &alice_signer,
+        &mls_group_create_config,
+        alice_credential,
+    )
+    .unwrap();
+    let (commit, welcome, _group_info) = alice_group
+        .add_members(
+            crypto,
+            &alice_signer,
+            &[bob_key_package
+                .validate(crypto.crypto(), ProtocolVersion::Mls10)
+                .unwrap()],
+        )
+        .unwrap();
+    alice_group.merge_pending_commit(crypto).unwrap();
+
+    // The first handshake message of the group, in epoch 0, makes a.example the
+    // owner of the group.
+    assert_eq!(
+        send_federated_message(&client, &url_a, commit, &[]).await,
+        reqwest::StatusCode::OK
+    );
+
+    // The Welcome is forwarded to b.example, which holds the reservation.
+    let response = client
+        .post(format!("{url_a}/send/welcome"))
+        .body(welcome.tls_serialize_detached().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let mut messages = recv_federated_messages(&client, &url_b, bob, &bob_token).await;
+    assert_eq!(messages.len(), 1);
+    let welcome = messages
+        .remove(0)
+        .into_welcome()
+        .expect("Expected a Welcome");
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        crypto,
+        mls_group_create_config.join_config(),
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .unwrap()
+    .into_group(crypto)
+    .unwrap();
+
+    // Bob's application message is forwarded from b.example to a.example.
+    let bob_message = b"Hello from b.example";
+    let msg = bob_group
+        .create_message(crypto, &bob_signer, bob_message)
+        .unwrap();
+    assert_eq!(
+        send_federated_message(&client, &url_b, msg, &[alice]).await,
+        reqwest::StatusCode::OK
+    );
+
+    let mut messages = recv_federated_messages(&client, &url_a, alice, &alice_token).await;
+    assert_eq!(messages.len(), 1);
+    let protocol_message: ProtocolMessage = messages.remove(0).try_into_protocol_message().unwrap();
+    let processed_message = alice_group
+        .process_message(crypto, protocol_message)
+        .unwrap();
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(bob_message, &application_message.into_bytes()[..])
+        }
+        _ => panic!("Expected application message"),
+    }
+
+    // Alice commits twice. a.example sequences the commits and forwards them to
+    // b.example.
+    for _ in 0..2 {
+        let bundle = alice_group
+            .self_update(crypto, &alice_signer, LeafNodeParameters::default())
+            .unwrap();
+        alice_group.merge_pending_commit(crypto).unwrap();
+        assert_eq!(
+            send_federated_message(&client, &url_a, bundle.commit().clone(), &[bob]).await,
+            reqwest::StatusCode::OK
+        );
+    }
+    let messages = recv_federated_messages(&client, &url_b, bob, &bob_token).await;
+    assert_eq!(messages.len(), 2);
+
+    // Bob's commit for an old epoch is sent to b.example, forwarded to the owner
+    // a.example and rejected there.
+    let bundle = bob_group
+        .self_update(crypto, &bob_signer, LeafNodeParameters::default())
+        .unwrap();
+    assert_eq!(
+        send_federated_message(&client, &url_b, bundle.commit().clone(), &[alice]).await,
+        reqwest::StatusCode::CONFLICT
+    );
+    assert!(
+        recv_federated_messages(&client, &url_a, alice, &alice_token)
+            .await
+            .is_empty()
+    );
+}
+
+#[actix_rt::test]
+async fn test_federation_group_ownership() {
+    let crypto = &OpenMlsRustCrypto::default();
+    let mls_group_create_config = MlsGroupCreateConfig::default();
+    let client = reqwest::Client::new();
+
+    let listener_a = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let listener_b = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url_a = format!("http://{}", listener_a.local_addr().unwrap());
+    let url_b = format!("http://{}", listener_b.local_addr().unwrap());
+    start_federated_ds(listener_a, "a.example", &[("b.example", url_b.clone())]);
+    start_federated_ds(listener_b, "b.example", &[("a.example", url_a.clone())]);
+
+    let alice = "alice@a.example";
+    let bob = "bob@b.example";
+    let (alice_credential, alice_signer, alice_token) =
+        register_federated_client(&client, &url_a, alice, crypto).await;
+    let (bob_credential, bob_signer, bob_token) =
+        register_federated_client(&client, &url_b, bob, crypto).await;
+    let bob_key_package = generate_key_package(
+        Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+        bob_credential,
+        Extensions::empty(),
+        crypto,
+        &bob_signer,
+    );
+
+    // Alice creates the group. b.example never sees the commit adding Bob.
+    let mut alice_group = MlsGroup::new(
+        crypto,
+        &alice_signer,
+        &mls_group_create_config,
+        alice_credential,
+    )
+    .unwrap();
+    let (commit, welcome, _group_info) = alice_group
+        .add_members(
+            crypto,
+            &alice_signer,
+            &[bob_key_package.key_package().clone()],
+        )
+        .unwrap();
+    alice_group.merge_pending_commit(crypto).unwrap();
+    assert_eq!(
+        send_federated_message(&client, &url_a, commit, &[]).await,
+        reqwest::StatusCode::OK
+    );
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        crypto,
+        mls_group_create_config.join_config(),
+        MlsMessageIn::from(welcome).into_welcome().unwrap(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .unwrap()
+    .into_group(crypto)
+    .unwrap();
+
+    // b.example looks up the owner of the group at a.example and forwards
+    // Bob's commit there.
+    let bundle = bob_group
+        .self_update(crypto, &bob_signer, LeafNodeParameters::default())
+        .unwrap();
+    assert_eq!(
+        send_federated_message(&client, &url_b, bundle.commit().clone(), &[alice]).await,
+        reqwest::StatusCode::OK
+    );
+    assert_eq!(
+        recv_federated_messages(&client, &url_a, alice, &alice_token)
+            .await
+            .len(),
+        1
+    );
+
+    // b.example doesn't own the group and rejects handshake messages for it.
+    let bundle = alice_group
+        .self_update(crypto, &alice_signer, LeafNodeParameters::default())
+        .unwrap();
+    let group_msg = GroupMessage::new(
+        MlsMessageIn::from(bundle.commit().clone()),
+        &[bob.as_bytes().to_vec()],
+    );
+    let response = client
+        .post(format!("{url_b}/federation/handshake"))
+        .header(federation::FEDERATION_DOMAIN_HEADER, "a.example")
+        .bearer_auth(PEER_TOKEN)
+        .body(group_msg.tls_serialize_detached().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+
+    // Forwarded handshake messages must come from the owner, and other
+    // messages must name the owner b.example knows.
+    for owner_domain in ["b.example", "c.example"] {
+        let federated_msg = FederatedGroupMessage {
+            owner_domain: owner_domain.as_bytes().to_vec(),
+            group_message: GroupMessage::new(
+                MlsMessageIn::from(bundle.commit().clone()),
+                &[bob.as_bytes().to_vec()],
+            ),
+        };
+        let response = client
+            .post(format!("{url_b}/federation/message"))
+            .header(federation::FEDERATION_DOMAIN_HEADER, "a.example")
+            .bearer_auth(PEER_TOKEN)
+            .body(federated_msg.tls_serialize_detached().unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+    let msg = alice_group
+        .create_message(crypto, &alice_signer, b"wrong owner")
+        .unwrap();
+    let federated_msg = FederatedGroupMessage {
+        owner_domain: b"c.example".to_vec(),
+        group_message: GroupMessage::new(msg.into(), &[bob.as_bytes().to_vec()]),
+    };
+    let response = client
+        .post(format!("{url_b}/federation/message"))
+        .header(federation::FEDERATION_DOMAIN_HEADER, "a.example")
+        .bearer_auth(PEER_TOKEN)
+        .body(federated_msg.tls_serialize_detached().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+    assert!(recv_federated_messages(&client, &url_b, bob, &bob_token)
+        .await
+        .is_empty());
+}
+
+#[actix_rt::test]
+async fn test_federation_queues_after_forwarding() {
+    let crypto = &OpenMlsRustCrypto::default();
+    let client = reqwest::Client::new();
+
+    // The peer for b.example is unreachable.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    start_federated_ds(
+        listener,
+        "a.example",
+        &[("b.example", "http://127.0.0.1:1".to_owned())],
+    );
+
+    let alice = "alice@a.example";
+    let (alice_credential, alice_signer, alice_token) =
+        register_federated_client(&client, &url, alice, crypto).await;
+    let mut alice_group = MlsGroup::new(
+        crypto,
+        &alice_signer,
+        &MlsGroupCreateConfig::default(),
+        alice_credential,
+    )
+    .unwrap();
+    let msg = alice_group
+        .create_message(crypto, &alice_signer, b"hello")
+        .unwrap();
+
+    // Forwarding to b.example fails, so the message isn't stored for Alice
+    // either and the client can retry.
+    assert_eq!(
+        send_federated_message(&client, &url, msg, &[alice, "bob@b.example"]).await,
+        reqwest::StatusCode::BAD_GATEWAY
+    );
+    assert!(recv_federated_messages(&client, &url, alice, &alice_token)
+        .await
+        .is_empty());
+}
+
+#[actix_rt::test]
+async fn test_federation_requires_peer_authentication() {
+    let crypto = &OpenMlsRustCrypto::default();
+    let client = reqwest::Client::new();
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    start_federated_ds(
+        listener,
+        "a.example",
+        &[("b.example", "http://127.0.0.1:1".to_owned())],
+    );
+
+    let alice = "alice@a.example";
+    let (alice_credential, alice_signer, alice_token) =
+        register_federated_client(&client, &url, alice, crypto).await;
+    let mut alice_group = MlsGroup::new(
+        crypto,
+        &alice_signer,
+        &MlsGroupCreateConfig::default(),
+        alice_credential,
+    )
+    .unwrap();
+    let msg = alice_group
+        .create_message(crypto, &alice_signer, b"injected")
+        .unwrap();
+    let federated_msg = FederatedGroupMessage {
+        owner_domain: b"b.example".to_vec(),
+        group_message: GroupMessage::new(msg.into(), &[alice.as_bytes().to_vec()]),
+    }
+    .tls_serialize_detached()
+    .unwrap();
+
+    // Requests without credentials, with an unknown domain, or with the wrong
+    // token are rejected.
+    let requests = [
+        client.post(format!("{url}/federation/message")),
+        client
+            .post(format!("{url}/federation/message"))
+            .header(federation::FEDERATION_DOMAIN_HEADER, "c.example")
+            .bearer_auth(PEER_TOKEN),
+        client
+            .post(format!("{url}/federation/message"))
+            .header(federation::FEDERATION_DOMAIN_HEADER, "b.example")
+            .bearer_auth("wrong token"),
+    ];
+    for request in requests {
+        let response = request.body(federated_msg.clone()).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+    for path in ["/federation/handshake", "/federation/welcome"] {
+        let response = client
+            .post(format!("{url}{path}"))
+            .body(Vec::new())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+    assert!(recv_federated_messages(&client, &url, alice, &alice_token)
+        .await
+        .is_empty());
+
+    // The peer with its token is accepted.
+    let response = client
+        .post(format!("{url}/federation/message"))
+        .header(federation::FEDERATION_DOMAIN_HEADER, "b.example")
+        .bearer_auth(PEER_TOKEN)
+        .body(federated_msg)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        recv_federated_messages(&client, &url, alice, &alice_token)
+            .await
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,82 @@
+#![no_main]
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use openmls::{
+    prelude::{tls_codec::*, *},
+    test_utils::frankenstein::*,
+};
+use openmls_fuzz::*;
+
+/// A change to Alice's group info before it is signed again.
+#[derive(Debug, Arbitrary)]
+enum Mutation {
+    GroupContext(FrankenGroupContext),
+    Extensions(Vec<FrankenExtension>),
+    ConfirmationTag(VLBytes),
+    Signer(u32),
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    mutations: Vec<Mutation>,
+    ratchet_tree: Option<FrankenRatchetTreeExtension>,
+}
+
+fuzz_target!(|input: Input| {
+    let group = TwoMemberGroup::new();
+
+    let group_info = group
+        .alice_group
+        .export_group_info(
+            group.alice.provider.crypto(),
+            &group.alice.signer,
+            input.ratchet_tree.is_none(),
+        )
+        .unwrap();
+    let mut message = FrankenMlsMessage::from(group_info);
+    let FrankenMlsMessageBody::GroupInfo(ref mut group_info) = message.body else {
+        unreachable!("expected a group info");
+    };
+
+    for mutation in input.mutations {
+        match mutation {
+            Mutation::GroupContext(group_context) => group_info.group_context = group_context,
+            Mutation::Extensions(extensions) => group_info.extensions = extensions,
+            Mutation::ConfirmationTag(confirmation_tag) => {
+                group_info.confirmation_tag = confirmation_tag
+            }
+            Mutation::Signer(signer) => group_info.signer = signer,
+        }
+    }
+    // Sign again, so that the joiner gets past the signature check.
+    group_info.resign(&group.alice.signer);
+
+    let Ok(message) =
+        MlsMessageIn::tls_deserialize_exact(message.tls_serialize_detached().unwrap())
+    else {
+        return;
+    };
+    let MlsMessageBodyIn::GroupInfo(verifiable_group_info) = message.extract() else {
+        unreachable!("expected a group info");
+    };
+    let ratchet_tree = match &input.ratchet_tree {
+        Some(ratchet_tree) => match ratchet_tree_in(ratchet_tree) {
+            Some(ratchet_tree) => Some(ratchet_tree),
+            None => return,
+        },
+        None => None,
+    };
+
+    let charlie = Client::new(b"Charlie");
+    assert_no_library_error(MlsGroup::join_by_external_commit(
+        &charlie.provider,
+        &charlie.signer,
+        ratchet_tree,
+        verifiable_group_info,
+        &Client::join_config(),
+        None,
+        None,
+        &[],
+        charlie.credential_with_key.clone(),
+    ));
+});
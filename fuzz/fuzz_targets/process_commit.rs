@@ -0,0 +1,28 @@
+#![no_main]
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use openmls::{prelude::tls_codec::VLBytes, test_utils::frankenstein::*};
+use openmls_fuzz::*;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    commit: FrankenCommit,
+    confirmation_tag: VLBytes,
+}
+
+fuzz_target!(|input: Input| {
+    let mut group = TwoMemberGroup::new();
+
+    // The commit is authenticated as coming from Alice, so that Bob gets
+    // past the framing checks and stages the commit.
+    let message = group.message_from_alice(
+        FrankenFramedContentBody::Commit(input.commit),
+        Some(input.confirmation_tag),
+    );
+
+    assert_no_library_error(
+        group
+            .bob_group
+            .process_message(&group.bob.provider, message),
+    );
+});
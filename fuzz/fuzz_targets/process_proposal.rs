@@ -0,0 +1,16 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use openmls::test_utils::frankenstein::*;
+use openmls_fuzz::*;
+
+fuzz_target!(|proposal: FrankenProposal| {
+    let mut group = TwoMemberGroup::new();
+
+    let message = group.message_from_alice(FrankenFramedContentBody::Proposal(proposal), None);
+
+    assert_no_library_error(
+        group
+            .bob_group
+            .process_message(&group.bob.provider, message),
+    );
+});
@@ -0,0 +1,30 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use openmls::{prelude::*, test_utils::frankenstein::FrankenRatchetTreeExtension};
+use openmls_fuzz::*;
+
+fuzz_target!(|ratchet_tree: FrankenRatchetTreeExtension| {
+    let Some(ratchet_tree) = ratchet_tree_in(&ratchet_tree) else {
+        return;
+    };
+
+    // Without the ratchet tree extension, Bob has to use the tree from the
+    // fuzzer to join.
+    let alice = Client::new(b"Alice");
+    let bob = Client::new(b"Bob");
+    let mut alice_group = alice.create_group(false);
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(&alice.provider, &alice.signer, &[bob.key_package()])
+        .unwrap();
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+
+    match StagedWelcome::new_from_welcome(
+        &bob.provider,
+        &Client::join_config(),
+        welcome,
+        Some(ratchet_tree),
+    ) {
+        Ok(staged_welcome) => assert_no_library_error(staged_welcome.into_group(&bob.provider)),
+        error => assert_no_library_error(error),
+    }
+});
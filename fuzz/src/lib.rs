@@ -0,0 +1,165 @@
+//! Shared setup for the stateful fuzz targets.
+//!
+//! The targets build real groups and feed them manipulated messages, built
+//! from the `frankenstein` test types. Processing may fail, but it must never
+//! panic or fail with a `LibraryError`, which would indicate a bug in OpenMLS.
+
+use std::fmt::Debug;
+
+use openmls::{
+    prelude::{tls_codec::*, *},
+    test_utils::frankenstein::*,
+};
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+/// The ciphersuite used by all fuzz targets.
+pub const CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+
+/// A client with its own provider and signature keys.
+pub struct Client {
+    pub provider: OpenMlsRustCrypto,
+    pub signer: SignatureKeyPair,
+    pub credential_with_key: CredentialWithKey,
+}
+
+impl Client {
+    /// Creates a client with a basic credential for `identity`.
+    pub fn new(identity: &[u8]) -> Self {
+        let provider = OpenMlsRustCrypto::default();
+        let signer = SignatureKeyPair::new(CIPHERSUITE.signature_algorithm()).unwrap();
+        signer.store(provider.storage()).unwrap();
+        let credential_with_key = CredentialWithKey {
+            credential: BasicCredential::new(identity.to_vec()).into(),
+            signature_key: signer.public().into(),
+        };
+
+        Self {
+            provider,
+            signer,
+            credential_with_key,
+        }
+    }
+
+    /// Creates a key package and puts it into the client's storage.
+    pub fn key_package(&self) -> KeyPackage {
+        KeyPackage::builder()
+            .build(
+                CIPHERSUITE,
+                &self.provider,
+                &self.signer,
+                self.credential_with_key.clone(),
+            )
+            .unwrap()
+            .key_package()
+            .clone()
+    }
+
+    /// Creates a new group with `PublicMessage`s for handshake messages, so
+    /// that manipulated messages can be authenticated again.
+    pub fn create_group(&self, use_ratchet_tree_extension: bool) -> MlsGroup {
+        MlsGroup::builder()
+            .ciphersuite(CIPHERSUITE)
+            .with_wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+            .use_ratchet_tree_extension(use_ratchet_tree_extension)
+            .build(
+                &self.provider,
+                &self.signer,
+                self.credential_with_key.clone(),
+            )
+            .unwrap()
+    }
+
+    /// The configuration used to join groups.
+    pub fn join_config() -> MlsGroupJoinConfig {
+        MlsGroupJoinConfig::builder()
+            .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+            .build()
+    }
+}
+
+/// A group with two members, Alice at leaf 0 and Bob at leaf 1.
+pub struct TwoMemberGroup {
+    pub alice: Client,
+    pub alice_group: MlsGroup,
+    pub bob: Client,
+    pub bob_group: MlsGroup,
+}
+
+impl TwoMemberGroup {
+    /// Alice creates the group and adds Bob.
+    pub fn new() -> Self {
+        let alice = Client::new(b"Alice");
+        let bob = Client::new(b"Bob");
+        let mut alice_group = alice.create_group(true);
+
+        let (_commit, welcome, _group_info) = alice_group
+            .add_members(&alice.provider, &alice.signer, &[bob.key_package()])
+            .unwrap();
+        alice_group.merge_pending_commit(&alice.provider).unwrap();
+
+        let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+        let bob_group =
+            StagedWelcome::new_from_welcome(&bob.provider, &Client::join_config(), welcome, None)
+                .unwrap()
+                .into_group(&bob.provider)
+                .unwrap();
+
+        Self {
+            alice,
+            alice_group,
+            bob,
+            bob_group,
+        }
+    }
+
+    /// Builds a `PublicMessage` from Alice with the given `body`. The message
+    /// is signed by Alice and has a valid membership tag.
+    pub fn message_from_alice(
+        &self,
+        body: FrankenFramedContentBody,
+        confirmation_tag: Option<VLBytes>,
+    ) -> ProtocolMessage {
+        let content = FrankenFramedContent {
+            group_id: self.alice_group.group_id().as_slice().into(),
+            epoch: self.alice_group.epoch().as_u64(),
+            sender: FrankenSender::Member(0),
+            authenticated_data: VLBytes::new(vec![]),
+            body,
+        };
+
+        let message = FrankenPublicMessage::auth_for_group(
+            &self.alice.provider,
+            &self.alice_group,
+            &self.alice.signer,
+            content,
+            confirmation_tag,
+        );
+
+        PublicMessage::from(message).into()
+    }
+}
+
+impl Default for TwoMemberGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Panics if `result` failed with a `LibraryError`, anywhere in the error.
+pub fn assert_no_library_error<T, E: Debug>(result: Result<T, E>) {
+    if let Err(error) = result {
+        let error = format!("{error:?}");
+        assert!(
+            !error.contains("LibraryError"),
+            "LibraryError escaped: {error}"
+        );
+    }
+}
+
+/// Converts a frankenstein ratchet tree into a `RatchetTreeIn`. Returns `None`
+/// if the tree doesn't decode.
+pub fn ratchet_tree_in(ratchet_tree: &FrankenRatchetTreeExtension) -> Option<RatchetTreeIn> {
+    let bytes = ratchet_tree.tls_serialize_detached().ok()?;
+    RatchetTreeIn::tls_deserialize_exact(bytes).ok()
+}
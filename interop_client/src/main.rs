@@ -17,21 +17,31 @@ use mls_client::{
 use mls_interop_proto::mls_client;
 use openmls::{
     credentials::{BasicCredential, Credential, CredentialType, CredentialWithKey},
+    extensions::{Extension, Extensions, ExternalSender, SenderExtensionIndex},
     framing::{MlsMessageBodyIn, MlsMessageIn, MlsMessageOut, ProcessedMessageContent},
     group::{
-        GroupEpoch, GroupId, MlsGroup, MlsGroupCreateConfig, MlsGroupJoinConfig, StagedWelcome,
-        WireFormatPolicy, PURE_CIPHERTEXT_WIRE_FORMAT_POLICY, PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+        GroupEpoch, GroupId, MlsGroup, MlsGroupCreateConfig, MlsGroupJoinConfig, ProposalStore,
+        PublicGroup, StagedCommit, StagedWelcome, WireFormatPolicy,
+        PURE_CIPHERTEXT_WIRE_FORMAT_POLICY, PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
     },
     key_packages::{KeyPackage, KeyPackageBundle},
+    messages::{
+        external_proposals::{ExternalProposal, JoinProposal},
+        group_info::VerifiableGroupInfo,
+        proposals::{Proposal, ReInitProposal},
+    },
     prelude::{Capabilities, ExtensionType, SenderRatchetConfiguration},
-    schedule::{psk::ResumptionPskUsage, ExternalPsk, PreSharedKeyId, Psk},
+    schedule::{
+        psk::{ResumptionPsk, ResumptionPskUsage},
+        ExternalPsk, PreSharedKeyId, Psk,
+    },
     treesync::{LeafNodeParameters, RatchetTreeIn},
     versions::ProtocolVersion,
 };
 use openmls_basic_credential::SignatureKeyPair;
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use openmls_traits::{random::OpenMlsRand, types::Ciphersuite, OpenMlsProvider};
-use tls_codec::{Deserialize, Serialize};
+use tls_codec::{Deserialize, Serialize, VLBytes};
 use tonic::{async_trait, transport::Server, Code, Request, Response, Status};
 use tracing::{debug, error, info, instrument, trace, Span};
 use tracing_subscriber::EnvFilter;
@@ -57,6 +67,49 @@ pub struct InteropGroup {
     crypto_provider: OpenMlsRustCrypto,
 }
 
+impl InteropGroup {
+    /// Process standalone proposals and store them for the next commit. Own
+    /// proposals are skipped because they are in the proposal store already.
+    fn handle_proposals(&mut self, proposals: &[Vec<u8>]) -> Result<(), Status> {
+        for proposal in proposals {
+            let message = MlsMessageIn::tls_deserialize(&mut proposal.as_slice())
+                .map_err(|_| Status::aborted("failed to deserialize proposal"))?;
+            if self.messages_out.contains(&message) {
+                trace!("Skipping processing of own proposal");
+                continue;
+            }
+            trace!("Processing proposal ...");
+            let processed_message = self
+                .group
+                .process_message(
+                    &self.crypto_provider,
+                    message.try_into_protocol_message().map_err(|_| {
+                        Status::invalid_argument("proposal is not a protocol message")
+                    })?,
+                )
+                .map_err(into_status)?;
+            trace!("... done");
+
+            match processed_message.into_content() {
+                ProcessedMessageContent::ProposalMessage(proposal)
+                | ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => {
+                    self.group
+                        .store_pending_proposal(self.crypto_provider.storage(), *proposal)
+                        .map_err(|err| {
+                            tonic::Status::internal(format!("error storing proposal: {err}"))
+                        })?;
+                }
+                ProcessedMessageContent::ApplicationMessage(_)
+                | ProcessedMessageContent::StagedCommitMessage(_) => {
+                    return Err(Status::invalid_argument("expected a proposal"))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 type PendingState = (
     KeyPackageBundle,
     Credential,
@@ -64,6 +117,23 @@ type PendingState = (
     OpenMlsRustCrypto,
 );
 
+/// What a client needs to create or join a group that resumes another group,
+/// i.e. after a re-init or for a branch. The resumption PSK of the old group
+/// is in the storage of the `crypto_provider` already.
+struct ResumptionState {
+    psk_id: PreSharedKeyId,
+    credential: Credential,
+    signature_keys: SignatureKeyPair,
+    wire_format_policy: WireFormatPolicy,
+    crypto_provider: OpenMlsRustCrypto,
+}
+
+/// The state of a client between a ReInit commit and the new group.
+struct ReInitState {
+    reinit: ReInitProposal,
+    resumption: ResumptionState,
+}
+
 /// This is the main state struct of the interop client. It keeps track of the
 /// individual MLS clients, as well as pending key packages that it was told to
 /// create. It also contains a transaction id map, that maps the `u32`
@@ -72,6 +142,10 @@ pub struct MlsClientImpl {
     groups: Mutex<Vec<InteropGroup>>,
     pending_state: Mutex<HashMap<Vec<u8>, PendingState>>,
     transaction_id_map: Mutex<HashMap<u32, Vec<u8>>>, // Indirection, linking to pending key packages
+    // Indexed by the `reinit_id`. A state is taken when the new group is created or joined.
+    reinits: Mutex<Vec<Option<ReInitState>>>,
+    // Indexed by the `signer_id`.
+    external_signers: Mutex<Vec<(SignatureKeyPair, ExternalSender)>>,
 }
 
 impl MlsClientImpl {
@@ -81,8 +155,218 @@ impl MlsClientImpl {
             groups: Mutex::new(Vec::new()),
             pending_state: Mutex::new(HashMap::new()),
             transaction_id_map: Mutex::new(HashMap::new()),
+            reinits: Mutex::new(Vec::new()),
+            external_signers: Mutex::new(Vec::new()),
         }
     }
+
+    /// Keep the pending state of a new key package until the client joins a
+    /// group with it. Returns the transaction id of the key package.
+    fn insert_pending_state(&self, identity: Vec<u8>, pending_state: PendingState) -> u32 {
+        let transaction_id: [u8; 4] = pending_state.3.rand().random_array().unwrap();
+        let transaction_id = u32::from_be_bytes(transaction_id);
+
+        self.transaction_id_map
+            .lock()
+            .unwrap()
+            .insert(transaction_id, identity.clone());
+        self.pending_state
+            .lock()
+            .unwrap()
+            .insert(identity, pending_state);
+
+        transaction_id
+    }
+
+    /// Remember the ReInit committed in `interop_group` and create a key
+    /// package for the new group.
+    fn start_reinit(
+        &self,
+        interop_group: &InteropGroup,
+        reinit: ReInitProposal,
+    ) -> Result<HandleReInitCommitResponse, Status> {
+        let ciphersuite = reinit.ciphersuite();
+        let crypto_provider = OpenMlsRustCrypto::default();
+        let psk_id = store_resumption_psk(
+            &interop_group.group,
+            ResumptionPskUsage::Reinit,
+            ciphersuite,
+            &crypto_provider,
+        )?;
+        let credential = interop_group
+            .group
+            .credential()
+            .map_err(into_status)?
+            .clone();
+        let signature_keys = SignatureKeyPair::new(ciphersuite.signature_algorithm())
+            .map_err(|_| Status::internal("unable to create signature keys"))?;
+
+        let key_package = KeyPackage::builder()
+            .leaf_node_capabilities(capabilities())
+            .build(
+                ciphersuite,
+                &crypto_provider,
+                &signature_keys,
+                CredentialWithKey {
+                    credential: credential.clone(),
+                    signature_key: signature_keys.public().into(),
+                },
+            )
+            .map_err(into_status)?;
+        let key_package: MlsMessageOut = key_package.key_package().clone().into();
+
+        let mut reinits = self.reinits.lock().unwrap();
+        let reinit_id = reinits.len() as u32;
+        reinits.push(Some(ReInitState {
+            reinit,
+            resumption: ResumptionState {
+                psk_id,
+                credential,
+                signature_keys,
+                wire_format_policy: interop_group.wire_format_policy,
+                crypto_provider,
+            },
+        }));
+
+        Ok(HandleReInitCommitResponse {
+            reinit_id,
+            key_package: key_package
+                .tls_serialize_detached()
+                .map_err(|_| Status::internal("failed to serialize key package"))?,
+            epoch_authenticator: interop_group
+                .group
+                .epoch_authenticator()
+                .as_slice()
+                .to_vec(),
+        })
+    }
+
+    /// Keep a group created by a ReInit or branch and build the response for
+    /// the harness.
+    fn push_subgroup(
+        &self,
+        interop_group: InteropGroup,
+        welcome: Vec<u8>,
+        external_tree: bool,
+    ) -> Result<CreateSubgroupResponse, Status> {
+        let ratchet_tree = if external_tree {
+            interop_group
+                .group
+                .export_ratchet_tree()
+                .tls_serialize_detached()
+                .map_err(|_| Status::aborted("failed to serialize ratchet tree"))?
+        } else {
+            vec![]
+        };
+        let epoch_authenticator = interop_group
+            .group
+            .epoch_authenticator()
+            .as_slice()
+            .to_vec();
+
+        let mut groups = self.groups.lock().unwrap();
+        let state_id = groups.len() as u32;
+        groups.push(interop_group);
+
+        Ok(CreateSubgroupResponse {
+            state_id,
+            welcome,
+            ratchet_tree,
+            epoch_authenticator,
+        })
+    }
+
+    /// Take the state of a ReInit out of the client.
+    fn take_reinit(&self, reinit_id: u32) -> Result<ReInitState, Status> {
+        self.reinits
+            .lock()
+            .unwrap()
+            .get_mut(reinit_id as usize)
+            .and_then(Option::take)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown reinit_id"))
+    }
+}
+
+impl ResumptionState {
+    /// Create the new group and add the members with the `key_packages`. The
+    /// resumption PSK is included in the first commit. Returns the group and
+    /// the serialized welcome.
+    fn create_group(
+        self,
+        group_id: GroupId,
+        ciphersuite: Ciphersuite,
+        extensions: Extensions,
+        key_packages: &[Vec<u8>],
+        force_path: bool,
+    ) -> Result<(InteropGroup, Vec<u8>), Status> {
+        let key_packages = key_packages
+            .iter()
+            .map(|key_package| {
+                MlsMessageIn::tls_deserialize_exact(key_package)
+                    .map_err(|_| Status::invalid_argument("invalid key package"))?
+                    .into_keypackage()
+                    .ok_or(Status::invalid_argument("message was not a key package"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Note: We use the same values as in `create_group`.
+        let mls_group_config = MlsGroupCreateConfig::builder()
+            .ciphersuite(ciphersuite)
+            .max_past_epochs(32)
+            .number_of_resumption_psks(32)
+            .sender_ratchet_configuration(SenderRatchetConfiguration::default())
+            .use_ratchet_tree_extension(true)
+            .wire_format_policy(self.wire_format_policy)
+            .capabilities(capabilities())
+            .with_group_context_extensions(extensions)
+            .map_err(into_status)?
+            .build();
+        let mut group = MlsGroup::new_with_group_id(
+            &self.crypto_provider,
+            &self.signature_keys,
+            &mls_group_config,
+            group_id,
+            CredentialWithKey {
+                credential: self.credential,
+                signature_key: self.signature_keys.public().into(),
+            },
+        )
+        .map_err(into_status)?;
+
+        let welcome = group
+            .commit_builder()
+            .propose_adds(key_packages)
+            .include_resumption_psk(self.psk_id)
+            .force_self_update(force_path)
+            .load_psks(self.crypto_provider.storage())
+            .map_err(into_status)?
+            .build(
+                self.crypto_provider.rand(),
+                self.crypto_provider.crypto(),
+                &self.signature_keys,
+                |_| true,
+            )
+            .map_err(into_status)?
+            .stage_commit(&self.crypto_provider)
+            .map_err(into_status)?
+            .into_welcome_msg()
+            .ok_or(Status::invalid_argument("no members to add"))?
+            .tls_serialize_detached()
+            .map_err(|_| Status::aborted("failed to serialize welcome"))?;
+        group
+            .merge_pending_commit(&self.crypto_provider)
+            .map_err(into_status)?;
+
+        let interop_group = InteropGroup {
+            group,
+            wire_format_policy: self.wire_format_policy,
+            signature_keys: self.signature_keys,
+            messages_out: Vec::new(),
+            crypto_provider: self.crypto_provider,
+        };
+
+        Ok((interop_group, welcome))
+    }
 }
 
 fn into_status<E: Display>(e: E) -> Status {
@@ -152,6 +436,158 @@ fn ratchet_tree_from_config(bytes: Vec<u8>) -> Option<RatchetTreeIn> {
     }
 }
 
+fn verifiable_group_info_from_bytes(bytes: &[u8]) -> Result<VerifiableGroupInfo, Status> {
+    debug!("Deserializing `MlsMessageIn` (to obtain group info).");
+    let message = MlsMessageIn::tls_deserialize_exact(bytes)
+        .map_err(|_| Status::invalid_argument("failed to deserialize group info"))?;
+    match message.extract() {
+        MlsMessageBodyIn::GroupInfo(verifiable_group_info) => Ok(verifiable_group_info),
+        _ => Err(Status::invalid_argument("message was not a group info")),
+    }
+}
+
+/// The capabilities of all leaf nodes created by the interop client.
+fn capabilities() -> Capabilities {
+    Capabilities::new(
+        Some(&[ProtocolVersion::Mls10, ProtocolVersion::Other(999)]),
+        Some(&[
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+            Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+        ]),
+        Some(&EXTENSION_TYPES),
+        None,
+        Some(&CREDENTIAL_TYPES),
+    )
+}
+
+/// Create a key package for `identity` with a fresh provider.
+fn new_key_package(ciphersuite: Ciphersuite, identity: Vec<u8>) -> Result<PendingState, Status> {
+    let crypto_provider = OpenMlsRustCrypto::default();
+    let credential = BasicCredential::new(identity);
+    let signature_keys = SignatureKeyPair::new(ciphersuite.signature_algorithm())
+        .map_err(|_| Status::internal("unable to create signature keys"))?;
+
+    let key_package = KeyPackage::builder()
+        .leaf_node_capabilities(capabilities())
+        .build(
+            ciphersuite,
+            &crypto_provider,
+            &signature_keys,
+            CredentialWithKey {
+                credential: credential.clone().into(),
+                signature_key: signature_keys.public().into(),
+            },
+        )
+        .map_err(into_status)?;
+
+    Ok((
+        key_package,
+        credential.into(),
+        signature_keys,
+        crypto_provider,
+    ))
+}
+
+/// Convert the extensions in a request into OpenMLS [`Extensions`].
+fn extensions_from_request(extensions: &[mls_client::Extension]) -> Result<Extensions, Status> {
+    let extensions = extensions
+        .iter()
+        .map(|extension| {
+            let extension_type = u16::try_from(extension.extension_type)
+                .map_err(|_| Status::invalid_argument("invalid extension type"))?;
+            let mut bytes = extension_type.to_be_bytes().to_vec();
+            bytes.extend(
+                VLBytes::new(extension.extension_data.clone())
+                    .tls_serialize_detached()
+                    .map_err(|_| Status::invalid_argument("extension data too long"))?,
+            );
+            Extension::tls_deserialize_exact(bytes)
+                .map_err(|_| Status::invalid_argument("invalid extension"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Extensions::from_vec(extensions).map_err(into_status)
+}
+
+/// Store the resumption PSK of the current epoch of `group` in the storage of
+/// `crypto_provider` to create or join a group that resumes `group`.
+fn store_resumption_psk(
+    group: &MlsGroup,
+    usage: ResumptionPskUsage,
+    ciphersuite: Ciphersuite,
+    crypto_provider: &OpenMlsRustCrypto,
+) -> Result<PreSharedKeyId, Status> {
+    let psk_id = PreSharedKeyId::new(
+        ciphersuite,
+        crypto_provider.rand(),
+        Psk::Resumption(ResumptionPsk::new(
+            usage,
+            group.group_id().clone(),
+            group.epoch(),
+        )),
+    )
+    .map_err(|_| Status::internal("unable to create PreSharedKeyId"))?;
+    psk_id
+        .store(crypto_provider, group.resumption_psk_secret().as_slice())
+        .map_err(|_| Status::internal("unable to store PSK"))?;
+
+    Ok(psk_id)
+}
+
+/// Join a group with a welcome. The key package must be in the storage of
+/// `crypto_provider`.
+fn join_with_welcome(
+    crypto_provider: OpenMlsRustCrypto,
+    signature_keys: SignatureKeyPair,
+    wire_format_policy: WireFormatPolicy,
+    welcome: &[u8],
+    ratchet_tree: Vec<u8>,
+) -> Result<InteropGroup, Status> {
+    // Note: We just use some values here that make live testing work.
+    //       There is nothing special about the used numbers and they
+    //       can be increased (or decreased) depending on the available scenarios.
+    let mls_group_config = MlsGroupJoinConfig::builder()
+        .max_past_epochs(32)
+        .number_of_resumption_psks(32)
+        .sender_ratchet_configuration(SenderRatchetConfiguration::default())
+        .use_ratchet_tree_extension(true)
+        .wire_format_policy(wire_format_policy)
+        .build();
+
+    let welcome = MlsMessageIn::tls_deserialize_exact(welcome)
+        .map_err(|_| Status::aborted("failed to deserialize MlsMessage with a Welcome"))?
+        .into_welcome()
+        .ok_or(Status::invalid_argument("expected a welcome"))?;
+
+    let ratchet_tree = ratchet_tree_from_config(ratchet_tree);
+
+    let group =
+        StagedWelcome::new_from_welcome(&crypto_provider, &mls_group_config, welcome, ratchet_tree)
+            .map_err(into_status)?
+            .into_group(&crypto_provider)
+            .map_err(into_status)?;
+    trace!(epoch=?group.epoch(), "Joined group.");
+
+    Ok(InteropGroup {
+        wire_format_policy,
+        group,
+        signature_keys,
+        messages_out: Vec::new(),
+        crypto_provider,
+    })
+}
+
+/// Find the ReInit proposal in a commit.
+fn reinit_proposal(staged_commit: &StagedCommit) -> Option<ReInitProposal> {
+    staged_commit
+        .queued_proposals()
+        .find_map(|queued_proposal| match queued_proposal.proposal() {
+            Proposal::ReInit(reinit) => Some(reinit.clone()),
+            _ => None,
+        })
+}
+
 fn bytes_to_string<B>(bytes: B) -> String
 where
     B: AsRef<[u8]>,
@@ -270,7 +706,6 @@ impl MlsClient for MlsClientImpl {
         let request = request.get_ref();
         info!(?request, "Request");
 
-        let crypto_provider = OpenMlsRustCrypto::default();
         let ciphersuite = *to_ciphersuite(request.cipher_suite)?;
         let identity = request.identity.clone();
 
@@ -279,66 +714,33 @@ impl MlsClient for MlsClientImpl {
             "Creating key package."
         );
 
-        let credential = BasicCredential::new(identity);
-        let signature_keys = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+        let pending_state = new_key_package(ciphersuite, identity)?;
+        let (key_package, _, signature_keys, _) = &pending_state;
 
-        let key_package = KeyPackage::builder()
-            .leaf_node_capabilities(Capabilities::new(
-                Some(&[ProtocolVersion::Mls10, ProtocolVersion::Other(999)]),
-                Some(&[
-                    Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
-                    Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
-                    Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
-                ]),
-                Some(&EXTENSION_TYPES),
-                None,
-                Some(&CREDENTIAL_TYPES),
-            ))
-            .build(
-                ciphersuite,
-                &crypto_provider,
-                &signature_keys,
-                CredentialWithKey {
-                    credential: credential.clone().into(),
-                    signature_key: signature_keys.public().into(),
-                },
-            )
+        let key_package_msg: MlsMessageOut = key_package.clone().into();
+        let key_package_msg = key_package_msg
+            .tls_serialize_detached()
+            .expect("error serializing key package");
+        let encryption_priv = key_package
+            .encryption_private_key()
+            .tls_serialize_detached()
+            .unwrap();
+        let init_priv = key_package
+            .init_private_key()
+            .tls_serialize_detached()
             .unwrap();
+        let signature_priv = signature_keys.private().to_vec();
 
-        let transaction_id: [u8; 4] = crypto_provider.rand().random_array().unwrap();
-        let transaction_id = u32::from_be_bytes(transaction_id);
+        let transaction_id = self.insert_pending_state(request.identity.clone(), pending_state);
 
-        let key_package_msg: MlsMessageOut = key_package.clone().into();
         let response = CreateKeyPackageResponse {
             transaction_id,
-            key_package: key_package_msg
-                .tls_serialize_detached()
-                .expect("error serializing key package"),
-            encryption_priv: key_package
-                .encryption_private_key()
-                .tls_serialize_detached()
-                .unwrap(),
-            init_priv: key_package
-                .init_private_key()
-                .tls_serialize_detached()
-                .unwrap(),
-            signature_priv: signature_keys.private().to_vec(),
+            key_package: key_package_msg,
+            encryption_priv,
+            init_priv,
+            signature_priv,
         };
 
-        self.transaction_id_map
-            .lock()
-            .unwrap()
-            .insert(transaction_id, request.identity.clone());
-        self.pending_state.lock().unwrap().insert(
-            request.identity.clone(),
-            (
-                key_package,
-                credential.into(),
-                signature_keys,
-                crypto_provider,
-            ),
-        );
-
         info!(?response, "Response");
         Ok(Response::new(response))
     }
@@ -354,16 +756,6 @@ impl MlsClient for MlsClientImpl {
         trace!(identity = String::from_utf8_lossy(&request.identity).to_string());
 
         let wire_format_policy = wire_format_policy(request.encrypt_handshake);
-        // Note: We just use some values here that make live testing work.
-        //       There is nothing special about the used numbers and they
-        //       can be increased (or decreased) depending on the available scenarios.
-        let mls_group_config = MlsGroupJoinConfig::builder()
-            .max_past_epochs(32)
-            .number_of_resumption_psks(32)
-            .sender_ratchet_configuration(SenderRatchetConfiguration::default())
-            .use_ratchet_tree_extension(true)
-            .wire_format_policy(wire_format_policy)
-            .build();
 
         let mut pending_key_packages = self.pending_state.lock().unwrap();
         let (my_key_package, _my_credential, my_signature_keys, crypto_provider) =
@@ -389,31 +781,13 @@ impl MlsClient for MlsClientImpl {
             )
             .map_err(into_status)?;
 
-        let welcome = MlsMessageIn::tls_deserialize(&mut request.welcome.as_slice())
-            .map_err(|_| Status::aborted("failed to deserialize MlsMessage with a Welcome"))?
-            .into_welcome()
-            .expect("expected a welcome");
-
-        let ratchet_tree = ratchet_tree_from_config(request.ratchet_tree.clone());
-
-        let group = StagedWelcome::new_from_welcome(
-            &crypto_provider,
-            &mls_group_config,
-            welcome,
-            ratchet_tree,
-        )
-        .map_err(into_status)?
-        .into_group(&crypto_provider)
-        .map_err(into_status)?;
-
-        let interop_group = InteropGroup {
-            wire_format_policy,
-            group,
-            signature_keys: my_signature_keys,
-            messages_out: Vec::new(),
+        let interop_group = join_with_welcome(
             crypto_provider,
-        };
-        trace!("   in epoch {:?}", interop_group.group.epoch());
+            my_signature_keys,
+            wire_format_policy,
+            &request.welcome,
+            request.ratchet_tree.clone(),
+        )?;
 
         let epoch_authenticator = interop_group
             .group
@@ -914,10 +1288,39 @@ impl MlsClient for MlsClientImpl {
         let request = request.get_ref();
         info!(?request, "Request");
 
-        let response = Status::unimplemented("Re-init is not implemented");
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+
+        let reinit = ReInitProposal::new(
+            GroupId::from_slice(&request.group_id),
+            ProtocolVersion::Mls10,
+            *to_ciphersuite(request.cipher_suite)?,
+            extensions_from_request(&request.extensions)?,
+        );
+
+        let (proposal, _) = interop_group
+            .group
+            .propose_reinit(
+                &interop_group.crypto_provider,
+                &interop_group.signature_keys,
+                reinit,
+            )
+            .map_err(into_status)?;
+
+        // Store the proposal for potential future use.
+        interop_group.messages_out.push(proposal.clone().into());
+
+        let proposal = proposal
+            .tls_serialize_detached()
+            .map_err(|_| Status::aborted("failed to serialize proposal"))?;
+        trace!("   generated reinit proposal");
+
+        let response = ProposalResponse { proposal };
 
         info!(?response, "Response");
-        Err(response)
+        Ok(Response::new(response))
     }
 
     #[instrument(skip_all, fields(actor))]
@@ -932,43 +1335,12 @@ impl MlsClient for MlsClientImpl {
         let interop_group = groups
             .get_mut(request.state_id as usize)
             .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
-        let group = &mut interop_group.group;
-
-        trace!(epoch=?group.epoch(), "Current group state.");
+        trace!(epoch=?interop_group.group.epoch(), "Current group state.");
 
         // Proposals by reference. These proposals are standalone proposals. They should
         // be appended to the proposal store.
-        for proposal in &request.by_reference {
-            trace!("Handling proposal by reference.");
-
-            let message = MlsMessageIn::tls_deserialize(&mut proposal.as_slice())
-                .map_err(|_| Status::aborted("failed to deserialize proposal"))?;
-            if interop_group.messages_out.contains(&message) {
-                trace!("Skipping processing of own proposal");
-                continue;
-            }
-            trace!("Processing proposal ...");
-            let processed_message = group
-                .process_message(
-                    &interop_group.crypto_provider,
-                    message.try_into_protocol_message().unwrap(),
-                )
-                .map_err(into_status)?;
-            trace!("... done");
-
-            match processed_message.into_content() {
-                ProcessedMessageContent::ApplicationMessage(_) => unreachable!(),
-                ProcessedMessageContent::ProposalMessage(proposal) => {
-                    group
-                        .store_pending_proposal(interop_group.crypto_provider.storage(), *proposal)
-                        .map_err(|err| {
-                            tonic::Status::internal(format!("error storing proposal: {err}"))
-                        })?;
-                }
-                ProcessedMessageContent::ExternalJoinProposalMessage(_) => unreachable!(),
-                ProcessedMessageContent::StagedCommitMessage(_) => unreachable!(),
-            }
-        }
+        interop_group.handle_proposals(&request.by_reference)?;
+        let group = &mut interop_group.group;
 
         // Proposals by value. These proposals are inline proposals. They should be
         // converted into group operations.
@@ -1116,43 +1488,10 @@ impl MlsClient for MlsClientImpl {
         let interop_group = groups
             .get_mut(request.state_id as usize)
             .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
-        let group = &mut interop_group.group;
-
-        trace!(epoch=?group.epoch(), "Current group state.");
+        trace!(epoch=?interop_group.group.epoch(), "Current group state.");
 
-        // XXX[FK]: This is a horrible API.
-
-        for proposal in &request.proposal {
-            // trace!("   proposals by reference ... we don't care.");
-            let message = MlsMessageIn::tls_deserialize(&mut proposal.as_slice())
-                .map_err(|_| Status::aborted("failed to deserialize proposal"))?;
-            if interop_group.messages_out.contains(&message) {
-                trace!("   skipping processing of own proposal");
-                continue;
-            }
-            trace!("   processing proposal ...");
-            let processed_message = group
-                .process_message(
-                    &interop_group.crypto_provider,
-                    message.try_into_protocol_message().unwrap(),
-                )
-                .map_err(into_status)?;
-            trace!("       done");
-            match processed_message.into_content() {
-                ProcessedMessageContent::ApplicationMessage(_) => unreachable!(),
-                ProcessedMessageContent::ProposalMessage(proposal) => {
-                    group
-                        .store_pending_proposal(interop_group.crypto_provider.storage(), *proposal)
-                        .map_err(|err| {
-                            tonic::Status::internal(format!(
-                                "error storing pending proposal: {err}"
-                            ))
-                        })?;
-                }
-                ProcessedMessageContent::ExternalJoinProposalMessage(_) => unreachable!(),
-                ProcessedMessageContent::StagedCommitMessage(_) => unreachable!(),
-            }
-        }
+        interop_group.handle_proposals(&request.proposal)?;
+        let group = &mut interop_group.group;
 
         debug!("Deserializing `MlsMessageIn`.");
         let message =
@@ -1364,88 +1703,581 @@ impl MlsClient for MlsClientImpl {
     #[instrument(skip_all, fields(actor))]
     async fn group_context_extensions_proposal(
         &self,
-        _request: Request<GroupContextExtensionsProposalRequest>,
+        request: Request<GroupContextExtensionsProposalRequest>,
     ) -> Result<Response<ProposalResponse>, Status> {
-        Err(Status::unimplemented(
-            "Group context extension is not implemented yet",
-        ))
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+
+        let extensions = extensions_from_request(&request.extensions)?;
+        let (proposal, _) = interop_group
+            .group
+            .propose_group_context_extensions(
+                &interop_group.crypto_provider,
+                extensions,
+                &interop_group.signature_keys,
+            )
+            .map_err(into_status)?;
+
+        // Store the proposal for potential future use.
+        interop_group.messages_out.push(proposal.clone().into());
+
+        let response = ProposalResponse {
+            proposal: proposal
+                .tls_serialize_detached()
+                .map_err(|_| Status::aborted("failed to serialize proposal"))?,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn re_init_commit(
         &self,
-        _: Request<CommitRequest>,
+        request: Request<CommitRequest>,
     ) -> Result<Response<CommitResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+
+        // The ReInit proposal can only be sent by reference.
+        if !request.by_value.is_empty() {
+            return Err(Status::invalid_argument(
+                "ReInit commits don't support proposals by value",
+            ));
+        }
+        interop_group.handle_proposals(&request.by_reference)?;
+
+        let (commit, _welcome, _group_info) = interop_group
+            .group
+            .commit_to_pending_proposals(
+                &interop_group.crypto_provider,
+                &interop_group.signature_keys,
+            )
+            .map_err(into_status)?;
+
+        // The commit is merged in `handle_pending_re_init_commit`.
+        debug!(commit=?interop_group.group.pending_commit(), "Pending ReInit commit created.");
+
+        let response = CommitResponse {
+            commit: commit
+                .tls_serialize_detached()
+                .map_err(|_| Status::aborted("failed to serialize commit"))?,
+            welcome: vec![],
+            ratchet_tree: vec![],
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn handle_pending_re_init_commit(
         &self,
-        _: Request<HandlePendingCommitRequest>,
+        request: Request<HandlePendingCommitRequest>,
     ) -> Result<Response<HandleReInitCommitResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+
+        let reinit = interop_group
+            .group
+            .pending_commit()
+            .and_then(reinit_proposal)
+            .ok_or(Status::failed_precondition("no pending ReInit commit"))?;
+
+        interop_group
+            .group
+            .merge_pending_commit(&interop_group.crypto_provider)
+            .map_err(into_status)?;
+        trace!(epoch=?interop_group.group.epoch(), "New group state.");
+
+        let response = self.start_reinit(interop_group, reinit)?;
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn handle_re_init_commit(
         &self,
-        _: Request<HandleCommitRequest>,
+        request: Request<HandleCommitRequest>,
     ) -> Result<Response<HandleReInitCommitResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+
+        interop_group.handle_proposals(&request.proposal)?;
+
+        let message = MlsMessageIn::tls_deserialize_exact(&request.commit)
+            .map_err(|_| Status::aborted("failed to deserialize commit"))?;
+        let processed_message = interop_group
+            .group
+            .process_message(
+                &interop_group.crypto_provider,
+                message
+                    .try_into_protocol_message()
+                    .map_err(|_| Status::invalid_argument("commit is not a protocol message"))?,
+            )
+            .map_err(into_status)?;
+
+        let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+            processed_message.into_content()
+        else {
+            return Err(Status::invalid_argument("expected a commit"));
+        };
+        let reinit = reinit_proposal(&staged_commit)
+            .ok_or(Status::invalid_argument("commit doesn't contain a ReInit"))?;
+
+        interop_group
+            .group
+            .merge_staged_commit(&interop_group.crypto_provider, *staged_commit)
+            .map_err(into_status)?;
+        trace!(epoch=?interop_group.group.epoch(), "New group state.");
+
+        let response = self.start_reinit(interop_group, reinit)?;
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn re_init_welcome(
         &self,
-        _: Request<ReInitWelcomeRequest>,
+        request: Request<ReInitWelcomeRequest>,
     ) -> Result<Response<CreateSubgroupResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let ReInitState { reinit, resumption } = self.take_reinit(request.reinit_id)?;
+
+        let (interop_group, welcome) = resumption.create_group(
+            reinit.group_id().clone(),
+            reinit.ciphersuite(),
+            reinit.extensions().clone(),
+            &request.key_package,
+            request.force_path,
+        )?;
+
+        let response = self.push_subgroup(interop_group, welcome, request.external_tree)?;
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn handle_re_init_welcome(
         &self,
-        _: Request<HandleReInitWelcomeRequest>,
+        request: Request<HandleReInitWelcomeRequest>,
     ) -> Result<Response<JoinGroupResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let ReInitState { reinit, resumption } = self.take_reinit(request.reinit_id)?;
+
+        let interop_group = join_with_welcome(
+            resumption.crypto_provider,
+            resumption.signature_keys,
+            resumption.wire_format_policy,
+            &request.welcome,
+            request.ratchet_tree.clone(),
+        )?;
+
+        // The new group has to be the one announced in the ReInit proposal.
+        if interop_group.group.group_id() != reinit.group_id()
+            || interop_group.group.ciphersuite() != reinit.ciphersuite()
+        {
+            return Err(Status::invalid_argument(
+                "the welcome doesn't match the ReInit proposal",
+            ));
+        }
+
+        let epoch_authenticator = interop_group
+            .group
+            .epoch_authenticator()
+            .as_slice()
+            .to_vec();
+
+        let mut groups = self.groups.lock().unwrap();
+        let state_id = groups.len() as u32;
+        groups.push(interop_group);
+
+        let response = JoinGroupResponse {
+            state_id,
+            epoch_authenticator,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn create_branch(
         &self,
-        _: Request<CreateBranchRequest>,
+        request: Request<CreateBranchRequest>,
     ) -> Result<Response<CreateSubgroupResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let (resumption, ciphersuite) = {
+            let groups = self.groups.lock().unwrap();
+            let interop_group = groups
+                .get(request.state_id as usize)
+                .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+            let ciphersuite = interop_group.group.ciphersuite();
+
+            let crypto_provider = OpenMlsRustCrypto::default();
+            let psk_id = store_resumption_psk(
+                &interop_group.group,
+                ResumptionPskUsage::Branch,
+                ciphersuite,
+                &crypto_provider,
+            )?;
+
+            let resumption = ResumptionState {
+                psk_id,
+                credential: interop_group
+                    .group
+                    .credential()
+                    .map_err(into_status)?
+                    .clone(),
+                signature_keys: SignatureKeyPair::new(ciphersuite.signature_algorithm())
+                    .map_err(|_| Status::internal("unable to create signature keys"))?,
+                wire_format_policy: interop_group.wire_format_policy,
+                crypto_provider,
+            };
+
+            (resumption, ciphersuite)
+        };
+
+        let (interop_group, welcome) = resumption.create_group(
+            GroupId::from_slice(&request.group_id),
+            ciphersuite,
+            extensions_from_request(&request.extensions)?,
+            &request.key_packages,
+            request.force_path,
+        )?;
+
+        let response = self.push_subgroup(interop_group, welcome, request.external_tree)?;
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn handle_branch(
         &self,
-        _: Request<HandleBranchRequest>,
+        request: Request<HandleBranchRequest>,
     ) -> Result<Response<HandleBranchResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let identity = self
+            .transaction_id_map
+            .lock()
+            .unwrap()
+            .get(&request.transaction_id)
+            .cloned()
+            .ok_or(Status::invalid_argument("unknown transaction_id"))?;
+        let (_key_package, _credential, signature_keys, crypto_provider) = self
+            .pending_state
+            .lock()
+            .unwrap()
+            .remove(&identity)
+            .ok_or(Status::invalid_argument("unknown transaction_id"))?;
+
+        let mut groups = self.groups.lock().unwrap();
+        let old_group = groups
+            .get(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+        store_resumption_psk(
+            &old_group.group,
+            ResumptionPskUsage::Branch,
+            old_group.group.ciphersuite(),
+            &crypto_provider,
+        )?;
+        let wire_format_policy = old_group.wire_format_policy;
+
+        let interop_group = join_with_welcome(
+            crypto_provider,
+            signature_keys,
+            wire_format_policy,
+            &request.welcome,
+            request.ratchet_tree.clone(),
+        )?;
+
+        let epoch_authenticator = interop_group
+            .group
+            .epoch_authenticator()
+            .as_slice()
+            .to_vec();
+
+        let state_id = groups.len() as u32;
+        groups.push(interop_group);
+
+        let response = HandleBranchResponse {
+            state_id,
+            epoch_authenticator,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn new_member_add_proposal(
         &self,
-        _: Request<NewMemberAddProposalRequest>,
+        request: Request<NewMemberAddProposalRequest>,
     ) -> Result<Response<NewMemberAddProposalResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        Span::current().record("actor", bytes_to_string(&request.identity));
+
+        let verifiable_group_info = verifiable_group_info_from_bytes(&request.group_info)?;
+        let pending_state = new_key_package(
+            verifiable_group_info.ciphersuite(),
+            request.identity.clone(),
+        )?;
+        let (key_package, _, signature_keys, _) = &pending_state;
+
+        let proposal =
+            JoinProposal::new::<<OpenMlsRustCrypto as OpenMlsProvider>::StorageProvider>(
+                key_package.key_package().clone(),
+                verifiable_group_info.group_id().clone(),
+                verifiable_group_info.epoch(),
+                signature_keys,
+            )
+            .map_err(into_status)?
+            .tls_serialize_detached()
+            .map_err(|_| Status::aborted("failed to serialize proposal"))?;
+        let init_priv = key_package
+            .init_private_key()
+            .tls_serialize_detached()
+            .map_err(|_| Status::internal("failed to serialize init key"))?;
+        let encryption_priv = key_package
+            .encryption_private_key()
+            .tls_serialize_detached()
+            .map_err(|_| Status::internal("failed to serialize encryption key"))?;
+        let signature_priv = signature_keys.private().to_vec();
+
+        let transaction_id = self.insert_pending_state(request.identity.clone(), pending_state);
+
+        let response = NewMemberAddProposalResponse {
+            transaction_id,
+            proposal,
+            init_priv,
+            encryption_priv,
+            signature_priv,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn create_external_signer(
         &self,
-        _: Request<CreateExternalSignerRequest>,
+        request: Request<CreateExternalSignerRequest>,
     ) -> Result<Response<CreateExternalSignerResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        Span::current().record("actor", bytes_to_string(&request.identity));
+
+        let ciphersuite = *to_ciphersuite(request.cipher_suite)?;
+        let signature_keys = SignatureKeyPair::new(ciphersuite.signature_algorithm())
+            .map_err(|_| Status::internal("unable to create signature keys"))?;
+        let external_sender = ExternalSender::new(
+            signature_keys.public().into(),
+            BasicCredential::new(request.identity.clone()).into(),
+        );
+
+        let serialized_external_sender = external_sender
+            .tls_serialize_detached()
+            .map_err(|_| Status::internal("failed to serialize external sender"))?;
+
+        let mut external_signers = self.external_signers.lock().unwrap();
+        let signer_id = external_signers.len() as u32;
+        external_signers.push((signature_keys, external_sender));
+
+        let response = CreateExternalSignerResponse {
+            signer_id,
+            external_sender: serialized_external_sender,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn add_external_signer(
         &self,
-        _: Request<AddExternalSignerRequest>,
+        request: Request<AddExternalSignerRequest>,
     ) -> Result<Response<ProposalResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let external_sender = ExternalSender::tls_deserialize_exact(&request.external_sender)
+            .map_err(|_| Status::invalid_argument("invalid external sender"))?;
+
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+
+        // Append the signer to the external senders of the group.
+        let mut extensions = interop_group.group.extensions().clone();
+        let mut external_senders = extensions.external_senders().cloned().unwrap_or_default();
+        external_senders.push(external_sender);
+        extensions.add_or_replace(Extension::ExternalSenders(external_senders));
+
+        let (proposal, _) = interop_group
+            .group
+            .propose_group_context_extensions(
+                &interop_group.crypto_provider,
+                extensions,
+                &interop_group.signature_keys,
+            )
+            .map_err(into_status)?;
+
+        // Store the proposal for potential future use.
+        interop_group.messages_out.push(proposal.clone().into());
+
+        let response = ProposalResponse {
+            proposal: proposal
+                .tls_serialize_detached()
+                .map_err(|_| Status::aborted("failed to serialize proposal"))?,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn external_signer_proposal(
         &self,
-        _: Request<ExternalSignerProposalRequest>,
+        request: Request<ExternalSignerProposalRequest>,
     ) -> Result<Response<ProposalResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let external_signers = self.external_signers.lock().unwrap();
+        let (signer, external_sender) = external_signers
+            .get(request.signer_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown signer_id"))?;
+        let description = request
+            .description
+            .as_ref()
+            .ok_or(Status::invalid_argument("missing proposal description"))?;
+
+        // The external senders are in the group context and the removed member
+        // is identified by its credential, so we need the public state of the group.
+        let verifiable_group_info = verifiable_group_info_from_bytes(&request.group_info)?;
+        let ratchet_tree = ratchet_tree_from_config(request.ratchet_tree.clone())
+            .or_else(|| {
+                verifiable_group_info
+                    .extensions()
+                    .ratchet_tree()
+                    .map(|extension| extension.ratchet_tree().clone())
+            })
+            .ok_or(Status::invalid_argument("missing ratchet tree"))?;
+        let crypto_provider = OpenMlsRustCrypto::default();
+        let (public_group, group_info) = PublicGroup::from_external(
+            crypto_provider.crypto(),
+            crypto_provider.storage(),
+            ratchet_tree,
+            verifiable_group_info,
+            ProposalStore::new(),
+        )
+        .map_err(into_status)?;
+        let group_id = group_info.group_context().group_id().clone();
+        let epoch = group_info.group_context().epoch();
+
+        // Look up the index of the signer in the external senders of the group.
+        let sender_index = group_info
+            .group_context()
+            .extensions()
+            .external_senders()
+            .and_then(|external_senders| {
+                external_senders
+                    .iter()
+                    .position(|sender| sender == external_sender)
+            })
+            .map(|index| SenderExtensionIndex::new(index as u32))
+            .ok_or(Status::failed_precondition(
+                "signer is not an external sender of the group",
+            ))?;
+
+        let proposal_type = String::from_utf8_lossy(&description.proposal_type).to_string();
+        trace!(r#type = proposal_type, "Creating external proposal.");
+
+        let proposal = match proposal_type.as_ref() {
+            "add" => {
+                let key_package = MlsMessageIn::tls_deserialize_exact(&description.key_package)
+                    .map_err(|_| Status::invalid_argument("Invalid key package"))?
+                    .into_keypackage()
+                    .ok_or(Status::invalid_argument("Message was not a key package"))?;
+
+                ExternalProposal::new_add::<OpenMlsRustCrypto>(
+                    key_package,
+                    group_id,
+                    epoch,
+                    signer,
+                    sender_index,
+                )
+                .map_err(into_status)?
+            }
+            "remove" => {
+                let removed_credential: Credential =
+                    BasicCredential::new(description.removed_id.clone()).into();
+                let removed = public_group
+                    .members()
+                    .find(|member| member.credential == removed_credential)
+                    .map(|member| member.index)
+                    .ok_or(Status::invalid_argument("unknown member"))?;
+
+                ExternalProposal::new_remove::<OpenMlsRustCrypto>(
+                    removed,
+                    group_id,
+                    epoch,
+                    signer,
+                    sender_index,
+                )
+                .map_err(into_status)?
+            }
+            _ => {
+                return Err(Status::invalid_argument(
+                    "Unsupported external proposal type",
+                ))
+            }
+        };
+
+        let response = ProposalResponse {
+            proposal: proposal
+                .tls_serialize_detached()
+                .map_err(|_| Status::aborted("failed to serialize proposal"))?,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
     async fn free(&self, _request: Request<FreeRequest>) -> Result<Response<FreeResponse>, Status> {
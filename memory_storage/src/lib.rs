@@ -270,6 +270,11 @@ const OWN_LEAF_NODE_INDEX_LABEL: &[u8] = b"OwnLeafNodeIndex";
 const EPOCH_SECRETS_LABEL: &[u8] = b"EpochSecrets";
 const RESUMPTION_PSK_STORE_LABEL: &[u8] = b"ResumptionPsk";
 const MESSAGE_SECRETS_LABEL: &[u8] = b"MessageSecrets";
+const MESSAGE_BUFFER_LABEL: &[u8] = b"MessageBuffer";
+const OWN_LEAF_UPDATE_LABEL: &[u8] = b"OwnLeafUpdate";
+const EPOCH_RECORD_LABEL: &[u8] = b"EpochRecord";
+const PAST_EXPORTER_SECRETS_LABEL: &[u8] = b"PastExporterSecrets";
+const ARCHIVED_MESSAGE_SECRETS_LABEL: &[u8] = b"ArchivedMessageSecrets";
 
 impl StorageProvider<CURRENT_VERSION> for MemoryStorage {
     type Error = MemoryStorageError;
@@ -775,6 +780,188 @@ impl StorageProvider<CURRENT_VERSION> for MemoryStorage {
         self.delete::<CURRENT_VERSION>(EPOCH_SECRETS_LABEL, &serde_json::to_vec(group_id)?)
     }
 
+    fn message_buffer<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        MessageBuffer: traits::MessageBuffer<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<MessageBuffer>, Self::Error> {
+        self.read(MESSAGE_BUFFER_LABEL, &serde_json::to_vec(group_id)?)
+    }
+
+    fn own_leaf_update<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        OwnLeafUpdate: traits::OwnLeafUpdate<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<OwnLeafUpdate>, Self::Error> {
+        self.read(OWN_LEAF_UPDATE_LABEL, &serde_json::to_vec(group_id)?)
+    }
+
+    fn epoch_record<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        EpochRecord: traits::EpochRecord<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<Option<EpochRecord>, Self::Error> {
+        self.read(EPOCH_RECORD_LABEL, &group_epoch_id(group_id, epoch)?)
+    }
+
+    fn past_exporter_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        PastExporterSecrets: traits::PastExporterSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<PastExporterSecrets>, Self::Error> {
+        self.read(PAST_EXPORTER_SECRETS_LABEL, &serde_json::to_vec(group_id)?)
+    }
+
+    fn archived_message_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        ArchivedMessageSecrets: traits::ArchivedMessageSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<Option<ArchivedMessageSecrets>, Self::Error> {
+        self.read(
+            ARCHIVED_MESSAGE_SECRETS_LABEL,
+            &group_epoch_id(group_id, epoch)?,
+        )
+    }
+
+    fn write_message_buffer<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        MessageBuffer: traits::MessageBuffer<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        message_buffer: &MessageBuffer,
+    ) -> Result<(), Self::Error> {
+        self.write::<CURRENT_VERSION>(
+            MESSAGE_BUFFER_LABEL,
+            &serde_json::to_vec(group_id)?,
+            serde_json::to_vec(message_buffer)?,
+        )
+    }
+
+    fn write_own_leaf_update<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        OwnLeafUpdate: traits::OwnLeafUpdate<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        own_leaf_update: &OwnLeafUpdate,
+    ) -> Result<(), Self::Error> {
+        self.write::<CURRENT_VERSION>(
+            OWN_LEAF_UPDATE_LABEL,
+            &serde_json::to_vec(group_id)?,
+            serde_json::to_vec(own_leaf_update)?,
+        )
+    }
+
+    fn write_epoch_record<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        EpochRecord: traits::EpochRecord<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        epoch_record: &EpochRecord,
+    ) -> Result<(), Self::Error> {
+        self.write::<CURRENT_VERSION>(
+            EPOCH_RECORD_LABEL,
+            &group_epoch_id(group_id, epoch)?,
+            serde_json::to_vec(epoch_record)?,
+        )
+    }
+
+    fn write_past_exporter_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        PastExporterSecrets: traits::PastExporterSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        past_exporter_secrets: &PastExporterSecrets,
+    ) -> Result<(), Self::Error> {
+        self.write::<CURRENT_VERSION>(
+            PAST_EXPORTER_SECRETS_LABEL,
+            &serde_json::to_vec(group_id)?,
+            serde_json::to_vec(past_exporter_secrets)?,
+        )
+    }
+
+    fn write_archived_message_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        ArchivedMessageSecrets: traits::ArchivedMessageSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        archived_message_secrets: &ArchivedMessageSecrets,
+    ) -> Result<(), Self::Error> {
+        self.write::<CURRENT_VERSION>(
+            ARCHIVED_MESSAGE_SECRETS_LABEL,
+            &group_epoch_id(group_id, epoch)?,
+            serde_json::to_vec(archived_message_secrets)?,
+        )
+    }
+
+    fn delete_message_buffer<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete::<CURRENT_VERSION>(MESSAGE_BUFFER_LABEL, &serde_json::to_vec(group_id)?)
+    }
+
+    fn delete_own_leaf_update<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete::<CURRENT_VERSION>(OWN_LEAF_UPDATE_LABEL, &serde_json::to_vec(group_id)?)
+    }
+
+    fn delete_epoch_record<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<(), Self::Error> {
+        self.delete::<CURRENT_VERSION>(EPOCH_RECORD_LABEL, &group_epoch_id(group_id, epoch)?)
+    }
+
+    fn delete_past_exporter_secrets<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete::<CURRENT_VERSION>(PAST_EXPORTER_SECRETS_LABEL, &serde_json::to_vec(group_id)?)
+    }
+
+    fn delete_archived_message_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<(), Self::Error> {
+        self.delete::<CURRENT_VERSION>(
+            ARCHIVED_MESSAGE_SECRETS_LABEL,
+            &group_epoch_id(group_id, epoch)?,
+        )
+    }
+
     fn write_encryption_epoch_key_pairs<
         GroupId: traits::GroupId<CURRENT_VERSION>,
         EpochKey: traits::EpochKey<CURRENT_VERSION>,
@@ -1001,6 +1188,15 @@ fn epoch_key_pairs_id(
     Ok(key)
 }
 
+fn group_epoch_id(
+    group_id: &impl traits::GroupId<CURRENT_VERSION>,
+    epoch: &impl traits::EpochKey<CURRENT_VERSION>,
+) -> Result<Vec<u8>, <MemoryStorage as StorageProvider<CURRENT_VERSION>>::Error> {
+    let mut key = serde_json::to_vec(group_id)?;
+    key.extend_from_slice(&serde_json::to_vec(epoch)?);
+    Ok(key)
+}
+
 impl From<serde_json::Error> for MemoryStorageError {
     fn from(_: serde_json::Error) -> Self {
         Self::SerializationError
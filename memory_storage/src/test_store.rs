@@ -416,6 +416,159 @@ impl StorageProvider<V_TEST> for MemoryStorage {
         todo!()
     }
 
+    fn message_buffer<
+        GroupId: traits::GroupId<V_TEST>,
+        MessageBuffer: traits::MessageBuffer<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+    ) -> Result<Option<MessageBuffer>, Self::Error> {
+        todo!()
+    }
+
+    fn own_leaf_update<
+        GroupId: traits::GroupId<V_TEST>,
+        OwnLeafUpdate: traits::OwnLeafUpdate<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+    ) -> Result<Option<OwnLeafUpdate>, Self::Error> {
+        todo!()
+    }
+
+    fn epoch_record<
+        GroupId: traits::GroupId<V_TEST>,
+        EpochKey: traits::EpochKey<V_TEST>,
+        EpochRecord: traits::EpochRecord<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+        _epoch: &EpochKey,
+    ) -> Result<Option<EpochRecord>, Self::Error> {
+        todo!()
+    }
+
+    fn past_exporter_secrets<
+        GroupId: traits::GroupId<V_TEST>,
+        PastExporterSecrets: traits::PastExporterSecrets<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+    ) -> Result<Option<PastExporterSecrets>, Self::Error> {
+        todo!()
+    }
+
+    fn archived_message_secrets<
+        GroupId: traits::GroupId<V_TEST>,
+        EpochKey: traits::EpochKey<V_TEST>,
+        ArchivedMessageSecrets: traits::ArchivedMessageSecrets<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+        _epoch: &EpochKey,
+    ) -> Result<Option<ArchivedMessageSecrets>, Self::Error> {
+        todo!()
+    }
+
+    fn write_message_buffer<
+        GroupId: traits::GroupId<V_TEST>,
+        MessageBuffer: traits::MessageBuffer<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+        _message_buffer: &MessageBuffer,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn write_own_leaf_update<
+        GroupId: traits::GroupId<V_TEST>,
+        OwnLeafUpdate: traits::OwnLeafUpdate<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+        _own_leaf_update: &OwnLeafUpdate,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn write_epoch_record<
+        GroupId: traits::GroupId<V_TEST>,
+        EpochKey: traits::EpochKey<V_TEST>,
+        EpochRecord: traits::EpochRecord<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+        _epoch: &EpochKey,
+        _epoch_record: &EpochRecord,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn write_past_exporter_secrets<
+        GroupId: traits::GroupId<V_TEST>,
+        PastExporterSecrets: traits::PastExporterSecrets<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+        _past_exporter_secrets: &PastExporterSecrets,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn write_archived_message_secrets<
+        GroupId: traits::GroupId<V_TEST>,
+        EpochKey: traits::EpochKey<V_TEST>,
+        ArchivedMessageSecrets: traits::ArchivedMessageSecrets<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+        _epoch: &EpochKey,
+        _archived_message_secrets: &ArchivedMessageSecrets,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn delete_message_buffer<GroupId: traits::GroupId<V_TEST>>(
+        &self,
+        _group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn delete_own_leaf_update<GroupId: traits::GroupId<V_TEST>>(
+        &self,
+        _group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn delete_epoch_record<GroupId: traits::GroupId<V_TEST>, EpochKey: traits::EpochKey<V_TEST>>(
+        &self,
+        _group_id: &GroupId,
+        _epoch: &EpochKey,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn delete_past_exporter_secrets<GroupId: traits::GroupId<V_TEST>>(
+        &self,
+        _group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
+    fn delete_archived_message_secrets<
+        GroupId: traits::GroupId<V_TEST>,
+        EpochKey: traits::EpochKey<V_TEST>,
+    >(
+        &self,
+        _group_id: &GroupId,
+        _epoch: &EpochKey,
+    ) -> Result<(), Self::Error> {
+        todo!()
+    }
+
     fn clear_proposal_queue<
         GroupId: traits::GroupId<V_TEST>,
         ProposalRef: traits::ProposalRef<V_TEST>,
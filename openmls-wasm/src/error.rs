@@ -0,0 +1,84 @@
+use std::fmt::Display;
+
+use wasm_bindgen::prelude::*;
+
+/// The kind of an [`MlsError`], exported as a TypeScript enum.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A message or another input couldn't be encoded or decoded.
+    Codec,
+    /// The input was decoded but is not what the operation expects, e.g. a
+    /// commit where a welcome was expected.
+    InvalidInput,
+    /// A cryptographic operation failed.
+    Crypto,
+    /// Reading from or writing to the storage failed.
+    Storage,
+    /// The MLS group rejected the operation or message.
+    Group,
+    /// The operation needs a welcome message but none was created.
+    NoWelcome,
+}
+
+/// The error thrown by all bindings.
+///
+/// In JavaScript, `kind` can be matched against the `ErrorKind` enum and
+/// `message` describes the error.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsError {
+    kind: ErrorKind,
+    message: String,
+}
+
+#[wasm_bindgen]
+impl MlsError {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl MlsError {
+    pub(crate) fn new(kind: ErrorKind, message: impl Display) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn codec(e: impl Display) -> Self {
+        Self::new(ErrorKind::Codec, e)
+    }
+
+    pub(crate) fn crypto(e: impl std::fmt::Debug) -> Self {
+        Self::new(ErrorKind::Crypto, format!("{e:?}"))
+    }
+
+    pub(crate) fn storage(e: impl Display) -> Self {
+        Self::new(ErrorKind::Storage, e)
+    }
+
+    pub(crate) fn group(e: impl Display) -> Self {
+        Self::new(ErrorKind::Group, e)
+    }
+}
+
+impl Display for MlsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for MlsError {}
@@ -1,16 +1,26 @@
+mod error;
+mod storage;
 mod utils;
 
+pub use error::{ErrorKind, MlsError};
+pub use storage::{KeyValueStore, Storage, StorageError};
+
 use js_sys::Uint8Array;
 use openmls::{
-    credentials::{BasicCredential, CredentialWithKey},
-    framing::{MlsMessageBodyIn, MlsMessageIn, MlsMessageOut},
-    group::{GroupId, MlsGroup, MlsGroupJoinConfig, StagedWelcome},
+    credentials::{BasicCredential, Credential, CredentialWithKey},
+    framing::{
+        MlsMessageBodyIn, MlsMessageIn, MlsMessageOut, ProcessedMessage as OpenMlsProcessedMessage,
+        ProcessedMessageContent, ProtocolMessage, Sender,
+    },
+    group::{
+        GroupId, LeaveGroupError, MlsGroup, MlsGroupJoinConfig, QueuedProposal, StagedWelcome,
+    },
     key_packages::KeyPackage as OpenMlsKeyPackage,
-    prelude::SignatureScheme,
+    prelude::{LeafNodeIndex, LeafNodeParameters, Proposal, SignatureScheme},
     treesync::RatchetTreeIn,
 };
 use openmls_basic_credential::SignatureKeyPair;
-use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_rust_crypto::RustCrypto;
 use openmls_traits::{types::Ciphersuite, OpenMlsProvider};
 use tls_codec::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -28,28 +38,64 @@ extern "C" {
 /// The ciphersuite used here. Fixed in order to reduce the binary size.
 static CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519;
 
+/// The crypto and storage provider used by [`Provider`].
+#[derive(Default)]
+pub struct WasmProvider {
+    crypto: RustCrypto,
+    storage: Storage,
+}
+
+impl OpenMlsProvider for WasmProvider {
+    type CryptoProvider = RustCrypto;
+    type RandProvider = RustCrypto;
+    type StorageProvider = Storage;
+
+    fn storage(&self) -> &Self::StorageProvider {
+        &self.storage
+    }
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.crypto
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Default)]
-pub struct Provider(OpenMlsRustCrypto);
+pub struct Provider(WasmProvider);
 
-impl AsRef<OpenMlsRustCrypto> for Provider {
-    fn as_ref(&self) -> &OpenMlsRustCrypto {
+impl AsRef<WasmProvider> for Provider {
+    fn as_ref(&self) -> &WasmProvider {
         &self.0
     }
 }
 
-impl AsMut<OpenMlsRustCrypto> for Provider {
-    fn as_mut(&mut self) -> &mut OpenMlsRustCrypto {
+impl AsMut<WasmProvider> for Provider {
+    fn as_mut(&mut self) -> &mut WasmProvider {
         &mut self.0
     }
 }
 
 #[wasm_bindgen]
 impl Provider {
+    /// Create a provider that keeps all state in memory.
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Create a provider that keeps all state in the `store` supplied by the
+    /// host. Groups and identities can be loaded again from the same store
+    /// after a reload.
+    pub fn with_store(store: KeyValueStore) -> Self {
+        Self(WasmProvider {
+            crypto: RustCrypto::default(),
+            storage: Storage::new(store),
+        })
+    }
 }
 
 #[wasm_bindgen]
@@ -66,13 +112,15 @@ pub struct Identity {
 #[wasm_bindgen]
 impl Identity {
     #[wasm_bindgen(constructor)]
-    pub fn new(provider: &Provider, name: &str) -> Result<Identity, JsError> {
+    pub fn new(provider: &Provider, name: &str) -> Result<Identity, MlsError> {
         let signature_scheme = SignatureScheme::ED25519;
         let identity = name.bytes().collect();
         let credential = BasicCredential::new(identity);
-        let keypair = SignatureKeyPair::new(signature_scheme)?;
+        let keypair = SignatureKeyPair::new(signature_scheme).map_err(MlsError::crypto)?;
 
-        keypair.store(provider.0.storage())?;
+        keypair
+            .store(provider.0.storage())
+            .map_err(MlsError::storage)?;
 
         let credential_with_key = CredentialWithKey {
             credential: credential.into(),
@@ -85,6 +133,38 @@ impl Identity {
         })
     }
 
+    /// Load the identity `name` with the signature key `signature_public_key`
+    /// from the storage of the provider.
+    /// Returns `undefined` if the signature key is not in the storage.
+    pub fn load(
+        provider: &Provider,
+        name: &str,
+        signature_public_key: &[u8],
+    ) -> Result<Option<Identity>, MlsError> {
+        let Some(keypair) = SignatureKeyPair::read(
+            provider.0.storage(),
+            signature_public_key,
+            SignatureScheme::ED25519,
+        ) else {
+            return Ok(None);
+        };
+        let credential = BasicCredential::new(name.bytes().collect());
+        let credential_with_key = CredentialWithKey {
+            credential: credential.into(),
+            signature_key: keypair.public().into(),
+        };
+
+        Ok(Some(Identity {
+            credential_with_key,
+            keypair,
+        }))
+    }
+
+    /// The public signature key, which is needed to load the identity again.
+    pub fn signature_public_key(&self) -> Vec<u8> {
+        self.keypair.public().to_vec()
+    }
+
     pub fn key_package(&self, provider: &Provider) -> KeyPackage {
         KeyPackage(
             OpenMlsKeyPackage::builder()
@@ -158,21 +238,79 @@ impl Group {
         provider: &Provider,
         mut welcome: &[u8],
         ratchet_tree: RatchetTree,
-    ) -> Result<Group, JsError> {
-        let welcome = match MlsMessageIn::tls_deserialize(&mut welcome)?.extract() {
+    ) -> Result<Group, MlsError> {
+        let welcome = match MlsMessageIn::tls_deserialize(&mut welcome)
+            .map_err(MlsError::codec)?
+            .extract()
+        {
             MlsMessageBodyIn::Welcome(welcome) => Ok(welcome),
-            other => Err(openmls::error::ErrorString::from(format!(
-                "expected a message of type welcome, got {other:?}",
-            ))),
+            other => Err(MlsError::new(
+                ErrorKind::InvalidInput,
+                format!("expected a message of type welcome, got {other:?}"),
+            )),
         }?;
         let config = MlsGroupJoinConfig::builder().build();
         let mls_group =
-            StagedWelcome::new_from_welcome(&provider.0, &config, welcome, Some(ratchet_tree.0))?
-                .into_group(&provider.0)?;
+            StagedWelcome::new_from_welcome(&provider.0, &config, welcome, Some(ratchet_tree.0))
+                .map_err(MlsError::group)?
+                .into_group(&provider.0)
+                .map_err(MlsError::group)?;
 
         Ok(Group { mls_group })
     }
 
+    /// Join a group with an external commit using a serialized GroupInfo.
+    ///
+    /// The `ratchet_tree` is required if the GroupInfo doesn't contain the
+    /// ratchet tree extension. The commit has to be sent to the group and
+    /// the returned group must merge it with `merge_pending_commit` once the
+    /// delivery service accepted it.
+    pub fn join_by_external_commit(
+        provider: &Provider,
+        identity: &Identity,
+        mut group_info: &[u8],
+        ratchet_tree: Option<RatchetTree>,
+    ) -> Result<ExternalJoin, MlsError> {
+        let group_info = MlsMessageIn::tls_deserialize(&mut group_info)
+            .map_err(MlsError::codec)?
+            .into_verifiable_group_info()
+            .ok_or_else(|| {
+                MlsError::new(
+                    ErrorKind::InvalidInput,
+                    "expected a message of type group info",
+                )
+            })?;
+        let config = MlsGroupJoinConfig::builder().build();
+        let (mls_group, commit, _group_info) = MlsGroup::join_by_external_commit(
+            &provider.0,
+            &identity.keypair,
+            ratchet_tree.map(|tree| tree.0),
+            group_info,
+            &config,
+            None,
+            None,
+            &[],
+            identity.credential_with_key.clone(),
+        )
+        .map_err(MlsError::group)?;
+
+        Ok(ExternalJoin {
+            group: Group { mls_group },
+            commit: mls_message_to_u8vec(&commit)?,
+        })
+    }
+
+    /// Load the group with the id `group_id` from the storage of the
+    /// provider.
+    /// Returns `undefined` if there's no such group in the storage.
+    pub fn load(provider: &Provider, group_id: &str) -> Result<Option<Group>, MlsError> {
+        let group_id = GroupId::from_slice(group_id.as_bytes());
+        let mls_group =
+            MlsGroup::load(provider.0.storage(), &group_id).map_err(MlsError::storage)?;
+
+        Ok(mls_group.map(|mls_group| Group { mls_group }))
+    }
+
     pub fn export_ratchet_tree(&self) -> RatchetTree {
         RatchetTree(self.mls_group.export_ratchet_tree().into())
     }
@@ -182,16 +320,18 @@ impl Group {
         provider: &Provider,
         sender: &Identity,
         new_member: &KeyPackage,
-    ) -> Result<AddMessages, JsError> {
-        let (proposal_msg, _proposal_ref) =
-            self.mls_group
-                .propose_add_member(provider.as_ref(), &sender.keypair, &new_member.0)?;
+    ) -> Result<AddMessages, MlsError> {
+        let (proposal_msg, _proposal_ref) = self
+            .mls_group
+            .propose_add_member(provider.as_ref(), &sender.keypair, &new_member.0)
+            .map_err(MlsError::group)?;
 
         let (commit_msg, welcome_msg, _group_info) = self
             .mls_group
-            .commit_to_pending_proposals(&provider.0, &sender.keypair)?;
+            .commit_to_pending_proposals(&provider.0, &sender.keypair)
+            .map_err(MlsError::group)?;
 
-        let welcome_msg = welcome_msg.ok_or(NoWelcomeError)?;
+        let welcome_msg = welcome_msg.ok_or_else(no_welcome)?;
 
         let proposal = mls_message_to_uint8array(&proposal_msg);
         let commit = mls_message_to_uint8array(&commit_msg);
@@ -204,10 +344,149 @@ impl Group {
         })
     }
 
-    pub fn merge_pending_commit(&mut self, provider: &mut Provider) -> Result<(), JsError> {
+    /// Remove the members at the given leaf indices with a commit.
+    pub fn remove_members(
+        &mut self,
+        provider: &Provider,
+        sender: &Identity,
+        leaf_indices: Vec<u32>,
+    ) -> Result<CommitMessages, MlsError> {
+        let members = leaf_indices
+            .into_iter()
+            .map(LeafNodeIndex::new)
+            .collect::<Vec<_>>();
+        let (commit, welcome, group_info) = self
+            .mls_group
+            .remove_members(provider.as_ref(), &sender.keypair, &members)
+            .map_err(MlsError::group)?;
+
+        CommitMessages::new(commit, welcome, group_info.map(Into::into))
+    }
+
+    /// Update the own leaf with fresh key material with a commit.
+    pub fn self_update(
+        &mut self,
+        provider: &Provider,
+        sender: &Identity,
+    ) -> Result<CommitMessages, MlsError> {
+        let bundle = self
+            .mls_group
+            .self_update(
+                provider.as_ref(),
+                &sender.keypair,
+                LeafNodeParameters::default(),
+            )
+            .map_err(MlsError::group)?;
+        let (commit, welcome, group_info) = bundle.into_messages();
+
+        CommitMessages::new(commit, welcome, group_info)
+    }
+
+    /// Create a proposal to remove ourselves from the group.
+    ///
+    /// A SelfRemove proposal is used if the wire format policy of the group
+    /// allows it and a Remove proposal otherwise. Another member has to
+    /// commit the proposal.
+    pub fn leave(&mut self, provider: &Provider, sender: &Identity) -> Result<Vec<u8>, MlsError> {
+        let proposal = match self
+            .mls_group
+            .leave_group_via_self_remove(provider.as_ref(), &sender.keypair)
+        {
+            Err(LeaveGroupError::CannotSelfRemoveWithPureCiphertext) => self
+                .mls_group
+                .leave_group(provider.as_ref(), &sender.keypair),
+            result => result,
+        }
+        .map_err(MlsError::group)?;
+
+        mls_message_to_u8vec(&proposal)
+    }
+
+    /// Commit to all pending proposals.
+    pub fn commit_to_pending_proposals(
+        &mut self,
+        provider: &Provider,
+        sender: &Identity,
+    ) -> Result<CommitMessages, MlsError> {
+        let (commit, welcome, group_info) = self
+            .mls_group
+            .commit_to_pending_proposals(provider.as_ref(), &sender.keypair)
+            .map_err(MlsError::group)?;
+
+        CommitMessages::new(commit, welcome, group_info.map(Into::into))
+    }
+
+    /// List the proposals that were received or created and wait for a
+    /// commit.
+    pub fn pending_proposals(&self) -> Vec<PendingProposal> {
+        self.mls_group
+            .pending_proposals()
+            .map(PendingProposal::from)
+            .collect()
+    }
+
+    /// Discard all pending proposals.
+    pub fn clear_pending_proposals(&mut self, provider: &Provider) -> Result<(), MlsError> {
+        self.mls_group
+            .clear_pending_proposals(provider.as_ref().storage())
+            .map_err(MlsError::storage)
+    }
+
+    /// List the members of the group with their credentials.
+    pub fn members(&self) -> Vec<Member> {
+        self.mls_group
+            .members()
+            .map(|member| Member {
+                index: member.index.u32(),
+                identity: member.credential.serialized_content().to_vec(),
+                credential: member
+                    .credential
+                    .tls_serialize_detached()
+                    .unwrap_or_default(),
+                signature_key: member.signature_key,
+            })
+            .collect()
+    }
+
+    /// The leaf index of the own leaf.
+    pub fn own_leaf_index(&self) -> u32 {
+        self.mls_group.own_leaf_index().u32()
+    }
+
+    /// The current epoch of the group.
+    pub fn epoch(&self) -> u64 {
+        self.mls_group.epoch().as_u64()
+    }
+
+    /// The id of the group.
+    pub fn group_id(&self) -> Vec<u8> {
+        self.mls_group.group_id().to_vec()
+    }
+
+    /// Export a signed GroupInfo that lets other clients join the group with
+    /// an external commit.
+    pub fn export_group_info(
+        &self,
+        provider: &Provider,
+        signer: &Identity,
+        with_ratchet_tree: bool,
+    ) -> Result<Vec<u8>, MlsError> {
+        let group_info = self
+            .mls_group
+            .export_group_info(
+                provider.as_ref().crypto(),
+                &signer.keypair,
+                with_ratchet_tree,
+            )
+            .map_err(MlsError::group)?;
+
+        mls_message_to_u8vec(&group_info)
+    }
+
+    pub fn merge_pending_commit(&mut self, provider: &mut Provider) -> Result<(), MlsError> {
         self.mls_group
             .merge_pending_commit(provider.as_mut())
-            .map_err(|e| e.into())
+            .map_err(MlsError::group)
     }
 
     pub fn create_message(
@@ -215,49 +494,41 @@ impl Group {
         provider: &Provider,
         sender: &Identity,
         msg: &[u8],
-    ) -> Result<Vec<u8>, JsError> {
+    ) -> Result<Vec<u8>, MlsError> {
         let msg_out = &self
             .mls_group
-            .create_message(provider.as_ref(), &sender.keypair, msg)?;
-        let mut serialized = vec![];
-        msg_out.tls_serialize(&mut serialized)?;
-        Ok(serialized)
+            .create_message(provider.as_ref(), &sender.keypair, msg)
+            .map_err(MlsError::group)?;
+        mls_message_to_u8vec(msg_out)
     }
 
+    /// Process a message for the group.
+    ///
+    /// Proposals are stored until they are committed and commits are merged
+    /// right away. The returned [`ProcessedMessage`] describes the content.
     pub fn process_message(
         &mut self,
         provider: &mut Provider,
         mut msg: &[u8],
-    ) -> Result<Vec<u8>, JsError> {
-        let msg = MlsMessageIn::tls_deserialize(&mut msg).unwrap();
-
-        let msg = match msg.extract() {
-            openmls::framing::MlsMessageBodyIn::PublicMessage(msg) => {
-                self.mls_group.process_message(provider.as_ref(), msg)?
-            }
-
-            openmls::framing::MlsMessageBodyIn::PrivateMessage(msg) => {
-                self.mls_group.process_message(provider.as_ref(), msg)?
+    ) -> Result<ProcessedMessage, MlsError> {
+        let msg = MlsMessageIn::tls_deserialize(&mut msg).map_err(MlsError::codec)?;
+
+        let msg: ProtocolMessage = match msg.extract() {
+            MlsMessageBodyIn::PublicMessage(msg) => msg.into(),
+            MlsMessageBodyIn::PrivateMessage(msg) => msg.into(),
+            other => {
+                return Err(MlsError::new(
+                    ErrorKind::InvalidInput,
+                    format!("expected a protocol message, got {other:?}"),
+                ))
             }
-            openmls::framing::MlsMessageBodyIn::Welcome(_) => todo!(),
-            openmls::framing::MlsMessageBodyIn::GroupInfo(_) => todo!(),
-            openmls::framing::MlsMessageBodyIn::KeyPackage(_) => todo!(),
         };
+        let msg = self
+            .mls_group
+            .process_message(provider.as_ref(), msg)
+            .map_err(MlsError::group)?;
 
-        match msg.into_content() {
-            openmls::framing::ProcessedMessageContent::ApplicationMessage(app_msg) => {
-                Ok(app_msg.into_bytes())
-            }
-            openmls::framing::ProcessedMessageContent::ProposalMessage(_)
-            | openmls::framing::ProcessedMessageContent::ExternalJoinProposalMessage(_) => {
-                Ok(vec![])
-            }
-            openmls::framing::ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
-                self.mls_group
-                    .merge_staged_commit(provider.as_mut(), *staged_commit)?;
-                Ok(vec![])
-            }
-        }
+        ProcessedMessage::process(&mut self.mls_group, provider, msg)
     }
 
     pub fn export_key(
@@ -266,13 +537,10 @@ impl Group {
         label: &str,
         context: &[u8],
         key_length: usize,
-    ) -> Result<Vec<u8>, JsError> {
+    ) -> Result<Vec<u8>, MlsError> {
         self.mls_group
             .export_secret(provider.as_ref().crypto(), label, context, key_length)
-            .map_err(|e| {
-                println!("export key error: {e}");
-                e.into()
-            })
+            .map_err(MlsError::group)
     }
 }
 
@@ -283,20 +551,22 @@ impl Group {
         provider: &Provider,
         sender: &Identity,
         new_member: &KeyPackage,
-    ) -> Result<NativeAddMessages, JsError> {
-        let (proposal_msg, _proposal_ref) =
-            self.mls_group
-                .propose_add_member(provider.as_ref(), &sender.keypair, &new_member.0)?;
+    ) -> Result<NativeAddMessages, MlsError> {
+        let (proposal_msg, _proposal_ref) = self
+            .mls_group
+            .propose_add_member(provider.as_ref(), &sender.keypair, &new_member.0)
+            .map_err(MlsError::group)?;
 
         let (commit_msg, welcome_msg, _group_info) = self
             .mls_group
-            .commit_to_pending_proposals(provider.as_ref(), &sender.keypair)?;
+            .commit_to_pending_proposals(provider.as_ref(), &sender.keypair)
+            .map_err(MlsError::group)?;
 
-        let welcome_msg = welcome_msg.ok_or(NoWelcomeError)?;
+        let welcome_msg = welcome_msg.ok_or_else(no_welcome)?;
 
-        let proposal = mls_message_to_u8vec(&proposal_msg);
-        let commit = mls_message_to_u8vec(&commit_msg);
-        let welcome = mls_message_to_u8vec(&welcome_msg);
+        let proposal = mls_message_to_u8vec(&proposal_msg)?;
+        let commit = mls_message_to_u8vec(&commit_msg)?;
+        let welcome = mls_message_to_u8vec(&welcome_msg)?;
 
         Ok(NativeAddMessages {
             proposal,
@@ -325,17 +595,308 @@ impl Group {
     }
 }
 
+fn no_welcome() -> MlsError {
+    MlsError::new(ErrorKind::NoWelcome, "no welcome")
+}
+
+/// The messages created by a commit.
+#[wasm_bindgen]
+pub struct CommitMessages {
+    commit: Vec<u8>,
+    welcome: Option<Vec<u8>>,
+    group_info: Option<Vec<u8>>,
+}
+
+impl CommitMessages {
+    fn new(
+        commit: MlsMessageOut,
+        welcome: Option<MlsMessageOut>,
+        group_info: Option<MlsMessageOut>,
+    ) -> Result<Self, MlsError> {
+        Ok(Self {
+            commit: mls_message_to_u8vec(&commit)?,
+            welcome: welcome.as_ref().map(mls_message_to_u8vec).transpose()?,
+            group_info: group_info.as_ref().map(mls_message_to_u8vec).transpose()?,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl CommitMessages {
+    #[wasm_bindgen(getter)]
+    pub fn commit(&self) -> Vec<u8> {
+        self.commit.clone()
+    }
+    /// The welcome for new members, if the commit added any.
+    #[wasm_bindgen(getter)]
+    pub fn welcome(&self) -> Option<Vec<u8>> {
+        self.welcome.clone()
+    }
+    /// The GroupInfo of the new epoch, if the group uses the ratchet tree
+    /// extension.
+    #[wasm_bindgen(getter)]
+    pub fn group_info(&self) -> Option<Vec<u8>> {
+        self.group_info.clone()
+    }
+}
+
+/// The result of joining a group with an external commit.
+#[wasm_bindgen]
+pub struct ExternalJoin {
+    group: Group,
+    commit: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl ExternalJoin {
+    /// The external commit that has to be sent to the group.
+    #[wasm_bindgen(getter)]
+    pub fn commit(&self) -> Vec<u8> {
+        self.commit.clone()
+    }
+
+    /// Take the new group. The external commit is still pending in it.
+    pub fn into_group(self) -> Group {
+        self.group
+    }
+}
+
+/// A member of a group.
+#[wasm_bindgen]
+pub struct Member {
+    index: u32,
+    identity: Vec<u8>,
+    credential: Vec<u8>,
+    signature_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Member {
+    /// The leaf index of the member.
+    #[wasm_bindgen(getter)]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// The serialized content of the credential, i.e. the identity for basic
+    /// credentials.
+    #[wasm_bindgen(getter)]
+    pub fn identity(&self) -> Vec<u8> {
+        self.identity.clone()
+    }
+    /// The TLS encoded credential.
+    #[wasm_bindgen(getter)]
+    pub fn credential(&self) -> Vec<u8> {
+        self.credential.clone()
+    }
+    #[wasm_bindgen(getter)]
+    pub fn signature_key(&self) -> Vec<u8> {
+        self.signature_key.clone()
+    }
+}
+
+/// A proposal that waits for a commit.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct PendingProposal {
+    proposal_type: String,
+    sender_index: Option<u32>,
+    added_identity: Option<Vec<u8>>,
+    removed_index: Option<u32>,
+}
+
+impl From<&QueuedProposal> for PendingProposal {
+    fn from(queued_proposal: &QueuedProposal) -> Self {
+        let (added_identity, removed_index) = match queued_proposal.proposal() {
+            Proposal::Add(add) => (
+                Some(identity(add.key_package().leaf_node().credential())),
+                None,
+            ),
+            Proposal::Remove(remove) => (None, Some(remove.removed().u32())),
+            _ => (None, None),
+        };
+        Self {
+            proposal_type: format!("{:?}", queued_proposal.proposal().proposal_type()),
+            sender_index: sender_index(queued_proposal.sender()),
+            added_identity,
+            removed_index,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl PendingProposal {
+    /// The type of the proposal, e.g. `Add` or `Remove`.
+    #[wasm_bindgen(getter)]
+    pub fn proposal_type(&self) -> String {
+        self.proposal_type.clone()
+    }
+    /// The leaf index of the sender if it is a member.
+    #[wasm_bindgen(getter)]
+    pub fn sender_index(&self) -> Option<u32> {
+        self.sender_index
+    }
+    /// The identity of the new member for Add proposals.
+    #[wasm_bindgen(getter)]
+    pub fn added_identity(&self) -> Option<Vec<u8>> {
+        self.added_identity.clone()
+    }
+    /// The leaf index of the removed member for Remove proposals.
+    #[wasm_bindgen(getter)]
+    pub fn removed_index(&self) -> Option<u32> {
+        self.removed_index
+    }
+}
+
+/// The kind of content of a [`ProcessedMessage`].
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessedMessageKind {
+    Application,
+    Proposal,
+    ExternalJoinProposal,
+    Commit,
+}
+
+/// A message processed by a group.
+#[wasm_bindgen]
+pub struct ProcessedMessage {
+    kind: ProcessedMessageKind,
+    epoch: u64,
+    sender_index: Option<u32>,
+    sender_identity: Vec<u8>,
+    authenticated_data: Vec<u8>,
+    application_data: Option<Vec<u8>>,
+    proposal: Option<PendingProposal>,
+    added_identities: Vec<String>,
+    removed_indices: Vec<u32>,
+    self_removed: bool,
+}
+
+impl ProcessedMessage {
+    /// Describe the message `msg`. Proposals are stored and commits are merged
+    /// into `mls_group`.
+    fn process(
+        mls_group: &mut MlsGroup,
+        provider: &mut Provider,
+        msg: OpenMlsProcessedMessage,
+    ) -> Result<Self, MlsError> {
+        let mut processed = Self {
+            kind: ProcessedMessageKind::Application,
+            epoch: msg.epoch().as_u64(),
+            sender_index: sender_index(msg.sender()),
+            sender_identity: identity(msg.credential()),
+            authenticated_data: msg.aad().to_vec(),
+            application_data: None,
+            proposal: None,
+            added_identities: vec![],
+            removed_indices: vec![],
+            self_removed: false,
+        };
+
+        match msg.into_content() {
+            ProcessedMessageContent::ApplicationMessage(app_msg) => {
+                processed.application_data = Some(app_msg.into_bytes());
+            }
+            ProcessedMessageContent::ProposalMessage(proposal) => {
+                processed.kind = ProcessedMessageKind::Proposal;
+                processed.proposal = Some(PendingProposal::from(proposal.as_ref()));
+                mls_group
+                    .store_pending_proposal(provider.as_ref().storage(), *proposal)
+                    .map_err(MlsError::storage)?;
+            }
+            ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => {
+                processed.kind = ProcessedMessageKind::ExternalJoinProposal;
+                processed.proposal = Some(PendingProposal::from(proposal.as_ref()));
+                mls_group
+                    .store_pending_proposal(provider.as_ref().storage(), *proposal)
+                    .map_err(MlsError::storage)?;
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                processed.kind = ProcessedMessageKind::Commit;
+                processed.added_identities = staged_commit
+                    .add_proposals()
+                    .map(|add| {
+                        let credential = add.add_proposal().key_package().leaf_node().credential();
+                        String::from_utf8_lossy(&identity(credential)).into_owned()
+                    })
+                    .collect();
+                processed.removed_indices = staged_commit
+                    .remove_proposals()
+                    .map(|remove| remove.remove_proposal().removed().u32())
+                    .collect();
+                processed.self_removed = staged_commit.self_removed();
+                mls_group
+                    .merge_staged_commit(provider.as_mut(), *staged_commit)
+                    .map_err(MlsError::group)?;
+            }
+        }
+
+        Ok(processed)
+    }
+}
+
 #[wasm_bindgen]
-#[derive(Debug)]
-pub struct NoWelcomeError;
+impl ProcessedMessage {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> ProcessedMessageKind {
+        self.kind
+    }
+    /// The epoch the message was sent in.
+    #[wasm_bindgen(getter)]
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+    /// The leaf index of the sender if it is a member.
+    #[wasm_bindgen(getter)]
+    pub fn sender_index(&self) -> Option<u32> {
+        self.sender_index
+    }
+    /// The identity in the credential of the sender.
+    #[wasm_bindgen(getter)]
+    pub fn sender_identity(&self) -> Vec<u8> {
+        self.sender_identity.clone()
+    }
+    #[wasm_bindgen(getter)]
+    pub fn authenticated_data(&self) -> Vec<u8> {
+        self.authenticated_data.clone()
+    }
+    /// The plaintext of application messages.
+    #[wasm_bindgen(getter)]
+    pub fn application_data(&self) -> Option<Vec<u8>> {
+        self.application_data.clone()
+    }
+    /// The proposal of proposal messages. The proposal is pending now.
+    #[wasm_bindgen(getter)]
+    pub fn proposal(&self) -> Option<PendingProposal> {
+        self.proposal.clone()
+    }
+    /// The identities of the members added by a commit.
+    #[wasm_bindgen(getter)]
+    pub fn added_identities(&self) -> Vec<String> {
+        self.added_identities.clone()
+    }
+    /// The leaf indices of the members removed by a commit.
+    #[wasm_bindgen(getter)]
+    pub fn removed_indices(&self) -> Vec<u32> {
+        self.removed_indices.clone()
+    }
+    /// Whether a commit removed us from the group.
+    #[wasm_bindgen(getter)]
+    pub fn self_removed(&self) -> bool {
+        self.self_removed
+    }
+}
 
-impl std::fmt::Display for NoWelcomeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "no welcome")
+fn sender_index(sender: &Sender) -> Option<u32> {
+    match sender {
+        Sender::Member(index) => Some(index.u32()),
+        _ => None,
     }
 }
 
-impl std::error::Error for NoWelcomeError {}
+fn identity(credential: &Credential) -> Vec<u8> {
+    credential.serialized_content().to_vec()
+}
 
 #[wasm_bindgen]
 pub struct KeyPackage(OpenMlsKeyPackage);
@@ -352,22 +913,21 @@ fn mls_message_to_uint8array(msg: &MlsMessageOut) -> Uint8Array {
     unsafe { Uint8Array::new(&Uint8Array::view(&serialized)) }
 }
 
-#[cfg(test)]
-fn mls_message_to_u8vec(msg: &MlsMessageOut) -> Vec<u8> {
-    // see https://github.com/rustwasm/wasm-bindgen/issues/1619#issuecomment-505065294
-
-    let mut serialized = vec![];
-    msg.tls_serialize(&mut serialized).unwrap();
-    serialized
+fn mls_message_to_u8vec(msg: &MlsMessageOut) -> Result<Vec<u8>, MlsError> {
+    msg.tls_serialize_detached().map_err(MlsError::codec)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn js_error_to_string(e: JsError) -> String {
-        let v: JsValue = e.into();
-        v.as_string().unwrap()
+    // The tests run natively with `cargo test` and in Node with
+    // `wasm-pack test --node`.
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    fn js_error_to_string(e: MlsError) -> String {
+        e.to_string()
     }
 
     fn create_group_alice_and_bob() -> (Provider, Identity, Group, Provider, Identity, Group) {
@@ -442,6 +1002,304 @@ mod tests {
             .map_err(js_error_to_string)
             .unwrap();
 
-        assert_eq!(alice_msg, bob_msg);
+        assert_eq!(bob_msg.kind(), ProcessedMessageKind::Application);
+        assert_eq!(bob_msg.application_data().unwrap(), alice_msg);
+        assert_eq!(bob_msg.sender_index(), Some(0));
+        assert_eq!(bob_msg.sender_identity(), b"alice");
+        assert_eq!(bob_msg.epoch(), chess_club_bob.epoch());
+    }
+
+    #[test]
+    fn members() {
+        let (_, _, chess_club_alice, _, _, chess_club_bob) = create_group_alice_and_bob();
+
+        let members = chess_club_bob.members();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].index(), 0);
+        assert_eq!(members[0].identity(), b"alice");
+        assert_eq!(members[1].index(), 1);
+        assert_eq!(members[1].identity(), b"bob");
+        assert_eq!(chess_club_bob.own_leaf_index(), 1);
+        assert_eq!(chess_club_alice.group_id(), b"chess club");
+    }
+
+    #[test]
+    fn remove_member() {
+        let (
+            mut alice_provider,
+            alice,
+            mut chess_club_alice,
+            mut bob_provider,
+            _,
+            mut chess_club_bob,
+        ) = create_group_alice_and_bob();
+
+        let messages = chess_club_alice
+            .remove_members(&alice_provider, &alice, vec![1])
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert!(messages.welcome().is_none());
+        chess_club_alice
+            .merge_pending_commit(&mut alice_provider)
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert_eq!(chess_club_alice.members().len(), 1);
+
+        let processed = chess_club_bob
+            .process_message(&mut bob_provider, &messages.commit())
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert_eq!(processed.kind(), ProcessedMessageKind::Commit);
+        assert_eq!(processed.removed_indices(), vec![1]);
+        assert!(processed.self_removed());
+    }
+
+    #[test]
+    fn self_update() {
+        let (
+            mut alice_provider,
+            _,
+            mut chess_club_alice,
+            mut bob_provider,
+            bob,
+            mut chess_club_bob,
+        ) = create_group_alice_and_bob();
+
+        let messages = chess_club_bob
+            .self_update(&bob_provider, &bob)
+            .map_err(js_error_to_string)
+            .unwrap();
+        chess_club_bob
+            .merge_pending_commit(&mut bob_provider)
+            .map_err(js_error_to_string)
+            .unwrap();
+
+        let processed = chess_club_alice
+            .process_message(&mut alice_provider, &messages.commit())
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert_eq!(processed.kind(), ProcessedMessageKind::Commit);
+        assert_eq!(processed.sender_index(), Some(1));
+        assert!(!processed.self_removed());
+        assert_eq!(chess_club_alice.epoch(), chess_club_bob.epoch());
+
+        let alice_key = chess_club_alice
+            .export_key(&alice_provider, "chess_key", &[0x30], 32)
+            .map_err(js_error_to_string)
+            .unwrap();
+        let bob_key = chess_club_bob
+            .export_key(&bob_provider, "chess_key", &[0x30], 32)
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert_eq!(alice_key, bob_key);
+    }
+
+    #[test]
+    fn leave_and_commit_pending_proposals() {
+        let (
+            mut alice_provider,
+            alice,
+            mut chess_club_alice,
+            mut bob_provider,
+            bob,
+            mut chess_club_bob,
+        ) = create_group_alice_and_bob();
+
+        let proposal = chess_club_bob
+            .leave(&bob_provider, &bob)
+            .map_err(js_error_to_string)
+            .unwrap();
+
+        let processed = chess_club_alice
+            .process_message(&mut alice_provider, &proposal)
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert_eq!(processed.kind(), ProcessedMessageKind::Proposal);
+        let pending = chess_club_alice.pending_proposals();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].proposal_type(), "Remove");
+        assert_eq!(pending[0].sender_index(), Some(1));
+        assert_eq!(pending[0].removed_index(), Some(1));
+
+        let messages = chess_club_alice
+            .commit_to_pending_proposals(&alice_provider, &alice)
+            .map_err(js_error_to_string)
+            .unwrap();
+        chess_club_alice
+            .merge_pending_commit(&mut alice_provider)
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert!(chess_club_alice.pending_proposals().is_empty());
+        assert_eq!(chess_club_alice.members().len(), 1);
+
+        let processed = chess_club_bob
+            .process_message(&mut bob_provider, &messages.commit())
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert!(processed.self_removed());
+    }
+
+    #[test]
+    fn external_join() {
+        let (mut alice_provider, alice, mut chess_club_alice, _, _, _) =
+            create_group_alice_and_bob();
+        let mut charlie_provider = Provider::new();
+        let charlie = Identity::new(&charlie_provider, "charlie")
+            .map_err(js_error_to_string)
+            .unwrap();
+
+        let group_info = chess_club_alice
+            .export_group_info(&alice_provider, &alice, true)
+            .map_err(js_error_to_string)
+            .unwrap();
+        let external_join =
+            Group::join_by_external_commit(&charlie_provider, &charlie, &group_info, None)
+                .map_err(js_error_to_string)
+                .unwrap();
+        let commit = external_join.commit();
+        let mut chess_club_charlie = external_join.into_group();
+        chess_club_charlie
+            .merge_pending_commit(&mut charlie_provider)
+            .map_err(js_error_to_string)
+            .unwrap();
+
+        let processed = chess_club_alice
+            .process_message(&mut alice_provider, &commit)
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert_eq!(processed.kind(), ProcessedMessageKind::Commit);
+        assert_eq!(processed.sender_index(), None);
+        assert_eq!(processed.sender_identity(), b"charlie");
+        assert_eq!(chess_club_alice.members().len(), 3);
+
+        let alice_key = chess_club_alice
+            .export_key(&alice_provider, "chess_key", &[0x30], 32)
+            .map_err(js_error_to_string)
+            .unwrap();
+        let charlie_key = chess_club_charlie
+            .export_key(&charlie_provider, "chess_key", &[0x30], 32)
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert_eq!(alice_key, charlie_key);
+    }
+
+    #[test]
+    fn load_from_storage() {
+        let (alice_provider, alice, chess_club_alice, mut bob_provider, _, mut chess_club_bob) =
+            create_group_alice_and_bob();
+        let signature_public_key = alice.signature_public_key();
+        let epoch = chess_club_alice.epoch();
+        drop(chess_club_alice);
+        drop(alice);
+
+        let alice = Identity::load(&alice_provider, "alice", &signature_public_key)
+            .map_err(js_error_to_string)
+            .unwrap()
+            .unwrap();
+        let mut chess_club_alice = Group::load(&alice_provider, "chess club")
+            .map_err(js_error_to_string)
+            .unwrap()
+            .unwrap();
+        assert_eq!(chess_club_alice.epoch(), epoch);
+        assert!(Group::load(&alice_provider, "checkers club")
+            .map_err(js_error_to_string)
+            .unwrap()
+            .is_none());
+
+        let msg_out = chess_club_alice
+            .create_message(&alice_provider, &alice, b"still here")
+            .map_err(js_error_to_string)
+            .unwrap();
+        let processed = chess_club_bob
+            .process_message(&mut bob_provider, &msg_out)
+            .map_err(js_error_to_string)
+            .unwrap();
+        assert_eq!(processed.application_data().unwrap(), b"still here");
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen(inline_js = "
+        export function plain_object_store() {
+            const values = {};
+            return {
+                get: (key) => values[key],
+                set: (key, value) => { values[key] = value; },
+                delete: (key) => { delete values[key]; },
+            };
+        }
+    ")]
+    extern "C" {
+        fn plain_object_store() -> KeyValueStore;
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn js_store() {
+        let store: JsValue = plain_object_store().into();
+        let mut alice_provider = Provider::with_store(store.clone().into());
+        let mut bob_provider = Provider::new();
+        let alice = Identity::new(&alice_provider, "alice").unwrap();
+        let bob = Identity::new(&bob_provider, "bob").unwrap();
+
+        let mut chess_club_alice = Group::create_new(&alice_provider, &alice, "chess club");
+        let add_msgs = chess_club_alice
+            .native_propose_and_commit_add(&alice_provider, &alice, &bob.key_package(&bob_provider))
+            .unwrap();
+        chess_club_alice
+            .merge_pending_commit(&mut alice_provider)
+            .unwrap();
+        let mut chess_club_bob = Group::native_join(
+            &bob_provider,
+            &add_msgs.welcome,
+            chess_club_alice.export_ratchet_tree(),
+        );
+        let signature_public_key = alice.signature_public_key();
+
+        // Reload everything from the JS store with a fresh provider.
+        let alice_provider = Provider::with_store(store.into());
+        let alice = Identity::load(&alice_provider, "alice", &signature_public_key)
+            .unwrap()
+            .unwrap();
+        let mut chess_club_alice = Group::load(&alice_provider, "chess club").unwrap().unwrap();
+        assert_eq!(chess_club_alice.members().len(), 2);
+
+        let msg_out = chess_club_alice
+            .create_message(&alice_provider, &alice, b"hello from the store")
+            .unwrap();
+        let processed = chess_club_bob
+            .process_message(&mut bob_provider, &msg_out)
+            .unwrap();
+        assert_eq!(
+            processed.application_data().unwrap(),
+            b"hello from the store"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let (mut alice_provider, alice, mut chess_club_alice, _, _, _) =
+            create_group_alice_and_bob();
+
+        let error = chess_club_alice
+            .process_message(&mut alice_provider, &[0xff, 0xff])
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::Codec);
+
+        let group_info = chess_club_alice
+            .export_group_info(&alice_provider, &alice, false)
+            .map_err(js_error_to_string)
+            .unwrap();
+        let error = chess_club_alice
+            .process_message(&mut alice_provider, &group_info)
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+        let error = chess_club_alice
+            .remove_members(&alice_provider, &alice, vec![])
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::Group);
     }
 }
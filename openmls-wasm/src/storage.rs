@@ -0,0 +1,940 @@
+//! # Storage
+//!
+//! The [`Storage`] implements the OpenMLS [`StorageProvider`] on top of a
+//! simple key/value store. By default, values are kept in memory. With a
+//! [`KeyValueStore`] supplied by the host, all reads, writes and deletes are
+//! delegated to JavaScript, such that the state can be persisted in e.g.
+//! `localStorage`, IndexedDB or a file in Node.
+//!
+//! The store is called synchronously. Hosts with an asynchronous backend like
+//! IndexedDB have to keep a synchronous cache and write it back on their own.
+
+use std::{cell::RefCell, collections::HashMap, fmt::Display};
+
+use openmls_traits::storage::{traits, StorageProvider, CURRENT_VERSION};
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const KEY_VALUE_STORE: &'static str = r#"
+/**
+ * A synchronous key/value store for the OpenMLS state.
+ * Keys are hex strings and `get` returns `undefined` for missing keys.
+ */
+export interface KeyValueStore {
+  get(key: string): Uint8Array | undefined;
+  set(key: string, value: Uint8Array): void;
+  delete(key: string): void;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A key/value store implemented in JavaScript.
+    #[wasm_bindgen(typescript_type = "KeyValueStore")]
+    pub type KeyValueStore;
+
+    #[wasm_bindgen(method, catch, js_name = get)]
+    fn get_value(this: &KeyValueStore, key: &str) -> Result<JsValue, JsValue>;
+
+    // The value is passed as a copy that the store can keep.
+    #[wasm_bindgen(method, catch, js_name = set)]
+    fn set_value(this: &KeyValueStore, key: &str, value: Vec<u8>) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = delete)]
+    fn delete_value(this: &KeyValueStore, key: &str) -> Result<(), JsValue>;
+}
+
+/// Errors of the [`Storage`].
+#[derive(Debug)]
+pub enum StorageError {
+    /// The JavaScript store threw an exception.
+    Js(String),
+    /// A value couldn't be serialized or deserialized.
+    Serialization(String),
+    /// A value that is referenced by another one is missing.
+    NotFound,
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Js(e) => write!(f, "the key/value store failed: {e}"),
+            StorageError::Serialization(e) => write!(f, "error serializing value: {e}"),
+            StorageError::NotFound => write!(f, "a referenced value is missing"),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization(e.to_string())
+    }
+}
+
+impl From<JsValue> for StorageError {
+    fn from(e: JsValue) -> Self {
+        Self::Js(e.as_string().unwrap_or_else(|| format!("{e:?}")))
+    }
+}
+
+enum Backend {
+    Memory(RefCell<HashMap<String, Vec<u8>>>),
+    Js(KeyValueStore),
+}
+
+/// The storage provider of the bindings.
+pub struct Storage {
+    backend: Backend,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self {
+            backend: Backend::Memory(RefCell::default()),
+        }
+    }
+}
+
+const KEY_PACKAGE_LABEL: &[u8] = b"KeyPackage";
+const PSK_LABEL: &[u8] = b"Psk";
+const ENCRYPTION_KEY_PAIR_LABEL: &[u8] = b"EncryptionKeyPair";
+const SIGNATURE_KEY_PAIR_LABEL: &[u8] = b"SignatureKeyPair";
+const EPOCH_KEY_PAIRS_LABEL: &[u8] = b"EpochKeyPairs";
+
+// related to PublicGroup
+const TREE_LABEL: &[u8] = b"Tree";
+const GROUP_CONTEXT_LABEL: &[u8] = b"GroupContext";
+const INTERIM_TRANSCRIPT_HASH_LABEL: &[u8] = b"InterimTranscriptHash";
+const CONFIRMATION_TAG_LABEL: &[u8] = b"ConfirmationTag";
+
+// related to MlsGroup
+const JOIN_CONFIG_LABEL: &[u8] = b"MlsGroupJoinConfig";
+const OWN_LEAF_NODES_LABEL: &[u8] = b"OwnLeafNodes";
+const GROUP_STATE_LABEL: &[u8] = b"GroupState";
+const QUEUED_PROPOSAL_LABEL: &[u8] = b"QueuedProposal";
+const PROPOSAL_QUEUE_REFS_LABEL: &[u8] = b"ProposalQueueRefs";
+const OWN_LEAF_NODE_INDEX_LABEL: &[u8] = b"OwnLeafNodeIndex";
+const EPOCH_SECRETS_LABEL: &[u8] = b"EpochSecrets";
+const RESUMPTION_PSK_STORE_LABEL: &[u8] = b"ResumptionPsk";
+const MESSAGE_SECRETS_LABEL: &[u8] = b"MessageSecrets";
+const MESSAGE_BUFFER_LABEL: &[u8] = b"MessageBuffer";
+const OWN_LEAF_UPDATE_LABEL: &[u8] = b"OwnLeafUpdate";
+const EPOCH_RECORD_LABEL: &[u8] = b"EpochRecord";
+const PAST_EXPORTER_SECRETS_LABEL: &[u8] = b"PastExporterSecrets";
+const ARCHIVED_MESSAGE_SECRETS_LABEL: &[u8] = b"ArchivedMessageSecrets";
+
+impl Storage {
+    /// Create a storage that delegates to the JavaScript `store`.
+    pub fn new(store: KeyValueStore) -> Self {
+        Self {
+            backend: Backend::Js(store),
+        }
+    }
+
+    fn get_raw(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        match &self.backend {
+            Backend::Memory(values) => Ok(values.borrow().get(key).cloned()),
+            Backend::Js(store) => {
+                let value = store.get_value(key)?;
+                if value.is_undefined() || value.is_null() {
+                    Ok(None)
+                } else {
+                    Ok(Some(js_sys::Uint8Array::new(&value).to_vec()))
+                }
+            }
+        }
+    }
+
+    fn set_raw(&self, key: &str, value: &[u8]) -> Result<(), StorageError> {
+        match &self.backend {
+            Backend::Memory(values) => {
+                values.borrow_mut().insert(key.to_owned(), value.to_vec());
+                Ok(())
+            }
+            Backend::Js(store) => Ok(store.set_value(key, value.to_vec())?),
+        }
+    }
+
+    fn delete_raw(&self, key: &str) -> Result<(), StorageError> {
+        match &self.backend {
+            Backend::Memory(values) => {
+                values.borrow_mut().remove(key);
+                Ok(())
+            }
+            Backend::Js(store) => Ok(store.delete_value(key)?),
+        }
+    }
+
+    fn write(
+        &self,
+        label: &[u8],
+        key: &impl Serialize,
+        value: &impl Serialize,
+    ) -> Result<(), StorageError> {
+        self.set_raw(&build_key(label, key)?, &serde_json::to_vec(value)?)
+    }
+
+    fn read<V: DeserializeOwned>(
+        &self,
+        label: &[u8],
+        key: &impl Serialize,
+    ) -> Result<Option<V>, StorageError> {
+        match self.get_raw(&build_key(label, key)?)? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn delete(&self, label: &[u8], key: &impl Serialize) -> Result<(), StorageError> {
+        self.delete_raw(&build_key(label, key)?)
+    }
+
+    fn read_list<V: DeserializeOwned>(
+        &self,
+        label: &[u8],
+        key: &impl Serialize,
+    ) -> Result<Vec<V>, StorageError> {
+        let list: Vec<Vec<u8>> = self.read(label, key)?.unwrap_or_default();
+        list.iter()
+            .map(|value| serde_json::from_slice(value).map_err(StorageError::from))
+            .collect()
+    }
+
+    fn append(
+        &self,
+        label: &[u8],
+        key: &impl Serialize,
+        value: &impl Serialize,
+    ) -> Result<(), StorageError> {
+        let mut list: Vec<Vec<u8>> = self.read(label, key)?.unwrap_or_default();
+        list.push(serde_json::to_vec(value)?);
+        self.write(label, key, &list)
+    }
+
+    fn remove_item(
+        &self,
+        label: &[u8],
+        key: &impl Serialize,
+        value: &impl Serialize,
+    ) -> Result<(), StorageError> {
+        let mut list: Vec<Vec<u8>> = self.read(label, key)?.unwrap_or_default();
+        let value = serde_json::to_vec(value)?;
+        if let Some(pos) = list.iter().position(|stored_item| stored_item == &value) {
+            list.remove(pos);
+        }
+        self.write(label, key, &list)
+    }
+}
+
+/// Build the hex encoded key for `key` with label and version.
+fn build_key(label: &[u8], key: &impl Serialize) -> Result<String, StorageError> {
+    let mut key_out = label.to_vec();
+    key_out.extend_from_slice(&serde_json::to_vec(key)?);
+    key_out.extend_from_slice(&u16::to_be_bytes(CURRENT_VERSION));
+    Ok(key_out.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+impl StorageProvider<CURRENT_VERSION> for Storage {
+    type Error = StorageError;
+
+    fn queue_proposal<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ProposalRef: traits::ProposalRef<CURRENT_VERSION>,
+        QueuedProposal: traits::QueuedProposal<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        proposal_ref: &ProposalRef,
+        proposal: &QueuedProposal,
+    ) -> Result<(), Self::Error> {
+        self.write(QUEUED_PROPOSAL_LABEL, &(group_id, proposal_ref), proposal)?;
+        self.append(PROPOSAL_QUEUE_REFS_LABEL, group_id, proposal_ref)
+    }
+
+    fn write_tree<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        TreeSync: traits::TreeSync<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        tree: &TreeSync,
+    ) -> Result<(), Self::Error> {
+        self.write(TREE_LABEL, group_id, tree)
+    }
+
+    fn write_interim_transcript_hash<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        InterimTranscriptHash: traits::InterimTranscriptHash<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        interim_transcript_hash: &InterimTranscriptHash,
+    ) -> Result<(), Self::Error> {
+        self.write(
+            INTERIM_TRANSCRIPT_HASH_LABEL,
+            group_id,
+            interim_transcript_hash,
+        )
+    }
+
+    fn write_context<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        GroupContext: traits::GroupContext<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        group_context: &GroupContext,
+    ) -> Result<(), Self::Error> {
+        self.write(GROUP_CONTEXT_LABEL, group_id, group_context)
+    }
+
+    fn write_confirmation_tag<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ConfirmationTag: traits::ConfirmationTag<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        confirmation_tag: &ConfirmationTag,
+    ) -> Result<(), Self::Error> {
+        self.write(CONFIRMATION_TAG_LABEL, group_id, confirmation_tag)
+    }
+
+    fn write_signature_key_pair<
+        SignaturePublicKey: traits::SignaturePublicKey<CURRENT_VERSION>,
+        SignatureKeyPair: traits::SignatureKeyPair<CURRENT_VERSION>,
+    >(
+        &self,
+        public_key: &SignaturePublicKey,
+        signature_key_pair: &SignatureKeyPair,
+    ) -> Result<(), Self::Error> {
+        self.write(SIGNATURE_KEY_PAIR_LABEL, public_key, signature_key_pair)
+    }
+
+    fn queued_proposal_refs<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ProposalRef: traits::ProposalRef<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Vec<ProposalRef>, Self::Error> {
+        self.read_list(PROPOSAL_QUEUE_REFS_LABEL, group_id)
+    }
+
+    fn queued_proposals<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ProposalRef: traits::ProposalRef<CURRENT_VERSION>,
+        QueuedProposal: traits::QueuedProposal<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Vec<(ProposalRef, QueuedProposal)>, Self::Error> {
+        let refs: Vec<ProposalRef> = self.read_list(PROPOSAL_QUEUE_REFS_LABEL, group_id)?;
+
+        refs.into_iter()
+            .map(|proposal_ref| -> Result<_, StorageError> {
+                let proposal = self
+                    .read(QUEUED_PROPOSAL_LABEL, &(group_id, &proposal_ref))?
+                    .ok_or(StorageError::NotFound)?;
+                Ok((proposal_ref, proposal))
+            })
+            .collect()
+    }
+
+    fn tree<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        TreeSync: traits::TreeSync<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<TreeSync>, Self::Error> {
+        self.read(TREE_LABEL, group_id)
+    }
+
+    fn group_context<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        GroupContext: traits::GroupContext<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<GroupContext>, Self::Error> {
+        self.read(GROUP_CONTEXT_LABEL, group_id)
+    }
+
+    fn interim_transcript_hash<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        InterimTranscriptHash: traits::InterimTranscriptHash<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<InterimTranscriptHash>, Self::Error> {
+        self.read(INTERIM_TRANSCRIPT_HASH_LABEL, group_id)
+    }
+
+    fn confirmation_tag<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ConfirmationTag: traits::ConfirmationTag<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<ConfirmationTag>, Self::Error> {
+        self.read(CONFIRMATION_TAG_LABEL, group_id)
+    }
+
+    fn signature_key_pair<
+        SignaturePublicKey: traits::SignaturePublicKey<CURRENT_VERSION>,
+        SignatureKeyPair: traits::SignatureKeyPair<CURRENT_VERSION>,
+    >(
+        &self,
+        public_key: &SignaturePublicKey,
+    ) -> Result<Option<SignatureKeyPair>, Self::Error> {
+        self.read(SIGNATURE_KEY_PAIR_LABEL, public_key)
+    }
+
+    fn write_key_package<
+        HashReference: traits::HashReference<CURRENT_VERSION>,
+        KeyPackage: traits::KeyPackage<CURRENT_VERSION>,
+    >(
+        &self,
+        hash_ref: &HashReference,
+        key_package: &KeyPackage,
+    ) -> Result<(), Self::Error> {
+        self.write(KEY_PACKAGE_LABEL, hash_ref, key_package)
+    }
+
+    fn write_psk<
+        PskId: traits::PskId<CURRENT_VERSION>,
+        PskBundle: traits::PskBundle<CURRENT_VERSION>,
+    >(
+        &self,
+        psk_id: &PskId,
+        psk: &PskBundle,
+    ) -> Result<(), Self::Error> {
+        self.write(PSK_LABEL, psk_id, psk)
+    }
+
+    fn write_encryption_key_pair<
+        EncryptionKey: traits::EncryptionKey<CURRENT_VERSION>,
+        HpkeKeyPair: traits::HpkeKeyPair<CURRENT_VERSION>,
+    >(
+        &self,
+        public_key: &EncryptionKey,
+        key_pair: &HpkeKeyPair,
+    ) -> Result<(), Self::Error> {
+        self.write(ENCRYPTION_KEY_PAIR_LABEL, public_key, key_pair)
+    }
+
+    fn key_package<
+        KeyPackageRef: traits::HashReference<CURRENT_VERSION>,
+        KeyPackage: traits::KeyPackage<CURRENT_VERSION>,
+    >(
+        &self,
+        hash_ref: &KeyPackageRef,
+    ) -> Result<Option<KeyPackage>, Self::Error> {
+        self.read(KEY_PACKAGE_LABEL, hash_ref)
+    }
+
+    fn psk<PskBundle: traits::PskBundle<CURRENT_VERSION>, PskId: traits::PskId<CURRENT_VERSION>>(
+        &self,
+        psk_id: &PskId,
+    ) -> Result<Option<PskBundle>, Self::Error> {
+        self.read(PSK_LABEL, psk_id)
+    }
+
+    fn encryption_key_pair<
+        HpkeKeyPair: traits::HpkeKeyPair<CURRENT_VERSION>,
+        EncryptionKey: traits::EncryptionKey<CURRENT_VERSION>,
+    >(
+        &self,
+        public_key: &EncryptionKey,
+    ) -> Result<Option<HpkeKeyPair>, Self::Error> {
+        self.read(ENCRYPTION_KEY_PAIR_LABEL, public_key)
+    }
+
+    fn delete_signature_key_pair<
+        SignaturePublicKeuy: traits::SignaturePublicKey<CURRENT_VERSION>,
+    >(
+        &self,
+        public_key: &SignaturePublicKeuy,
+    ) -> Result<(), Self::Error> {
+        self.delete(SIGNATURE_KEY_PAIR_LABEL, public_key)
+    }
+
+    fn delete_encryption_key_pair<EncryptionKey: traits::EncryptionKey<CURRENT_VERSION>>(
+        &self,
+        public_key: &EncryptionKey,
+    ) -> Result<(), Self::Error> {
+        self.delete(ENCRYPTION_KEY_PAIR_LABEL, public_key)
+    }
+
+    fn delete_key_package<KeyPackageRef: traits::HashReference<CURRENT_VERSION>>(
+        &self,
+        hash_ref: &KeyPackageRef,
+    ) -> Result<(), Self::Error> {
+        self.delete(KEY_PACKAGE_LABEL, hash_ref)
+    }
+
+    fn delete_psk<PskKey: traits::PskId<CURRENT_VERSION>>(
+        &self,
+        psk_id: &PskKey,
+    ) -> Result<(), Self::Error> {
+        self.delete(PSK_LABEL, psk_id)
+    }
+
+    fn group_state<
+        GroupState: traits::GroupState<CURRENT_VERSION>,
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<GroupState>, Self::Error> {
+        self.read(GROUP_STATE_LABEL, group_id)
+    }
+
+    fn write_group_state<
+        GroupState: traits::GroupState<CURRENT_VERSION>,
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        group_state: &GroupState,
+    ) -> Result<(), Self::Error> {
+        self.write(GROUP_STATE_LABEL, group_id, group_state)
+    }
+
+    fn delete_group_state<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(GROUP_STATE_LABEL, group_id)
+    }
+
+    fn message_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        MessageSecrets: traits::MessageSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<MessageSecrets>, Self::Error> {
+        self.read(MESSAGE_SECRETS_LABEL, group_id)
+    }
+
+    fn write_message_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        MessageSecrets: traits::MessageSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        message_secrets: &MessageSecrets,
+    ) -> Result<(), Self::Error> {
+        self.write(MESSAGE_SECRETS_LABEL, group_id, message_secrets)
+    }
+
+    fn delete_message_secrets<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(MESSAGE_SECRETS_LABEL, group_id)
+    }
+
+    fn resumption_psk_store<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ResumptionPskStore: traits::ResumptionPskStore<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<ResumptionPskStore>, Self::Error> {
+        self.read(RESUMPTION_PSK_STORE_LABEL, group_id)
+    }
+
+    fn write_resumption_psk_store<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ResumptionPskStore: traits::ResumptionPskStore<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        resumption_psk_store: &ResumptionPskStore,
+    ) -> Result<(), Self::Error> {
+        self.write(RESUMPTION_PSK_STORE_LABEL, group_id, resumption_psk_store)
+    }
+
+    fn delete_all_resumption_psk_secrets<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(RESUMPTION_PSK_STORE_LABEL, group_id)
+    }
+
+    fn own_leaf_index<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        LeafNodeIndex: traits::LeafNodeIndex<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<LeafNodeIndex>, Self::Error> {
+        self.read(OWN_LEAF_NODE_INDEX_LABEL, group_id)
+    }
+
+    fn write_own_leaf_index<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        LeafNodeIndex: traits::LeafNodeIndex<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        own_leaf_index: &LeafNodeIndex,
+    ) -> Result<(), Self::Error> {
+        self.write(OWN_LEAF_NODE_INDEX_LABEL, group_id, own_leaf_index)
+    }
+
+    fn delete_own_leaf_index<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(OWN_LEAF_NODE_INDEX_LABEL, group_id)
+    }
+
+    fn group_epoch_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        GroupEpochSecrets: traits::GroupEpochSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<GroupEpochSecrets>, Self::Error> {
+        self.read(EPOCH_SECRETS_LABEL, group_id)
+    }
+
+    fn write_group_epoch_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        GroupEpochSecrets: traits::GroupEpochSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        group_epoch_secrets: &GroupEpochSecrets,
+    ) -> Result<(), Self::Error> {
+        self.write(EPOCH_SECRETS_LABEL, group_id, group_epoch_secrets)
+    }
+
+    fn delete_group_epoch_secrets<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(EPOCH_SECRETS_LABEL, group_id)
+    }
+
+    fn message_buffer<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        MessageBuffer: traits::MessageBuffer<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<MessageBuffer>, Self::Error> {
+        self.read(MESSAGE_BUFFER_LABEL, group_id)
+    }
+
+    fn own_leaf_update<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        OwnLeafUpdate: traits::OwnLeafUpdate<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<OwnLeafUpdate>, Self::Error> {
+        self.read(OWN_LEAF_UPDATE_LABEL, group_id)
+    }
+
+    fn epoch_record<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        EpochRecord: traits::EpochRecord<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<Option<EpochRecord>, Self::Error> {
+        self.read(EPOCH_RECORD_LABEL, &(group_id, epoch))
+    }
+
+    fn past_exporter_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        PastExporterSecrets: traits::PastExporterSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<PastExporterSecrets>, Self::Error> {
+        self.read(PAST_EXPORTER_SECRETS_LABEL, group_id)
+    }
+
+    fn archived_message_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        ArchivedMessageSecrets: traits::ArchivedMessageSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<Option<ArchivedMessageSecrets>, Self::Error> {
+        self.read(ARCHIVED_MESSAGE_SECRETS_LABEL, &(group_id, epoch))
+    }
+
+    fn write_message_buffer<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        MessageBuffer: traits::MessageBuffer<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        message_buffer: &MessageBuffer,
+    ) -> Result<(), Self::Error> {
+        self.write(MESSAGE_BUFFER_LABEL, group_id, message_buffer)
+    }
+
+    fn write_own_leaf_update<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        OwnLeafUpdate: traits::OwnLeafUpdate<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        own_leaf_update: &OwnLeafUpdate,
+    ) -> Result<(), Self::Error> {
+        self.write(OWN_LEAF_UPDATE_LABEL, group_id, own_leaf_update)
+    }
+
+    fn write_epoch_record<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        EpochRecord: traits::EpochRecord<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        epoch_record: &EpochRecord,
+    ) -> Result<(), Self::Error> {
+        self.write(EPOCH_RECORD_LABEL, &(group_id, epoch), epoch_record)
+    }
+
+    fn write_past_exporter_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        PastExporterSecrets: traits::PastExporterSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        past_exporter_secrets: &PastExporterSecrets,
+    ) -> Result<(), Self::Error> {
+        self.write(PAST_EXPORTER_SECRETS_LABEL, group_id, past_exporter_secrets)
+    }
+
+    fn write_archived_message_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        ArchivedMessageSecrets: traits::ArchivedMessageSecrets<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        archived_message_secrets: &ArchivedMessageSecrets,
+    ) -> Result<(), Self::Error> {
+        self.write(
+            ARCHIVED_MESSAGE_SECRETS_LABEL,
+            &(group_id, epoch),
+            archived_message_secrets,
+        )
+    }
+
+    fn delete_message_buffer<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(MESSAGE_BUFFER_LABEL, group_id)
+    }
+
+    fn delete_own_leaf_update<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(OWN_LEAF_UPDATE_LABEL, group_id)
+    }
+
+    fn delete_epoch_record<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<(), Self::Error> {
+        self.delete(EPOCH_RECORD_LABEL, &(group_id, epoch))
+    }
+
+    fn delete_past_exporter_secrets<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(PAST_EXPORTER_SECRETS_LABEL, group_id)
+    }
+
+    fn delete_archived_message_secrets<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<(), Self::Error> {
+        self.delete(ARCHIVED_MESSAGE_SECRETS_LABEL, &(group_id, epoch))
+    }
+
+    fn write_encryption_epoch_key_pairs<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        HpkeKeyPair: traits::HpkeKeyPair<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        leaf_index: u32,
+        key_pairs: &[HpkeKeyPair],
+    ) -> Result<(), Self::Error> {
+        self.write(
+            EPOCH_KEY_PAIRS_LABEL,
+            &(group_id, epoch, leaf_index),
+            &key_pairs,
+        )
+    }
+
+    fn encryption_epoch_key_pairs<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+        HpkeKeyPair: traits::HpkeKeyPair<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        leaf_index: u32,
+    ) -> Result<Vec<HpkeKeyPair>, Self::Error> {
+        Ok(self
+            .read(EPOCH_KEY_PAIRS_LABEL, &(group_id, epoch, leaf_index))?
+            .unwrap_or_default())
+    }
+
+    fn delete_encryption_epoch_key_pairs<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        EpochKey: traits::EpochKey<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        leaf_index: u32,
+    ) -> Result<(), Self::Error> {
+        self.delete(EPOCH_KEY_PAIRS_LABEL, &(group_id, epoch, leaf_index))
+    }
+
+    fn clear_proposal_queue<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ProposalRef: traits::ProposalRef<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        let refs: Vec<ProposalRef> = self.read_list(PROPOSAL_QUEUE_REFS_LABEL, group_id)?;
+        for proposal_ref in refs {
+            self.delete(QUEUED_PROPOSAL_LABEL, &(group_id, proposal_ref))?;
+        }
+        self.delete(PROPOSAL_QUEUE_REFS_LABEL, group_id)
+    }
+
+    fn mls_group_join_config<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        MlsGroupJoinConfig: traits::MlsGroupJoinConfig<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<MlsGroupJoinConfig>, Self::Error> {
+        self.read(JOIN_CONFIG_LABEL, group_id)
+    }
+
+    fn write_mls_join_config<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        MlsGroupJoinConfig: traits::MlsGroupJoinConfig<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        config: &MlsGroupJoinConfig,
+    ) -> Result<(), Self::Error> {
+        self.write(JOIN_CONFIG_LABEL, group_id, config)
+    }
+
+    fn own_leaf_nodes<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        LeafNode: traits::LeafNode<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Vec<LeafNode>, Self::Error> {
+        self.read_list(OWN_LEAF_NODES_LABEL, group_id)
+    }
+
+    fn append_own_leaf_node<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        LeafNode: traits::LeafNode<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        leaf_node: &LeafNode,
+    ) -> Result<(), Self::Error> {
+        self.append(OWN_LEAF_NODES_LABEL, group_id, leaf_node)
+    }
+
+    fn delete_own_leaf_nodes<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(OWN_LEAF_NODES_LABEL, group_id)
+    }
+
+    fn delete_group_config<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(JOIN_CONFIG_LABEL, group_id)
+    }
+
+    fn delete_tree<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(TREE_LABEL, group_id)
+    }
+
+    fn delete_confirmation_tag<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(CONFIRMATION_TAG_LABEL, group_id)
+    }
+
+    fn delete_context<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(GROUP_CONTEXT_LABEL, group_id)
+    }
+
+    fn delete_interim_transcript_hash<GroupId: traits::GroupId<CURRENT_VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        self.delete(INTERIM_TRANSCRIPT_HASH_LABEL, group_id)
+    }
+
+    fn remove_proposal<
+        GroupId: traits::GroupId<CURRENT_VERSION>,
+        ProposalRef: traits::ProposalRef<CURRENT_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        proposal_ref: &ProposalRef,
+    ) -> Result<(), Self::Error> {
+        self.remove_item(PROPOSAL_QUEUE_REFS_LABEL, group_id, proposal_ref)?;
+        self.delete(QUEUED_PROPOSAL_LABEL, &(group_id, proposal_ref))
+    }
+}
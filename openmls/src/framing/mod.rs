@@ -62,6 +62,7 @@ pub(crate) mod mls_auth_content;
 pub(crate) mod mls_auth_content_in;
 pub(crate) mod mls_content;
 pub(crate) mod mls_content_in;
+pub(crate) mod padding;
 pub(crate) mod private_message;
 pub(crate) mod private_message_in;
 pub(crate) mod public_message;
@@ -88,6 +89,7 @@ pub mod errors;
 
 pub use message_in::*;
 pub use message_out::*;
+pub use padding::*;
 pub use private_message::*;
 pub use private_message_in::*;
 pub use public_message::*;
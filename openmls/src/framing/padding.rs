@@ -0,0 +1,149 @@
+//! # Padding of private messages
+//!
+//! The content of a [`PrivateMessage`] is padded with zero bytes before it is
+//! encrypted, so that the length of the ciphertext reveals less about the
+//! length of the content. How many bytes are added is decided by a
+//! [`PaddingPolicy`].
+//!
+//! [`PaddingStrategy`] provides the built-in policies, which can be
+//! configured per group and separately for application and handshake
+//! messages in the [`MlsGroupJoinConfig`]. Applications that need a custom
+//! policy can implement [`PaddingPolicy`] and pass it to
+//! [`MlsGroup::create_message_with_padding()`].
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(doc)]
+use crate::group::{MlsGroup, MlsGroupJoinConfig};
+
+#[cfg(doc)]
+use super::PrivateMessage;
+
+/// Decides how many bytes of padding are added to the content of a
+/// [`PrivateMessage`].
+pub trait PaddingPolicy {
+    /// Returns the number of zero bytes to append to content that has the
+    /// given `length` once encrypted without padding, i.e. including the
+    /// AEAD tag.
+    fn padding_length(&self, length: usize) -> usize;
+
+    /// Returns the length of the encrypted content with padding.
+    fn padded_length(&self, length: usize) -> usize {
+        length + self.padding_length(length)
+    }
+}
+
+/// The built-in padding policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaddingStrategy {
+    /// Pads to a multiple of the given block size in bytes. A block size of 0
+    /// disables padding.
+    FixedBlock(usize),
+    /// Pads to the next power of two.
+    PowerOfTwo,
+    /// Pads with the Padmé scheme, which hides the least significant bits of
+    /// the length while adding at most 12% of overhead. See
+    /// [Reducing Metadata Leakage from Encrypted Files and Communication with PURBs](https://arxiv.org/abs/1806.03160).
+    Padme,
+    /// Pads every message to the given size in bytes, so that all messages
+    /// up to that size have the same length. Larger messages are padded to a
+    /// multiple of the size.
+    ConstantSize(usize),
+}
+
+impl Default for PaddingStrategy {
+    fn default() -> Self {
+        PaddingStrategy::FixedBlock(0)
+    }
+}
+
+impl PaddingPolicy for PaddingStrategy {
+    fn padding_length(&self, length: usize) -> usize {
+        match *self {
+            PaddingStrategy::FixedBlock(0) | PaddingStrategy::ConstantSize(0) => 0,
+            PaddingStrategy::FixedBlock(block_size) => {
+                (block_size - (length % block_size)) % block_size
+            }
+            PaddingStrategy::PowerOfTwo => length
+                .checked_next_power_of_two()
+                .map_or(0, |padded_length| padded_length - length),
+            PaddingStrategy::Padme => padme(length) - length,
+            PaddingStrategy::ConstantSize(size) if length <= size => size - length,
+            PaddingStrategy::ConstantSize(size) => (size - (length % size)) % size,
+        }
+    }
+}
+
+/// Returns the length padded with the Padmé scheme, which leaks only
+/// O(log log L) bits of the length L.
+fn padme(length: usize) -> usize {
+    if length < 2 {
+        return length;
+    }
+    // E = floor(log2(L)), S = floor(log2(E)) + 1
+    let exponent = usize::BITS - 1 - length.leading_zeros();
+    let significant_bits = u32::BITS - exponent.leading_zeros();
+    let last_bits = exponent - significant_bits;
+    let bit_mask = (1usize << last_bits) - 1;
+    // Padmé never exceeds the next power of two, so this can only overflow
+    // for lengths close to usize::MAX.
+    length.saturating_add(bit_mask) & !bit_mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_block() {
+        let strategy = PaddingStrategy::FixedBlock(16);
+        assert_eq!(strategy.padded_length(1), 16);
+        assert_eq!(strategy.padded_length(16), 16);
+        assert_eq!(strategy.padded_length(17), 32);
+        assert_eq!(PaddingStrategy::FixedBlock(0).padding_length(17), 0);
+    }
+
+    #[test]
+    fn power_of_two() {
+        let strategy = PaddingStrategy::PowerOfTwo;
+        assert_eq!(strategy.padded_length(1), 1);
+        assert_eq!(strategy.padded_length(17), 32);
+        assert_eq!(strategy.padded_length(1024), 1024);
+        assert_eq!(strategy.padded_length(1025), 2048);
+    }
+
+    #[test]
+    fn padme_lengths() {
+        let strategy = PaddingStrategy::Padme;
+        for (length, padded_length) in [
+            (0, 0),
+            (1, 1),
+            (7, 7),
+            (8, 8),
+            (9, 10),
+            (33, 36),
+            (100, 104),
+            (1000, 1024),
+            (1025, 1088),
+            (65_537, 67_584),
+        ] {
+            assert_eq!(strategy.padded_length(length), padded_length);
+        }
+
+        // The overhead is at most 12%.
+        for length in 1..10_000 {
+            let padded_length = strategy.padded_length(length);
+            assert!(padded_length >= length);
+            assert!(padded_length * 100 <= length * 112);
+        }
+    }
+
+    #[test]
+    fn constant_size() {
+        let strategy = PaddingStrategy::ConstantSize(1024);
+        assert_eq!(strategy.padded_length(1), 1024);
+        assert_eq!(strategy.padded_length(1024), 1024);
+        assert_eq!(strategy.padded_length(1025), 2048);
+        assert_eq!(PaddingStrategy::ConstantSize(0).padding_length(17), 0);
+    }
+}
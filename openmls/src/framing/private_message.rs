@@ -72,7 +72,7 @@ impl PrivateMessage {
         public_message: &AuthenticatedContent,
         ciphersuite: Ciphersuite,
         message_secrets: &mut MessageSecrets,
-        padding_size: usize,
+        padding: &impl PaddingPolicy,
     ) -> Result<PrivateMessage, MessageEncryptionError<T>> {
         log::debug!("PrivateMessage::try_from_authenticated_content");
         log::trace!("  ciphersuite: {}", ciphersuite);
@@ -87,7 +87,7 @@ impl PrivateMessage {
             public_message,
             ciphersuite,
             message_secrets,
-            padding_size,
+            padding,
         )
     }
 
@@ -107,7 +107,7 @@ impl PrivateMessage {
             public_message,
             ciphersuite,
             message_secrets,
-            padding_size,
+            &PaddingStrategy::FixedBlock(padding_size),
         )
     }
 
@@ -128,7 +128,7 @@ impl PrivateMessage {
             public_message,
             ciphersuite,
             message_secrets,
-            padding_size,
+            &PaddingStrategy::FixedBlock(padding_size),
         )
     }
 
@@ -141,7 +141,7 @@ impl PrivateMessage {
         public_message: &AuthenticatedContent,
         ciphersuite: Ciphersuite,
         message_secrets: &mut MessageSecrets,
-        padding_size: usize,
+        padding: &impl PaddingPolicy,
     ) -> Result<PrivateMessage, MessageEncryptionError<T>> {
         // https://validation.openmls.tech/#valn1305
         let sender_index = if let Some(index) = public_message.sender().as_member() {
@@ -190,7 +190,7 @@ impl PrivateMessage {
                 crypto,
                 &Self::encode_padded_ciphertext_content_detached(
                     public_message,
-                    padding_size,
+                    padding,
                     ciphersuite.mac_length(),
                 )
                 .map_err(LibraryError::missing_bound_check)?,
@@ -261,7 +261,7 @@ impl PrivateMessage {
     /// Encodes the `PrivateMessageContent` struct with padding.
     fn encode_padded_ciphertext_content_detached(
         authenticated_content: &AuthenticatedContent,
-        padding_size: usize,
+        padding: &impl PaddingPolicy,
         mac_len: usize,
     ) -> Result<Vec<u8>, tls_codec::Error> {
         let plaintext_length = authenticated_content
@@ -269,15 +269,8 @@ impl PrivateMessage {
             .serialized_len_without_type()
             + authenticated_content.auth.tls_serialized_len();
 
-        let padding_length = if padding_size > 0 {
-            // Calculate padding block size.
-            // Only the AEAD tag is added.
-            let padding_offset = plaintext_length + mac_len;
-            // Return padding block size
-            (padding_size - (padding_offset % padding_size)) % padding_size
-        } else {
-            0
-        };
+        // Only the AEAD tag is added.
+        let padding_length = padding.padding_length(plaintext_length + mac_len);
 
         // Persist all initial fields manually (avoids cloning them)
         let buffer = &mut Vec::with_capacity(plaintext_length + padding_length);
@@ -274,7 +274,7 @@ fn wire_format_checks() {
             &plaintext,
             ciphersuite,
             &mut message_secrets,
-            0,
+            &PaddingStrategy::default(),
         )
         .expect_err("Could encrypt despite wrong wire format."),
         MessageEncryptionError::WrongWireFormat
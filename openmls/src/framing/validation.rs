@@ -265,6 +265,7 @@ impl UnverifiedMessage {
 
 /// A message that has passed all syntax and semantics checks.
 #[derive(Debug)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(Clone, PartialEq))]
 pub struct ProcessedMessage {
     group_id: GroupId,
     epoch: GroupEpoch,
@@ -335,6 +336,7 @@ impl ProcessedMessage {
 /// See the content variants' documentation for more information.
 /// [`StagedCommit`] and [`QueuedProposal`] can be inspected for authorization purposes.
 #[derive(Debug)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(Clone, PartialEq))]
 pub enum ProcessedMessageContent {
     /// An application message.
     ///
@@ -365,6 +367,7 @@ pub enum ProcessedMessageContent {
 
 /// Application message received through a [ProcessedMessage].
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(Clone))]
 pub struct ApplicationMessage {
     bytes: Vec<u8>,
 }
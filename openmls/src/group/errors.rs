@@ -5,9 +5,11 @@
 use thiserror::Error;
 
 pub use super::mls_group::errors::*;
+use super::mls_group::proposal_store::QueuedProposal;
 use super::public_group::errors::CreationFromExternalError;
 use crate::{
-    ciphersuite::signable::SignatureError,
+    binary_tree::LeafNodeIndex,
+    ciphersuite::{hash_ref::ProposalRef, signable::SignatureError},
     error::LibraryError,
     extensions::errors::{ExtensionError, InvalidExtensionError},
     framing::errors::MessageDecryptionError,
@@ -182,6 +184,9 @@ pub enum StageCommitError {
     /// See [`ProposalValidationError`] for more details.
     #[error(transparent)]
     ProposalValidationError(#[from] ProposalValidationError),
+    /// See [`ProposalValidationReport`] for more details.
+    #[error(transparent)]
+    InvalidProposal(#[from] ProposalValidationReport),
     /// See [`PskError`] for more details.
     #[error(transparent)]
     PskError(#[from] PskError),
@@ -231,6 +236,9 @@ pub enum CreateCommitError {
     /// See [`ProposalValidationError`] for more details.
     #[error(transparent)]
     ProposalValidationError(#[from] ProposalValidationError),
+    /// See [`ProposalValidationReport`] for more details.
+    #[error(transparent)]
+    InvalidProposal(#[from] ProposalValidationReport),
     /// See [`SignatureError`] for more details.
     #[error(transparent)]
     SignatureError(#[from] SignatureError),
@@ -408,6 +416,166 @@ pub enum ProposalValidationError {
     /// Regular Commits may not contain ExternalInit proposals, but one was found
     #[error("Found ExternalInit proposal in regular commit")]
     ExternalInitProposalInRegularCommit,
+    /// A Commit with a ReInit proposal contains other proposals.
+    #[error("A Commit with a ReInit proposal contains other proposals.")]
+    ReInitWithOtherProposals,
+}
+
+/// A validation rule for proposals, named after its identifier in the
+/// [validation overview](https://validation.openmls.tech).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProposalValidationRule {
+    /// Add Proposal: Signature public key in proposals must be unique among
+    /// proposals & members.
+    ValSem101,
+    /// Add Proposal: Init key in proposals must be unique among proposals.
+    ValSem102,
+    /// Add Proposal: Encryption key in proposals must be unique among
+    /// proposals & members.
+    ValSem103,
+    /// Add Proposal: Init key and encryption key must be different.
+    ValSem104,
+    /// Add Proposal: Ciphersuite & protocol version must match the group.
+    ValSem105,
+    /// Add Proposal: Required capabilities.
+    ValSem106,
+    /// Remove Proposal: Removed member must be unique among proposals.
+    ValSem107,
+    /// Remove Proposal: Removed member must be an existing group member.
+    ValSem108,
+    /// Update Proposal: Required capabilities.
+    ValSem109,
+    /// Update Proposal: Encryption key must be unique among proposals &
+    /// members.
+    ValSem110,
+    /// Update Proposal: The sender of a full Commit must not include own
+    /// update proposals.
+    ValSem111,
+    /// Update Proposal: The sender of a standalone update proposal must be of
+    /// type member.
+    ValSem112,
+    /// All Proposals: The proposal type must be supported by all members of
+    /// the group.
+    ValSem113,
+    /// All Proposals: A Commit with a ReInit proposal must not contain any
+    /// other proposal.
+    ValSem114,
+    /// Commit: Path leaf node encryption key must be unique among proposals &
+    /// members.
+    ValSem206,
+    /// Commit: Path encryption keys must be unique among proposals & members.
+    ValSem207,
+    /// PreSharedKey Proposal: The nonce of a PreSharedKeyID must have length
+    /// KDF.Nh.
+    ValSem401,
+    /// PreSharedKey Proposal: The PSK must be of type Resumption (with usage
+    /// Application) or External.
+    ValSem402,
+    /// PreSharedKey Proposal: The proposal list must not contain multiple
+    /// PreSharedKey proposals that reference the same PreSharedKeyID.
+    ValSem403,
+    /// Add Proposal: The leaf node of the key package must be valid.
+    Valn0202,
+    /// Commit: Regular commits must not contain ExternalInit proposals.
+    Valn0310,
+    /// Update Proposal: The leaf node must be valid.
+    Valn0601,
+}
+
+impl std::fmt::Display for ProposalValidationRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Valn0202 => write!(f, "valn0202"),
+            Self::Valn0310 => write!(f, "valn0310"),
+            Self::Valn0601 => write!(f, "valn0601"),
+            rule => write!(f, "{rule:?}"),
+        }
+    }
+}
+
+/// Report on a proposal that failed validation.
+///
+/// In addition to the [`ProposalValidationError`], the report names the
+/// violated [`ProposalValidationRule`], the failing proposal and the leaves
+/// involved in the violation, such as the removed member or the member whose
+/// key collides with the proposal.
+#[derive(Error, Debug, PartialEq, Clone)]
+#[error("Proposal validation failed ({}): {}", .0.rule, .0.error)]
+pub struct ProposalValidationReport(Box<ProposalValidationReportInner>);
+
+// The report is boxed to keep the errors that carry it small.
+#[derive(Debug, PartialEq, Clone)]
+struct ProposalValidationReportInner {
+    error: ProposalValidationError,
+    rule: ProposalValidationRule,
+    proposal_ref: Option<ProposalRef>,
+    proposal_index: Option<usize>,
+    leaves: Vec<LeafNodeIndex>,
+}
+
+impl ProposalValidationReport {
+    pub(crate) fn new(
+        error: impl Into<ProposalValidationError>,
+        rule: ProposalValidationRule,
+    ) -> Self {
+        Self(Box::new(ProposalValidationReportInner {
+            error: error.into(),
+            rule,
+            proposal_ref: None,
+            proposal_index: None,
+            leaves: Vec::new(),
+        }))
+    }
+
+    /// Attach the failing proposal and its position in the proposal list.
+    pub(crate) fn with_proposal(mut self, index: usize, proposal: &QueuedProposal) -> Self {
+        self.0.proposal_ref = Some(proposal.proposal_reference());
+        self.0.proposal_index = Some(index);
+        self
+    }
+
+    /// Attach the leaves involved in the violation.
+    pub(crate) fn with_leaves(mut self, leaves: impl IntoIterator<Item = LeafNodeIndex>) -> Self {
+        for leaf in leaves {
+            if !self.0.leaves.contains(&leaf) {
+                self.0.leaves.push(leaf);
+            }
+        }
+        self
+    }
+
+    /// Returns the validation error.
+    pub fn error(&self) -> &ProposalValidationError {
+        &self.0.error
+    }
+
+    /// Returns the violated validation rule.
+    pub fn rule(&self) -> ProposalValidationRule {
+        self.0.rule
+    }
+
+    /// Returns the reference of the failing proposal, if the violation can be
+    /// attributed to a single proposal.
+    pub fn proposal_ref(&self) -> Option<&ProposalRef> {
+        self.0.proposal_ref.as_ref()
+    }
+
+    /// Returns the position of the failing proposal in the list of proposals
+    /// covered by the commit.
+    pub fn proposal_index(&self) -> Option<usize> {
+        self.0.proposal_index
+    }
+
+    /// Returns the leaves involved in the violation.
+    pub fn leaves(&self) -> &[LeafNodeIndex] {
+        &self.0.leaves
+    }
+}
+
+impl From<ProposalValidationReport> for ProposalValidationError {
+    fn from(report: ProposalValidationReport) -> Self {
+        report.0.error
+    }
 }
 
 /// External Commit validaton error
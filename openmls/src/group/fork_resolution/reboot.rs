@@ -24,10 +24,22 @@ impl MlsGroup {
         let group_builder = MlsGroup::builder()
             .with_wire_format_policy(self.configuration().wire_format_policy)
             .padding_size(self.configuration().padding_size)
+            .application_padding(self.configuration().application_padding())
+            .handshake_padding(self.configuration().handshake_padding())
             .max_past_epochs(self.configuration().max_past_epochs)
             .number_of_resumption_psks(self.configuration().number_of_resumption_psks)
             .use_ratchet_tree_extension(self.configuration().use_ratchet_tree_extension)
             .sender_ratchet_configuration(self.configuration().sender_ratchet_configuration)
+            .application_sender_ratchet_configuration(
+                *self
+                    .configuration()
+                    .application_sender_ratchet_configuration(),
+            )
+            .handshake_sender_ratchet_configuration(
+                *self
+                    .configuration()
+                    .handshake_sender_ratchet_configuration(),
+            )
             .ciphersuite(self.ciphersuite())
             .with_group_id(group_id);
 
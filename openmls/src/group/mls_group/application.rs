@@ -18,6 +18,20 @@ impl MlsGroup {
         provider: &Provider,
         signer: &impl Signer,
         message: &[u8],
+    ) -> Result<MlsMessageOut, CreateMessageError> {
+        let padding = self.configuration().application_padding();
+        self.create_message_with_padding(provider, signer, message, &padding)
+    }
+
+    /// Creates an application message like [`Self::create_message()`], but
+    /// pads it with the given `padding` policy instead of the configured
+    /// [`MlsGroupJoinConfig::application_padding()`].
+    pub fn create_message_with_padding<Provider: OpenMlsProvider>(
+        &mut self,
+        provider: &Provider,
+        signer: &impl Signer,
+        message: &[u8],
+        padding: &impl PaddingPolicy,
     ) -> Result<MlsMessageOut, CreateMessageError> {
         if !self.is_active() {
             return Err(CreateMessageError::GroupStateError(
@@ -38,7 +52,7 @@ impl MlsGroup {
             signer,
         )?;
         let ciphertext = self
-            .encrypt(authenticated_content, provider)
+            .encrypt_with_padding(authenticated_content, provider, padding)
             // We know the application message is wellformed and we have the key material of the current epoch
             .map_err(|_| LibraryError::custom("Malformed plaintext"))?;
 
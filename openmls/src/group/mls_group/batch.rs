@@ -0,0 +1,171 @@
+//! Processing of message batches.
+//!
+//! Clients that were offline for a while typically receive many messages at
+//! once. [`MlsGroup::process_messages()`] processes such a batch in order and
+//! applies a [`ProcessingPolicy`] to the result of each message, instead of
+//! leaving every proposal and commit to the application.
+
+use super::{
+    errors::{BatchError, ProcessMessageError, ProcessMessagesError},
+    *,
+};
+
+/// Determines what [`MlsGroup::process_messages()`] does with processed
+/// handshake messages. Application messages are always returned to the
+/// caller.
+///
+/// By default, proposals are stored and commits are merged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProcessingPolicy {
+    store_proposals: bool,
+    merge_commits: bool,
+}
+
+impl Default for ProcessingPolicy {
+    fn default() -> Self {
+        Self {
+            store_proposals: true,
+            merge_commits: true,
+        }
+    }
+}
+
+impl ProcessingPolicy {
+    /// Sets whether proposals, including external join proposals, are stored
+    /// in the group's proposal queue.
+    pub fn store_proposals(mut self, store_proposals: bool) -> Self {
+        self.store_proposals = store_proposals;
+        self
+    }
+
+    /// Sets whether valid commits are merged into the group.
+    pub fn merge_commits(mut self, merge_commits: bool) -> Self {
+        self.merge_commits = merge_commits;
+        self
+    }
+}
+
+/// The outcome of a single message processed by
+/// [`MlsGroup::process_messages()`].
+#[derive(Debug)]
+pub enum MessageOutcome {
+    /// An application message.
+    ApplicationMessage(ProcessedMessage),
+    /// A proposal that was stored in the group's proposal queue.
+    ProposalStored(Box<QueuedProposal>),
+    /// A commit that was merged. Contains the group's new epoch.
+    CommitMerged(GroupEpoch),
+    /// A handshake message that the [`ProcessingPolicy`] leaves to the
+    /// application.
+    Processed(ProcessedMessage),
+    /// The message could not be processed.
+    Failed(ProcessMessageError),
+}
+
+impl MlsGroup {
+    /// Processes a batch of messages in the given order and applies the
+    /// `policy` to each processed message. Returns one [`MessageOutcome`] per
+    /// message.
+    ///
+    /// A message that fails to process doesn't stop the batch. Note that
+    /// messages for the next epoch fail if the commit that precedes them isn't
+    /// merged, either because it is invalid or because of the `policy`.
+    ///
+    /// Stored proposals and the ratchet state used to decrypt the messages are
+    /// written to storage before each commit is merged and after the last
+    /// message, instead of after every message. Merging a commit writes the
+    /// new epoch to storage right away, because processing the following
+    /// messages requires its key material from storage.
+    ///
+    /// # Errors:
+    /// Returns a [`BatchError`] if a commit can't be merged or if writing to
+    /// storage fails. The batch stops at that point, and the error contains
+    /// the outcomes of the messages processed before, including decrypted
+    /// application messages whose keys are already consumed. If a merge
+    /// failed, the commit is the message after the last of these outcomes.
+    /// The group's state in storage matches its state in memory: it contains
+    /// the proposals stored and the commits merged so far.
+    pub fn process_messages<Provider: OpenMlsProvider>(
+        &mut self,
+        provider: &Provider,
+        messages: impl IntoIterator<Item = impl Into<ProtocolMessage>>,
+        policy: ProcessingPolicy,
+    ) -> Result<Vec<MessageOutcome>, BatchError<Provider::StorageError>> {
+        let mut outcomes = vec![];
+        match self.process_messages_into(provider, messages, policy, &mut outcomes) {
+            Ok(()) => Ok(outcomes),
+            Err(e) => Err(BatchError::new(outcomes, e)),
+        }
+    }
+
+    /// Processes the `messages` as described in
+    /// [`MlsGroup::process_messages()`] and appends their outcomes to
+    /// `outcomes`, which the caller keeps on error.
+    fn process_messages_into<Provider: OpenMlsProvider>(
+        &mut self,
+        provider: &Provider,
+        messages: impl IntoIterator<Item = impl Into<ProtocolMessage>>,
+        policy: ProcessingPolicy,
+        outcomes: &mut Vec<MessageOutcome>,
+    ) -> Result<(), ProcessMessagesError<Provider::StorageError>> {
+        // Proposals stored in this epoch that still need to be written.
+        let mut new_proposals = vec![];
+
+        for message in messages {
+            let processed_message = match self.process_message(provider, message) {
+                Ok(processed_message) => processed_message,
+                Err(e) => {
+                    outcomes.push(MessageOutcome::Failed(e));
+                    continue;
+                }
+            };
+
+            let outcome = match processed_message.content() {
+                ProcessedMessageContent::ApplicationMessage(_) => {
+                    MessageOutcome::ApplicationMessage(processed_message)
+                }
+                ProcessedMessageContent::ProposalMessage(proposal)
+                | ProcessedMessageContent::ExternalJoinProposalMessage(proposal)
+                    if policy.store_proposals =>
+                {
+                    let proposal = proposal.clone();
+                    self.proposal_store_mut().add((*proposal).clone());
+                    new_proposals.push((*proposal).clone());
+                    MessageOutcome::ProposalStored(proposal)
+                }
+                ProcessedMessageContent::StagedCommitMessage(_) if policy.merge_commits => {
+                    // Persist the state of the current epoch first, so that
+                    // storage doesn't miss it if the merge fails.
+                    self.write_batch_state(provider.storage(), new_proposals.drain(..))?;
+                    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+                        processed_message.into_content()
+                    {
+                        self.merge_staged_commit(provider, *staged_commit)?;
+                    }
+                    MessageOutcome::CommitMerged(self.epoch())
+                }
+                _ => MessageOutcome::Processed(processed_message),
+            };
+            outcomes.push(outcome);
+        }
+
+        self.write_batch_state(provider.storage(), new_proposals)
+    }
+
+    /// Writes the proposals stored while processing a batch and the ratchet
+    /// state to storage.
+    fn write_batch_state<Storage: StorageProvider>(
+        &self,
+        storage: &Storage,
+        new_proposals: impl IntoIterator<Item = QueuedProposal>,
+    ) -> Result<(), ProcessMessagesError<Storage::Error>> {
+        for proposal in new_proposals {
+            storage
+                .queue_proposal(self.group_id(), &proposal.proposal_reference(), &proposal)
+                .map_err(ProcessMessagesError::StorageError)?;
+        }
+        storage
+            .write_message_secrets(self.group_id(), &self.message_secrets_store)
+            .map_err(ProcessMessagesError::StorageError)
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     credentials::CredentialWithKey,
     error::LibraryError,
     extensions::{errors::InvalidExtensionError, Extensions},
+    framing::PaddingStrategy,
     group::{
         public_group::errors::PublicGroupBuildError, GroupId, MlsGroupCreateConfig,
         MlsGroupCreateConfigBuilder, NewGroupError, PublicGroup, WireFormatPolicy,
@@ -17,17 +18,24 @@ use crate::{
         JoinerSecret, KeySchedule, PreSharedKeyId,
     },
     storage::OpenMlsProvider,
-    tree::sender_ratchet::SenderRatchetConfiguration,
+    tree::sender_ratchet::{MessageKeyCacheConfig, SenderRatchetConfiguration},
     treesync::{errors::LeafNodeValidationError, node::leaf_node::Capabilities},
 };
 
-use super::{past_secrets::MessageSecretsStore, MlsGroup, MlsGroupState};
+use super::{
+    commit_history::CommitHistoryConfig,
+    message_archive::MessageArchiveConfig,
+    message_buffer::MessageBufferConfig,
+    past_secrets::{MessageSecretsStore, PastExporterSecrets},
+    Clock, GroupClock, GroupProposalPolicy, MlsGroup, MlsGroupState,
+};
 
 #[derive(Default, Debug)]
 pub struct MlsGroupBuilder {
     group_id: Option<GroupId>,
     mls_group_create_config_builder: MlsGroupCreateConfigBuilder,
     psk_ids: Vec<PreSharedKeyId>,
+    clock: GroupClock,
 }
 
 impl MlsGroupBuilder {
@@ -41,6 +49,12 @@ impl MlsGroupBuilder {
         self
     }
 
+    /// Sets the [`Clock`] of the [`MlsGroup`]. See [`MlsGroup::set_clock()`].
+    pub fn with_clock(mut self, clock: impl Clock + Send + Sync + 'static) -> Self {
+        self.clock = GroupClock::new(clock);
+        self
+    }
+
     /// Build a new group as configured by this builder.
     pub fn build<Provider: OpenMlsProvider>(
         self,
@@ -152,12 +166,24 @@ impl MlsGroupBuilder {
             mls_group_config: mls_group_create_config.join_config.clone(),
             own_leaf_nodes: vec![],
             aad: vec![],
+            proposal_policy: GroupProposalPolicy::default(),
             group_state: MlsGroupState::Operational,
+            own_leaf_update: MlsGroup::initial_own_leaf_update(
+                &self.clock,
+                public_group.group_context().epoch(),
+            ),
+            clock: self.clock,
+            replayed_messages: vec![],
             public_group,
             group_epoch_secrets,
             own_leaf_index: LeafNodeIndex::new(0),
             message_secrets_store,
             resumption_psk_store,
+            past_exporter_secrets: PastExporterSecrets::new(
+                mls_group_create_config
+                    .join_config
+                    .max_past_exporter_epochs(),
+            ),
         };
 
         mls_group
@@ -188,6 +214,22 @@ impl MlsGroupBuilder {
         self
     }
 
+    /// Sets the [`PaddingStrategy`] for application messages of the MlsGroup.
+    pub fn application_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .application_padding(padding);
+        self
+    }
+
+    /// Sets the [`PaddingStrategy`] for handshake messages of the MlsGroup.
+    pub fn handshake_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .handshake_padding(padding);
+        self
+    }
+
     /// Sets the `max_past_epochs` property of the MlsGroup.
     /// This allows application messages from previous epochs to be decrypted.
     ///
@@ -233,6 +275,89 @@ impl MlsGroupBuilder {
         self
     }
 
+    /// Sets the [`SenderRatchetConfiguration`] for application messages of
+    /// the MlsGroup.
+    pub fn application_sender_ratchet_configuration(
+        mut self,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .application_sender_ratchet_configuration(sender_ratchet_configuration);
+        self
+    }
+
+    /// Sets the [`SenderRatchetConfiguration`] for handshake messages of the
+    /// MlsGroup.
+    pub fn handshake_sender_ratchet_configuration(
+        mut self,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .handshake_sender_ratchet_configuration(sender_ratchet_configuration);
+        self
+    }
+
+    /// Sets the `message_key_cache_config` property of the MlsGroup.
+    /// See [`MessageKeyCacheConfig`] for more information.
+    pub fn message_key_cache_config(
+        mut self,
+        message_key_cache_config: MessageKeyCacheConfig,
+    ) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .message_key_cache_config(message_key_cache_config);
+        self
+    }
+
+    /// Sets the `message_buffer_config` property of the MlsGroup.
+    /// See [`MessageBufferConfig`] for more information.
+    pub fn message_buffer_config(mut self, message_buffer_config: MessageBufferConfig) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .message_buffer_config(message_buffer_config);
+        self
+    }
+
+    /// Sets the `message_archive_config` property of the MlsGroup.
+    /// See [`MlsGroupCreateConfigBuilder::message_archive_config()`] for more
+    /// information.
+    pub fn message_archive_config(mut self, message_archive_config: MessageArchiveConfig) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .message_archive_config(message_archive_config);
+        self
+    }
+
+    /// Sets the `commit_history_config` property of the MlsGroup.
+    /// See [`CommitHistoryConfig`] for more information.
+    pub fn commit_history_config(mut self, commit_history_config: CommitHistoryConfig) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .commit_history_config(commit_history_config);
+        self
+    }
+
+    /// Sets the `require_proposal_policy` property of the MlsGroup.
+    /// See [`MlsGroupJoinConfig::require_proposal_policy()`](crate::group::MlsGroupJoinConfig::require_proposal_policy()).
+    pub fn require_proposal_policy(mut self, require_proposal_policy: bool) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .require_proposal_policy(require_proposal_policy);
+        self
+    }
+
+    /// Sets the `max_past_exporter_epochs` property of the MlsGroup.
+    /// See [`MlsGroupCreateConfigBuilder::max_past_exporter_epochs()`] for
+    /// more information.
+    pub fn max_past_exporter_epochs(mut self, max_past_exporter_epochs: usize) -> Self {
+        self.mls_group_create_config_builder = self
+            .mls_group_create_config_builder
+            .max_past_exporter_epochs(max_past_exporter_epochs);
+        self
+    }
+
     /// Sets the `lifetime` of the group creator's leaf.
     pub fn lifetime(mut self, lifetime: Lifetime) -> Self {
         self.mls_group_create_config_builder =
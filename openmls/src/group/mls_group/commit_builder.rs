@@ -12,7 +12,8 @@ use crate::{
     group::{
         create_commit::CommitType, diff::compute_path::PathComputationResult,
         CommitBuilderStageError, CreateCommitError, Extension, Extensions, ExternalPubExtension,
-        ProposalQueue, ProposalQueueError, QueuedProposal, RatchetTreeExtension, StagedCommit,
+        GroupEpoch, ProposalQueue, ProposalQueueError, QueuedProposal, RatchetTreeExtension,
+        StagedCommit, ValidateProposalsError,
     },
     key_packages::KeyPackage,
     messages::{
@@ -21,7 +22,8 @@ use crate::{
     },
     prelude::{LeafNodeParameters, LibraryError, NewSignerBundle},
     schedule::{
-        psk::{load_psks, PskSecret},
+        errors::PskError,
+        psk::{load_psks, Psk, PskSecret, PskType, ResumptionPskUsage},
         BaseCommitSecret, ChildInitSecret, CommitConfirmation, CommitSecret, JoinerSecret,
         KeySchedule, PreSharedKeyId,
     },
@@ -35,8 +37,9 @@ use super::MlsGroupJoinConfig;
 use super::{
     mls_auth_content::AuthenticatedContent,
     staged_commit::{MemberStagedCommitState, StagedCommitState},
-    AddProposal, CreateCommitResult, GroupContextExtensionProposal, MlsGroup, MlsGroupState,
-    MlsMessageOut, PendingCommitState, Proposal, RemoveProposal, Sender,
+    AddProposal, CommitChanges, CommittedContent, CreateCommitResult,
+    GroupContextExtensionProposal, MlsGroup, MlsGroupState, MlsMessageOut, PendingCommitState,
+    Proposal, RemoveProposal, Sender,
 };
 
 /// This stage is for populating the builder.
@@ -49,6 +52,9 @@ pub struct Initial {
     /// Whether or not to clear the proposal queue of the group when staging the commit. Needs to
     /// be done when we include the commits that have already been queued.
     consume_proposal_store: bool,
+
+    /// Re-init or branch PSKs that are used without a PreSharedKey proposal.
+    resumption_psks: Vec<PreSharedKeyId>,
 }
 
 impl Default for Initial {
@@ -59,6 +65,7 @@ impl Default for Initial {
             leaf_node_parameters: LeafNodeParameters::default(),
             create_group_info: false,
             own_proposals: vec![],
+            resumption_psks: vec![],
         }
     }
 }
@@ -74,6 +81,7 @@ pub struct LoadedPsks {
     /// be done when we include the commits that have already been queued.
     consume_proposal_store: bool,
     psks: Vec<(PreSharedKeyId, Secret)>,
+    resumption_psks: Vec<PreSharedKeyId>,
 }
 
 /// This stage is after we validated the data, ready for staging and exporting the messages
@@ -249,6 +257,32 @@ impl<'a> CommitBuilder<'a, Initial> {
         self
     }
 
+    /// Includes the resumption PSK of another group in the key schedule of the new epoch.
+    ///
+    /// This is needed for the first commit of a group that re-initializes or branches from
+    /// another group (Section 11 of RFC 9420). The PSK must have the usage
+    /// [`ResumptionPskUsage::Reinit`] or [`ResumptionPskUsage::Branch`] and must be in the
+    /// storage (see [`PreSharedKeyId::store`]). It is sent to the new members in the Welcome
+    /// instead of a PreSharedKey proposal.
+    pub fn include_resumption_psk(mut self, psk_id: PreSharedKeyId) -> Self {
+        self.stage.resumption_psks.push(psk_id);
+        self
+    }
+
+    /// Validates the proposals marked for inclusion without building the commit.
+    ///
+    /// See [`MlsGroup::validate_proposals`] for details.
+    pub fn validate_proposals(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+    ) -> Result<(), ValidateProposalsError> {
+        self.group.validate_proposal_candidates(
+            crypto,
+            self.stage.consume_proposal_store,
+            self.stage.own_proposals.iter().cloned(),
+        )
+    }
+
     /// Loads the PSKs for the PskProposals marked for inclusion and moves on to the next phase.
     pub fn load_psks<Storage: StorageProvider>(
         self,
@@ -268,8 +302,35 @@ impl<'a> CommitBuilder<'a, Initial> {
                 Proposal::PreSharedKey(psk_proposal) => Some(psk_proposal.clone().into_psk_id()),
                 _ => None,
             })
+            .chain(self.stage.resumption_psks.iter().cloned())
             .collect();
 
+        // Re-init and branch PSKs can only be used when creating a new group.
+        for psk_id in &self.stage.resumption_psks {
+            match psk_id.psk() {
+                Psk::Resumption(resumption)
+                    if resumption.usage() == ResumptionPskUsage::Application =>
+                {
+                    return Err(PskError::UsageMismatch {
+                        allowed: vec![ResumptionPskUsage::Reinit, ResumptionPskUsage::Branch],
+                        got: resumption.usage(),
+                    }
+                    .into());
+                }
+                Psk::Resumption(_) => {}
+                Psk::External(_) => {
+                    return Err(PskError::TypeMismatch {
+                        allowed: vec![PskType::Resumption],
+                        got: PskType::External,
+                    }
+                    .into());
+                }
+            }
+            if self.group.epoch() != GroupEpoch::from(0) {
+                return Err(PskError::NotFirstCommit.into());
+            }
+        }
+
         // Load the PSKs and make the PskIds owned.
         let psks = load_psks(storage, &self.group.resumption_psk_store, &psk_ids)?
             .into_iter()
@@ -287,6 +348,7 @@ impl<'a> CommitBuilder<'a, Initial> {
                         leaf_node_parameters: stage.leaf_node_parameters,
                         consume_proposal_store: stage.consume_proposal_store,
                         create_group_info: stage.create_group_info,
+                        resumption_psks: stage.resumption_psks,
                     },
                 )
             })
@@ -376,58 +438,10 @@ impl<'a> CommitBuilder<'a, LoadedPsks> {
                 }
             })?;
 
-        // Validate the proposals by doing the following checks:
-
-        // ValSem113: All Proposals: The proposal type must be supported by all
-        // members of the group
+        // Validate the proposals
         builder
             .group
-            .public_group
-            .validate_proposal_type_support(&proposal_queue)?;
-        // ValSem101
-        // ValSem102
-        // ValSem103
-        // ValSem104
-        builder
-            .group
-            .public_group
-            .validate_key_uniqueness(&proposal_queue, None)?;
-        // ValSem105
-        builder
-            .group
-            .public_group
-            .validate_add_proposals(&proposal_queue)?;
-        // ValSem106
-        // ValSem109
-        builder
-            .group
-            .public_group
-            .validate_capabilities(&proposal_queue)?;
-        // ValSem107
-        // ValSem108
-        builder
-            .group
-            .public_group
-            .validate_remove_proposals(&proposal_queue)?;
-        builder
-            .group
-            .public_group
-            .validate_pre_shared_key_proposals(&proposal_queue)?;
-        // Validate update proposals for member commits
-        // ValSem110
-        // ValSem111
-        // ValSem112
-        builder
-            .group
-            .public_group
-            .validate_update_proposals(&proposal_queue, builder.group.own_leaf_index())?;
-
-        // ValSem208
-        // ValSem209
-        builder
-            .group
-            .public_group
-            .validate_group_context_extensions_proposal(&proposal_queue)?;
+            .validate_own_commit_proposals(&proposal_queue)?;
 
         let ciphersuite = builder.group.ciphersuite();
         let sender = Sender::build_member(builder.group.own_leaf_index());
@@ -650,13 +664,22 @@ impl<'a> CommitBuilder<'a, LoadedPsks> {
                 )
                 .map_err(LibraryError::unexpected_crypto_error)?;
 
+            // The new members need the re-init or branch PSKs as well, even though
+            // they are not proposals.
+            let presharedkeys: Vec<_> = apply_proposals_values
+                .presharedkeys
+                .iter()
+                .chain(cur_stage.resumption_psks.iter())
+                .cloned()
+                .collect();
+
             // Create group secrets for later use, so we can afterwards consume the
             // `joiner_secret`.
             let encrypted_secrets = diff.encrypt_group_secrets(
                 &joiner_secret,
                 apply_proposals_values.invitation_list,
                 path_computation_result.plain_path.as_deref(),
-                &apply_proposals_values.presharedkeys,
+                &presharedkeys,
                 &encrypted_group_info,
                 crypto,
                 builder.group.own_leaf_index(),
@@ -674,6 +697,13 @@ impl<'a> CommitBuilder<'a, LoadedPsks> {
                 builder.group.own_leaf_index(),
             );
 
+        let changes = CommitChanges::new(&builder.group.public_group, &diff, &proposal_queue);
+        let committed_content = CommittedContent::new(
+            crypto,
+            ciphersuite,
+            builder.group.own_leaf_index(),
+            &authenticated_content,
+        )?;
         let staged_commit_state = MemberStagedCommitState::new(
             old_init_secret,
             provisional_group_epoch_secrets,
@@ -688,6 +718,8 @@ impl<'a> CommitBuilder<'a, LoadedPsks> {
         let staged_commit = StagedCommit::new(
             proposal_queue,
             StagedCommitState::GroupMember(Box::new(staged_commit_state)),
+            changes,
+            committed_content,
         );
 
         Ok(builder.into_stage(Complete {
@@ -0,0 +1,202 @@
+//! Changes that a commit makes to a group.
+//!
+//! A [`CommitChanges`] is computed when a commit is staged and is available
+//! through [`StagedCommit::changes()`], so applications don't need to compare
+//! the group before and after merging the commit.
+
+use super::*;
+use crate::{group::public_group::diff::PublicGroupDiff, schedule::PreSharedKeyId};
+
+/// The changes that a [`StagedCommit`] makes to the members and the
+/// [`GroupContext`] of a group.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitChanges {
+    added: Vec<Member>,
+    removed: Vec<Member>,
+    updated: Vec<UpdatedMember>,
+    extensions: Option<ExtensionsChange>,
+    psks: Vec<PreSharedKeyId>,
+}
+
+impl CommitChanges {
+    /// Computes the changes from the `original` group to the `diff`, which
+    /// has the `proposal_queue` and the update path of the commit applied.
+    pub(crate) fn new(
+        original: &PublicGroup,
+        diff: &PublicGroupDiff,
+        proposal_queue: &ProposalQueue,
+    ) -> Self {
+        let removed_indices: Vec<LeafNodeIndex> = proposal_queue
+            .queued_proposals()
+            .filter_map(|queued_proposal| match queued_proposal.proposal() {
+                Proposal::Remove(remove_proposal) => Some(remove_proposal.removed()),
+                Proposal::SelfRemove => match queued_proposal.sender() {
+                    Sender::Member(leaf_index) => Some(*leaf_index),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        let removed = removed_indices
+            .iter()
+            .filter_map(|&leaf_index| {
+                original
+                    .leaf(leaf_index)
+                    .map(|leaf_node| member(leaf_index, leaf_node))
+            })
+            .collect();
+
+        // Leaves of removed members may have been reused for new members.
+        let mut added = vec![];
+        let mut updated = vec![];
+        for leaf_index in (0..diff.tree_size().leaf_count()).map(LeafNodeIndex::new) {
+            let Some(new_leaf) = diff.leaf(leaf_index) else {
+                continue;
+            };
+            match original.leaf(leaf_index) {
+                Some(old_leaf) if !removed_indices.contains(&leaf_index) => {
+                    if old_leaf != new_leaf {
+                        updated.push(UpdatedMember {
+                            index: leaf_index,
+                            old_credential: old_leaf.credential().clone(),
+                            new_credential: new_leaf.credential().clone(),
+                            old_signature_key: old_leaf.signature_key().as_slice().to_vec(),
+                            new_signature_key: new_leaf.signature_key().as_slice().to_vec(),
+                        });
+                    }
+                }
+                _ => added.push(member(leaf_index, new_leaf)),
+            }
+        }
+
+        let old_extensions = original.group_context().extensions();
+        let new_extensions = diff.group_context().extensions();
+        let extensions = (old_extensions != new_extensions).then(|| ExtensionsChange {
+            before: old_extensions.clone(),
+            after: new_extensions.clone(),
+        });
+
+        let psks = proposal_queue
+            .psk_proposals()
+            .map(|queued_psk_proposal| queued_psk_proposal.psk_proposal().clone().into_psk_id())
+            .collect();
+
+        Self {
+            added,
+            removed,
+            updated,
+            extensions,
+            psks,
+        }
+    }
+
+    /// Returns the members added by the commit, with their leaf index in the
+    /// new epoch. This includes a member joining through an external commit.
+    pub fn added(&self) -> &[Member] {
+        &self.added
+    }
+
+    /// Returns the removed members, with their leaf index and credential
+    /// before the commit.
+    pub fn removed(&self) -> &[Member] {
+        &self.removed
+    }
+
+    /// Returns the members whose leaf changed, either through an Update
+    /// proposal or through the update path of the committer.
+    pub fn updated(&self) -> &[UpdatedMember] {
+        &self.updated
+    }
+
+    /// Returns the change of the [`GroupContext`] extensions, if any.
+    pub fn extensions(&self) -> Option<&ExtensionsChange> {
+        self.extensions.as_ref()
+    }
+
+    /// Returns the ids of the PSKs injected by the commit.
+    pub fn psks(&self) -> &[PreSharedKeyId] {
+        &self.psks
+    }
+
+    /// Returns `true` if the commit doesn't change the group's members,
+    /// extensions or PSKs.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.updated.is_empty()
+            && self.extensions.is_none()
+            && self.psks.is_empty()
+    }
+}
+
+/// A member whose leaf was changed by a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatedMember {
+    index: LeafNodeIndex,
+    old_credential: Credential,
+    new_credential: Credential,
+    old_signature_key: Vec<u8>,
+    new_signature_key: Vec<u8>,
+}
+
+impl UpdatedMember {
+    /// Returns the member's leaf index.
+    pub fn index(&self) -> LeafNodeIndex {
+        self.index
+    }
+
+    /// Returns the member's credential before the commit.
+    pub fn old_credential(&self) -> &Credential {
+        &self.old_credential
+    }
+
+    /// Returns the member's credential after the commit.
+    pub fn new_credential(&self) -> &Credential {
+        &self.new_credential
+    }
+
+    /// Returns the member's public signature key before the commit.
+    pub fn old_signature_key(&self) -> &[u8] {
+        &self.old_signature_key
+    }
+
+    /// Returns the member's public signature key after the commit.
+    pub fn new_signature_key(&self) -> &[u8] {
+        &self.new_signature_key
+    }
+
+    /// Returns `true` if the credential or the signature key changed, i.e.
+    /// if the member's identity may have changed.
+    pub fn credential_changed(&self) -> bool {
+        self.old_credential != self.new_credential
+            || self.old_signature_key != self.new_signature_key
+    }
+}
+
+/// The [`GroupContext`] extensions before and after a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionsChange {
+    before: Extensions,
+    after: Extensions,
+}
+
+impl ExtensionsChange {
+    /// Returns the extensions before the commit.
+    pub fn before(&self) -> &Extensions {
+        &self.before
+    }
+
+    /// Returns the extensions after the commit.
+    pub fn after(&self) -> &Extensions {
+        &self.after
+    }
+}
+
+fn member(leaf_index: LeafNodeIndex, leaf_node: &LeafNode) -> Member {
+    Member::new(
+        leaf_index,
+        leaf_node.encryption_key().as_slice().to_vec(),
+        leaf_node.signature_key().as_slice().to_vec(),
+        leaf_node.credential().clone(),
+    )
+}
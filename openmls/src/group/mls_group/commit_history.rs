@@ -0,0 +1,336 @@
+//! History of the commits of a group.
+//!
+//! If a [`CommitHistoryConfig`] is set in the group's [`MlsGroupJoinConfig`],
+//! the group keeps an [`EpochRecord`] for every merged commit, one storage
+//! entry per epoch. Records of epochs more than
+//! [`CommitHistoryConfig::max_epochs()`] epochs in the past are deleted.
+//!
+//! The records are compact and don't contain the commits themselves. Together
+//! with the commits, which are available with
+//! [`StagedCommit::transcript_hash_input()`], the [`CommitHistory`] can be
+//! verified by anyone who trusts the [`GroupContext`] and [`ConfirmationTag`]
+//! of the epoch before the first record, without access to the group's
+//! secrets.
+
+use openmls_traits::crypto::OpenMlsCrypto;
+use tls_codec::{
+    Serialize as _, TlsDeserialize, TlsDeserializeBytes, TlsSerialize, TlsSize, VLBytes,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{errors::CommitHistoryError, *};
+use crate::{
+    framing::public_message::{ConfirmedTranscriptHashInput, InterimTranscriptHashInput},
+    group::errors::MergeCommitError,
+};
+
+/// Configuration of the commit history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitHistoryConfig {
+    max_epochs: usize,
+}
+
+impl CommitHistoryConfig {
+    /// Create a new configuration that keeps the records of at most
+    /// `max_epochs` epochs.
+    pub fn new(max_epochs: usize) -> Self {
+        Self { max_epochs }
+    }
+
+    /// Returns the maximum number of epochs whose records are kept.
+    pub fn max_epochs(&self) -> usize {
+        self.max_epochs
+    }
+}
+
+impl Default for CommitHistoryConfig {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+/// The part of a commit that is needed to extend the transcript hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CommittedContent {
+    committer: LeafNodeIndex,
+    commit_hash: VLBytes,
+    transcript_hash_input: VLBytes,
+}
+
+impl CommittedContent {
+    /// Extracts the transcript hash input from the `commit` sent by the member
+    /// at the `committer` leaf index in the new epoch.
+    pub(crate) fn new(
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+        committer: LeafNodeIndex,
+        commit: &AuthenticatedContent,
+    ) -> Result<Self, LibraryError> {
+        let transcript_hash_input = ConfirmedTranscriptHashInput::try_from(commit)
+            .map_err(|_| LibraryError::custom("AuthenticatedContent did not contain a commit"))?
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        let commit_hash = crypto
+            .hash(ciphersuite.hash_algorithm(), &transcript_hash_input)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        Ok(Self {
+            committer,
+            commit_hash: commit_hash.into(),
+            transcript_hash_input: transcript_hash_input.into(),
+        })
+    }
+
+    /// Returns the serialized `ConfirmedTranscriptHashInput` of the commit.
+    pub(crate) fn transcript_hash_input(&self) -> &[u8] {
+        self.transcript_hash_input.as_slice()
+    }
+}
+
+/// The record of a commit and the epoch it created.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    TlsSerialize,
+    TlsDeserialize,
+    TlsDeserializeBytes,
+    TlsSize,
+)]
+pub struct EpochRecord {
+    epoch: GroupEpoch,
+    committer: LeafNodeIndex,
+    committer_credential: Credential,
+    commit_hash: VLBytes,
+    confirmed_transcript_hash: VLBytes,
+    confirmation_tag: ConfirmationTag,
+    epoch_authenticator: VLBytes,
+    tree_hash: VLBytes,
+}
+
+impl EpochRecord {
+    /// Returns the epoch created by the commit.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the leaf index of the committer in the new epoch.
+    pub fn committer(&self) -> LeafNodeIndex {
+        self.committer
+    }
+
+    /// Returns the credential of the committer in the new epoch.
+    ///
+    /// The credential is not covered by the transcript hash and thus not
+    /// checked by [`CommitHistory::verify()`]. It is taken from the group's
+    /// ratchet tree, which the [`Self::tree_hash()`] commits to, so it can only
+    /// be verified with the ratchet tree of the epoch.
+    pub fn committer_credential(&self) -> &Credential {
+        &self.committer_credential
+    }
+
+    /// Returns the hash of the commit, i.e. of its transcript hash input.
+    pub fn commit_hash(&self) -> &[u8] {
+        self.commit_hash.as_slice()
+    }
+
+    /// Returns the confirmed transcript hash of the new epoch.
+    pub fn confirmed_transcript_hash(&self) -> &[u8] {
+        self.confirmed_transcript_hash.as_slice()
+    }
+
+    /// Returns the confirmation tag of the commit.
+    pub fn confirmation_tag(&self) -> &ConfirmationTag {
+        &self.confirmation_tag
+    }
+
+    /// Returns the epoch authenticator of the new epoch.
+    pub fn epoch_authenticator(&self) -> &[u8] {
+        self.epoch_authenticator.as_slice()
+    }
+
+    /// Returns the tree hash of the new epoch, which commits to its members.
+    pub fn tree_hash(&self) -> &[u8] {
+        self.tree_hash.as_slice()
+    }
+}
+
+/// The [`EpochRecord`]s of consecutive epochs of a group.
+///
+/// The history can be exported with its TLS serialization.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    TlsSerialize,
+    TlsDeserialize,
+    TlsDeserializeBytes,
+    TlsSize,
+)]
+pub struct CommitHistory {
+    records: Vec<EpochRecord>,
+}
+
+impl CommitHistory {
+    /// Returns the records, oldest first.
+    pub fn records(&self) -> &[EpochRecord] {
+        &self.records
+    }
+
+    /// Verifies that the records form a chain of transcript hashes that
+    /// starts at the epoch with the given `start_context` and
+    /// `start_confirmation_tag`, e.g. taken from a trusted [`GroupInfo`] of
+    /// that epoch. `transcript_hash_inputs` contains the serialized
+    /// `ConfirmedTranscriptHashInput` of the commit of every record, see
+    /// [`StagedCommit::transcript_hash_input()`].
+    ///
+    /// The epoch authenticators and tree hashes can't be verified without
+    /// the group's secrets and ratchet trees, but they are bound to the
+    /// verified transcript through the confirmation tags. The committer
+    /// credentials are not verified, see
+    /// [`EpochRecord::committer_credential()`].
+    pub fn verify(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        start_context: &GroupContext,
+        start_confirmation_tag: &ConfirmationTag,
+        transcript_hash_inputs: &[impl AsRef<[u8]>],
+    ) -> Result<(), CommitHistoryError> {
+        if transcript_hash_inputs.len() != self.records.len() {
+            return Err(CommitHistoryError::CommitCountMismatch);
+        }
+
+        let ciphersuite = start_context.ciphersuite();
+        let mut epoch = start_context.epoch();
+        let mut interim_transcript_hash = InterimTranscriptHashInput::from(start_confirmation_tag)
+            .calculate_interim_transcript_hash(
+                crypto,
+                ciphersuite,
+                start_context.confirmed_transcript_hash(),
+            )?;
+
+        for (record, transcript_hash_input) in self.records.iter().zip(transcript_hash_inputs) {
+            if record.epoch.as_u64() != epoch.as_u64() + 1 {
+                return Err(CommitHistoryError::UnexpectedEpoch);
+            }
+
+            let transcript_hash_input = transcript_hash_input.as_ref();
+            let commit_hash = crypto
+                .hash(ciphersuite.hash_algorithm(), transcript_hash_input)
+                .map_err(LibraryError::unexpected_crypto_error)?;
+            if commit_hash != record.commit_hash.as_slice() {
+                return Err(CommitHistoryError::CommitHashMismatch);
+            }
+
+            let confirmed_transcript_hash = crypto
+                .hash(
+                    ciphersuite.hash_algorithm(),
+                    &[interim_transcript_hash.as_slice(), transcript_hash_input].concat(),
+                )
+                .map_err(LibraryError::unexpected_crypto_error)?;
+            if confirmed_transcript_hash != record.confirmed_transcript_hash.as_slice() {
+                return Err(CommitHistoryError::TranscriptHashMismatch);
+            }
+
+            interim_transcript_hash = InterimTranscriptHashInput::from(&record.confirmation_tag)
+                .calculate_interim_transcript_hash(
+                    crypto,
+                    ciphersuite,
+                    &confirmed_transcript_hash,
+                )?;
+            epoch = record.epoch;
+        }
+
+        Ok(())
+    }
+}
+
+impl MlsGroup {
+    /// Returns the [`CommitHistory`] of the group. It is empty unless a
+    /// [`CommitHistoryConfig`] is set.
+    pub fn commit_history<Storage: StorageProvider>(
+        &self,
+        storage: &Storage,
+    ) -> Result<CommitHistory, Storage::Error> {
+        let mut records = vec![];
+        let mut epoch = self.epoch();
+        while let Some(record) =
+            storage.epoch_record::<_, _, EpochRecord>(self.group_id(), &epoch)?
+        {
+            records.push(record);
+            epoch = match epoch.as_u64().checked_sub(1) {
+                Some(previous) => previous.into(),
+                None => break,
+            };
+        }
+        records.reverse();
+
+        Ok(CommitHistory { records })
+    }
+
+    /// Writes the record of the current epoch, which was created by the
+    /// commit with the `committed_content`, and deletes the records that are
+    /// older than the `config` allows.
+    pub(super) fn record_epoch<Storage: StorageProvider>(
+        &self,
+        storage: &Storage,
+        committed_content: CommittedContent,
+        config: CommitHistoryConfig,
+    ) -> Result<(), MergeCommitError<Storage::Error>> {
+        let committer_credential = self
+            .public_group()
+            .leaf(committed_content.committer)
+            .ok_or_else(|| LibraryError::custom("The committer is not in the group"))?
+            .credential()
+            .clone();
+        let record = EpochRecord {
+            epoch: self.epoch(),
+            committer: committed_content.committer,
+            committer_credential,
+            commit_hash: committed_content.commit_hash,
+            confirmed_transcript_hash: self.context().confirmed_transcript_hash().to_vec().into(),
+            confirmation_tag: self.confirmation_tag().clone(),
+            epoch_authenticator: self.epoch_authenticator().as_slice().to_vec().into(),
+            tree_hash: self.context().tree_hash().to_vec().into(),
+        };
+        storage
+            .write_epoch_record(self.group_id(), &record.epoch, &record)
+            .map_err(MergeCommitError::StorageError)?;
+
+        // Usually only the oldest record expires, but more are left over if
+        // the retention was reduced.
+        if let Some(expired) = self
+            .epoch()
+            .as_u64()
+            .checked_sub(config.max_epochs() as u64)
+        {
+            self.delete_epoch_records(storage, expired.into())
+                .map_err(MergeCommitError::StorageError)?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the record of the `epoch` and of the epochs before it.
+    pub(super) fn delete_epoch_records<Storage: StorageProvider>(
+        &self,
+        storage: &Storage,
+        mut epoch: GroupEpoch,
+    ) -> Result<(), Storage::Error> {
+        while storage
+            .epoch_record::<_, _, EpochRecord>(self.group_id(), &epoch)?
+            .is_some()
+        {
+            storage.delete_epoch_record(self.group_id(), &epoch)?;
+            epoch = match epoch.as_u64().checked_sub(1) {
+                Some(previous) => previous.into(),
+                None => break,
+            };
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+//! Automatic commits of pending proposals.
+//!
+//! Which member commits pending proposals, and when, is up to the
+//! application. A [`CommitPolicyEngine`] takes this decision based on a
+//! [`CommitPolicy`]. If all members of a group use the same policy, they agree
+//! on who commits, which avoids conflicting commits.
+
+#[cfg(target_arch = "wasm32")]
+use fluvio_wasm_timer::{SystemTime, UNIX_EPOCH};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use std::collections::HashMap;
+
+use commit_builder::CommitMessageBundle;
+
+use super::{errors::PolicyCommitError, *};
+
+/// A source of the current time, in seconds since the Unix epoch.
+///
+/// The [`CommitPolicyEngine`] uses the clock to decide whether proposals have
+/// been pending for too long. Tests can provide their own implementation.
+pub trait Clock {
+    /// Returns the current time in seconds since the Unix epoch.
+    fn now(&self) -> u64;
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default()
+    }
+}
+
+/// Determines which member commits pending proposals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Committer {
+    /// Every member commits.
+    Any,
+    /// Only the member at the given leaf index commits. If that member is not
+    /// in the group or is leaving it, the member with the lowest leaf index
+    /// that isn't leaving commits instead.
+    Designated(LeafNodeIndex),
+}
+
+/// Determines when the [`CommitPolicyEngine`] commits pending proposals.
+///
+/// By default, no member commits the proposals of others, except for
+/// SelfRemove proposals. These are committed by the member with the lowest
+/// leaf index that isn't leaving the group. Members don't commit their own
+/// proposals either, because members that do so independently of each other
+/// create conflicting commits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CommitPolicy {
+    committer: Option<Committer>,
+    max_pending_proposals: Option<usize>,
+    max_delay: Option<u64>,
+    commit_own_proposals: bool,
+}
+
+impl CommitPolicy {
+    /// Sets which member commits the pending proposals of other members.
+    ///
+    /// The committer commits once one of the limits set with
+    /// [`Self::max_pending_proposals()`] and [`Self::max_delay()`] is
+    /// reached, or immediately if no limit is set. SelfRemove proposals are
+    /// always committed immediately, because the leaving member can't commit
+    /// them.
+    pub fn committer(mut self, committer: Committer) -> Self {
+        self.committer = Some(committer);
+        self
+    }
+
+    /// Sets the number of pending proposals at which the committer commits.
+    pub fn max_pending_proposals(mut self, max_pending_proposals: usize) -> Self {
+        self.max_pending_proposals = Some(max_pending_proposals);
+        self
+    }
+
+    /// Sets the number of seconds after which the committer commits pending
+    /// proposals. The delay starts when the first pending proposal of the
+    /// epoch is seen by the [`CommitPolicyEngine`].
+    pub fn max_delay(mut self, seconds: u64) -> Self {
+        self.max_delay = Some(seconds);
+        self
+    }
+
+    /// Sets whether a member immediately commits the pending proposals it
+    /// sent itself, independent of the committer. This is off by default.
+    pub fn commit_own_proposals(mut self, commit_own_proposals: bool) -> Self {
+        self.commit_own_proposals = commit_own_proposals;
+        self
+    }
+}
+
+/// Commits the pending proposals of groups according to a [`CommitPolicy`].
+///
+/// The engine keeps track of how long proposals have been pending in each
+/// group. The application calls [`Self::poll()`] whenever the proposals of a
+/// group change, e.g. after storing a proposal, and periodically if the policy
+/// has a [`CommitPolicy::max_delay()`].
+#[derive(Debug)]
+pub struct CommitPolicyEngine<C: Clock = SystemClock> {
+    policy: CommitPolicy,
+    clock: C,
+    // The epoch and the time at which pending proposals were first seen.
+    pending_since: HashMap<GroupId, (GroupEpoch, u64)>,
+}
+
+impl CommitPolicyEngine<SystemClock> {
+    /// Creates a new engine for the `policy` that uses the system clock.
+    pub fn new(policy: CommitPolicy) -> Self {
+        Self::with_clock(policy, SystemClock)
+    }
+}
+
+impl<C: Clock> CommitPolicyEngine<C> {
+    /// Creates a new engine for the `policy` that uses the given `clock`.
+    pub fn with_clock(policy: CommitPolicy, clock: C) -> Self {
+        Self {
+            policy,
+            clock,
+            pending_since: HashMap::new(),
+        }
+    }
+
+    /// Returns the policy of the engine.
+    pub fn policy(&self) -> &CommitPolicy {
+        &self.policy
+    }
+
+    /// Returns `true` if the policy requires this member to commit the
+    /// pending proposals of the `group` now.
+    ///
+    /// A group that is inactive or has a pending commit is never due. The
+    /// same holds if this member has sent a SelfRemove proposal.
+    pub fn is_commit_due(&mut self, group: &MlsGroup) -> bool {
+        if !group.is_active() || group.pending_commit().is_some() {
+            self.pending_since.remove(group.group_id());
+            return false;
+        }
+
+        let pending_proposals: Vec<&QueuedProposal> = group.pending_proposals().collect();
+        if pending_proposals.is_empty() {
+            self.pending_since.remove(group.group_id());
+            return false;
+        }
+
+        let now = self.clock.now();
+        let epoch = group.epoch();
+        let (_, since) = self
+            .pending_since
+            .entry(group.group_id().clone())
+            .and_modify(|pending_since| {
+                if pending_since.0 != epoch {
+                    *pending_since = (epoch, now);
+                }
+            })
+            .or_insert((epoch, now));
+        let since = *since;
+
+        let own_sender = Sender::Member(group.own_leaf_index());
+        let leaving: Vec<LeafNodeIndex> = pending_proposals
+            .iter()
+            .filter(|queued_proposal| queued_proposal.proposal() == &Proposal::SelfRemove)
+            .filter_map(|queued_proposal| match queued_proposal.sender() {
+                Sender::Member(leaf_index) => Some(*leaf_index),
+                _ => None,
+            })
+            .collect();
+        if leaving.contains(&group.own_leaf_index()) {
+            return false;
+        }
+
+        if self.policy.commit_own_proposals
+            && pending_proposals
+                .iter()
+                .any(|queued_proposal| queued_proposal.sender() == &own_sender)
+        {
+            return true;
+        }
+
+        let committer = match self.policy.committer {
+            Some(committer) => committer,
+            // The leaving members can't commit their SelfRemove proposals, so
+            // the member with the lowest leaf index does.
+            None if !leaving.is_empty() => Committer::Designated(LeafNodeIndex::new(0)),
+            None => return false,
+        };
+        let is_committer = match committer {
+            Committer::Any => true,
+            Committer::Designated(designated) => {
+                let committer =
+                    if group.member_at(designated).is_some() && !leaving.contains(&designated) {
+                        Some(designated)
+                    } else {
+                        group
+                            .members()
+                            .map(|member| member.index)
+                            .filter(|leaf_index| !leaving.contains(leaf_index))
+                            .min()
+                    };
+                committer == Some(group.own_leaf_index())
+            }
+        };
+        if !is_committer {
+            return false;
+        }
+
+        if !leaving.is_empty() {
+            return true;
+        }
+        let CommitPolicy {
+            max_pending_proposals,
+            max_delay,
+            ..
+        } = self.policy;
+        if max_pending_proposals.is_none() && max_delay.is_none() {
+            return true;
+        }
+        max_pending_proposals.is_some_and(|max| pending_proposals.len() >= max)
+            || max_delay.is_some_and(|delay| now.saturating_sub(since) >= delay)
+    }
+
+    /// Commits the pending proposals of the `group` if the policy requires
+    /// this member to do so now.
+    ///
+    /// The commit is staged as the group's pending commit and returned, so it
+    /// can be sent to the Delivery Service. Returns `None` if no commit is due.
+    pub fn poll<Provider: OpenMlsProvider>(
+        &mut self,
+        group: &mut MlsGroup,
+        provider: &Provider,
+        signer: &impl Signer,
+    ) -> Result<Option<CommitMessageBundle>, PolicyCommitError<Provider::StorageError>> {
+        if !self.is_commit_due(group) {
+            return Ok(None);
+        }
+
+        let bundle = group
+            .commit_builder()
+            .load_psks(provider.storage())?
+            .build(provider.rand(), provider.crypto(), signer, |_| true)?
+            .stage_commit(provider)?;
+        self.pending_since.remove(group.group_id());
+
+        Ok(Some(bundle))
+    }
+
+    /// Stops tracking the `group`, e.g. after it was deleted.
+    pub fn forget_group(&mut self, group_id: &GroupId) {
+        self.pending_since.remove(group_id);
+    }
+}
@@ -31,7 +31,7 @@ use super::*;
 use crate::{
     extensions::errors::InvalidExtensionError,
     key_packages::Lifetime,
-    tree::sender_ratchet::SenderRatchetConfiguration,
+    tree::sender_ratchet::{MessageKeyCacheConfig, SenderRatchetConfiguration},
     treesync::{errors::LeafNodeValidationError, node::leaf_node::Capabilities},
 };
 use serde::{Deserialize, Serialize};
@@ -56,6 +56,44 @@ pub struct MlsGroupJoinConfig {
     pub(crate) use_ratchet_tree_extension: bool,
     /// Sender ratchet configuration
     pub(crate) sender_ratchet_configuration: SenderRatchetConfiguration,
+    /// Sender ratchet configuration of application messages. Uses
+    /// `sender_ratchet_configuration` if `None`.
+    #[serde(default)]
+    pub(crate) application_sender_ratchet_configuration: Option<SenderRatchetConfiguration>,
+    /// Sender ratchet configuration of handshake messages. Uses
+    /// `sender_ratchet_configuration` if `None`.
+    #[serde(default)]
+    pub(crate) handshake_sender_ratchet_configuration: Option<SenderRatchetConfiguration>,
+    /// Limit of cached message keys across all senders. Unlimited if `None`.
+    #[serde(default)]
+    pub(crate) message_key_cache_config: Option<MessageKeyCacheConfig>,
+    /// Buffering of messages for future epochs. Disabled if `None`.
+    #[serde(default)]
+    pub(crate) message_buffer_config: Option<MessageBufferConfig>,
+    /// Archiving of message secrets of past epochs beyond `max_past_epochs`.
+    /// Disabled if `None`.
+    #[serde(default)]
+    pub(crate) message_archive_config: Option<MessageArchiveConfig>,
+    /// Configuration of the [`CommitHistory`](super::CommitHistory).
+    /// Disabled if `None`.
+    #[serde(default)]
+    pub(crate) commit_history_config: Option<CommitHistoryConfig>,
+    /// Maximum number of past epochs for which exporter secrets and epoch
+    /// authenticators are kept. The default is 0.
+    #[serde(default)]
+    pub(crate) max_past_exporter_epochs: usize,
+    /// Padding of application messages. Padded to a multiple of
+    /// `padding_size` if `None`.
+    #[serde(default)]
+    pub(crate) application_padding: Option<PaddingStrategy>,
+    /// Padding of handshake messages. Padded to a multiple of `padding_size`
+    /// if `None`.
+    #[serde(default)]
+    pub(crate) handshake_padding: Option<PaddingStrategy>,
+    /// Flag to indicate that handshake messages must not be processed
+    /// without a [`ProposalPolicy`](super::ProposalPolicy).
+    #[serde(default)]
+    pub(crate) require_proposal_policy: bool,
 }
 
 impl MlsGroupJoinConfig {
@@ -74,10 +112,99 @@ impl MlsGroupJoinConfig {
         self.padding_size
     }
 
+    /// Returns the [`PaddingStrategy`] for application messages set in this
+    /// [`MlsGroupJoinConfig`].
+    pub fn application_padding(&self) -> PaddingStrategy {
+        self.application_padding
+            .unwrap_or(PaddingStrategy::FixedBlock(self.padding_size))
+    }
+
+    /// Returns the [`PaddingStrategy`] for handshake messages set in this
+    /// [`MlsGroupJoinConfig`].
+    pub fn handshake_padding(&self) -> PaddingStrategy {
+        self.handshake_padding
+            .unwrap_or(PaddingStrategy::FixedBlock(self.padding_size))
+    }
+
+    /// Returns the [`PaddingStrategy`] for messages with the `content_type`.
+    pub(crate) fn padding_strategy(&self, content_type: ContentType) -> PaddingStrategy {
+        match content_type {
+            ContentType::Application => self.application_padding(),
+            ContentType::Proposal | ContentType::Commit => self.handshake_padding(),
+        }
+    }
+
     /// Returns the [`SenderRatchetConfiguration`] set in this  [`MlsGroupJoinConfig`].
     pub fn sender_ratchet_configuration(&self) -> &SenderRatchetConfiguration {
         &self.sender_ratchet_configuration
     }
+
+    /// Returns the [`SenderRatchetConfiguration`] for application messages set
+    /// in this [`MlsGroupJoinConfig`].
+    pub fn application_sender_ratchet_configuration(&self) -> &SenderRatchetConfiguration {
+        self.application_sender_ratchet_configuration
+            .as_ref()
+            .unwrap_or(&self.sender_ratchet_configuration)
+    }
+
+    /// Returns the [`SenderRatchetConfiguration`] for handshake messages set
+    /// in this [`MlsGroupJoinConfig`].
+    pub fn handshake_sender_ratchet_configuration(&self) -> &SenderRatchetConfiguration {
+        self.handshake_sender_ratchet_configuration
+            .as_ref()
+            .unwrap_or(&self.sender_ratchet_configuration)
+    }
+
+    /// Returns the [`SenderRatchetConfiguration`] for messages with the
+    /// `content_type`.
+    pub(crate) fn sender_ratchet_configuration_for(
+        &self,
+        content_type: ContentType,
+    ) -> &SenderRatchetConfiguration {
+        match content_type {
+            ContentType::Application => self.application_sender_ratchet_configuration(),
+            ContentType::Proposal | ContentType::Commit => {
+                self.handshake_sender_ratchet_configuration()
+            }
+        }
+    }
+
+    /// Returns the [`MessageKeyCacheConfig`] set in this
+    /// [`MlsGroupJoinConfig`], if the number of cached message keys is
+    /// limited.
+    pub fn message_key_cache_config(&self) -> Option<&MessageKeyCacheConfig> {
+        self.message_key_cache_config.as_ref()
+    }
+
+    /// Returns the [`MessageBufferConfig`] set in this [`MlsGroupJoinConfig`],
+    /// if message buffering is enabled.
+    pub fn message_buffer_config(&self) -> Option<&MessageBufferConfig> {
+        self.message_buffer_config.as_ref()
+    }
+
+    /// Returns the [`MessageArchiveConfig`] set in this
+    /// [`MlsGroupJoinConfig`], if the message archive is enabled.
+    pub fn message_archive_config(&self) -> Option<&MessageArchiveConfig> {
+        self.message_archive_config.as_ref()
+    }
+
+    /// Returns the [`CommitHistoryConfig`] set in this
+    /// [`MlsGroupJoinConfig`], if the commit history is enabled.
+    pub fn commit_history_config(&self) -> Option<&CommitHistoryConfig> {
+        self.commit_history_config.as_ref()
+    }
+
+    /// Returns the maximum number of past epochs for which exporter secrets
+    /// and epoch authenticators are kept.
+    pub fn max_past_exporter_epochs(&self) -> usize {
+        self.max_past_exporter_epochs
+    }
+
+    /// Returns `true` if handshake messages must not be processed without a
+    /// [`ProposalPolicy`](super::ProposalPolicy).
+    pub fn require_proposal_policy(&self) -> bool {
+        self.require_proposal_policy
+    }
 }
 
 /// Specifies configuration for the creation of an [`MlsGroup`]. Refer to the
@@ -138,6 +265,20 @@ impl MlsGroupJoinConfigBuilder {
         self
     }
 
+    /// Sets the [`PaddingStrategy`] for application messages. Overrides the
+    /// `padding_size` for application messages.
+    pub fn application_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.join_config.application_padding = Some(padding);
+        self
+    }
+
+    /// Sets the [`PaddingStrategy`] for handshake messages. Overrides the
+    /// `padding_size` for handshake messages.
+    pub fn handshake_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.join_config.handshake_padding = Some(padding);
+        self
+    }
+
     /// Sets the `max_past_epochs` property of the [`MlsGroupJoinConfig`].
     pub fn max_past_epochs(mut self, max_past_epochs: usize) -> Self {
         self.join_config.max_past_epochs = max_past_epochs;
@@ -165,6 +306,72 @@ impl MlsGroupJoinConfigBuilder {
         self
     }
 
+    /// Sets the [`SenderRatchetConfiguration`] for application messages.
+    /// Overrides the `sender_ratchet_configuration` for application messages.
+    pub fn application_sender_ratchet_configuration(
+        mut self,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) -> Self {
+        self.join_config.application_sender_ratchet_configuration =
+            Some(sender_ratchet_configuration);
+        self
+    }
+
+    /// Sets the [`SenderRatchetConfiguration`] for handshake messages.
+    /// Overrides the `sender_ratchet_configuration` for handshake messages.
+    pub fn handshake_sender_ratchet_configuration(
+        mut self,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) -> Self {
+        self.join_config.handshake_sender_ratchet_configuration =
+            Some(sender_ratchet_configuration);
+        self
+    }
+
+    /// Sets the `message_key_cache_config` property of the [`MlsGroupJoinConfig`].
+    /// This limits the number of cached message keys across all senders.
+    pub fn message_key_cache_config(
+        mut self,
+        message_key_cache_config: MessageKeyCacheConfig,
+    ) -> Self {
+        self.join_config.message_key_cache_config = Some(message_key_cache_config);
+        self
+    }
+
+    /// Sets the `message_buffer_config` property of the [`MlsGroupJoinConfig`].
+    /// This enables buffering of messages for future epochs.
+    pub fn message_buffer_config(mut self, message_buffer_config: MessageBufferConfig) -> Self {
+        self.join_config.message_buffer_config = Some(message_buffer_config);
+        self
+    }
+
+    /// Sets the `message_archive_config` property of the [`MlsGroupJoinConfig`].
+    /// This enables archiving of message secrets of past epochs.
+    pub fn message_archive_config(mut self, message_archive_config: MessageArchiveConfig) -> Self {
+        self.join_config.message_archive_config = Some(message_archive_config);
+        self
+    }
+
+    /// Sets the `commit_history_config` property of the [`MlsGroupJoinConfig`].
+    /// This enables recording of the [`CommitHistory`](super::CommitHistory).
+    pub fn commit_history_config(mut self, commit_history_config: CommitHistoryConfig) -> Self {
+        self.join_config.commit_history_config = Some(commit_history_config);
+        self
+    }
+
+    /// Sets the `max_past_exporter_epochs` property of the [`MlsGroupJoinConfig`].
+    pub fn max_past_exporter_epochs(mut self, max_past_exporter_epochs: usize) -> Self {
+        self.join_config.max_past_exporter_epochs = max_past_exporter_epochs;
+        self
+    }
+
+    /// Sets the `require_proposal_policy` property of the [`MlsGroupJoinConfig`].
+    /// See [`MlsGroupJoinConfig::require_proposal_policy()`].
+    pub fn require_proposal_policy(mut self, require_proposal_policy: bool) -> Self {
+        self.join_config.require_proposal_policy = require_proposal_policy;
+        self
+    }
+
     /// Finalizes the builder and returns an [`MlsGroupJoinConfig`].
     pub fn build(self) -> MlsGroupJoinConfig {
         self.join_config
@@ -187,6 +394,18 @@ impl MlsGroupCreateConfig {
         self.join_config.padding_size
     }
 
+    /// Returns the [`MlsGroupCreateConfig`] padding strategy for application
+    /// messages.
+    pub fn application_padding(&self) -> PaddingStrategy {
+        self.join_config.application_padding()
+    }
+
+    /// Returns the [`MlsGroupCreateConfig`] padding strategy for handshake
+    /// messages.
+    pub fn handshake_padding(&self) -> PaddingStrategy {
+        self.join_config.handshake_padding()
+    }
+
     /// Returns the [`MlsGroupCreateConfig`] max past epochs.
     pub fn max_past_epochs(&self) -> usize {
         self.join_config.max_past_epochs
@@ -207,6 +426,48 @@ impl MlsGroupCreateConfig {
         &self.join_config.sender_ratchet_configuration
     }
 
+    /// Returns the [`MlsGroupCreateConfig`] sender ratchet configuration for
+    /// application messages.
+    pub fn application_sender_ratchet_configuration(&self) -> &SenderRatchetConfiguration {
+        self.join_config.application_sender_ratchet_configuration()
+    }
+
+    /// Returns the [`MlsGroupCreateConfig`] sender ratchet configuration for
+    /// handshake messages.
+    pub fn handshake_sender_ratchet_configuration(&self) -> &SenderRatchetConfiguration {
+        self.join_config.handshake_sender_ratchet_configuration()
+    }
+
+    /// Returns the [`MlsGroupCreateConfig`] message key cache configuration.
+    pub fn message_key_cache_config(&self) -> Option<&MessageKeyCacheConfig> {
+        self.join_config.message_key_cache_config.as_ref()
+    }
+
+    /// Returns the [`MlsGroupCreateConfig`] message buffer configuration.
+    pub fn message_buffer_config(&self) -> Option<&MessageBufferConfig> {
+        self.join_config.message_buffer_config.as_ref()
+    }
+
+    /// Returns the [`MlsGroupCreateConfig`] message archive configuration.
+    pub fn message_archive_config(&self) -> Option<&MessageArchiveConfig> {
+        self.join_config.message_archive_config.as_ref()
+    }
+
+    /// Returns the [`MlsGroupCreateConfig`] commit history configuration.
+    pub fn commit_history_config(&self) -> Option<&CommitHistoryConfig> {
+        self.join_config.commit_history_config.as_ref()
+    }
+
+    /// Returns the [`MlsGroupCreateConfig`] max past exporter epochs.
+    pub fn max_past_exporter_epochs(&self) -> usize {
+        self.join_config.max_past_exporter_epochs
+    }
+
+    /// Returns the [`MlsGroupCreateConfig`] require proposal policy flag.
+    pub fn require_proposal_policy(&self) -> bool {
+        self.join_config.require_proposal_policy
+    }
+
     /// Returns the [`Extensions`] set as the initial group context.
     /// This does not contain the initial group context extensions
     /// added from builder calls to `external_senders` or `required_capabilities`.
@@ -267,6 +528,22 @@ impl MlsGroupCreateConfigBuilder {
         self
     }
 
+    /// Sets the [`PaddingStrategy`] for application messages of the
+    /// MlsGroupCreateConfig. Overrides the `padding_size` for application
+    /// messages.
+    pub fn application_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.config.join_config.application_padding = Some(padding);
+        self
+    }
+
+    /// Sets the [`PaddingStrategy`] for handshake messages of the
+    /// MlsGroupCreateConfig. Overrides the `padding_size` for handshake
+    /// messages.
+    pub fn handshake_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.config.join_config.handshake_padding = Some(padding);
+        self
+    }
+
     /// Sets the `max_past_epochs` property of the MlsGroupCreateConfig.
     /// This allows application messages from previous epochs to be decrypted.
     ///
@@ -310,6 +587,88 @@ impl MlsGroupCreateConfigBuilder {
         self
     }
 
+    /// Sets the [`SenderRatchetConfiguration`] for application messages of the
+    /// MlsGroupCreateConfig. Overrides the `sender_ratchet_configuration` for
+    /// application messages.
+    pub fn application_sender_ratchet_configuration(
+        mut self,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) -> Self {
+        self.config
+            .join_config
+            .application_sender_ratchet_configuration = Some(sender_ratchet_configuration);
+        self
+    }
+
+    /// Sets the [`SenderRatchetConfiguration`] for handshake messages of the
+    /// MlsGroupCreateConfig. Overrides the `sender_ratchet_configuration` for
+    /// handshake messages.
+    pub fn handshake_sender_ratchet_configuration(
+        mut self,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) -> Self {
+        self.config
+            .join_config
+            .handshake_sender_ratchet_configuration = Some(sender_ratchet_configuration);
+        self
+    }
+
+    /// Sets the `message_key_cache_config` property of the MlsGroupCreateConfig.
+    /// See [`MessageKeyCacheConfig`] for more information.
+    pub fn message_key_cache_config(
+        mut self,
+        message_key_cache_config: MessageKeyCacheConfig,
+    ) -> Self {
+        self.config.join_config.message_key_cache_config = Some(message_key_cache_config);
+        self
+    }
+
+    /// Sets the `message_buffer_config` property of the MlsGroupCreateConfig.
+    /// See [`MessageBufferConfig`] for more information.
+    pub fn message_buffer_config(mut self, message_buffer_config: MessageBufferConfig) -> Self {
+        self.config.join_config.message_buffer_config = Some(message_buffer_config);
+        self
+    }
+
+    /// Sets the `message_archive_config` property of the MlsGroupCreateConfig.
+    /// See [`MessageArchiveConfig`] for more information.
+    ///
+    /// **WARNING**
+    ///
+    /// Archived message secrets allow decrypting messages of past epochs and
+    /// thus weaken forward secrecy until they expire.
+    pub fn message_archive_config(mut self, message_archive_config: MessageArchiveConfig) -> Self {
+        self.config.join_config.message_archive_config = Some(message_archive_config);
+        self
+    }
+
+    /// Sets the `commit_history_config` property of the MlsGroupCreateConfig.
+    /// See [`CommitHistoryConfig`] for more information.
+    pub fn commit_history_config(mut self, commit_history_config: CommitHistoryConfig) -> Self {
+        self.config.join_config.commit_history_config = Some(commit_history_config);
+        self
+    }
+
+    /// Sets the `max_past_exporter_epochs` property of the MlsGroupCreateConfig.
+    /// This allows secrets to be exported from previous epochs.
+    ///
+    /// **WARNING**
+    ///
+    /// Keeping exporter secrets of past epochs is a trade-off between
+    /// functionality and forward secrecy. Secrets that are no longer needed
+    /// should be deleted with [`MlsGroup::delete_past_exporter_secrets()`].
+    pub fn max_past_exporter_epochs(mut self, max_past_exporter_epochs: usize) -> Self {
+        self.config.join_config.max_past_exporter_epochs = max_past_exporter_epochs;
+        self
+    }
+
+    /// Sets the `require_proposal_policy` property of the MlsGroupCreateConfig.
+    /// See [`MlsGroupJoinConfig::require_proposal_policy()`].
+    pub fn require_proposal_policy(mut self, require_proposal_policy: bool) -> Self {
+        self.config.join_config.require_proposal_policy = require_proposal_policy;
+        self
+    }
+
     /// Sets the `lifetime` property of the MlsGroupCreateConfig.
     pub fn lifetime(mut self, lifetime: Lifetime) -> Self {
         self.config.lifetime = lifetime;
@@ -128,6 +128,9 @@ impl MlsGroup {
         // members of the group
         self.public_group
             .validate_proposal_type_support(&proposal_queue)?;
+        // ValSem114
+        self.public_group
+            .validate_reinit_proposals(&proposal_queue)?;
         // ValSem101
         // ValSem102
         // ValSem103
@@ -372,6 +375,13 @@ impl MlsGroup {
                 self.own_leaf_index(),
             );
 
+        let changes = CommitChanges::new(&self.public_group, &diff, &proposal_queue);
+        let committed_content = CommittedContent::new(
+            provider.crypto(),
+            ciphersuite,
+            self.own_leaf_index(),
+            &authenticated_content,
+        )?;
         let staged_commit_state = MemberStagedCommitState::new(
             old_init_secret,
             provisional_group_epoch_secrets,
@@ -386,6 +396,8 @@ impl MlsGroup {
         let staged_commit = StagedCommit::new(
             proposal_queue,
             StagedCommitState::GroupMember(Box::new(staged_commit_state)),
+            changes,
+            committed_content,
         );
 
         Ok(CreateCommitResult {
@@ -190,12 +190,19 @@ impl MlsGroup {
             mls_group_config: mls_group_config.clone(),
             own_leaf_nodes: vec![],
             aad: vec![],
+            proposal_policy: GroupProposalPolicy::default(),
+            clock: GroupClock::default(),
+            replayed_messages: vec![],
             group_state: MlsGroupState::Operational,
+            own_leaf_update: None,
             public_group,
             group_epoch_secrets,
             own_leaf_index,
             message_secrets_store,
             resumption_psk_store: ResumptionPskStore::new(32),
+            past_exporter_secrets: PastExporterSecrets::new(
+                mls_group_config.max_past_exporter_epochs(),
+            ),
         };
 
         mls_group.set_max_past_epochs(mls_group_config.max_past_epochs);
@@ -471,6 +478,7 @@ impl ProcessedWelcome {
             verifiable_group_info: self.verifiable_group_info,
             key_package_bundle: self.key_package_bundle,
             path_keypairs,
+            clock: GroupClock::default(),
         };
 
         Ok(staged_welcome)
@@ -526,6 +534,13 @@ impl StagedWelcome {
         self.public_group.members()
     }
 
+    /// Sets the [`Clock`] of the [`MlsGroup`] that is created from this
+    /// welcome. See [`MlsGroup::set_clock()`].
+    pub fn with_clock(mut self, clock: impl Clock + Send + Sync + 'static) -> Self {
+        self.clock = GroupClock::new(clock);
+        self
+    }
+
     /// Consumes the [`StagedWelcome`] and returns the respective [`MlsGroup`].
     pub fn into_group<Provider: OpenMlsProvider>(
         self,
@@ -541,16 +556,26 @@ impl StagedWelcome {
             vec![self.key_package_bundle.encryption_key_pair()]
         };
 
+        let past_exporter_secrets =
+            PastExporterSecrets::new(self.mls_group_config.max_past_exporter_epochs());
         let mut mls_group = MlsGroup {
             mls_group_config: self.mls_group_config,
             own_leaf_nodes: vec![],
             aad: vec![],
+            proposal_policy: GroupProposalPolicy::default(),
             group_state: MlsGroupState::Operational,
+            own_leaf_update: MlsGroup::initial_own_leaf_update(
+                &self.clock,
+                self.public_group.group_context().epoch(),
+            ),
+            clock: self.clock,
+            replayed_messages: vec![],
             public_group: self.public_group,
             group_epoch_secrets: self.group_epoch_secrets,
             own_leaf_index: self.own_leaf_index,
             message_secrets_store: self.message_secrets_store,
             resumption_psk_store: self.resumption_psk_store,
+            past_exporter_secrets,
         };
 
         mls_group
@@ -10,13 +10,17 @@ use thiserror::Error;
 use crate::{
     error::LibraryError,
     extensions::errors::InvalidExtensionError,
+    framing::Sender,
     group::{
         errors::{
-            CreateAddProposalError, CreateCommitError, MergeCommitError, StageCommitError,
-            ValidationError,
+            CreateAddProposalError, CreateCommitError,
+            GroupContextExtensionsProposalValidationError, MergeCommitError,
+            ProposalValidationReport, StageCommitError, ValidationError,
         },
-        CommitBuilderStageError, CreateGroupContextExtProposalError,
+        CommitBuilderStageError, CreateGroupContextExtProposalError, MessageOutcome,
+        ProposalRejection,
     },
+    messages::proposals::ProposalType,
     schedule::errors::PskError,
     treesync::{
         errors::{LeafNodeValidationError, PublicTreeError},
@@ -119,6 +123,90 @@ pub enum ProcessMessageError {
     /// The proposal is invalid for the Sender of type [External](crate::prelude::Sender::External)
     #[error("The proposal is invalid for the Sender of type External")]
     UnsupportedProposalType,
+    /// Error reading or writing the message archive.
+    #[error("Error reading or writing the message archive.")]
+    MessageArchiveError,
+    /// A proposal was rejected by the [`ProposalPolicy`](crate::group::ProposalPolicy).
+    #[error("The {proposal_type:?} proposal was rejected by the proposal policy: {rejection}")]
+    ProposalRejected {
+        /// The sender of the proposal.
+        sender: Sender,
+        /// The type of the proposal.
+        proposal_type: ProposalType,
+        /// The reason given by the policy.
+        rejection: ProposalRejection,
+    },
+    /// The group requires a [`ProposalPolicy`](crate::group::ProposalPolicy),
+    /// but none is set.
+    #[error("The group requires a proposal policy, but none is set.")]
+    MissingProposalPolicy,
+}
+
+/// Process or buffer message error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ProcessOrBufferMessageError<StorageError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`ProcessMessageError`] for more details.
+    #[error(transparent)]
+    ProcessMessageError(#[from] ProcessMessageError),
+    /// The message buffer is full.
+    #[error("The message buffer is full.")]
+    BufferFull,
+    /// Error reading or writing the message buffer.
+    #[error("Error reading or writing the message buffer.")]
+    StorageError(StorageError),
+}
+
+/// Process messages error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ProcessMessagesError<StorageError> {
+    /// See [`MergeCommitError`] for more details.
+    #[error(transparent)]
+    MergeCommitError(#[from] MergeCommitError<StorageError>),
+    /// Error writing to storage.
+    #[error("Error writing to storage")]
+    StorageError(StorageError),
+}
+
+/// Batch error
+///
+/// Returned by [`MlsGroup::process_messages()`](super::MlsGroup::process_messages())
+/// when the batch is aborted. It carries the outcomes of the messages that
+/// were processed before the error, so that the application messages
+/// decrypted so far aren't lost.
+#[derive(Error, Debug)]
+#[error("{error}")]
+pub struct BatchError<StorageError> {
+    outcomes: Vec<MessageOutcome>,
+    #[source]
+    error: ProcessMessagesError<StorageError>,
+}
+
+impl<StorageError> BatchError<StorageError> {
+    pub(super) fn new(
+        outcomes: Vec<MessageOutcome>,
+        error: ProcessMessagesError<StorageError>,
+    ) -> Self {
+        Self { outcomes, error }
+    }
+
+    /// Returns the outcomes of the messages processed before the error.
+    pub fn outcomes(&self) -> &[MessageOutcome] {
+        &self.outcomes
+    }
+
+    /// Returns the error that aborted the batch.
+    pub fn error(&self) -> &ProcessMessagesError<StorageError> {
+        &self.error
+    }
+
+    /// Returns the outcomes of the messages processed before the error and
+    /// the error that aborted the batch.
+    pub fn into_parts(self) -> (Vec<MessageOutcome>, ProcessMessagesError<StorageError>) {
+        (self.outcomes, self.error)
+    }
 }
 
 /// Create message error
@@ -296,6 +384,57 @@ pub enum CommitToPendingProposalsError<StorageError> {
     StorageError(StorageError),
 }
 
+/// Rebase commit error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RebaseCommitError<StorageError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MergeCommitError`] for more details.
+    #[error(transparent)]
+    MergeCommitError(#[from] MergeCommitError<StorageError>),
+    /// See [`CreateCommitError`] for more details.
+    #[error(transparent)]
+    CreateCommitError(#[from] CreateCommitError),
+    /// See [`CommitBuilderStageError`] for more details.
+    #[error(transparent)]
+    CommitBuilderStageError(#[from] CommitBuilderStageError<StorageError>),
+}
+
+/// Policy commit error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum PolicyCommitError<StorageError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`CreateCommitError`] for more details.
+    #[error(transparent)]
+    CreateCommitError(#[from] CreateCommitError),
+    /// See [`CommitBuilderStageError`] for more details.
+    #[error(transparent)]
+    CommitBuilderStageError(#[from] CommitBuilderStageError<StorageError>),
+}
+
+/// Commit history verification error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CommitHistoryError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The epochs of the records are not consecutive.
+    #[error("The epochs of the records are not consecutive.")]
+    UnexpectedEpoch,
+    /// The number of commits doesn't match the number of records.
+    #[error("The number of commits doesn't match the number of records.")]
+    CommitCountMismatch,
+    /// The commit hash of a record doesn't match its commit.
+    #[error("The commit hash of a record doesn't match its commit.")]
+    CommitHashMismatch,
+    /// The confirmed transcript hash of a record doesn't match its commit and the previous epoch.
+    #[error("The confirmed transcript hash of a record doesn't match its commit and the previous epoch.")]
+    TranscriptHashMismatch,
+}
+
 /// Errors that can happen when exporting a group info object.
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ExportGroupInfoError {
@@ -319,6 +458,32 @@ pub enum ExportSecretError {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// No exporter secret is available for the requested epoch.
+    #[error("No exporter secret is available for the requested epoch.")]
+    UnknownEpoch,
+}
+
+/// Safe export secret error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SafeExportSecretError<StorageError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The requested key length is too long.
+    #[error("The requested key length is too long.")]
+    KeyLengthTooLong,
+    /// The secret of the component was already exported in this epoch.
+    #[error("The secret of the component was already exported in this epoch.")]
+    AlreadyExported,
+    /// The epoch was stored without an exporter tree.
+    #[error("The epoch was stored without an exporter tree.")]
+    Unsupported,
+    /// Error writing the punctured exporter tree to storage.
+    #[error("Error writing the punctured exporter tree to storage.")]
+    StorageError(StorageError),
 }
 
 /// Propose PSK error
@@ -367,6 +532,36 @@ pub enum ProposalError<StorageError> {
     StorageError(StorageError),
 }
 
+/// Validate proposals error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ValidateProposalsError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// A proposal has the wrong sender type.
+    #[error("A proposal has the wrong sender type.")]
+    WrongProposalSenderType,
+    /// See [`ProposalValidationReport`] for more details.
+    #[error(transparent)]
+    InvalidProposal(#[from] ProposalValidationReport),
+    /// See [`GroupContextExtensionsProposalValidationError`] for more details.
+    #[error(transparent)]
+    GroupContextExtensionsProposalValidationError(
+        #[from] GroupContextExtensionsProposalValidationError,
+    ),
+}
+
+impl From<ValidateProposalsError> for CreateCommitError {
+    fn from(error: ValidateProposalsError) -> Self {
+        match error {
+            ValidateProposalsError::LibraryError(e) => Self::LibraryError(e),
+            ValidateProposalsError::WrongProposalSenderType => Self::WrongProposalSenderType,
+            ValidateProposalsError::InvalidProposal(report) => Self::InvalidProposal(report),
+            ValidateProposalsError::GroupContextExtensionsProposalValidationError(e) => e.into(),
+        }
+    }
+}
+
 /// Remove proposal error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum RemoveProposalError<StorageError> {
@@ -1,4 +1,4 @@
-use errors::{ExportGroupInfoError, ExportSecretError};
+use errors::{ExportGroupInfoError, ExportSecretError, SafeExportSecretError};
 use openmls_traits::{crypto::OpenMlsCrypto, signatures::Signer};
 
 use crate::{
@@ -41,11 +41,132 @@ impl MlsGroup {
         }
     }
 
+    /// Exports a secret from the given `epoch`, which is either the current
+    /// epoch or a past epoch whose exporter secret is still kept.
+    ///
+    /// Exporter secrets of up to
+    /// [`MlsGroupJoinConfig::max_past_exporter_epochs()`] past epochs are
+    /// kept. Returns [`ExportSecretError::UnknownEpoch`] if no exporter
+    /// secret is available for the `epoch`.
+    pub fn export_secret_for_epoch<CryptoProvider: OpenMlsCrypto>(
+        &self,
+        crypto: &CryptoProvider,
+        epoch: GroupEpoch,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, ExportSecretError> {
+        if epoch == self.epoch() {
+            return self.export_secret(crypto, label, context, key_length);
+        }
+        if key_length > u16::MAX as usize {
+            log::error!("Got a key that is larger than u16::MAX");
+            return Err(ExportSecretError::KeyLengthTooLong);
+        }
+        if !self.is_active() {
+            return Err(ExportSecretError::GroupStateError(
+                MlsGroupStateError::UseAfterEviction,
+            ));
+        }
+
+        Ok(self
+            .past_exporter_secrets
+            .exporter_secret(epoch)
+            .ok_or(ExportSecretError::UnknownEpoch)?
+            .derive_exported_secret(self.ciphersuite(), crypto, label, context, key_length)
+            .map_err(LibraryError::unexpected_crypto_error)?)
+    }
+
+    /// Exports a secret of `key_length` bytes for the `label` and `context`
+    /// from the leaf of the component with the `component_id` in the exporter
+    /// tree of the current epoch.
+    ///
+    /// Unlike [`Self::export_secret()`], a secret can be exported only once
+    /// per epoch and component. The exporter tree is punctured afterwards and
+    /// the punctured tree is written to storage, so the secret can't be
+    /// derived again by anyone who later gets access to the stored state. If
+    /// writing to storage fails, the tree is left as it was and the export
+    /// can be retried.
+    ///
+    /// Returns [`SafeExportSecretError::AlreadyExported`] if a secret was
+    /// already exported for the component in this epoch, and
+    /// [`SafeExportSecretError::Unsupported`] if the epoch was stored by a
+    /// version without exporter trees.
+    pub fn safe_export_secret<Provider: OpenMlsProvider>(
+        &mut self,
+        provider: &Provider,
+        component_id: u32,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, SafeExportSecretError<Provider::StorageError>> {
+        if key_length > u16::MAX as usize {
+            log::error!("Got a key that is larger than u16::MAX");
+            return Err(SafeExportSecretError::KeyLengthTooLong);
+        }
+        if !self.is_active() {
+            return Err(SafeExportSecretError::GroupStateError(
+                MlsGroupStateError::UseAfterEviction,
+            ));
+        }
+
+        let ciphersuite = self.ciphersuite();
+        let exporter_tree = self
+            .group_epoch_secrets
+            .application_export_secret_mut()
+            .ok_or(SafeExportSecretError::Unsupported)?;
+        let unpunctured_tree = exporter_tree.clone();
+        let secret = exporter_tree
+            .derive_exported_secret(
+                provider.crypto(),
+                ciphersuite,
+                component_id,
+                label,
+                context,
+                key_length,
+            )?
+            .ok_or(SafeExportSecretError::AlreadyExported)?;
+        if let Err(e) = provider
+            .storage()
+            .write_group_epoch_secrets(self.group_id(), &self.group_epoch_secrets)
+        {
+            self.group_epoch_secrets
+                .set_application_export_secret(unpunctured_tree);
+            return Err(SafeExportSecretError::StorageError(e));
+        }
+        Ok(secret)
+    }
+
     /// Returns the epoch authenticator of the current epoch.
     pub fn epoch_authenticator(&self) -> &EpochAuthenticator {
         self.group_epoch_secrets().epoch_authenticator()
     }
 
+    /// Returns the epoch authenticator of the given `epoch`, which is either
+    /// the current epoch or a past epoch whose secrets are still kept. If no
+    /// epoch authenticator is available for that epoch, `None` is returned.
+    pub fn epoch_authenticator_for_epoch(&self, epoch: GroupEpoch) -> Option<&EpochAuthenticator> {
+        if epoch == self.epoch() {
+            return Some(self.epoch_authenticator());
+        }
+        self.past_exporter_secrets.epoch_authenticator(epoch)
+    }
+
+    /// Deletes the exporter secret and epoch authenticator of the past
+    /// `epoch` and writes the change to storage, so that secrets can no
+    /// longer be exported from that epoch. Returns `true` if they were kept.
+    pub fn delete_past_exporter_secrets<Storage: StorageProvider>(
+        &mut self,
+        storage: &Storage,
+        epoch: GroupEpoch,
+    ) -> Result<bool, Storage::Error> {
+        if !self.past_exporter_secrets.remove(epoch) {
+            return Ok(false);
+        }
+        storage.write_past_exporter_secrets(self.group_id(), &self.past_exporter_secrets)?;
+        Ok(true)
+    }
+
     /// Returns the resumption PSK secret of the current epoch.
     pub fn resumption_psk_secret(&self) -> &ResumptionPskSecret {
         self.group_epoch_secrets().resumption_psk()
@@ -0,0 +1,169 @@
+//! Archive of message secrets of past epochs.
+//!
+//! The group keeps the message secrets of the last
+//! [`MlsGroupJoinConfigBuilder::max_past_epochs()`] epochs in memory. If a
+//! [`MessageArchiveConfig`] is set in the group's [`MlsGroupJoinConfig`], the
+//! message secrets of older epochs are not dropped, but written to the
+//! storage provider together with the members of the epoch, one entry per
+//! epoch. When [`MlsGroup::process_message()`] receives a message for an
+//! archived epoch, the entry is loaded to decrypt the message and
+//! authenticate its sender, and written back afterwards.
+//!
+//! Archived epochs expire when more than
+//! [`MessageArchiveConfig::max_epochs()`] epochs are archived, or when they
+//! were archived more than [`MessageArchiveConfig::max_age()`] seconds ago.
+//! Expired entries are deleted whenever a commit is merged, or explicitly
+//! with [`MlsGroup::expire_archived_message_secrets()`].
+
+use serde::{Deserialize, Serialize};
+
+use super::{past_secrets::EpochTree, *};
+
+/// Configuration of the archive of past-epoch message secrets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageArchiveConfig {
+    max_epochs: usize,
+    max_age: Option<u64>,
+}
+
+impl MessageArchiveConfig {
+    /// Create a new configuration that archives the message secrets of at
+    /// most `max_epochs` epochs, in addition to the ones kept in memory.
+    pub fn new(max_epochs: usize) -> Self {
+        Self {
+            max_epochs,
+            max_age: None,
+        }
+    }
+
+    /// Expire archived epochs `max_age` seconds after they were archived.
+    pub fn with_max_age(mut self, max_age: u64) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Returns the maximum number of archived epochs.
+    pub fn max_epochs(&self) -> usize {
+        self.max_epochs
+    }
+
+    /// Returns the number of seconds after which archived epochs expire, if
+    /// they expire by age.
+    pub fn max_age(&self) -> Option<u64> {
+        self.max_age
+    }
+}
+
+impl Default for MessageArchiveConfig {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl MlsGroup {
+    /// Returns the epochs whose message secrets are in the message archive,
+    /// oldest first.
+    pub fn archived_epochs(&self) -> Vec<GroupEpoch> {
+        self.message_secrets_store
+            .archived_epochs()
+            .map(|archived| archived.epoch)
+            .collect()
+    }
+
+    /// Deletes the archived message secrets that have expired at the time of
+    /// the `clock` and returns the number of deleted epochs. If the message
+    /// archive is disabled, all archived message secrets are deleted.
+    pub fn expire_archived_message_secrets<Storage: StorageProvider>(
+        &mut self,
+        storage: &Storage,
+        clock: &impl Clock,
+    ) -> Result<usize, Storage::Error> {
+        let expired = self.expire_message_archive(storage, clock.now())?;
+        if expired > 0 {
+            storage.write_message_secrets(self.group_id(), &self.message_secrets_store)?;
+        }
+        Ok(expired)
+    }
+
+    /// Writes the `epoch_tree` that was evicted from the message secrets
+    /// store to the message archive, if the archive is enabled, and deletes
+    /// the expired entries. The caller has to write the message secrets
+    /// store.
+    pub(super) fn archive_message_secrets<Storage: StorageProvider>(
+        &mut self,
+        storage: &Storage,
+        epoch_tree: EpochTree,
+    ) -> Result<(), Storage::Error> {
+        if self.mls_group_config.message_archive_config.is_none() {
+            return Ok(());
+        }
+
+        let now = SystemClock.now();
+        let epoch = epoch_tree.epoch();
+        storage.write_archived_message_secrets(self.group_id(), &epoch, &epoch_tree)?;
+        self.message_secrets_store.add_archived_epoch(epoch, now);
+        self.expire_message_archive(storage, now)?;
+        Ok(())
+    }
+
+    /// Loads the archived message secrets of the `epoch` for processing a
+    /// message, if they are in the message archive. Returns `true` if they
+    /// were loaded.
+    pub(super) fn load_archived_message_secrets<Storage: StorageProvider>(
+        &mut self,
+        storage: &Storage,
+        epoch: GroupEpoch,
+    ) -> Result<bool, Storage::Error> {
+        if epoch >= self.epoch() || !self.message_secrets_store.is_archived(epoch) {
+            return Ok(false);
+        }
+
+        let Some(epoch_tree) = storage.archived_message_secrets(self.group_id(), &epoch)? else {
+            return Ok(false);
+        };
+        self.message_secrets_store.set_archived_tree(epoch_tree);
+        Ok(true)
+    }
+
+    /// Writes the message secrets loaded with
+    /// [`Self::load_archived_message_secrets()`] back to the message archive,
+    /// so that the used secrets can't be used again.
+    pub(super) fn store_archived_message_secrets<Storage: StorageProvider>(
+        &mut self,
+        storage: &Storage,
+    ) -> Result<(), Storage::Error> {
+        let Some(epoch_tree) = self.message_secrets_store.take_archived_tree() else {
+            return Ok(());
+        };
+        storage.write_archived_message_secrets(self.group_id(), &epoch_tree.epoch(), &epoch_tree)
+    }
+
+    /// Deletes all archived message secrets.
+    pub(super) fn delete_message_archive<Storage: StorageProvider>(
+        &self,
+        storage: &Storage,
+    ) -> Result<(), Storage::Error> {
+        for archived in self.message_secrets_store.archived_epochs() {
+            storage.delete_archived_message_secrets(self.group_id(), &archived.epoch)?;
+        }
+        Ok(())
+    }
+
+    fn expire_message_archive<Storage: StorageProvider>(
+        &mut self,
+        storage: &Storage,
+        now: u64,
+    ) -> Result<usize, Storage::Error> {
+        let (max_epochs, max_age) = match self.mls_group_config.message_archive_config {
+            Some(config) => (config.max_epochs(), config.max_age()),
+            None => (0, None),
+        };
+        let expired = self
+            .message_secrets_store
+            .expire_archived_epochs(max_epochs, max_age, now);
+        for epoch in &expired {
+            storage.delete_archived_message_secrets(self.group_id(), epoch)?;
+        }
+        Ok(expired.len())
+    }
+}
@@ -0,0 +1,337 @@
+//! Buffering of messages for future epochs.
+//!
+//! A proposal or application message for epoch `N + 1` may arrive before the
+//! commit that creates epoch `N + 1`. Such a message can't be processed yet.
+//! If a [`MessageBufferConfig`] is set in the group's [`MlsGroupJoinConfig`],
+//! [`MlsGroup::process_or_buffer_message()`] stores these messages in the
+//! storage provider instead of failing. When a commit is merged, buffered
+//! messages for the new epoch are processed. The results can be taken with
+//! [`MlsGroup::take_replayed_messages()`], and the messages stay in storage
+//! until then.
+
+use serde::{Deserialize, Serialize};
+use tls_codec::{Deserialize as _, Serialize as _};
+
+use super::{
+    errors::{ProcessMessageError, ProcessOrBufferMessageError},
+    *,
+};
+use crate::group::errors::ValidationError;
+
+/// Configuration for buffering messages for future epochs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageBufferConfig {
+    max_messages: usize,
+    max_epochs_ahead: u64,
+}
+
+impl MessageBufferConfig {
+    /// Create a new configuration. At most `max_messages` messages are
+    /// buffered, and only for epochs at most `max_epochs_ahead` epochs after
+    /// the current one.
+    pub fn new(max_messages: usize, max_epochs_ahead: u64) -> Self {
+        Self {
+            max_messages,
+            max_epochs_ahead,
+        }
+    }
+
+    /// Returns the maximum number of buffered messages.
+    pub fn max_messages(&self) -> usize {
+        self.max_messages
+    }
+
+    /// Returns how many epochs ahead of the current epoch messages are
+    /// buffered.
+    pub fn max_epochs_ahead(&self) -> u64 {
+        self.max_epochs_ahead
+    }
+}
+
+impl Default for MessageBufferConfig {
+    fn default() -> Self {
+        Self::new(100, 1)
+    }
+}
+
+/// A message for a future epoch that is held in the message buffer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BufferedMessage {
+    epoch: GroupEpoch,
+    content_type: ContentType,
+    wire_format: WireFormat,
+    // The TLS-serialized `PrivateMessageIn` or `PublicMessageIn`.
+    message: Vec<u8>,
+}
+
+impl BufferedMessage {
+    fn new(message: &ProtocolMessage) -> Result<Self, LibraryError> {
+        let bytes = match message {
+            ProtocolMessage::PrivateMessage(m) => m.tls_serialize_detached(),
+            ProtocolMessage::PublicMessage(m) => m.tls_serialize_detached(),
+        }
+        .map_err(LibraryError::missing_bound_check)?;
+
+        Ok(Self {
+            epoch: message.epoch(),
+            content_type: message.content_type(),
+            wire_format: message.wire_format(),
+            message: bytes,
+        })
+    }
+
+    /// Returns the epoch of the message.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the content type of the message.
+    pub fn content_type(&self) -> ContentType {
+        self.content_type
+    }
+
+    /// Returns the wire format of the message.
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire_format
+    }
+
+    /// Returns the buffered message as a [`ProtocolMessage`].
+    pub fn protocol_message(&self) -> Result<ProtocolMessage, tls_codec::Error> {
+        match self.wire_format {
+            WireFormat::PrivateMessage => {
+                PrivateMessageIn::tls_deserialize_exact(&self.message).map(Into::into)
+            }
+            WireFormat::PublicMessage => {
+                PublicMessageIn::tls_deserialize_exact(&self.message).map(Into::into)
+            }
+            _ => Err(tls_codec::Error::DecodingError(
+                "Buffered message has an unexpected wire format.".to_string(),
+            )),
+        }
+    }
+}
+
+/// A buffered message that was processed after a commit was merged.
+#[derive(Debug)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(Clone, PartialEq))]
+pub struct ReplayedMessage {
+    message: BufferedMessage,
+    result: Result<ProcessedMessage, ProcessMessageError>,
+}
+
+impl ReplayedMessage {
+    /// Returns the buffered message.
+    pub fn message(&self) -> &BufferedMessage {
+        &self.message
+    }
+
+    /// Returns the result of processing the buffered message.
+    pub fn result(&self) -> Result<&ProcessedMessage, &ProcessMessageError> {
+        self.result.as_ref()
+    }
+
+    /// Returns the result of processing the buffered message, consuming the
+    /// [`ReplayedMessage`].
+    pub fn into_result(self) -> Result<ProcessedMessage, ProcessMessageError> {
+        self.result
+    }
+}
+
+/// The buffered messages of a group, in the order they were received.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct MessageBuffer {
+    messages: Vec<BufferedMessage>,
+}
+
+impl MlsGroup {
+    /// Processes the message like [`MlsGroup::process_message()`], unless it
+    /// is for a future epoch that can't be processed yet. Such a message is
+    /// stored in the message buffer and `None` is returned. It is processed
+    /// when a commit that advances the group to the message's epoch is merged.
+    ///
+    /// Messages are only buffered if a [`MessageBufferConfig`] is set in the
+    /// group's configuration, and only if the message's epoch is at most
+    /// [`MessageBufferConfig::max_epochs_ahead()`] epochs after the current
+    /// epoch.
+    ///
+    /// # Errors:
+    /// Returns [`ProcessOrBufferMessageError::BufferFull`] if the message
+    /// should be buffered, but the buffer already holds
+    /// [`MessageBufferConfig::max_messages()`] messages.
+    pub fn process_or_buffer_message<Provider: OpenMlsProvider>(
+        &mut self,
+        provider: &Provider,
+        message: impl Into<ProtocolMessage>,
+    ) -> Result<Option<ProcessedMessage>, ProcessOrBufferMessageError<Provider::StorageError>> {
+        let message = message.into();
+
+        let Some(config) = self.mls_group_config.message_buffer_config else {
+            return Ok(Some(self.process_message(provider, message)?));
+        };
+
+        let current_epoch = self.epoch().as_u64();
+        let message_epoch = message.epoch().as_u64();
+        if !self.is_active()
+            || message.group_id() != self.group_id()
+            || message_epoch <= current_epoch
+            || message_epoch - current_epoch > config.max_epochs_ahead
+        {
+            return Ok(Some(self.process_message(provider, message)?));
+        }
+
+        let storage = provider.storage();
+        let mut buffer: MessageBuffer = storage
+            .message_buffer(self.group_id())
+            .map_err(ProcessOrBufferMessageError::StorageError)?
+            .unwrap_or_default();
+        if buffer.messages.len() >= config.max_messages {
+            return Err(ProcessOrBufferMessageError::BufferFull);
+        }
+
+        buffer.messages.push(BufferedMessage::new(&message)?);
+        storage
+            .write_message_buffer(self.group_id(), &buffer)
+            .map_err(ProcessOrBufferMessageError::StorageError)?;
+
+        Ok(None)
+    }
+
+    /// Returns the messages that are currently buffered, in the order they
+    /// were received.
+    pub fn buffered_messages<Storage: StorageProvider>(
+        &self,
+        storage: &Storage,
+    ) -> Result<Vec<BufferedMessage>, Storage::Error> {
+        let buffer: Option<MessageBuffer> = storage.message_buffer(self.group_id())?;
+        Ok(buffer.map(|buffer| buffer.messages).unwrap_or_default())
+    }
+
+    /// Removes all buffered messages for which `evict` returns `true` and
+    /// returns the number of removed messages.
+    pub fn evict_buffered_messages<Storage: StorageProvider>(
+        &self,
+        storage: &Storage,
+        mut evict: impl FnMut(&BufferedMessage) -> bool,
+    ) -> Result<usize, Storage::Error> {
+        let Some(mut buffer) = storage.message_buffer::<GroupId, MessageBuffer>(self.group_id())?
+        else {
+            return Ok(0);
+        };
+
+        let before = buffer.messages.len();
+        buffer.messages.retain(|message| !evict(message));
+        let evicted = before - buffer.messages.len();
+
+        if buffer.messages.is_empty() {
+            storage.delete_message_buffer(self.group_id())?;
+        } else if evicted > 0 {
+            storage.write_message_buffer(self.group_id(), &buffer)?;
+        }
+
+        Ok(evicted)
+    }
+
+    /// Removes all buffered messages.
+    pub fn clear_message_buffer<Storage: StorageProvider>(
+        &self,
+        storage: &Storage,
+    ) -> Result<(), Storage::Error> {
+        storage.delete_message_buffer(self.group_id())
+    }
+
+    /// Processes the buffered messages for the current epoch that haven't
+    /// been processed yet. The results are kept in the group until they are
+    /// taken with [`MlsGroup::take_replayed_messages()`].
+    ///
+    /// This is done automatically when a commit is merged. It only needs to be
+    /// called after loading a group from storage whose replayed messages were
+    /// not taken before.
+    ///
+    /// Buffered messages for past epochs can't be processed anymore and are
+    /// replayed with a [`ValidationError::WrongEpoch`] error.
+    pub fn replay_buffered_messages<Provider: OpenMlsProvider>(
+        &mut self,
+        provider: &Provider,
+    ) -> Result<(), Provider::StorageError> {
+        let Some(buffer) = provider
+            .storage()
+            .message_buffer::<GroupId, MessageBuffer>(self.group_id())?
+        else {
+            return Ok(());
+        };
+
+        // Messages that were replayed before are still in the buffer until
+        // they are taken.
+        let mut replayed: Vec<&BufferedMessage> = self
+            .replayed_messages
+            .iter()
+            .map(|replayed| &replayed.message)
+            .collect();
+        let mut pending = Vec::new();
+        for message in buffer.messages {
+            match replayed.iter().position(|replayed| **replayed == message) {
+                Some(position) => {
+                    replayed.swap_remove(position);
+                }
+                None => pending.push(message),
+            }
+        }
+
+        let current_epoch = self.epoch();
+        for message in pending {
+            if message.epoch > current_epoch {
+                continue;
+            }
+            let result = if message.epoch < current_epoch {
+                Err(ValidationError::WrongEpoch.into())
+            } else {
+                message
+                    .protocol_message()
+                    .map_err(|_| {
+                        LibraryError::custom("Buffered message could not be decoded").into()
+                    })
+                    .and_then(|protocol_message| self.process_message(provider, protocol_message))
+            };
+            self.replayed_messages
+                .push(ReplayedMessage { message, result });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the buffered messages that were processed since the last call,
+    /// in the order they were received, and removes them from the message
+    /// buffer. This includes messages that failed to process.
+    ///
+    /// Replayed proposals have to be stored with
+    /// [`MlsGroup::store_pending_proposal()`] and replayed commits have to be
+    /// merged with [`MlsGroup::merge_staged_commit()`], just like messages
+    /// returned by [`MlsGroup::process_message()`].
+    pub fn take_replayed_messages<Storage: StorageProvider>(
+        &mut self,
+        storage: &Storage,
+    ) -> Result<Vec<ReplayedMessage>, Storage::Error> {
+        if self.replayed_messages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut buffer: MessageBuffer =
+            storage.message_buffer(self.group_id())?.unwrap_or_default();
+        for replayed in &self.replayed_messages {
+            if let Some(position) = buffer
+                .messages
+                .iter()
+                .position(|message| *message == replayed.message)
+            {
+                buffer.messages.remove(position);
+            }
+        }
+        if buffer.messages.is_empty() {
+            storage.delete_message_buffer(self.group_id())?;
+        } else {
+            storage.write_message_buffer(self.group_id(), &buffer)?;
+        }
+
+        Ok(std::mem::take(&mut self.replayed_messages))
+    }
+}
@@ -4,7 +4,7 @@
 //!
 
 use create_commit::CreateCommitParams;
-use past_secrets::MessageSecretsStore;
+use past_secrets::{MessageSecretsStore, PastExporterSecrets};
 use proposal_store::ProposalQueue;
 use serde::{Deserialize, Serialize};
 use staged_commit::{MemberStagedCommitState, StagedCommitState};
@@ -38,6 +38,7 @@ use crate::{
         GroupEpochSecrets, JoinerSecret, KeySchedule,
     },
     storage::{OpenMlsProvider, StorageProvider},
+    tree::sender_ratchet::SenderRatchetState,
     treesync::{
         node::{encryption_keys::EncryptionKeyPair, leaf_node::LeafNode},
         RatchetTree,
@@ -48,12 +49,32 @@ use openmls_traits::{signatures::Signer, storage::StorageProvider as _, types::C
 
 // Private
 mod application;
+mod batch;
+mod commit_changes;
+mod commit_history;
+mod commit_policy;
 mod creation;
 mod exporting;
+mod proposal_policy;
+mod rebase;
+mod update_scheduler;
 mod updates;
 
 use config::*;
 
+pub use batch::{MessageOutcome, ProcessingPolicy};
+pub use commit_changes::{CommitChanges, ExtensionsChange, UpdatedMember};
+pub(crate) use commit_history::CommittedContent;
+pub use commit_history::{CommitHistory, CommitHistoryConfig, EpochRecord};
+pub use commit_policy::{Clock, CommitPolicy, CommitPolicyEngine, Committer, SystemClock};
+pub use message_archive::MessageArchiveConfig;
+pub use message_buffer::{BufferedMessage, MessageBufferConfig, ReplayedMessage};
+use proposal_policy::GroupProposalPolicy;
+pub use proposal_policy::{ProposalDelivery, ProposalPolicy, ProposalRejection};
+pub use rebase::CommitIntent;
+use update_scheduler::GroupClock;
+pub use update_scheduler::{OwnLeafUpdate, UpdateScheduler};
+
 // Crate
 pub(crate) mod builder;
 pub(crate) mod commit_builder;
@@ -62,6 +83,8 @@ pub(crate) mod create_commit;
 pub mod dmls;
 pub(crate) mod errors;
 pub(crate) mod membership;
+pub(crate) mod message_archive;
+pub(crate) mod message_buffer;
 pub(crate) mod past_secrets;
 pub(crate) mod processing;
 pub(crate) mod proposal;
@@ -158,7 +181,9 @@ impl From<PendingCommitState> for StagedCommit {
 ///   state when it processes a commit that removes this client from the group.
 ///   This is a terminal state that the group can not exit from. If the clients
 ///   wants to re-join the group, it can either be added by a group member or it
-///   can join via external commit.
+///   can join via external commit. A group also enters this state when it
+///   merges a commit with a ReInit proposal. The members then continue in the
+///   new group announced by the proposal.
 ///
 /// * [`MlsGroupState::PendingCommit`]: This state is split into two possible
 ///   sub-states, one for each Commit type:
@@ -192,7 +217,8 @@ pub enum MlsGroupState {
     PendingCommit(Box<PendingCommitState>),
     /// The group state is in an opertaional state, where new messages and Commits can be created.
     Operational,
-    /// The group is inactive because the member has been removed.
+    /// The group is inactive because the member has been removed or the group
+    /// was reinitialized.
     Inactive,
 }
 
@@ -240,6 +266,8 @@ pub struct MlsGroup {
     message_secrets_store: MessageSecretsStore,
     // Resumption psk store. This is where the resumption psks are kept in a rollover list.
     resumption_psk_store: ResumptionPskStore,
+    // Exporter secrets and epoch authenticators of past epochs.
+    past_exporter_secrets: PastExporterSecrets,
     // Own [`LeafNode`]s that were created for update proposals and that
     // are needed in case an update proposal is committed by another group
     // member. The vector is emptied after every epoch change.
@@ -251,6 +279,18 @@ pub struct MlsGroup {
     // A variable that indicates the state of the group. See [`MlsGroupState`]
     // for more information.
     group_state: MlsGroupState,
+    // The epoch and time of the last update of the own leaf.
+    own_leaf_update: Option<OwnLeafUpdate>,
+    // The policy incoming proposals are checked against. It is set by the
+    // application and not persisted.
+    proposal_policy: GroupProposalPolicy,
+    // The clock the time of own leaf updates is taken from. It is set by the
+    // application and not persisted.
+    clock: GroupClock,
+    // Buffered messages that were processed after merging a commit and that
+    // haven't been taken by the application yet. This is not persisted, the
+    // messages stay in the message buffer until they are taken.
+    replayed_messages: Vec<ReplayedMessage>,
 }
 
 impl MlsGroup {
@@ -268,6 +308,11 @@ impl MlsGroup {
         mls_group_config: &MlsGroupJoinConfig,
     ) -> Result<(), Storage::Error> {
         self.mls_group_config = mls_group_config.clone();
+        if self.past_exporter_secrets.max_epochs() != mls_group_config.max_past_exporter_epochs() {
+            self.past_exporter_secrets
+                .resize(mls_group_config.max_past_exporter_epochs());
+            storage.write_past_exporter_secrets(self.group_id(), &self.past_exporter_secrets)?;
+        }
         storage.write_mls_join_config(self.group_id(), mls_group_config)
     }
 
@@ -334,6 +379,32 @@ impl MlsGroup {
         self.public_group.group_context().epoch()
     }
 
+    /// Returns the state of the sender ratchets of the other members in the
+    /// current epoch, ordered by sender. A ratchet is only reported once a
+    /// message of the sender was received in the current epoch.
+    ///
+    /// This helps to tune the
+    /// [`SenderRatchetConfiguration`](crate::tree::sender_ratchet::SenderRatchetConfiguration)
+    /// and to diagnose lost or replayed messages.
+    pub fn sender_ratchet_states(&self) -> Vec<SenderRatchetState> {
+        self.message_secrets().secret_tree().sender_ratchet_states()
+    }
+
+    /// Returns the state of the sender ratchets of the other members in the
+    /// past `epoch`, or `None` if the message secrets of the epoch aren't kept
+    /// in memory. See [`Self::sender_ratchet_states()`].
+    pub fn sender_ratchet_states_for_epoch(
+        &self,
+        epoch: GroupEpoch,
+    ) -> Option<Vec<SenderRatchetState>> {
+        if epoch == self.epoch() {
+            return Some(self.sender_ratchet_states());
+        }
+        self.message_secrets_store
+            .secrets_for_epoch(epoch)
+            .map(|message_secrets| message_secrets.secret_tree().sender_ratchet_states())
+    }
+
     /// Returns an `Iterator` over pending proposals.
     pub fn pending_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
         self.proposal_store().proposals()
@@ -418,6 +489,9 @@ impl MlsGroup {
     // === Storage Methods ===
 
     /// Loads the state of the group with given id from persisted state.
+    ///
+    /// The [`ProposalPolicy`] isn't persisted, use
+    /// [`MlsGroup::load_with_policy()`] to set it again.
     pub fn load<Storage: crate::storage::StorageProvider>(
         storage: &Storage,
         group_id: &GroupId,
@@ -430,18 +504,30 @@ impl MlsGroup {
         let mls_group_config = storage.mls_group_join_config(group_id)?;
         let own_leaf_nodes = storage.own_leaf_nodes(group_id)?;
         let group_state = storage.group_state(group_id)?;
+        let own_leaf_update = storage.own_leaf_update(group_id)?;
+        let past_exporter_secrets = storage.past_exporter_secrets(group_id)?;
 
         let build = || -> Option<Self> {
+            let mls_group_config: MlsGroupJoinConfig = mls_group_config?;
+            // Groups stored by earlier versions don't have past exporter secrets.
+            let past_exporter_secrets = past_exporter_secrets.unwrap_or_else(|| {
+                PastExporterSecrets::new(mls_group_config.max_past_exporter_epochs())
+            });
             Some(Self {
                 public_group: public_group?,
                 group_epoch_secrets: group_epoch_secrets?,
                 own_leaf_index: own_leaf_index?,
                 message_secrets_store: message_secrets_store?,
                 resumption_psk_store: resumption_psk_store?,
-                mls_group_config: mls_group_config?,
+                past_exporter_secrets,
+                mls_group_config,
                 own_leaf_nodes,
                 aad: vec![],
                 group_state: group_state?,
+                own_leaf_update,
+                proposal_policy: GroupProposalPolicy::default(),
+                clock: GroupClock::default(),
+                replayed_messages: vec![],
             })
         };
 
@@ -458,11 +544,16 @@ impl MlsGroup {
         PublicGroup::delete(storage, self.group_id())?;
         storage.delete_own_leaf_index(self.group_id())?;
         storage.delete_group_epoch_secrets(self.group_id())?;
+        self.delete_message_archive(storage)?;
         storage.delete_message_secrets(self.group_id())?;
         storage.delete_all_resumption_psk_secrets(self.group_id())?;
         storage.delete_group_config(self.group_id())?;
         storage.delete_own_leaf_nodes(self.group_id())?;
         storage.delete_group_state(self.group_id())?;
+        storage.delete_own_leaf_update(self.group_id())?;
+        self.delete_epoch_records(storage, self.epoch())?;
+        storage.delete_past_exporter_secrets(self.group_id())?;
+        storage.delete_message_buffer(self.group_id())?;
         storage.clear_proposal_queue::<GroupId, ProposalRef>(self.group_id())?;
 
         self.proposal_store_mut().empty();
@@ -598,14 +689,27 @@ impl MlsGroup {
         public_message: AuthenticatedContent,
         provider: &Provider,
     ) -> Result<PrivateMessage, MessageEncryptionError<Provider::StorageError>> {
-        let padding_size = self.configuration().padding_size();
+        let padding = self
+            .configuration()
+            .padding_strategy(public_message.content().content_type());
+        self.encrypt_with_padding(public_message, provider, &padding)
+    }
+
+    // Encrypt an AuthenticatedContent into an PrivateMessage with the given
+    // padding policy instead of the configured one
+    pub(crate) fn encrypt_with_padding<Provider: OpenMlsProvider>(
+        &mut self,
+        public_message: AuthenticatedContent,
+        provider: &Provider,
+        padding: &impl PaddingPolicy,
+    ) -> Result<PrivateMessage, MessageEncryptionError<Provider::StorageError>> {
         let msg = PrivateMessage::try_from_authenticated_content(
             provider.crypto(),
             provider.rand(),
             &public_message,
             self.ciphersuite(),
             self.message_secrets_store.message_secrets_mut(),
-            padding_size,
+            padding,
         )?;
 
         provider
@@ -716,8 +820,12 @@ impl MlsGroup {
         storage.write_own_leaf_index(self.group_id(), &self.own_leaf_index)?;
         storage.write_message_secrets(self.group_id(), &self.message_secrets_store)?;
         storage.write_resumption_psk_store(self.group_id(), &self.resumption_psk_store)?;
+        storage.write_past_exporter_secrets(self.group_id(), &self.past_exporter_secrets)?;
         storage.write_mls_join_config(self.group_id(), &self.mls_group_config)?;
         storage.write_group_state(self.group_id(), &self.group_state)?;
+        if let Some(own_leaf_update) = &self.own_leaf_update {
+            storage.write_own_leaf_update(self.group_id(), own_leaf_update)?;
+        }
 
         Ok(())
     }
@@ -848,6 +956,9 @@ pub struct StagedWelcome {
 
     /// If we got a path secret, these are the derived path keys.
     path_keypairs: Option<Vec<EncryptionKeyPair>>,
+
+    /// The clock of the group. See [`MlsGroup::set_clock()`].
+    clock: GroupClock,
 }
 
 /// A `Welcome` message that has been processed but not staged yet.
@@ -1,19 +1,49 @@
 use std::collections::VecDeque;
 
-use crate::schedule::message_secrets::MessageSecrets;
+use crate::{
+    schedule::{message_secrets::MessageSecrets, EpochAuthenticator, ExporterSecret},
+    tree::{secret_tree::evict_cached_keys, sender_ratchet::MessageKeyCacheConfig},
+};
 
 use super::*;
 
-// Internal helper struct
+/// The message secrets of a past epoch together with the members of that
+/// epoch. Also the entity that is written to the message archive.
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "test-utils"), derive(Clone, PartialEq))]
 #[cfg_attr(feature = "crypto-debug", derive(Debug))]
-struct EpochTree {
+pub(crate) struct EpochTree {
     epoch: u64,
     message_secrets: MessageSecrets,
     leaves: Vec<Member>,
 }
 
+#[cfg(not(feature = "crypto-debug"))]
+impl core::fmt::Debug for EpochTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EpochTree")
+            .field("epoch", &self.epoch)
+            .field("message_secrets", &"***")
+            .field("leaves", &self.leaves)
+            .finish()
+    }
+}
+
+impl EpochTree {
+    /// Returns the epoch of the tree.
+    pub(crate) fn epoch(&self) -> GroupEpoch {
+        GroupEpoch(self.epoch)
+    }
+}
+
+// Internal helper struct
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ArchivedEpoch {
+    pub(crate) epoch: GroupEpoch,
+    // Seconds since the Unix epoch.
+    pub(crate) archived_at: u64,
+}
+
 /// Can store message secrets for up to `max_epochs`. The trees are added with [`self::add()`] and can be queried
 /// with [`Self::get_epoch()`].
 #[derive(Serialize, Deserialize)]
@@ -26,6 +56,13 @@ pub(crate) struct MessageSecretsStore {
     past_epoch_trees: VecDeque<EpochTree>,
     // The message secrets of the current epoch.
     message_secrets: MessageSecrets,
+    // The epochs in the message archive, oldest first.
+    #[serde(default)]
+    archived_epochs: VecDeque<ArchivedEpoch>,
+    // The tree of an archived epoch that is loaded while a message of that
+    // epoch is processed. Never persisted with the store.
+    #[serde(skip)]
+    archived_tree: Option<EpochTree>,
 }
 
 #[cfg(not(feature = "crypto-debug"))]
@@ -35,6 +72,7 @@ impl core::fmt::Debug for MessageSecretsStore {
             .field("max_epochs", &"***")
             .field("past_epoch_trees", &"***")
             .field("message_secrets", &"***")
+            .field("archived_epochs", &self.archived_epochs)
             .finish()
     }
 }
@@ -47,6 +85,8 @@ impl MessageSecretsStore {
             max_epochs,
             past_epoch_trees: VecDeque::new(),
             message_secrets,
+            archived_epochs: VecDeque::new(),
+            archived_tree: None,
         }
     }
 
@@ -63,32 +103,36 @@ impl MessageSecretsStore {
 
     /// Add a secret tree for a given epoch `group_epoch`.
     /// Note that this does not take the epoch into account and pops out the
-    /// oldest element.
+    /// oldest element, which is returned. If the store doesn't hold any
+    /// past epochs, the new tree is returned instead.
     pub(crate) fn add(
         &mut self,
         group_epoch: impl Into<GroupEpoch>,
         message_secrets: MessageSecrets,
         leaves: Vec<Member>,
-    ) {
-        // Don't store the tree if it's not intended
-        if self.max_epochs == 0 {
-            return;
-        }
-        if self.past_epoch_trees.len() >= self.max_epochs {
-            self.past_epoch_trees.rotate_left(1);
-            self.past_epoch_trees.truncate(self.max_epochs - 1);
-        }
-        self.past_epoch_trees.push_back(EpochTree {
+    ) -> Option<EpochTree> {
+        let epoch_tree = EpochTree {
             epoch: group_epoch.into().as_u64(),
             message_secrets,
             leaves,
-        });
+        };
+        // Don't store the tree if it's not intended
+        if self.max_epochs == 0 {
+            return Some(epoch_tree);
+        }
+        let evicted = if self.past_epoch_trees.len() >= self.max_epochs {
+            self.past_epoch_trees.pop_front()
+        } else {
+            None
+        };
+        self.past_epoch_trees.push_back(epoch_tree);
         debug_assert!(
             self.max_epochs >= self.past_epoch_trees.len(),
             "Only {} past secrets must be stored but we found {}",
             self.max_epochs,
             self.past_epoch_trees.len()
         );
+        evicted
     }
 
     /// Get a mutable reference to a secret tree for a given epoch `group_epoch`.
@@ -98,7 +142,11 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
     ) -> Option<&mut MessageSecrets> {
         let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter_mut() {
+        for epoch_tree in self
+            .past_epoch_trees
+            .iter_mut()
+            .chain(&mut self.archived_tree)
+        {
             if epoch_tree.epoch == epoch {
                 return Some(&mut epoch_tree.message_secrets);
             }
@@ -113,7 +161,7 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
     ) -> Option<&MessageSecrets> {
         let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter() {
+        for epoch_tree in self.past_epoch_trees.iter().chain(&self.archived_tree) {
             if epoch_tree.epoch == epoch {
                 return Some(&epoch_tree.message_secrets);
             }
@@ -129,7 +177,11 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
     ) -> Option<(&mut MessageSecrets, &[Member])> {
         let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter_mut() {
+        for epoch_tree in self
+            .past_epoch_trees
+            .iter_mut()
+            .chain(&mut self.archived_tree)
+        {
             if epoch_tree.epoch == epoch {
                 return Some((&mut epoch_tree.message_secrets, &epoch_tree.leaves));
             }
@@ -140,7 +192,7 @@ impl MessageSecretsStore {
     /// Return a slice with the [`Member`]s of the `group_epoch`.
     pub(crate) fn leaves_for_epoch(&self, group_epoch: impl Into<GroupEpoch>) -> &[Member] {
         let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter() {
+        for epoch_tree in self.past_epoch_trees.iter().chain(&self.archived_tree) {
             if epoch_tree.epoch == epoch {
                 return &epoch_tree.leaves;
             }
@@ -154,12 +206,15 @@ impl MessageSecretsStore {
         group_epoch: GroupEpoch,
         leaf_index: LeafNodeIndex,
     ) -> bool {
-        self.past_epoch_trees.iter().any(|t| {
-            t.epoch == group_epoch.0
-                && t.leaves
-                    .iter()
-                    .any(|Member { index, .. }| *index == leaf_index)
-        })
+        self.past_epoch_trees
+            .iter()
+            .chain(&self.archived_tree)
+            .any(|t| {
+                t.epoch == group_epoch.0
+                    && t.leaves
+                        .iter()
+                        .any(|Member { index, .. }| *index == leaf_index)
+            })
     }
 
     /// Get a mutable reference to the message secrets of the current epoch.
@@ -171,4 +226,167 @@ impl MessageSecretsStore {
     pub(crate) fn message_secrets(&self) -> &MessageSecrets {
         &self.message_secrets
     }
+
+    /// Evicts cached message keys of the current and the past epochs until
+    /// the limit of the `config` is met. Returns the number of evicted keys.
+    pub(crate) fn evict_cached_keys(&mut self, config: &MessageKeyCacheConfig) -> usize {
+        let secret_trees = std::iter::once(&mut self.message_secrets)
+            .chain(
+                self.past_epoch_trees
+                    .iter_mut()
+                    .map(|epoch_tree| &mut epoch_tree.message_secrets),
+            )
+            .map(|message_secrets| message_secrets.secret_tree_mut());
+        evict_cached_keys(secret_trees, config)
+    }
+
+    /// Returns `true` if the secrets of the past `group_epoch` are in the
+    /// message archive and not in memory.
+    pub(crate) fn is_archived(&self, group_epoch: GroupEpoch) -> bool {
+        self.archived_epochs
+            .iter()
+            .any(|archived| archived.epoch == group_epoch)
+            && !self
+                .past_epoch_trees
+                .iter()
+                .any(|epoch_tree| epoch_tree.epoch == group_epoch.as_u64())
+    }
+
+    /// Returns the epochs in the message archive, oldest first.
+    pub(crate) fn archived_epochs(&self) -> impl Iterator<Item = &ArchivedEpoch> {
+        self.archived_epochs.iter()
+    }
+
+    /// Records that the secrets of the `group_epoch` were archived at
+    /// `archived_at`.
+    pub(crate) fn add_archived_epoch(&mut self, group_epoch: GroupEpoch, archived_at: u64) {
+        self.archived_epochs.push_back(ArchivedEpoch {
+            epoch: group_epoch,
+            archived_at,
+        });
+    }
+
+    /// Removes the archived epochs that are older than `max_age` seconds at
+    /// `now`, and the oldest ones beyond `max_epochs`, from the record and
+    /// returns them.
+    pub(crate) fn expire_archived_epochs(
+        &mut self,
+        max_epochs: usize,
+        max_age: Option<u64>,
+        now: u64,
+    ) -> Vec<GroupEpoch> {
+        let mut expired = Vec::new();
+        while let Some(oldest) = self.archived_epochs.front() {
+            let too_old =
+                max_age.is_some_and(|max_age| now.saturating_sub(oldest.archived_at) > max_age);
+            if !too_old && self.archived_epochs.len() <= max_epochs {
+                break;
+            }
+            expired.push(oldest.epoch);
+            self.archived_epochs.pop_front();
+        }
+        expired
+    }
+
+    /// Makes the archived `epoch_tree` available to the lookups until it is
+    /// taken back with [`Self::take_archived_tree()`].
+    pub(crate) fn set_archived_tree(&mut self, epoch_tree: EpochTree) {
+        self.archived_tree = Some(epoch_tree);
+    }
+
+    /// Takes the archived tree set with [`Self::set_archived_tree()`].
+    pub(crate) fn take_archived_tree(&mut self) -> Option<EpochTree> {
+        self.archived_tree.take()
+    }
+}
+
+// Internal helper struct
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(Clone, PartialEq))]
+struct PastExporterSecret {
+    epoch: GroupEpoch,
+    exporter_secret: ExporterSecret,
+    epoch_authenticator: EpochAuthenticator,
+}
+
+/// Can store exporter secrets and epoch authenticators for up to `max_epochs`
+/// past epochs.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(Clone, PartialEq))]
+pub(crate) struct PastExporterSecrets {
+    max_epochs: usize,
+    // Oldest first
+    secrets: VecDeque<PastExporterSecret>,
+}
+
+impl core::fmt::Debug for PastExporterSecrets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PastExporterSecrets")
+            .field("max_epochs", &self.max_epochs)
+            .field("secrets", &"***")
+            .finish()
+    }
+}
+
+impl PastExporterSecrets {
+    /// Create a new store that can hold the secrets of up to `max_epochs`
+    /// past epochs.
+    pub(crate) fn new(max_epochs: usize) -> Self {
+        Self {
+            max_epochs,
+            secrets: VecDeque::new(),
+        }
+    }
+
+    /// Returns the maximum number of past epochs.
+    pub(crate) fn max_epochs(&self) -> usize {
+        self.max_epochs
+    }
+
+    /// Resize the store, dropping the oldest secrets if it shrinks.
+    pub(crate) fn resize(&mut self, max_epochs: usize) {
+        self.max_epochs = max_epochs;
+        while self.secrets.len() > max_epochs {
+            self.secrets.pop_front();
+        }
+    }
+
+    /// Add the secrets of the past `epoch`, dropping the oldest secrets if
+    /// the store is full.
+    pub(crate) fn add(&mut self, epoch: GroupEpoch, group_epoch_secrets: GroupEpochSecrets) {
+        if self.max_epochs == 0 {
+            return;
+        }
+        let (exporter_secret, epoch_authenticator) = group_epoch_secrets.into_exporter_secrets();
+        if self.secrets.len() >= self.max_epochs {
+            self.secrets.pop_front();
+        }
+        self.secrets.push_back(PastExporterSecret {
+            epoch,
+            exporter_secret,
+            epoch_authenticator,
+        });
+    }
+
+    /// Get the exporter secret of the past `epoch`, if it is stored.
+    pub(crate) fn exporter_secret(&self, epoch: GroupEpoch) -> Option<&ExporterSecret> {
+        self.get(epoch).map(|secret| &secret.exporter_secret)
+    }
+
+    /// Get the epoch authenticator of the past `epoch`, if it is stored.
+    pub(crate) fn epoch_authenticator(&self, epoch: GroupEpoch) -> Option<&EpochAuthenticator> {
+        self.get(epoch).map(|secret| &secret.epoch_authenticator)
+    }
+
+    /// Delete the secrets of the past `epoch`. Returns `true` if they were
+    /// stored.
+    pub(crate) fn remove(&mut self, epoch: GroupEpoch) -> bool {
+        let len = self.secrets.len();
+        self.secrets.retain(|secret| secret.epoch != epoch);
+        self.secrets.len() != len
+    }
+
+    fn get(&self, epoch: GroupEpoch) -> Option<&PastExporterSecret> {
+        self.secrets.iter().find(|secret| secret.epoch == epoch)
+    }
 }
@@ -22,9 +22,16 @@ impl MlsGroup {
     /// and semantic validation of the message. It returns a [ProcessedMessage]
     /// enum.
     ///
+    /// If a [`ProposalPolicy`] is set, every proposal in the message is
+    /// checked against it. This includes standalone proposals, also from
+    /// external senders, and the proposals a commit covers inline or by
+    /// reference. See [`MlsGroup::set_proposal_policy()`].
+    ///
     /// # Errors:
     /// Returns an [`ProcessMessageError`] when the validation checks fail
-    /// with the exact reason of the failure.
+    /// with the exact reason of the failure. Returns
+    /// [`ProcessMessageError::ProposalRejected`] if the policy rejects a
+    /// proposal. Commits are rejected before they are staged.
     pub fn process_message<Provider: OpenMlsProvider>(
         &mut self,
         provider: &Provider,
@@ -51,20 +58,47 @@ impl MlsGroup {
         }
 
         // Parse the message
-        let sender_ratchet_configuration = *self.configuration().sender_ratchet_configuration();
+        let sender_ratchet_configuration = *self
+            .configuration()
+            .sender_ratchet_configuration_for(message.content_type());
+
+        // Load the message secrets if the message is for an archived epoch
+        let archived = self
+            .load_archived_message_secrets(provider.storage(), message.epoch())
+            .map_err(|e| {
+                log::error!("Error reading the message archive: {:?}", e);
+                ProcessMessageError::MessageArchiveError
+            })?;
 
         // Checks the following semantic validation:
         //  - ValSem002
         //  - ValSem003
         //  - ValSem006
         //  - ValSem007 MembershipTag presence
-        let decrypted_message =
-            self.decrypt_message(provider.crypto(), message, &sender_ratchet_configuration)?;
-
         let unverified_message = self
-            .public_group
-            .parse_message(decrypted_message, &self.message_secrets_store)
-            .map_err(ProcessMessageError::from)?;
+            .decrypt_message(provider.crypto(), message, &sender_ratchet_configuration)
+            .map_err(ProcessMessageError::from)
+            .and_then(|decrypted_message| {
+                self.public_group
+                    .parse_message(decrypted_message, &self.message_secrets_store)
+                    .map_err(ProcessMessageError::from)
+            });
+
+        // Write the ratcheted message secrets back to the archive
+        if archived {
+            self.store_archived_message_secrets(provider.storage())
+                .map_err(|e| {
+                    log::error!("Error writing the message archive: {:?}", e);
+                    ProcessMessageError::MessageArchiveError
+                })?;
+        }
+
+        // Keep the cached message keys within the budget
+        if let Some(message_key_cache_config) = self.configuration().message_key_cache_config {
+            self.message_secrets_store
+                .evict_cached_keys(&message_key_cache_config);
+        }
+        let unverified_message = unverified_message?;
 
         // If this is a commit, we need to load the private key material we need for decryption.
         let (old_epoch_keypairs, leaf_node_keypairs) =
@@ -137,12 +171,17 @@ impl MlsGroup {
 
     /// Merge a [StagedCommit] into the group after inspection. As this advances
     /// the epoch of the group, it also clears any pending commits.
+    ///
+    /// If message buffering is enabled, buffered messages for the new epoch
+    /// are processed afterwards. See [`MlsGroup::take_replayed_messages()`].
     pub fn merge_staged_commit<Provider: OpenMlsProvider>(
         &mut self,
         provider: &Provider,
         staged_commit: StagedCommit,
     ) -> Result<(), MergeCommitError<Provider::StorageError>> {
-        self.merge_staged_commit_inner(provider.storage(), staged_commit)
+        self.merge_staged_commit_inner(provider.storage(), staged_commit)?;
+        self.replay_buffered_messages(provider)
+            .map_err(MergeCommitError::StorageError)
     }
 
     // TODO: Maybe remove? This exists just to avoid changing the public API of
@@ -153,8 +192,21 @@ impl MlsGroup {
         storage: &Provider,
         staged_commit: StagedCommit,
     ) -> Result<(), MergeCommitError<Provider::Error>> {
-        // Check if we were removed from the group
-        if staged_commit.self_removed() {
+        // Remember the own encryption key to detect updates of the own leaf.
+        let own_encryption_key = self
+            .own_leaf_node()
+            .map(|leaf_node| leaf_node.encryption_key().clone());
+
+        let committed_content = staged_commit.committed_content().cloned();
+
+        // Check if we were removed from the group or if the group was
+        // reinitialized. After a ReInit, the group must not be used anymore
+        // (RFC 9420, Section 11.2).
+        if staged_commit.self_removed()
+            || staged_commit
+                .queued_proposals()
+                .any(|p| matches!(p.proposal(), Proposal::ReInit(_)))
+        {
             self.group_state = MlsGroupState::Inactive;
         }
         storage
@@ -164,6 +216,24 @@ impl MlsGroup {
         // Merge staged commit
         self.merge_commit(storage, staged_commit)?;
 
+        if self.is_active()
+            && self
+                .own_leaf_node()
+                .map(|leaf_node| leaf_node.encryption_key())
+                != own_encryption_key.as_ref()
+        {
+            self.record_own_leaf_update(storage)
+                .map_err(MergeCommitError::StorageError)?;
+        }
+
+        if let (true, Some(config), Some(committed_content)) = (
+            self.is_active(),
+            self.configuration().commit_history_config,
+            committed_content,
+        ) {
+            self.record_epoch(storage, committed_content, config)?;
+        }
+
         // Extract and store the resumption psk for the current epoch
         let resumption_psk = self.group_epoch_secrets().resumption_psk();
         self.resumption_psk_store
@@ -187,6 +257,9 @@ impl MlsGroup {
 
     /// Merges the pending [`StagedCommit`] if there is one, and
     /// clears the field by setting it to `None`.
+    ///
+    /// If message buffering is enabled, buffered messages for the new epoch
+    /// are processed afterwards. See [`MlsGroup::take_replayed_messages()`].
     pub fn merge_pending_commit<Provider: OpenMlsProvider>(
         &mut self,
         provider: &Provider,
@@ -199,6 +272,8 @@ impl MlsGroup {
                         provider.storage(),
                         (*pending_commit_state).into(),
                     )?;
+                    self.replay_buffered_messages(provider)
+                        .map_err(MergeCommitError::StorageError)?;
                 }
                 Ok(())
             }
@@ -276,6 +351,10 @@ impl MlsGroup {
         let (content, credential) =
             unverified_message.verify(self.ciphersuite(), provider.crypto(), self.version())?;
 
+        // Let the application authorize the proposals before the message is
+        // processed further
+        self.apply_proposal_policy(content.sender(), content.content())?;
+
         match content.sender() {
             Sender::Member(_) | Sender::NewMemberCommit | Sender::NewMemberProposal => {
                 let sender = content.sender().clone();
@@ -1,17 +1,26 @@
-use openmls_traits::{signatures::Signer, storage::StorageProvider as _, types::Ciphersuite};
+use openmls_traits::{
+    crypto::OpenMlsCrypto, signatures::Signer, storage::StorageProvider as _, types::Ciphersuite,
+};
 
 use super::{
-    errors::{ProposalError, ProposeAddMemberError, ProposeRemoveMemberError, RemoveProposalError},
+    errors::{
+        ProposalError, ProposeAddMemberError, ProposeRemoveMemberError, RemoveProposalError,
+        ValidateProposalsError,
+    },
     AddProposal, CreateGroupContextExtProposalError, CustomProposal, FramingParameters, MlsGroup,
-    PreSharedKeyProposal, Proposal, QueuedProposal, RemoveProposal, UpdateProposal, WireFormat,
+    PreSharedKeyProposal, Proposal, QueuedProposal, ReInitProposal, RemoveProposal, UpdateProposal,
+    WireFormat,
 };
 use crate::{
     binary_tree::LeafNodeIndex,
     ciphersuite::hash_ref::ProposalRef,
     credentials::Credential,
     extensions::Extensions,
-    framing::{mls_auth_content::AuthenticatedContent, MlsMessageOut},
-    group::{errors::CreateAddProposalError, GroupId, ValidationError},
+    framing::{mls_auth_content::AuthenticatedContent, MlsMessageOut, Sender},
+    group::{
+        errors::CreateAddProposalError, proposal_store::ProposalQueue, GroupId, ProposalQueueError,
+        ValidationError,
+    },
     key_packages::KeyPackage,
     messages::{group_info::GroupInfo, proposals::ProposalOrRefType},
     prelude::LibraryError,
@@ -125,6 +134,13 @@ impl MlsGroup {
         ProposalOrRefType::Proposal
     );
 
+    impl_propose_fun!(
+        propose_reinit,
+        ReInitProposal,
+        create_reinit_proposal,
+        ProposalOrRefType::Reference
+    );
+
     impl_propose_fun!(
         propose_custom_proposal_by_value,
         CustomProposal,
@@ -199,13 +215,20 @@ impl MlsGroup {
                 )),
             },
             Propose::ReInit {
-                group_id: _,
-                version: _,
-                ciphersuite: _,
-                extensions: _,
-            } => Err(ProposalError::LibraryError(LibraryError::custom(
-                "Unsupported proposal type ReInit",
-            ))),
+                group_id,
+                version,
+                ciphersuite,
+                extensions,
+            } => match ref_or_value {
+                ProposalOrRefType::Proposal => Err(ProposalError::LibraryError(
+                    LibraryError::custom("ReInit proposals can only be sent by reference"),
+                )),
+                ProposalOrRefType::Reference => self.propose_reinit(
+                    provider,
+                    signer,
+                    ReInitProposal::new(group_id, version, ciphersuite, extensions),
+                ),
+            },
             Propose::ExternalInit(_) => Err(ProposalError::LibraryError(LibraryError::custom(
                 "Unsupported proposal type ExternalInit",
             ))),
@@ -432,6 +455,118 @@ impl MlsGroup {
             .ok_or(RemoveProposalError::ProposalNotFound)
     }
 
+    /// Validates a candidate set of proposals without creating a commit.
+    ///
+    /// The `proposals` are validated together with the pending proposals in
+    /// the proposal store, in the same way as when this member creates a commit
+    /// covering all of them. Proposals that such a commit wouldn't include,
+    /// e.g. own update proposals or superseded proposals, are filtered out
+    /// first.
+    ///
+    /// Returns a [`ValidateProposalsError::InvalidProposal`] with a
+    /// [`ProposalValidationReport`](crate::group::ProposalValidationReport) if a
+    /// proposal fails validation. The proposal index in the report refers to
+    /// the list of proposals the commit would cover.
+    ///
+    /// To validate the proposals of a commit that is being built, use
+    /// `validate_proposals()` of the builder returned by
+    /// [`MlsGroup::commit_builder()`].
+    pub fn validate_proposals(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        proposals: impl IntoIterator<Item = Proposal>,
+    ) -> Result<(), ValidateProposalsError> {
+        self.validate_proposal_candidates(crypto, true, proposals)
+    }
+
+    /// Validates the given proposals, optionally together with the pending
+    /// proposals, as a commit by this member would.
+    pub(crate) fn validate_proposal_candidates(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        include_pending_proposals: bool,
+        proposals: impl IntoIterator<Item = Proposal>,
+    ) -> Result<(), ValidateProposalsError> {
+        let sender = Sender::build_member(self.own_leaf_index());
+        let own_proposals: Vec<_> = proposals
+            .into_iter()
+            .map(|proposal| {
+                QueuedProposal::from_proposal_and_sender(
+                    self.ciphersuite(),
+                    crypto,
+                    proposal,
+                    &sender,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+
+        let (proposal_queue, _contains_own_updates) =
+            ProposalQueue::filter_proposals_without_inline(
+                self.pending_proposals()
+                    .filter(|_| include_pending_proposals)
+                    .cloned()
+                    .chain(own_proposals),
+                self.own_leaf_index(),
+            )
+            .map_err(|e| match e {
+                ProposalQueueError::LibraryError(e) => e.into(),
+                ProposalQueueError::ProposalNotFound => {
+                    LibraryError::custom("filtered proposals have to be in the proposal pool")
+                        .into()
+                }
+                ProposalQueueError::UpdateFromExternalSender
+                | ProposalQueueError::SelfRemoveFromNonMember => {
+                    ValidateProposalsError::WrongProposalSenderType
+                }
+            })?;
+
+        self.validate_own_commit_proposals(&proposal_queue)
+    }
+
+    /// Validates the proposals of a commit created by this member.
+    pub(crate) fn validate_own_commit_proposals(
+        &self,
+        proposal_queue: &ProposalQueue,
+    ) -> Result<(), ValidateProposalsError> {
+        // ValSem113: All Proposals: The proposal type must be supported by all
+        // members of the group
+        self.public_group
+            .validate_proposal_type_support(proposal_queue)?;
+        // ValSem114
+        self.public_group
+            .validate_reinit_proposals(proposal_queue)?;
+        // ValSem101
+        // ValSem102
+        // ValSem103
+        // ValSem104
+        self.public_group
+            .validate_key_uniqueness(proposal_queue, None)?;
+        // ValSem105
+        self.public_group.validate_add_proposals(proposal_queue)?;
+        // ValSem106
+        // ValSem109
+        self.public_group.validate_capabilities(proposal_queue)?;
+        // ValSem107
+        // ValSem108
+        self.public_group
+            .validate_remove_proposals(proposal_queue)?;
+        self.public_group
+            .validate_pre_shared_key_proposals(proposal_queue)?;
+        // Validate update proposals for member commits
+        // ValSem110
+        // ValSem111
+        // ValSem112
+        self.public_group
+            .validate_update_proposals(proposal_queue, self.own_leaf_index())?;
+
+        // ValSem208
+        // ValSem209
+        self.public_group
+            .validate_group_context_extensions_proposal(proposal_queue)?;
+
+        Ok(())
+    }
+
     // === Create handshake messages ===
 
     // 12.1.1. Add
@@ -552,6 +687,29 @@ impl MlsGroup {
         )
     }
 
+    // 12.1.5. ReInit
+    // struct {
+    //     opaque group_id<V>;
+    //     ProtocolVersion version;
+    //     CipherSuite cipher_suite;
+    //     Extension extensions<V>;
+    // } ReInit;
+    pub(crate) fn create_reinit_proposal(
+        &self,
+        framing_parameters: FramingParameters,
+        reinit: ReInitProposal,
+        signer: &impl Signer,
+    ) -> Result<AuthenticatedContent, LibraryError> {
+        let proposal = Proposal::ReInit(reinit);
+        AuthenticatedContent::member_proposal(
+            framing_parameters,
+            self.own_leaf_index(),
+            proposal,
+            self.context(),
+            signer,
+        )
+    }
+
     pub(crate) fn create_custom_proposal(
         &self,
         framing_parameters: FramingParameters,
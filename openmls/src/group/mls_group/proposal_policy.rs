@@ -0,0 +1,226 @@
+//! Authorization of proposals.
+//!
+//! RFC 9420 leaves it to the application to decide which member may propose
+//! what. A [`ProposalPolicy`] set with [`MlsGroup::set_proposal_policy()`]
+//! takes this decision for every proposal in an incoming message: standalone
+//! proposals, including those of external senders, as well as proposals that a
+//! commit covers inline or by reference. The policy applies to every message
+//! the group processes, including buffered and batched messages. Messages
+//! with a rejected proposal fail before the commit is staged or the proposal
+//! is returned to the application.
+
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::{framing::mls_content::FramedContentBody, group::StageCommitError};
+
+use super::{errors::ProcessMessageError, *};
+
+/// How a proposal checked by a [`ProposalPolicy`] was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalDelivery<'a> {
+    /// A standalone proposal message.
+    Standalone,
+    /// A proposal sent inline in a commit. The sender of the proposal is the
+    /// committer.
+    Inline,
+    /// A proposal that was sent earlier and is covered by reference in a
+    /// commit of the `committer`.
+    Reference {
+        /// The sender of the commit.
+        committer: &'a Sender,
+    },
+}
+
+/// The reason a [`ProposalPolicy`] rejected a proposal.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{reason}")]
+pub struct ProposalRejection {
+    reason: String,
+}
+
+impl ProposalRejection {
+    /// Creates a new rejection with the given `reason`.
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns the reason of the rejection.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// Decides which sender may make which proposal in a group.
+///
+/// The policy is called with the state of the group before the message is
+/// applied. Commits that cover a proposal by reference, which isn't in the
+/// group's proposal store, are rejected before the policy is called.
+///
+/// The policy is implemented for closures with the signature of
+/// [`ProposalPolicy::check()`].
+pub trait ProposalPolicy {
+    /// Returns a [`ProposalRejection`] if the `sender` may not make the
+    /// `proposal` in the `group`.
+    fn check(
+        &self,
+        group: &PublicGroup,
+        sender: &Sender,
+        proposal: &Proposal,
+        delivery: ProposalDelivery<'_>,
+    ) -> Result<(), ProposalRejection>;
+}
+
+impl<F> ProposalPolicy for F
+where
+    F: Fn(&PublicGroup, &Sender, &Proposal, ProposalDelivery<'_>) -> Result<(), ProposalRejection>,
+{
+    fn check(
+        &self,
+        group: &PublicGroup,
+        sender: &Sender,
+        proposal: &Proposal,
+        delivery: ProposalDelivery<'_>,
+    ) -> Result<(), ProposalRejection> {
+        self(group, sender, proposal, delivery)
+    }
+}
+
+/// The [`ProposalPolicy`] of an [`MlsGroup`].
+///
+/// The policy is set by the application at runtime and isn't part of the
+/// group state. It is neither persisted nor considered when comparing groups.
+#[derive(Clone, Default)]
+pub(super) struct GroupProposalPolicy(Option<Arc<dyn ProposalPolicy + Send + Sync>>);
+
+impl std::fmt::Debug for GroupProposalPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("GroupProposalPolicy")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl PartialEq for GroupProposalPolicy {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl MlsGroup {
+    /// Sets the [`ProposalPolicy`] that every proposal in an incoming message
+    /// is checked against. This replaces the previous policy.
+    ///
+    /// The policy isn't persisted and must be set again after the group is
+    /// loaded from storage, see [`MlsGroup::load_with_policy()`]. To make sure
+    /// that a lost policy isn't missed, set
+    /// [`MlsGroupJoinConfig::require_proposal_policy()`].
+    pub fn set_proposal_policy(&mut self, policy: impl ProposalPolicy + Send + Sync + 'static) {
+        self.proposal_policy = GroupProposalPolicy(Some(Arc::new(policy)));
+    }
+
+    /// Removes the [`ProposalPolicy`] of the group. Afterwards, all proposals
+    /// are accepted.
+    pub fn clear_proposal_policy(&mut self) {
+        self.proposal_policy = GroupProposalPolicy::default();
+    }
+
+    /// Loads the group with the given id from storage, like
+    /// [`MlsGroup::load()`], and sets its [`ProposalPolicy`].
+    pub fn load_with_policy<Storage: crate::storage::StorageProvider>(
+        storage: &Storage,
+        group_id: &GroupId,
+        policy: impl ProposalPolicy + Send + Sync + 'static,
+    ) -> Result<Option<MlsGroup>, Storage::Error> {
+        let mut group = MlsGroup::load(storage, group_id)?;
+        if let Some(group) = group.as_mut() {
+            group.set_proposal_policy(policy);
+        }
+        Ok(group)
+    }
+
+    /// Returns `true` if a [`ProposalPolicy`] is set.
+    pub fn has_proposal_policy(&self) -> bool {
+        self.proposal_policy.0.is_some()
+    }
+
+    /// Checks all proposals in the `body` of a message of the `sender`
+    /// against the policy of the group, if there is one. Handshake messages
+    /// are rejected without a policy if the join config requires one.
+    pub(super) fn apply_proposal_policy(
+        &self,
+        sender: &Sender,
+        body: &FramedContentBody,
+    ) -> Result<(), ProcessMessageError> {
+        match (&self.proposal_policy.0, body) {
+            (Some(policy), _) => self.check_proposal_policy(policy.as_ref(), sender, body),
+            // Fail closed if the policy was lost, e.g. when the group was
+            // loaded without setting it again
+            (None, FramedContentBody::Proposal(_) | FramedContentBody::Commit(_))
+                if self.configuration().require_proposal_policy() =>
+            {
+                Err(ProcessMessageError::MissingProposalPolicy)
+            }
+            (None, _) => Ok(()),
+        }
+    }
+
+    /// Checks all proposals in the `body` of a message of the `sender`
+    /// against the `policy`.
+    fn check_proposal_policy(
+        &self,
+        policy: &dyn ProposalPolicy,
+        sender: &Sender,
+        body: &FramedContentBody,
+    ) -> Result<(), ProcessMessageError> {
+        let check = |sender: &Sender, proposal: &Proposal, delivery| {
+            policy
+                .check(self.public_group(), sender, proposal, delivery)
+                .map_err(|rejection| {
+                    log::debug!("Proposal rejected by the proposal policy: {rejection}");
+                    ProcessMessageError::ProposalRejected {
+                        sender: sender.clone(),
+                        proposal_type: proposal.proposal_type(),
+                        rejection,
+                    }
+                })
+        };
+
+        match body {
+            FramedContentBody::Application(_) => Ok(()),
+            FramedContentBody::Proposal(proposal) => {
+                check(sender, proposal, ProposalDelivery::Standalone)
+            }
+            FramedContentBody::Commit(commit) => {
+                for proposal_or_ref in &commit.proposals {
+                    match proposal_or_ref {
+                        ProposalOrRef::Proposal(proposal) => {
+                            check(sender, proposal, ProposalDelivery::Inline)?
+                        }
+                        ProposalOrRef::Reference(proposal_ref) => {
+                            let queued_proposal = self
+                                .proposal_store()
+                                .proposals()
+                                .find(|queued_proposal| {
+                                    &queued_proposal.proposal_reference() == proposal_ref
+                                })
+                                .ok_or(ProcessMessageError::InvalidCommit(
+                                    StageCommitError::MissingProposal,
+                                ))?;
+                            check(
+                                queued_proposal.sender(),
+                                queued_proposal.proposal(),
+                                ProposalDelivery::Reference { committer: sender },
+                            )?
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -177,6 +177,11 @@ impl OrderedProposalRefs {
         }
     }
 
+    /// Returns `true` if no proposal reference was added.
+    fn is_empty(&self) -> bool {
+        self.ordered_proposal_refs.is_empty()
+    }
+
     /// Returns an iterator over the proposal references in the order in which
     /// they were inserted.
     fn iter(&self) -> impl Iterator<Item = &ProposalRef> {
@@ -400,6 +405,7 @@ impl ProposalQueue {
     /// - Check for presence of SelfRemoves and delete Removes and Updates
     /// - Check for presence of Removes and delete Updates
     /// - Only keep the last Update
+    /// - Only keep the first ReInit, and only if there are no other proposals
     ///
     /// Return a [`ProposalQueue`] and a bool that indicates whether Updates for
     /// the own node were included
@@ -418,6 +424,7 @@ impl ProposalQueue {
         let mut proposal_pool: HashMap<ProposalRef, QueuedProposal> = HashMap::new();
         let mut contains_own_updates = false;
         let mut contains_external_init = false;
+        let mut reinit = None;
 
         let mut member_specific_proposals: HashMap<LeafNodeIndex, QueuedProposal> = HashMap::new();
         let mut register_member_specific_proposal =
@@ -492,7 +499,10 @@ impl ProposalQueue {
                     valid_proposals.add(queued_proposal.proposal_reference());
                 }
                 Proposal::ReInit(_) => {
-                    // TODO #751: Only keep one ReInit
+                    // Only use the first re-init proposal we find.
+                    if reinit.is_none() {
+                        reinit = Some(queued_proposal.proposal_reference());
+                    }
                 }
                 Proposal::ExternalInit(_) => {
                     // Only use the first external init proposal we find.
@@ -524,6 +534,15 @@ impl ProposalQueue {
             valid_proposals.add(proposal.proposal_reference());
         }
 
+        // A re-init proposal must be the only proposal in a commit. Prefer the
+        // other proposals, so that the re-init can be sent again in the next
+        // epoch (RFC 9420, Section 12.1.5).
+        if let Some(reinit) = reinit {
+            if adds.is_empty() && valid_proposals.is_empty() {
+                valid_proposals.add(reinit);
+            }
+        }
+
         // Only retain `adds` and `valid_proposals`
         let mut proposal_queue = ProposalQueue::default();
         for proposal_reference in adds.iter().chain(valid_proposals.iter()) {
@@ -555,6 +574,7 @@ impl ProposalQueue {
     /// - Check for presence of SelfRemoves and delete Removes and Updates
     /// - Check for presence of Removes and delete Updates
     /// - Only keep the last Update
+    /// - Only keep the first ReInit, and only if there are no other proposals
     ///
     /// Return a [`ProposalQueue`] and a bool that indicates whether Updates for
     /// the own node were included
@@ -569,6 +589,7 @@ impl ProposalQueue {
         let mut proposal_pool: HashMap<ProposalRef, QueuedProposal> = HashMap::new();
         let mut contains_own_updates = false;
         let mut contains_external_init = false;
+        let mut reinit = None;
 
         let mut member_specific_proposals: HashMap<LeafNodeIndex, QueuedProposal> = HashMap::new();
         let mut register_member_specific_proposal =
@@ -624,7 +645,10 @@ impl ProposalQueue {
                     valid_proposals.add(queued_proposal.proposal_reference());
                 }
                 Proposal::ReInit(_) => {
-                    // TODO #751: Only keep one ReInit
+                    // Only use the first re-init proposal we find.
+                    if reinit.is_none() {
+                        reinit = Some(queued_proposal.proposal_reference());
+                    }
                 }
                 Proposal::ExternalInit(_) => {
                     // Only use the first external init proposal we find.
@@ -656,6 +680,15 @@ impl ProposalQueue {
             valid_proposals.add(proposal.proposal_reference());
         }
 
+        // A re-init proposal must be the only proposal in a commit. Prefer the
+        // other proposals, so that the re-init can be sent again in the next
+        // epoch (RFC 9420, Section 12.1.5).
+        if let Some(reinit) = reinit {
+            if adds.is_empty() && valid_proposals.is_empty() {
+                valid_proposals.add(reinit);
+            }
+        }
+
         // Only retain `adds` and `valid_proposals`
         let mut proposal_queue = ProposalQueue::default();
         for proposal_reference in adds.iter().chain(valid_proposals.iter()) {
@@ -0,0 +1,199 @@
+//! Recovery from commit conflicts.
+//!
+//! If two members commit in the same epoch, the Delivery Service accepts only
+//! one of the commits. The member whose commit lost has to merge the winning
+//! commit instead and create a new commit for what it wanted to achieve.
+//! [`MlsGroup::merge_and_rebase_pending_commit()`] does this in one step,
+//! based on the [`CommitIntent`] of the pending commit.
+
+use commit_builder::CommitMessageBundle;
+
+use super::{errors::RebaseCommitError, *};
+
+/// The changes a pending commit makes to the group, independent of the epoch
+/// it was created in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitIntent {
+    proposals: Vec<Proposal>,
+    removed_members: Vec<Member>,
+    self_update: bool,
+}
+
+impl CommitIntent {
+    /// Returns the proposals of the commit, except for removals.
+    ///
+    /// Update and SelfRemove proposals are not part of the intent, because
+    /// they can't be committed by value.
+    pub fn proposals(&self) -> &[Proposal] {
+        &self.proposals
+    }
+
+    /// Returns the members removed by the commit.
+    pub fn removed_members(&self) -> &[Member] {
+        &self.removed_members
+    }
+
+    /// Returns `true` if the commit updates the committer's leaf although
+    /// none of its proposals requires it.
+    pub fn self_update(&self) -> bool {
+        self.self_update
+    }
+
+    /// Returns `true` if the commit doesn't change anything.
+    pub fn is_empty(&self) -> bool {
+        self.proposals.is_empty() && self.removed_members.is_empty() && !self.self_update
+    }
+
+    /// Drops the parts of the intent that are already applied to the group
+    /// or that are no longer valid in its current epoch. `applied` contains
+    /// the proposals of the commit that was merged instead of the pending
+    /// one, and `removed_leaves` the leaves it removed.
+    fn rebase(
+        self,
+        group: &MlsGroup,
+        applied: &[Proposal],
+        removed_leaves: &[LeafNodeIndex],
+    ) -> Self {
+        let proposals = self
+            .proposals
+            .into_iter()
+            .filter(|proposal| !applied.contains(proposal))
+            .filter(|proposal| match proposal {
+                // Skip members that are already in the group.
+                Proposal::Add(add_proposal) => {
+                    let signature_key = add_proposal
+                        .key_package()
+                        .leaf_node()
+                        .signature_key()
+                        .as_slice();
+                    !group
+                        .members()
+                        .any(|member| member.signature_key == signature_key)
+                }
+                Proposal::GroupContextExtensions(gce_proposal) => {
+                    gce_proposal.extensions() != group.extensions()
+                }
+                _ => true,
+            })
+            .collect();
+
+        // Skip members that already left the group. Members keep their leaf
+        // when others are removed, but may have changed their credential or
+        // signature key, so the others are looked up by their leaf index.
+        // Leaves that were removed may have been taken by new members, so
+        // they are skipped before the lookup.
+        let removed_members = self
+            .removed_members
+            .into_iter()
+            .filter(|removed| !removed_leaves.contains(&removed.index))
+            .filter_map(|removed| group.member_at(removed.index))
+            .filter(|member| member.index != group.own_leaf_index())
+            .collect();
+
+        Self {
+            proposals,
+            removed_members,
+            self_update: self.self_update,
+        }
+    }
+}
+
+impl MlsGroup {
+    /// Returns the [`CommitIntent`] of the pending commit, or `None` if there
+    /// is no pending commit created by this member.
+    pub fn pending_commit_intent(&self) -> Option<CommitIntent> {
+        let MlsGroupState::PendingCommit(ref pending_commit_state) = self.group_state else {
+            return None;
+        };
+        let PendingCommitState::Member(ref staged_commit) = **pending_commit_state else {
+            return None;
+        };
+
+        let mut proposals = vec![];
+        let mut removed_members = vec![];
+        let mut path_required = false;
+        for queued_proposal in staged_commit.queued_proposals() {
+            path_required |= queued_proposal.proposal().is_path_required();
+            match queued_proposal.proposal() {
+                Proposal::Remove(remove_proposal) => {
+                    if let Some(member) = self.member_at(remove_proposal.removed()) {
+                        removed_members.push(member);
+                    }
+                }
+                Proposal::Update(_) | Proposal::SelfRemove => {}
+                proposal => proposals.push(proposal.clone()),
+            }
+        }
+
+        Some(CommitIntent {
+            proposals,
+            removed_members,
+            self_update: !path_required && staged_commit.update_path_leaf_node().is_some(),
+        })
+    }
+
+    /// Merges a commit of another member that won against the pending commit
+    /// of this member, and re-creates the pending commit on the new epoch.
+    ///
+    /// The new commit covers the [`CommitIntent`] of the pending commit,
+    /// except for changes that the merged commit already made and changes
+    /// that are no longer valid, such as removals of members who already left
+    /// the group. It is staged as the new pending commit and returned, so it
+    /// can be sent to the Delivery Service.
+    ///
+    /// Returns `None` if there was no pending commit, if nothing is left to
+    /// commit, or if this member was removed by the merged commit.
+    pub fn merge_and_rebase_pending_commit<Provider: OpenMlsProvider>(
+        &mut self,
+        provider: &Provider,
+        signer: &impl Signer,
+        staged_commit: StagedCommit,
+    ) -> Result<Option<CommitMessageBundle>, RebaseCommitError<Provider::StorageError>> {
+        let intent = self.pending_commit_intent();
+        let applied: Vec<Proposal> = staged_commit
+            .queued_proposals()
+            .map(|queued_proposal| queued_proposal.proposal().clone())
+            .collect();
+        let removed_leaves: Vec<LeafNodeIndex> = staged_commit
+            .queued_proposals()
+            .filter_map(|queued_proposal| {
+                match (queued_proposal.proposal(), queued_proposal.sender()) {
+                    (Proposal::Remove(remove_proposal), _) => Some(remove_proposal.removed()),
+                    (Proposal::SelfRemove, Sender::Member(leaf_index)) => Some(*leaf_index),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        self.merge_staged_commit(provider, staged_commit)?;
+
+        let Some(intent) = intent else {
+            return Ok(None);
+        };
+        if !self.is_active() {
+            return Ok(None);
+        }
+
+        let intent = intent.rebase(self, &applied, &removed_leaves);
+        if intent.is_empty() {
+            return Ok(None);
+        }
+
+        let removed: Vec<LeafNodeIndex> = intent
+            .removed_members
+            .iter()
+            .map(|member| member.index)
+            .collect();
+        let bundle = self
+            .commit_builder()
+            .consume_proposal_store(false)
+            .force_self_update(intent.self_update)
+            .add_proposals(intent.proposals)
+            .propose_removals(removed)
+            .load_psks(provider.storage())?
+            .build(provider.rand(), provider.crypto(), signer, |_| true)?
+            .stage_commit(provider)?;
+
+        Ok(Some(bundle))
+    }
+}
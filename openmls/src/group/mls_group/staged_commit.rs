@@ -10,9 +10,10 @@ use super::proposal_store::{
 };
 
 use super::{
-    super::errors::*, load_psks, Credential, Extension, GroupContext, GroupEpochSecrets, GroupId,
-    JoinerSecret, KeySchedule, LeafNode, LibraryError, MessageSecrets, MlsGroup, OpenMlsProvider,
-    Proposal, ProposalQueue, PskSecret, QueuedProposal, Sender,
+    super::errors::*, load_psks, CommitChanges, CommittedContent, Credential, Extension,
+    GroupContext, GroupEpochSecrets, GroupId, JoinerSecret, KeySchedule, LeafNode, LibraryError,
+    MessageSecrets, MlsGroup, OpenMlsProvider, Proposal, ProposalQueue, PskSecret, QueuedProposal,
+    Sender,
 };
 use crate::storage::StorageProvider;
 use crate::{
@@ -197,6 +198,13 @@ impl MlsGroup {
                 // Check if we were removed from the group
                 if apply_proposals_values.self_removed {
                     // If so, we return here, because we can't decrypt the path
+                    let changes = CommitChanges::new(&self.public_group, &diff, &proposal_queue);
+                    let committed_content = CommittedContent::new(
+                        provider.crypto(),
+                        ciphersuite,
+                        sender_index,
+                        mls_content,
+                    )?;
                     let staged_diff = diff.into_staged_diff(provider.crypto(), ciphersuite)?;
                     let staged_state = PublicStagedCommitState::new(
                         staged_diff,
@@ -205,6 +213,8 @@ impl MlsGroup {
                     return Ok(StagedCommit::new(
                         proposal_queue,
                         StagedCommitState::PublicState(Box::new(staged_state)),
+                        changes,
+                        committed_content,
                     ));
                 }
 
@@ -325,6 +335,9 @@ impl MlsGroup {
 
         diff.update_interim_transcript_hash(ciphersuite, provider.crypto(), own_confirmation_tag)?;
 
+        let changes = CommitChanges::new(&self.public_group, &diff, &proposal_queue);
+        let committed_content =
+            CommittedContent::new(provider.crypto(), ciphersuite, sender_index, mls_content)?;
         let staged_diff = diff.into_staged_diff(provider.crypto(), ciphersuite)?;
         let staged_commit_state =
             StagedCommitState::GroupMember(Box::new(MemberStagedCommitState::new(
@@ -337,7 +350,12 @@ impl MlsGroup {
                 update_path_leaf_node,
             )));
 
-        Ok(StagedCommit::new(proposal_queue, staged_commit_state))
+        Ok(StagedCommit::new(
+            proposal_queue,
+            staged_commit_state,
+            changes,
+            committed_content,
+        ))
     }
 
     /// Merges a [StagedCommit] into the group state and optionally return a [`SecretTree`]
@@ -370,7 +388,10 @@ impl MlsGroup {
                 let leaves = self.public_group().members().collect();
                 // Merge the staged commit into the group state and store the secret tree from the
                 // previous epoch in the message secrets store.
-                self.group_epoch_secrets = state.group_epoch_secrets;
+                let past_epoch_secrets =
+                    mem::replace(&mut self.group_epoch_secrets, state.group_epoch_secrets);
+                self.past_exporter_secrets
+                    .add(past_epoch, past_epoch_secrets);
 
                 // Replace the previous message secrets with the new ones and return the previous message secrets
                 let mut message_secrets = state.message_secrets;
@@ -378,8 +399,9 @@ impl MlsGroup {
                     &mut message_secrets,
                     self.message_secrets_store.message_secrets_mut(),
                 );
-                self.message_secrets_store
-                    .add(past_epoch, message_secrets, leaves);
+                let evicted_epoch_tree =
+                    self.message_secrets_store
+                        .add(past_epoch, message_secrets, leaves);
 
                 self.public_group.merge_diff(state.staged_diff);
 
@@ -410,6 +432,13 @@ impl MlsGroup {
                     .into());
                 }
 
+                // Archive the message secrets that no longer fit into the
+                // message secrets store.
+                if let Some(epoch_tree) = evicted_epoch_tree {
+                    self.archive_message_secrets(storage, epoch_tree)
+                        .map_err(MergeCommitError::StorageError)?;
+                }
+
                 // Store the updated group state
                 let group_id = self.group_id();
 
@@ -422,6 +451,9 @@ impl MlsGroup {
                 storage
                     .write_message_secrets(group_id, &self.message_secrets_store)
                     .map_err(MergeCommitError::StorageError)?;
+                storage
+                    .write_past_exporter_secrets(group_id, &self.past_exporter_secrets)
+                    .map_err(MergeCommitError::StorageError)?;
 
                 // Store the relevant keys under the new epoch
                 self.store_epoch_keypairs(storage, epoch_keypairs.as_slice())
@@ -461,18 +493,53 @@ pub(crate) enum StagedCommitState {
 pub struct StagedCommit {
     staged_proposal_queue: ProposalQueue,
     state: StagedCommitState,
+    // Pending commits stored by earlier versions don't have the changes.
+    #[serde(default)]
+    changes: CommitChanges,
+    // Nor the committed content.
+    #[serde(default)]
+    committed_content: Option<CommittedContent>,
 }
 
 impl StagedCommit {
     /// Create a new [`StagedCommit`] from the provisional group state created
     /// during the commit process.
-    pub(crate) fn new(staged_proposal_queue: ProposalQueue, state: StagedCommitState) -> Self {
+    pub(crate) fn new(
+        staged_proposal_queue: ProposalQueue,
+        state: StagedCommitState,
+        changes: CommitChanges,
+        committed_content: CommittedContent,
+    ) -> Self {
         StagedCommit {
             staged_proposal_queue,
             state,
+            changes,
+            committed_content: Some(committed_content),
         }
     }
 
+    /// Returns the changes the commit makes to the group's members and
+    /// [`GroupContext`] extensions.
+    pub fn changes(&self) -> &CommitChanges {
+        &self.changes
+    }
+
+    /// Returns the part of the commit that is kept in the commit history.
+    pub(crate) fn committed_content(&self) -> Option<&CommittedContent> {
+        self.committed_content.as_ref()
+    }
+
+    /// Returns the serialized `ConfirmedTranscriptHashInput` of the commit,
+    /// which contains the commit and its signature. It is not part of the
+    /// [`CommitHistory`](super::CommitHistory), but needed to verify it.
+    ///
+    /// Returns `None` for pending commits stored by earlier versions.
+    pub fn transcript_hash_input(&self) -> Option<&[u8]> {
+        self.committed_content
+            .as_ref()
+            .map(CommittedContent::transcript_hash_input)
+    }
+
     /// Returns the Add proposals that are covered by the Commit message as in iterator over [QueuedAddProposal].
     pub fn add_proposals(&self) -> impl Iterator<Item = QueuedAddProposal> {
         self.staged_proposal_queue.add_proposals()
@@ -1,7 +1,8 @@
 //! This module contains tests regarding the use of [`MessageSecretsStore`]
 
 use crate::{
-    binary_tree::LeafNodeIndex, group::past_secrets::MessageSecretsStore,
+    binary_tree::LeafNodeIndex,
+    group::{past_secrets::MessageSecretsStore, GroupEpoch},
     schedule::message_secrets::MessageSecrets,
 };
 
@@ -62,3 +63,75 @@ fn test_empty_secret_tree_store() {
     // Make sure we cannot access the message secrets we just stored
     assert!(message_secrets_store.secrets_for_epoch_mut(0).is_none());
 }
+
+#[openmls_test::openmls_test]
+fn test_evicted_secret_trees() {
+    // Create a store that keeps 1 epoch
+    let mut message_secrets_store = MessageSecretsStore::new_with_secret(
+        1,
+        MessageSecrets::random(ciphersuite, provider.rand(), LeafNodeIndex::new(0)),
+    );
+
+    // Nothing is evicted until the store is full
+    assert!(message_secrets_store
+        .add(
+            0,
+            MessageSecrets::random(ciphersuite, provider.rand(), LeafNodeIndex::new(0)),
+            Vec::new(),
+        )
+        .is_none());
+
+    // The oldest tree is evicted
+    let evicted = message_secrets_store
+        .add(
+            1,
+            MessageSecrets::random(ciphersuite, provider.rand(), LeafNodeIndex::new(0)),
+            Vec::new(),
+        )
+        .unwrap();
+    assert_eq!(evicted.epoch(), GroupEpoch::from(0));
+
+    // A store that keeps no epochs evicts the new tree
+    let mut message_secrets_store = MessageSecretsStore::new_with_secret(
+        0,
+        MessageSecrets::random(ciphersuite, provider.rand(), LeafNodeIndex::new(0)),
+    );
+    let evicted = message_secrets_store
+        .add(
+            2,
+            MessageSecrets::random(ciphersuite, provider.rand(), LeafNodeIndex::new(0)),
+            Vec::new(),
+        )
+        .unwrap();
+    assert_eq!(evicted.epoch(), GroupEpoch::from(2));
+}
+
+#[openmls_test::openmls_test]
+fn test_expire_archived_epochs() {
+    let mut message_secrets_store = MessageSecretsStore::new_with_secret(
+        0,
+        MessageSecrets::random(ciphersuite, provider.rand(), LeafNodeIndex::new(0)),
+    );
+    for epoch in 0..4u64 {
+        message_secrets_store.add_archived_epoch(GroupEpoch::from(epoch), 100 * epoch);
+    }
+    assert!(message_secrets_store.is_archived(GroupEpoch::from(0)));
+
+    // Expire by count
+    assert_eq!(
+        message_secrets_store.expire_archived_epochs(3, None, 1000),
+        vec![GroupEpoch::from(0)]
+    );
+    assert!(!message_secrets_store.is_archived(GroupEpoch::from(0)));
+
+    // Expire by age
+    assert_eq!(
+        message_secrets_store.expire_archived_epochs(3, Some(100), 300),
+        vec![GroupEpoch::from(1)]
+    );
+    assert!(message_secrets_store
+        .expire_archived_epochs(3, Some(100), 300)
+        .is_empty());
+    assert!(message_secrets_store.is_archived(GroupEpoch::from(2)));
+    assert!(message_secrets_store.is_archived(GroupEpoch::from(3)));
+}
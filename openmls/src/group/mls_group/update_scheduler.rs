@@ -0,0 +1,190 @@
+//! Scheduling of self-updates.
+//!
+//! Post-compromise security requires members to update their leaf
+//! regularly. [`MlsGroup`] records when its own leaf was last updated in an
+//! [`OwnLeafUpdate`], and an [`UpdateScheduler`] reports the groups in which
+//! this happened too long ago, so the application can call
+//! [`MlsGroup::self_update()`] for them.
+
+use std::sync::Arc;
+
+use super::*;
+
+/// The epoch and time of the last update of the own leaf in a group.
+///
+/// The own leaf is updated by the own commits with a path and by own Update
+/// proposals that another member commits. Creating or joining a group also
+/// counts as an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnLeafUpdate {
+    epoch: GroupEpoch,
+    timestamp: u64,
+}
+
+impl OwnLeafUpdate {
+    /// Returns the epoch in which the own leaf was updated.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the time of the update in seconds since the Unix epoch.
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+/// The [`Clock`] of a group, which the time of own leaf updates is taken
+/// from. Without a clock set by the application, the [`SystemClock`] is used.
+#[derive(Clone, Default)]
+pub(super) struct GroupClock(Option<Arc<dyn Clock + Send + Sync>>);
+
+impl GroupClock {
+    pub(super) fn new(clock: impl Clock + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(clock)))
+    }
+
+    fn now(&self) -> u64 {
+        match &self.0 {
+            Some(clock) => clock.now(),
+            None => SystemClock.now(),
+        }
+    }
+}
+
+impl std::fmt::Debug for GroupClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("GroupClock")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl PartialEq for GroupClock {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl MlsGroup {
+    /// Sets the [`Clock`] that the time of updates of the own leaf is taken
+    /// from. Use the same clock for the [`UpdateScheduler`]. This replaces the
+    /// previous clock.
+    ///
+    /// The clock isn't persisted and must be set again after the group is
+    /// loaded from storage. Until then, the [`SystemClock`] is used.
+    pub fn set_clock(&mut self, clock: impl Clock + Send + Sync + 'static) {
+        self.clock = GroupClock::new(clock);
+    }
+
+    /// Returns the epoch and time of the last update of the own leaf, or
+    /// `None` if it is unknown because the group was stored before updates
+    /// were recorded.
+    pub fn own_leaf_update(&self) -> Option<&OwnLeafUpdate> {
+        self.own_leaf_update.as_ref()
+    }
+
+    /// Records an update of the own leaf in the current epoch.
+    pub(super) fn record_own_leaf_update<Storage: StorageProvider>(
+        &mut self,
+        storage: &Storage,
+    ) -> Result<(), Storage::Error> {
+        let own_leaf_update = OwnLeafUpdate {
+            epoch: self.epoch(),
+            timestamp: self.clock.now(),
+        };
+        storage.write_own_leaf_update(self.group_id(), &own_leaf_update)?;
+        self.own_leaf_update = Some(own_leaf_update);
+        Ok(())
+    }
+
+    /// Returns the record of an update of the own leaf in the `epoch`, taking
+    /// the time from the `clock`, for groups that were just created or joined.
+    pub(super) fn initial_own_leaf_update(
+        clock: &GroupClock,
+        epoch: GroupEpoch,
+    ) -> Option<OwnLeafUpdate> {
+        Some(OwnLeafUpdate {
+            epoch,
+            timestamp: clock.now(),
+        })
+    }
+}
+
+/// Reports groups in which the own leaf is overdue for an update.
+///
+/// An update is due once the group has advanced by
+/// [`Self::max_epochs()`] epochs or once [`Self::max_age()`] seconds have
+/// passed since the last update of the own leaf. Without any threshold, no
+/// update is ever due.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateScheduler<C: Clock = SystemClock> {
+    max_epochs: Option<u64>,
+    max_age: Option<u64>,
+    clock: C,
+}
+
+impl UpdateScheduler<SystemClock> {
+    /// Creates a new scheduler without thresholds that uses the system clock.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+
+impl<C: Clock> UpdateScheduler<C> {
+    /// Creates a new scheduler without thresholds that uses the given `clock`.
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            max_epochs: None,
+            max_age: None,
+            clock,
+        }
+    }
+
+    /// Sets the number of epochs after which an update is due.
+    pub fn max_epochs(mut self, max_epochs: u64) -> Self {
+        self.max_epochs = Some(max_epochs);
+        self
+    }
+
+    /// Sets the number of seconds after which an update is due.
+    pub fn max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Returns `true` if the own leaf in the `group` is overdue for an
+    /// update.
+    ///
+    /// Inactive groups are never due. Groups without a record of the last
+    /// update are due as soon as a threshold is set.
+    pub fn is_update_due(&self, group: &MlsGroup) -> bool {
+        if !group.is_active() || (self.max_epochs.is_none() && self.max_age.is_none()) {
+            return false;
+        }
+        let Some(own_leaf_update) = group.own_leaf_update() else {
+            return true;
+        };
+
+        let epochs = group
+            .epoch()
+            .as_u64()
+            .saturating_sub(own_leaf_update.epoch.as_u64());
+        let age = self.clock.now().saturating_sub(own_leaf_update.timestamp);
+        self.max_epochs.is_some_and(|max| epochs >= max)
+            || self.max_age.is_some_and(|max| age >= max)
+    }
+
+    /// Returns the ids of the `groups` in which the own leaf is overdue for an
+    /// update.
+    pub fn overdue_groups<'a>(
+        &self,
+        groups: impl IntoIterator<Item = &'a MlsGroup>,
+    ) -> Vec<GroupId> {
+        groups
+            .into_iter()
+            .filter(|group| self.is_update_due(group))
+            .map(|group| group.group_id().clone())
+            .collect()
+    }
+}
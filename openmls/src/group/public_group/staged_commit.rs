@@ -2,7 +2,8 @@ use super::{super::errors::*, *};
 use crate::{
     framing::{mls_auth_content::AuthenticatedContent, mls_content::FramedContentBody, Sender},
     group::{
-        mls_group::staged_commit::StagedCommitState, proposal_store::ProposalQueue, StagedCommit,
+        mls_group::staged_commit::StagedCommitState, proposal_store::ProposalQueue, CommitChanges,
+        CommittedContent, StagedCommit,
     },
     messages::{proposals::ProposalOrRef, Commit},
 };
@@ -153,6 +154,9 @@ impl PublicGroup {
                 self.validate_update_proposals(&proposal_queue, *leaf_index)?;
 
                 self.validate_no_external_init_proposals(&proposal_queue)?;
+
+                // ValSem114
+                self.validate_reinit_proposals(&proposal_queue)?;
             }
             Sender::External(_) => {
                 // A commit cannot be issued by a pre-configured sender.
@@ -196,13 +200,17 @@ impl PublicGroup {
     fn validate_no_external_init_proposals(
         &self,
         proposal_queue: &ProposalQueue,
-    ) -> Result<(), ProposalValidationError> {
-        for proposal in proposal_queue.queued_proposals() {
+    ) -> Result<(), ProposalValidationReport> {
+        for (index, proposal) in proposal_queue.queued_proposals().enumerate() {
             if matches!(
                 proposal.proposal().proposal_type(),
                 ProposalType::ExternalInit
             ) {
-                return Err(ProposalValidationError::ExternalInitProposalInRegularCommit);
+                return Err(ProposalValidationReport::new(
+                    ProposalValidationError::ExternalInitProposalInRegularCommit,
+                    ProposalValidationRule::Valn0310,
+                )
+                .with_proposal(index, proposal));
             }
         }
 
@@ -252,7 +260,10 @@ impl PublicGroup {
     ) -> Result<StagedCommit, StageCommitError> {
         let (commit, proposal_queue, sender_index) = self.validate_commit(mls_content, crypto)?;
 
-        let staged_diff = self.stage_diff(mls_content, &proposal_queue, sender_index, crypto)?;
+        let (staged_diff, changes) =
+            self.stage_diff(mls_content, &proposal_queue, sender_index, crypto)?;
+        let committed_content =
+            CommittedContent::new(crypto, self.ciphersuite(), sender_index, mls_content)?;
         let staged_state = PublicStagedCommitState {
             staged_diff,
             update_path_leaf_node: commit.path.as_ref().map(|p| p.leaf_node().clone()),
@@ -260,7 +271,12 @@ impl PublicGroup {
 
         let staged_commit_state = StagedCommitState::PublicState(Box::new(staged_state));
 
-        Ok(StagedCommit::new(proposal_queue, staged_commit_state))
+        Ok(StagedCommit::new(
+            proposal_queue,
+            staged_commit_state,
+            changes,
+            committed_content,
+        ))
     }
 
     fn stage_diff(
@@ -269,7 +285,7 @@ impl PublicGroup {
         proposal_queue: &ProposalQueue,
         sender_index: LeafNodeIndex,
         crypto: &impl OpenMlsCrypto,
-    ) -> Result<StagedPublicGroupDiff, StageCommitError> {
+    ) -> Result<(StagedPublicGroupDiff, CommitChanges), StageCommitError> {
         let ciphersuite = self.ciphersuite();
         let mut diff = self.empty_diff();
 
@@ -309,9 +325,10 @@ impl PublicGroup {
             received_confirmation_tag.clone(),
         )?;
 
+        let changes = CommitChanges::new(self, &diff, proposal_queue);
         let staged_diff = diff.into_staged_diff(crypto, ciphersuite)?;
 
-        Ok(staged_diff)
+        Ok((staged_diff, changes))
     }
 
     /// Merges a [StagedCommit] into the public group state.
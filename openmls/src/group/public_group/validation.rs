@@ -1,13 +1,13 @@
 //! This module contains validation functions for incoming messages
 //! as defined in <https://github.com/openmls/openmls/wiki/Message-validation>
 
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use openmls_traits::types::VerifiableCiphersuite;
 
 use super::PublicGroup;
 use crate::extensions::RequiredCapabilitiesExtension;
-use crate::group::proposal_store::ProposalQueue;
+use crate::group::proposal_store::{ProposalQueue, QueuedProposal};
 use crate::group::GroupContextExtensionsProposalValidationError;
 use crate::prelude::LibraryError;
 use crate::treesync::{errors::LeafNodeValidationError, LeafNode};
@@ -18,7 +18,10 @@ use crate::{
         Sender, WireFormat,
     },
     group::{
-        errors::{ExternalCommitValidationError, ProposalValidationError, ValidationError},
+        errors::{
+            ExternalCommitValidationError, ProposalValidationError, ProposalValidationReport,
+            ProposalValidationRule, ValidationError,
+        },
         past_secrets::MessageSecretsStore,
         Member,
     },
@@ -130,7 +133,7 @@ impl PublicGroup {
     pub(crate) fn validate_proposal_type_support(
         &self,
         proposal_queue: &ProposalQueue,
-    ) -> Result<(), ProposalValidationError> {
+    ) -> Result<(), ProposalValidationReport> {
         let mut leaves = self.treesync().full_leaves();
         let Some(first_leaf) = leaves.next() else {
             return Ok(());
@@ -152,12 +155,16 @@ impl PublicGroup {
         }
 
         // Check that the types of all proposals are supported by all members
-        for proposal in proposal_queue.queued_proposals() {
+        for (index, proposal) in proposal_queue.queued_proposals().enumerate() {
             let proposal_type = proposal.proposal().proposal_type();
             if matches!(proposal_type, ProposalType::Custom(_))
                 && !capabilities_intersection.contains(&proposal_type)
             {
-                return Err(ProposalValidationError::UnsupportedProposalType);
+                return Err(ProposalValidationReport::new(
+                    ProposalValidationError::UnsupportedProposalType,
+                    ProposalValidationRule::ValSem113,
+                )
+                .with_proposal(index, proposal));
             }
         }
         Ok(())
@@ -181,10 +188,12 @@ impl PublicGroup {
         &self,
         proposal_queue: &ProposalQueue,
         commit: Option<&Commit>,
-    ) -> Result<(), ProposalValidationError> {
-        let mut signature_key_set = HashSet::new();
+    ) -> Result<(), ProposalValidationReport> {
+        // The signature and encryption keys are mapped to the leaf that holds
+        // them, so that collisions can be reported with the involved members.
+        let mut signature_key_set = HashMap::new();
         let mut init_key_set = HashSet::new();
-        let mut encryption_key_set = HashSet::new();
+        let mut encryption_key_set = HashMap::new();
 
         // Handle the exceptions needed for https://validation.openmls.tech/#valn0306
         let remove_proposals = HashSet::<LeafNodeIndex>::from_iter(
@@ -203,72 +212,100 @@ impl PublicGroup {
         } in self.treesync().full_leave_members()
         {
             if !remove_proposals.contains(&index) {
-                signature_key_set.insert(signature_key);
-                encryption_key_set.insert(encryption_key);
+                signature_key_set.insert(signature_key, Some(index));
+                encryption_key_set.insert(encryption_key, Some(index));
             }
         }
 
+        let indexed_proposals = || proposal_queue.queued_proposals().enumerate();
+
         // Collect signature keys from add proposals
-        let signature_keys = proposal_queue.add_proposals().map(|add_proposal| {
-            add_proposal
-                .add_proposal()
+        let signature_keys = indexed_proposals().filter_map(|(index, queued_proposal)| {
+            let Proposal::Add(add_proposal) = queued_proposal.proposal() else {
+                return None;
+            };
+            let signature_key = add_proposal
                 .key_package()
                 .leaf_node()
                 .signature_key()
                 .as_slice()
-                .to_vec()
+                .to_vec();
+            Some((signature_key, KeyOrigin::proposal(index, queued_proposal)))
         });
 
         // Collect encryption keys from add proposals, update proposals, the
         // commit leaf node and path keys
-        let encryption_keys = proposal_queue
-            .add_proposals()
-            .map(|add_proposal| {
-                add_proposal
-                    .add_proposal()
+        let encryption_keys = indexed_proposals()
+            .filter_map(|(index, queued_proposal)| {
+                let Proposal::Add(add_proposal) = queued_proposal.proposal() else {
+                    return None;
+                };
+                let encryption_key = add_proposal
                     .key_package()
                     .leaf_node()
                     .encryption_key()
                     .key()
                     .as_slice()
-                    .to_vec()
+                    .to_vec();
+                Some((
+                    encryption_key,
+                    KeyOrigin::proposal(index, queued_proposal),
+                    ProposalValidationRule::ValSem103,
+                ))
             })
-            .chain(proposal_queue.update_proposals().map(|update_proposal| {
-                update_proposal
-                    .update_proposal()
+            .chain(indexed_proposals().filter_map(|(index, queued_proposal)| {
+                let Proposal::Update(update_proposal) = queued_proposal.proposal() else {
+                    return None;
+                };
+                let encryption_key = update_proposal
                     .leaf_node()
                     .encryption_key()
                     .key()
                     .as_slice()
-                    .to_vec()
+                    .to_vec();
+                Some((
+                    encryption_key,
+                    KeyOrigin::proposal(index, queued_proposal),
+                    ProposalValidationRule::ValSem110,
+                ))
             }))
             .chain(commit.and_then(|commit| {
-                commit
-                    .path
-                    .as_ref()
-                    .map(|path| path.leaf_node().encryption_key().as_slice().to_vec())
+                commit.path.as_ref().map(|path| {
+                    (
+                        path.leaf_node().encryption_key().as_slice().to_vec(),
+                        KeyOrigin::path(),
+                        ProposalValidationRule::ValSem206,
+                    )
+                })
             }))
             .chain(
                 commit
                     .iter()
                     .filter_map(|commit| {
                         commit.path.as_ref().map(|path| {
-                            path.nodes()
-                                .iter()
-                                .map(|node| node.encryption_key().as_slice().to_vec())
+                            path.nodes().iter().map(|node| {
+                                (
+                                    node.encryption_key().as_slice().to_vec(),
+                                    KeyOrigin::path(),
+                                    ProposalValidationRule::ValSem207,
+                                )
+                            })
                         })
                     })
                     .flatten(),
             );
 
         // Collect init keys from add proposals
-        let init_keys = proposal_queue.add_proposals().map(|add_proposal| {
-            add_proposal
-                .add_proposal()
+        let init_keys = indexed_proposals().filter_map(|(index, queued_proposal)| {
+            let Proposal::Add(add_proposal) = queued_proposal.proposal() else {
+                return None;
+            };
+            let init_key = add_proposal
                 .key_package()
                 .hpke_init_key()
                 .as_slice()
-                .to_vec()
+                .to_vec();
+            Some((init_key, KeyOrigin::proposal(index, queued_proposal)))
         });
 
         // Validate uniqueness of signature keys
@@ -276,9 +313,13 @@ impl PublicGroup {
         //  - https://validation.openmls.tech/#valn0111
         //  - https://validation.openmls.tech/#valn0305
         //  - https://validation.openmls.tech/#valn0306
-        for signature_key in signature_keys {
-            if !signature_key_set.insert(signature_key) {
-                return Err(ProposalValidationError::DuplicateSignatureKey);
+        for (signature_key, origin) in signature_keys {
+            if let Some(owner) = signature_key_set.insert(signature_key, origin.leaf) {
+                return Err(origin.report(
+                    ProposalValidationError::DuplicateSignatureKey,
+                    ProposalValidationRule::ValSem101,
+                    owner,
+                ));
             }
         }
 
@@ -289,24 +330,40 @@ impl PublicGroup {
         //  - ValSem206
         //  - ValSem207
         //  - https://validation.openmls.tech/#valn0112
-        for encryption_key in encryption_keys {
+        for (encryption_key, origin, rule) in encryption_keys {
             if init_key_set.contains(&encryption_key) {
-                return Err(ProposalValidationError::InitEncryptionKeyCollision);
+                return Err(origin.report(
+                    ProposalValidationError::InitEncryptionKeyCollision,
+                    ProposalValidationRule::ValSem104,
+                    None,
+                ));
             }
-            if !encryption_key_set.insert(encryption_key) {
-                return Err(ProposalValidationError::DuplicateEncryptionKey);
+            if let Some(owner) = encryption_key_set.insert(encryption_key, origin.leaf) {
+                return Err(origin.report(
+                    ProposalValidationError::DuplicateEncryptionKey,
+                    rule,
+                    owner,
+                ));
             }
         }
 
         // Validate uniqueness of init keys
         //  - ValSem102
         //  - ValSem104
-        for init_key in init_keys {
-            if encryption_key_set.contains(&init_key) {
-                return Err(ProposalValidationError::InitEncryptionKeyCollision);
+        for (init_key, origin) in init_keys {
+            if let Some(owner) = encryption_key_set.get(&init_key) {
+                return Err(origin.report(
+                    ProposalValidationError::InitEncryptionKeyCollision,
+                    ProposalValidationRule::ValSem104,
+                    *owner,
+                ));
             }
             if !init_key_set.insert(init_key) {
-                return Err(ProposalValidationError::DuplicateInitKey);
+                return Err(origin.report(
+                    ProposalValidationError::DuplicateInitKey,
+                    ProposalValidationRule::ValSem102,
+                    None,
+                ));
             }
         }
 
@@ -320,7 +377,7 @@ impl PublicGroup {
     pub(crate) fn validate_capabilities(
         &self,
         proposal_queue: &ProposalQueue,
-    ) -> Result<(), ProposalValidationError> {
+    ) -> Result<(), ProposalValidationReport> {
         // ValSem106/ValSem109: Check the required capabilities of the add & update
         // proposals This includes the following checks:
         // - Are ciphersuite & version listed in the `Capabilities` Extension?
@@ -336,17 +393,28 @@ impl PublicGroup {
         //   members.
 
         // Extract the leaf nodes from the add & update proposals and validate them
-        proposal_queue
-            .queued_proposals()
-            .filter_map(|p| match p.proposal() {
-                Proposal::Add(add_proposal) => Some(add_proposal.key_package().leaf_node()),
-                Proposal::Update(update_proposal) => Some(update_proposal.leaf_node()),
-                _ => None,
-            })
-            .try_for_each(|leaf_node| {
-                self.validate_leaf_node_capabilities(leaf_node)
-                    .map_err(|_| ProposalValidationError::InsufficientCapabilities)
-            })
+        for (index, queued_proposal) in proposal_queue.queued_proposals().enumerate() {
+            let origin = KeyOrigin::proposal(index, queued_proposal);
+            let (leaf_node, rule) = match queued_proposal.proposal() {
+                Proposal::Add(add_proposal) => (
+                    add_proposal.key_package().leaf_node(),
+                    ProposalValidationRule::ValSem106,
+                ),
+                Proposal::Update(update_proposal) => (
+                    update_proposal.leaf_node(),
+                    ProposalValidationRule::ValSem109,
+                ),
+                _ => continue,
+            };
+            if self.validate_leaf_node_capabilities(leaf_node).is_err() {
+                return Err(origin.report(
+                    ProposalValidationError::InsufficientCapabilities,
+                    rule,
+                    None,
+                ));
+            }
+        }
+        Ok(())
     }
 
     /// Validate Add proposals. This function implements the following checks:
@@ -354,22 +422,32 @@ impl PublicGroup {
     pub(crate) fn validate_add_proposals(
         &self,
         proposal_queue: &ProposalQueue,
-    ) -> Result<(), ProposalValidationError> {
-        let add_proposals = proposal_queue.add_proposals();
-
+    ) -> Result<(), ProposalValidationReport> {
         // We do the key package validation checks here inline
         // https://validation.openmls.tech/#valn0501
-        for add_proposal in add_proposals {
+        for (index, queued_proposal) in proposal_queue.queued_proposals().enumerate() {
+            let Proposal::Add(add_proposal) = queued_proposal.proposal() else {
+                continue;
+            };
+
             // ValSem105: Check if ciphersuite and version of the group are correct:
             // https://validation.openmls.tech/#valn0201
-            if add_proposal.add_proposal().key_package().ciphersuite() != self.ciphersuite()
-                || add_proposal.add_proposal().key_package().protocol_version() != self.version()
+            if add_proposal.key_package().ciphersuite() != self.ciphersuite()
+                || add_proposal.key_package().protocol_version() != self.version()
             {
-                return Err(ProposalValidationError::InvalidAddProposalCiphersuiteOrVersion);
+                return Err(ProposalValidationReport::new(
+                    ProposalValidationError::InvalidAddProposalCiphersuiteOrVersion,
+                    ProposalValidationRule::ValSem105,
+                )
+                .with_proposal(index, queued_proposal));
             }
 
             // https://validation.openmls.tech/#valn0202
-            self.validate_leaf_node(add_proposal.add_proposal().key_package().leaf_node())?;
+            self.validate_leaf_node(add_proposal.key_package().leaf_node())
+                .map_err(|e| {
+                    ProposalValidationReport::new(e, ProposalValidationRule::Valn0202)
+                        .with_proposal(index, queued_proposal)
+                })?;
         }
         Ok(())
     }
@@ -380,43 +458,66 @@ impl PublicGroup {
     pub(crate) fn validate_remove_proposals(
         &self,
         proposal_queue: &ProposalQueue,
-    ) -> Result<(), ProposalValidationError> {
-        let updates_set: HashSet<_> = proposal_queue
-            .update_proposals()
-            .map(|proposal| {
-                if let Sender::Member(index) = proposal.sender() {
-                    Ok(*index)
-                } else {
-                    Err(ProposalValidationError::UpdateFromNonMember)
-                }
-            })
-            .collect::<Result<_, _>>()?;
-
-        let remove_proposals = proposal_queue.remove_proposals();
+    ) -> Result<(), ProposalValidationReport> {
+        let mut updates_set = HashSet::new();
+        for (index, queued_proposal) in proposal_queue.queued_proposals().enumerate() {
+            if !matches!(queued_proposal.proposal(), Proposal::Update(_)) {
+                continue;
+            }
+            let Sender::Member(sender_index) = queued_proposal.sender() else {
+                return Err(ProposalValidationReport::new(
+                    ProposalValidationError::UpdateFromNonMember,
+                    ProposalValidationRule::ValSem112,
+                )
+                .with_proposal(index, queued_proposal));
+            };
+            updates_set.insert(*sender_index);
+        }
 
         let mut removes_set = HashSet::new();
 
         // https://validation.openmls.tech/#valn0701
-        for remove_proposal in remove_proposals {
-            let removed = remove_proposal.remove_proposal().removed();
+        for (index, queued_proposal) in proposal_queue.queued_proposals().enumerate() {
+            let Proposal::Remove(remove_proposal) = queued_proposal.proposal() else {
+                continue;
+            };
+            let removed = remove_proposal.removed();
+            let report = |error, rule| {
+                ProposalValidationReport::new(error, rule)
+                    .with_proposal(index, queued_proposal)
+                    .with_leaves([removed])
+            };
+
             // The node has to be a leaf in the tree
             // ValSem108
             if !self.treesync().is_leaf_in_tree(removed) {
-                return Err(ProposalValidationError::UnknownMemberRemoval);
+                return Err(report(
+                    ProposalValidationError::UnknownMemberRemoval,
+                    ProposalValidationRule::ValSem108,
+                ));
             }
 
             // ValSem107
             // https://validation.openmls.tech/#valn0304
             if !removes_set.insert(removed) {
-                return Err(ProposalValidationError::DuplicateMemberRemoval);
+                return Err(report(
+                    ProposalValidationError::DuplicateMemberRemoval,
+                    ProposalValidationRule::ValSem107,
+                ));
             }
             if updates_set.contains(&removed) {
-                return Err(ProposalValidationError::DuplicateMemberRemoval);
+                return Err(report(
+                    ProposalValidationError::DuplicateMemberRemoval,
+                    ProposalValidationRule::ValSem107,
+                ));
             }
 
             // removed node can not be blank
             if self.treesync().leaf(removed).is_none() {
-                return Err(ProposalValidationError::UnknownMemberRemoval);
+                return Err(report(
+                    ProposalValidationError::UnknownMemberRemoval,
+                    ProposalValidationRule::ValSem108,
+                ));
             }
         }
 
@@ -432,25 +533,41 @@ impl PublicGroup {
         &self,
         proposal_queue: &ProposalQueue,
         committer: LeafNodeIndex,
-    ) -> Result<(), ProposalValidationError> {
+    ) -> Result<(), ProposalValidationReport> {
         // Check the update proposals from the proposal queue first
-        let update_proposals = proposal_queue.update_proposals();
+        for (index, queued_proposal) in proposal_queue.queued_proposals().enumerate() {
+            let Proposal::Update(update_proposal) = queued_proposal.proposal() else {
+                continue;
+            };
 
-        for update_proposal in update_proposals {
             // ValSem112
             // The sender of a standalone update proposal must be of type member
-            if let Sender::Member(sender_index) = update_proposal.sender() {
-                // ValSem111
-                // The sender of a full Commit must not include own update proposals
-                if committer == *sender_index {
-                    return Err(ProposalValidationError::CommitterIncludedOwnUpdate);
-                }
-            } else {
-                return Err(ProposalValidationError::UpdateFromNonMember);
+            let Sender::Member(sender_index) = queued_proposal.sender() else {
+                return Err(ProposalValidationReport::new(
+                    ProposalValidationError::UpdateFromNonMember,
+                    ProposalValidationRule::ValSem112,
+                )
+                .with_proposal(index, queued_proposal));
+            };
+
+            // ValSem111
+            // The sender of a full Commit must not include own update proposals
+            if committer == *sender_index {
+                return Err(ProposalValidationReport::new(
+                    ProposalValidationError::CommitterIncludedOwnUpdate,
+                    ProposalValidationRule::ValSem111,
+                )
+                .with_proposal(index, queued_proposal)
+                .with_leaves([committer]));
             }
 
             // https://validation.openmls.tech/#valn0601
-            self.validate_leaf_node(update_proposal.update_proposal().leaf_node())?;
+            self.validate_leaf_node(update_proposal.leaf_node())
+                .map_err(|e| {
+                    ProposalValidationReport::new(e, ProposalValidationRule::Valn0601)
+                        .with_proposal(index, queued_proposal)
+                        .with_leaves([*sender_index])
+                })?;
         }
         Ok(())
     }
@@ -465,30 +582,73 @@ impl PublicGroup {
     pub(crate) fn validate_pre_shared_key_proposals(
         &self,
         proposal_queue: &ProposalQueue,
-    ) -> Result<(), ProposalValidationError> {
+    ) -> Result<(), ProposalValidationReport> {
         // ValSem403 (1/2)
         // TODO(#1335): Duplicate proposals are (likely) filtered.
         //              Let's do this check here until we haven't made sure.
         let mut visited_psk_ids = BTreeSet::new();
 
-        for proposal in proposal_queue.psk_proposals() {
-            let psk_id = proposal.psk_proposal().clone().into_psk_id();
+        for (index, queued_proposal) in proposal_queue.queued_proposals().enumerate() {
+            let Proposal::PreSharedKey(psk_proposal) = queued_proposal.proposal() else {
+                continue;
+            };
+            let psk_id = psk_proposal.clone().into_psk_id();
 
             // ValSem401
             // ValSem402
-            let psk_id = psk_id.validate_in_proposal(self.ciphersuite())?;
+            let psk_id = psk_id
+                .validate_in_proposal(self.ciphersuite())
+                .map_err(|e| {
+                    let rule = match e {
+                        PskError::NonceLengthMismatch { .. } => ProposalValidationRule::ValSem401,
+                        _ => ProposalValidationRule::ValSem402,
+                    };
+                    ProposalValidationReport::new(e, rule).with_proposal(index, queued_proposal)
+                })?;
 
             // ValSem403 (2/2)
             if !visited_psk_ids.contains(&psk_id) {
                 visited_psk_ids.insert(psk_id);
             } else {
-                return Err(PskError::Duplicate { first: psk_id }.into());
+                return Err(ProposalValidationReport::new(
+                    PskError::Duplicate { first: psk_id },
+                    ProposalValidationRule::ValSem403,
+                )
+                .with_proposal(index, queued_proposal));
             }
         }
 
         Ok(())
     }
 
+    /// Validate ReInit proposals.
+    ///
+    /// This method implements the following check:
+    ///
+    /// * ValSem114: A Commit with a ReInit proposal must not contain any other
+    ///   proposal.
+    pub(crate) fn validate_reinit_proposals(
+        &self,
+        proposal_queue: &ProposalQueue,
+    ) -> Result<(), ProposalValidationReport> {
+        let Some((index, reinit_proposal)) = proposal_queue
+            .queued_proposals()
+            .enumerate()
+            .find(|(_, p)| matches!(p.proposal(), Proposal::ReInit(_)))
+        else {
+            return Ok(());
+        };
+        if proposal_queue.queued_proposals().count() > 1 {
+            return Err(ProposalValidationReport::new(
+                ProposalValidationError::ReInitWithOtherProposals,
+                ProposalValidationRule::ValSem114,
+            )
+            .with_proposal(index, reinit_proposal));
+        }
+
+        Ok(())
+    }
+
     /// Validate constraints on an external commit. This function implements the following checks:
     ///  - ValSem240: External Commit, inline Proposals: There MUST be at least one ExternalInit proposal.
     ///  - ValSem241: External Commit, inline Proposals: There MUST be at most one ExternalInit proposal.
@@ -703,3 +863,49 @@ impl PublicGroup {
         Ok(())
     }
 }
+
+/// The origin of a key or leaf node that is checked during proposal
+/// validation.
+struct KeyOrigin<'a> {
+    proposal: Option<(usize, &'a QueuedProposal)>,
+    leaf: Option<LeafNodeIndex>,
+}
+
+impl<'a> KeyOrigin<'a> {
+    /// A key introduced by a proposal. The keys of update proposals belong to
+    /// the leaf of the sender.
+    fn proposal(index: usize, queued_proposal: &'a QueuedProposal) -> Self {
+        let leaf = match (queued_proposal.proposal(), queued_proposal.sender()) {
+            (Proposal::Update(_), Sender::Member(sender_index)) => Some(*sender_index),
+            _ => None,
+        };
+        Self {
+            proposal: Some((index, queued_proposal)),
+            leaf,
+        }
+    }
+
+    /// A key introduced by the update path of the commit.
+    fn path() -> Self {
+        Self {
+            proposal: None,
+            leaf: None,
+        }
+    }
+
+    /// Report a violation of `rule` by this key, which collides with a key of
+    /// the `owner` leaf, if any.
+    fn report(
+        &self,
+        error: ProposalValidationError,
+        rule: ProposalValidationRule,
+        owner: Option<LeafNodeIndex>,
+    ) -> ProposalValidationReport {
+        let report = ProposalValidationReport::new(error, rule);
+        let report = match self.proposal {
+            Some((index, queued_proposal)) => report.with_proposal(index, queued_proposal),
+            None => report,
+        };
+        report.with_leaves(self.leaf.into_iter().chain(owner))
+    }
+}
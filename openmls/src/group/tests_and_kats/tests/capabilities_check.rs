@@ -4,8 +4,8 @@ use crate::treesync::errors::LeafNodeValidationError;
 
 // Helper macro for checking error matches a provided pattern
 macro_rules! assert_err_matches {
-    ($err:expr, $pattern:pat) => {
-        assert!(matches!($err.expect_err("Expected an error"), $pattern));
+    ($err:expr, $pattern:pat $(if $guard:expr)?) => {
+        assert!(matches!($err.expect_err("Expected an error"), $pattern $(if $guard)?));
     };
 }
 
@@ -14,12 +14,11 @@ fn expect_valn0104_error<Provider: OpenMlsProvider>(error: Result<(), GroupError
     assert_err_matches!(
         error,
         GroupError::<Provider>::AddMembers(AddMembersError::CreateCommitError(
-            CreateCommitError::ProposalValidationError(
-                ProposalValidationError::LeafNodeValidation(
-                    LeafNodeValidationError::UnsupportedCredentials,
-                )
+            CreateCommitError::InvalidProposal(report)
+        )) if *report.error()
+            == ProposalValidationError::LeafNodeValidation(
+                LeafNodeValidationError::UnsupportedCredentials,
             )
-        ))
     );
 }
 
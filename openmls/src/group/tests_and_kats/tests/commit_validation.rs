@@ -10,7 +10,7 @@ use crate::group::tests_and_kats::utils::{
 };
 use crate::{
     binary_tree::LeafNodeIndex,
-    ciphersuite::signable::Signable,
+    ciphersuite::{hash_ref::ProposalRef, signable::Signable},
     framing::*,
     group::*,
     messages::proposals::*,
@@ -19,6 +19,7 @@ use crate::{
         errors::ApplyUpdatePathError, node::parent_node::PlainUpdatePathNode, treekem::UpdatePath,
         LeafNodeParameters,
     },
+    versions::ProtocolVersion,
 };
 
 struct CommitValidationTestSetup {
@@ -891,3 +892,251 @@ fn test_partial_proposal_commit(
         .merge_pending_commit(provider)
         .expect("Commits with partial proposals are not supported");
 }
+
+// Creates a ReInit proposal in Alice's group and stores it in Bob's group.
+fn propose_reinit(
+    alice_group: &mut MlsGroup,
+    alice_credential: &CredentialWithKeyAndSigner,
+    bob_group: &mut MlsGroup,
+    provider: &impl crate::storage::OpenMlsProvider,
+) -> ProposalRef {
+    let reinit = ReInitProposal::new(
+        GroupId::from_slice(b"New Test Group"),
+        ProtocolVersion::Mls10,
+        alice_group.ciphersuite(),
+        Extensions::empty(),
+    );
+    let (proposal, proposal_ref) = alice_group
+        .propose_reinit(provider, &alice_credential.signer, reinit)
+        .expect("error creating ReInit proposal");
+    let proposal = bob_group
+        .process_message(
+            provider,
+            MlsMessageIn::from(proposal)
+                .try_into_protocol_message()
+                .unwrap(),
+        )
+        .expect("error processing ReInit proposal");
+    match proposal.into_content() {
+        ProcessedMessageContent::ProposalMessage(p) => bob_group
+            .store_pending_proposal(provider.storage(), *p)
+            .unwrap(),
+        _ => unreachable!(),
+    }
+
+    proposal_ref
+}
+
+// ValSem114: A Commit with a ReInit proposal must not contain any other
+// proposal. After the ReInit is merged, the group can't be used anymore.
+#[openmls_test::openmls_test]
+fn test_valsem114_reinit_only() {
+    let CommitValidationTestSetup {
+        mut alice_group,
+        alice_credential,
+        mut bob_group,
+        ..
+    } = validation_test_setup(PURE_PLAINTEXT_WIRE_FORMAT_POLICY, ciphersuite, provider);
+
+    propose_reinit(
+        &mut alice_group,
+        &alice_credential,
+        &mut bob_group,
+        provider,
+    );
+
+    let (commit, _, _) = alice_group
+        .commit_to_pending_proposals(provider, &alice_credential.signer)
+        .expect("error committing to ReInit proposal");
+    alice_group
+        .merge_pending_commit(provider)
+        .expect("error merging ReInit commit");
+
+    let processed = bob_group
+        .process_message(
+            provider,
+            MlsMessageIn::from(commit)
+                .try_into_protocol_message()
+                .unwrap(),
+        )
+        .expect("error processing ReInit commit");
+    match processed.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+            assert!(matches!(
+                staged_commit.queued_proposals().collect::<Vec<_>>().as_slice(),
+                [queued] if matches!(queued.proposal(), Proposal::ReInit(_))
+            ));
+            bob_group
+                .merge_staged_commit(provider, *staged_commit)
+                .expect("error merging ReInit commit");
+        }
+        _ => unreachable!(),
+    }
+
+    assert!(!alice_group.is_active());
+    assert!(!bob_group.is_active());
+    assert!(matches!(
+        alice_group.create_message(provider, &alice_credential.signer, b"Hello"),
+        Err(CreateMessageError::GroupStateError(
+            MlsGroupStateError::UseAfterEviction
+        ))
+    ));
+    assert!(matches!(
+        alice_group.self_update(
+            provider,
+            &alice_credential.signer,
+            LeafNodeParameters::default()
+        ),
+        Err(SelfUpdateError::GroupStateError(
+            MlsGroupStateError::UseAfterEviction
+        ))
+    ));
+}
+
+// ValSem114: A pending ReInit proposal is left out of a commit that covers
+// other proposals.
+#[openmls_test::openmls_test]
+fn test_valsem114_reinit_with_other_proposals() {
+    let CommitValidationTestSetup {
+        mut alice_group,
+        alice_credential,
+        mut bob_group,
+        ..
+    } = validation_test_setup(PURE_PLAINTEXT_WIRE_FORMAT_POLICY, ciphersuite, provider);
+
+    propose_reinit(
+        &mut alice_group,
+        &alice_credential,
+        &mut bob_group,
+        provider,
+    );
+
+    let dave_credential =
+        generate_credential_with_key("Dave".into(), ciphersuite.signature_algorithm(), provider);
+    let dave_key_package =
+        generate_key_package(ciphersuite, Extensions::empty(), provider, dave_credential);
+    alice_group
+        .propose_add_member(
+            provider,
+            &alice_credential.signer,
+            dave_key_package.key_package(),
+        )
+        .expect("error creating Add proposal");
+
+    let (commit, _, _) = alice_group
+        .commit_to_pending_proposals(provider, &alice_credential.signer)
+        .expect("error committing to pending proposals");
+
+    let plaintext = MlsMessageIn::from(commit)
+        .into_plaintext()
+        .expect("Message was not a plaintext.");
+    let FramedContentBody::Commit(commit_content) = plaintext.content() else {
+        panic!("Unexpected content type.");
+    };
+    assert_eq!(commit_content.proposals.len(), 1);
+
+    alice_group
+        .merge_pending_commit(provider)
+        .expect("error merging pending commit");
+    assert!(alice_group.is_active());
+    assert_eq!(alice_group.members().count(), 4);
+}
+
+// ValSem114: A receiver rejects a Commit that covers a ReInit proposal along
+// with other proposals.
+#[openmls_test::openmls_test]
+fn test_valsem114_receiver() {
+    let CommitValidationTestSetup {
+        mut alice_group,
+        alice_credential,
+        mut bob_group,
+        ..
+    } = validation_test_setup(PURE_PLAINTEXT_WIRE_FORMAT_POLICY, ciphersuite, provider);
+
+    let reinit_ref = propose_reinit(
+        &mut alice_group,
+        &alice_credential,
+        &mut bob_group,
+        provider,
+    );
+
+    // Alice won't commit to the ReInit along with other proposals, so we add
+    // the ReInit to a commit with an Add proposal manually.
+    alice_group
+        .clear_pending_proposals(provider.storage())
+        .unwrap();
+
+    let dave_credential =
+        generate_credential_with_key("Dave".into(), ciphersuite.signature_algorithm(), provider);
+    let dave_key_package =
+        generate_key_package(ciphersuite, Extensions::empty(), provider, dave_credential);
+    let serialized_message = alice_group
+        .add_members(
+            provider,
+            &alice_credential.signer,
+            &[dave_key_package.key_package().clone()],
+        )
+        .expect("error adding Dave")
+        .0
+        .tls_serialize_detached()
+        .expect("Could not serialize message.");
+
+    let mut plaintext = MlsMessageIn::tls_deserialize(&mut serialized_message.as_slice())
+        .expect("Could not deserialize message.")
+        .into_plaintext()
+        .expect("Message was not a plaintext.");
+    let original_plaintext = plaintext.clone();
+
+    let mut commit_content = if let FramedContentBody::Commit(commit) = plaintext.content() {
+        commit.clone()
+    } else {
+        panic!("Unexpected content type.");
+    };
+    commit_content
+        .proposals
+        .push(ProposalOrRef::Reference(reinit_ref));
+    plaintext.set_content(FramedContentBody::Commit(commit_content));
+
+    let serialized_context = alice_group
+        .export_group_context()
+        .tls_serialize_detached()
+        .expect("error serializing context");
+
+    // We have to re-sign, since we changed the content.
+    let tbs: FramedContentTbs = plaintext.into();
+    let mut signed_plaintext: AuthenticatedContent = tbs
+        .with_context(serialized_context)
+        .sign(&alice_credential.signer)
+        .expect("Error signing modified payload.");
+    signed_plaintext.set_confirmation_tag(
+        original_plaintext
+            .confirmation_tag()
+            .expect("no confirmation tag on original message")
+            .clone(),
+    );
+
+    let mut signed_plaintext: PublicMessage = signed_plaintext.into();
+    let membership_key = alice_group.message_secrets().membership_key();
+    signed_plaintext
+        .set_membership_tag(
+            provider.crypto(),
+            ciphersuite,
+            membership_key,
+            alice_group.message_secrets().serialized_context(),
+        )
+        .expect("error refreshing membership tag");
+
+    let err = bob_group
+        .process_message(provider, ProtocolMessage::from(signed_plaintext))
+        .expect_err("Could process commit with ReInit and other proposals.");
+    assert!(matches!(
+        err,
+        ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(ref report))
+            if report.error() == &ProposalValidationError::ReInitWithOtherProposals
+    ));
+
+    // Positive case
+    bob_group
+        .process_message(provider, ProtocolMessage::from(original_plaintext))
+        .expect("Unexpected error.");
+}
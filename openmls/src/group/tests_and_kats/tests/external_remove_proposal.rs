@@ -194,11 +194,8 @@ fn external_remove_proposal_should_remove_member() {
         alice_group
             .commit_to_pending_proposals(provider, &alice_credential.signer)
             .unwrap_err(),
-        CommitToPendingProposalsError::CreateCommitError(
-            CreateCommitError::ProposalValidationError(
-                ProposalValidationError::UnknownMemberRemoval
-            )
-        )
+        CommitToPendingProposalsError::CreateCommitError(CreateCommitError::InvalidProposal(report))
+            if report.error() == &ProposalValidationError::UnknownMemberRemoval
     ));
 }
 
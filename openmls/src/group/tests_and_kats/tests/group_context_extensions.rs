@@ -528,11 +528,11 @@ fn fail_insufficient_extensiontype_capabilities_add_valn103() {
     assert!(
         matches!(
             err,
-            ProcessMessageError::InvalidCommit(StageCommitError::ProposalValidationError(
-                ProposalValidationError::LeafNodeValidation(
-                    LeafNodeValidationError::UnsupportedExtensions
-                )
-            ))
+            ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(ref report))
+                if *report.error()
+                    == ProposalValidationError::LeafNodeValidation(
+                        LeafNodeValidationError::UnsupportedExtensions
+                    )
         ),
         "got wrong error: {err:#?}"
     );
@@ -705,9 +705,8 @@ fn fail_insufficient_extensiontype_capabilities_update_valn103() {
     assert!(
         matches!(
             err,
-            ProcessMessageError::InvalidCommit(StageCommitError::ProposalValidationError(
-                ProposalValidationError::InsufficientCapabilities
-            ))
+            ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(ref report))
+                if report.error() == &ProposalValidationError::InsufficientCapabilities
         ),
         "expected a different error, got: {err} ({err:#?})"
     );
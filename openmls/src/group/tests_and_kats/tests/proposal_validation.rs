@@ -310,9 +310,8 @@ fn test_valsem101a() {
                 let err = res.expect_err("was able to add users with the same signature key!");
                 assert!(matches!(
                     err,
-                    AddMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
-                        ProposalValidationError::DuplicateSignatureKey
-                    ))
+                    AddMembersError::CreateCommitError(CreateCommitError::InvalidProposal(report))
+                        if report.error() == &ProposalValidationError::DuplicateSignatureKey
                 ));
             }
             KeyUniqueness::PositiveDifferentKey => {
@@ -396,9 +395,8 @@ fn test_valsem101a() {
 
     assert!(matches!(
         err,
-        ProcessMessageError::InvalidCommit(StageCommitError::ProposalValidationError(
-            ProposalValidationError::DuplicateSignatureKey
-        ))
+        ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(report))
+            if report.error() == &ProposalValidationError::DuplicateSignatureKey
     ));
 
     let original_update_plaintext =
@@ -477,9 +475,8 @@ fn test_valsem102() {
                 let err = res.expect_err("was able to add users with the same HPKE init key!");
                 assert!(matches!(
                     err,
-                    AddMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
-                        ProposalValidationError::DuplicateInitKey
-                    ))
+                    AddMembersError::CreateCommitError(CreateCommitError::InvalidProposal(report))
+                        if report.error() == &ProposalValidationError::DuplicateInitKey
                 ));
             }
             KeyUniqueness::PositiveDifferentKey => {
@@ -563,9 +560,8 @@ fn test_valsem102() {
 
     assert!(matches!(
         err,
-        ProcessMessageError::InvalidCommit(StageCommitError::ProposalValidationError(
-            ProposalValidationError::DuplicateInitKey
-        ))
+        ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(report))
+            if report.error() == &ProposalValidationError::DuplicateInitKey
     ));
 
     let original_update_plaintext =
@@ -665,9 +661,8 @@ fn test_valsem101b() {
                     .expect_err("was able to add user with same signature key as a group member!");
                 assert!(matches!(
                     err,
-                    AddMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
-                        ProposalValidationError::DuplicateSignatureKey
-                    ))
+                    AddMembersError::CreateCommitError(CreateCommitError::InvalidProposal(report))
+                        if report.error() == &ProposalValidationError::DuplicateSignatureKey
                 ));
             }
             KeyUniqueness::PositiveDifferentKey => {
@@ -813,11 +808,14 @@ fn test_valsem101b() {
                     .process_message(provider, verifiable_plaintext)
                     .expect_err("Could process message despite modified public key in path.");
 
+                let ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(report)) =
+                    err
+                else {
+                    panic!("Unexpected error: {err:?}");
+                };
                 assert_eq!(
-                    err,
-                    ProcessMessageError::InvalidCommit(StageCommitError::ProposalValidationError(
-                        ProposalValidationError::ExistingSignatureKeyAddProposal
-                    ))
+                    report.error(),
+                    &ProposalValidationError::ExistingSignatureKeyAddProposal
                 );
             }
             KeyUniqueness::PositiveSameKeyWithRemove => {
@@ -887,9 +885,8 @@ fn test_valsem103_valsem104(ciphersuite: Ciphersuite, provider: &impl OpenMlsPro
                     res.expect_err("was able to add user with colliding init and encryption keys!");
                 assert!(matches!(
                     err,
-                    AddMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
-                        ProposalValidationError::InitEncryptionKeyCollision
-                    ))
+                    AddMembersError::CreateCommitError(CreateCommitError::InvalidProposal(report))
+                        if report.error() == &ProposalValidationError::InitEncryptionKeyCollision
                 ));
             }
             KeyUniqueness::PositiveDifferentKey => {
@@ -974,9 +971,8 @@ fn test_valsem103_valsem104(ciphersuite: Ciphersuite, provider: &impl OpenMlsPro
 
     assert!(matches!(
         err,
-        ProcessMessageError::InvalidCommit(StageCommitError::ProposalValidationError(
-            ProposalValidationError::DuplicateEncryptionKey
-        ))
+        ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(report))
+            if report.error() == &ProposalValidationError::DuplicateEncryptionKey
     ));
 
     let original_update_plaintext =
@@ -1280,10 +1276,10 @@ fn test_valsem105() {
                         matches!(
                             err,
                             ProcessMessageError::InvalidCommit(
-                                StageCommitError::ProposalValidationError(
-                                    ProposalValidationError::InvalidAddProposalCiphersuiteOrVersion,
-                                ),
+                                StageCommitError::InvalidProposal(ref report),
                             )
+                                if *report.error()
+                                    == ProposalValidationError::InvalidAddProposalCiphersuiteOrVersion
                         ) || matches!(
                             err,
                             ProcessMessageError::ValidationError(
@@ -1307,10 +1303,10 @@ fn test_valsem105() {
                         matches!(
                             err,
                             ProcessMessageError::InvalidCommit(
-                                StageCommitError::ProposalValidationError(
-                                    ProposalValidationError::InvalidAddProposalCiphersuiteOrVersion,
-                                ),
+                                StageCommitError::InvalidProposal(ref report),
                             )
+                                if *report.error()
+                                    == ProposalValidationError::InvalidAddProposalCiphersuiteOrVersion
                         ) || matches!(
                             err,
                             ProcessMessageError::ValidationError(
@@ -1333,12 +1329,12 @@ fn test_valsem105() {
                         ) || matches!(
                             err,
                             ProcessMessageError::InvalidCommit(
-                                StageCommitError::ProposalValidationError(
-                                    ProposalValidationError::LeafNodeValidation(
-                                        LeafNodeValidationError::CiphersuiteNotInCapabilities
-                                    ),
-                                ),
+                                StageCommitError::InvalidProposal(ref report),
                             )
+                                if *report.error()
+                                    == ProposalValidationError::LeafNodeValidation(
+                                        LeafNodeValidationError::CiphersuiteNotInCapabilities
+                                    )
                         ),
                         "unexpected error: {:?}",
                         err
@@ -1349,12 +1345,12 @@ fn test_valsem105() {
                         matches!(
                             err,
                             ProcessMessageError::InvalidCommit(
-                                StageCommitError::ProposalValidationError(
-                                    ProposalValidationError::LeafNodeValidation(
-                                        LeafNodeValidationError::CiphersuiteNotInCapabilities
-                                    ),
-                                ),
+                                StageCommitError::InvalidProposal(ref report),
                             )
+                                if *report.error()
+                                    == ProposalValidationError::LeafNodeValidation(
+                                        LeafNodeValidationError::CiphersuiteNotInCapabilities
+                                    )
                         ),
                         "unexpected error: {:?}",
                         err
@@ -1599,9 +1595,8 @@ fn test_valsem108() {
 
     assert!(matches!(
         err,
-        RemoveMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
-            ProposalValidationError::UnknownMemberRemoval
-        ))
+        RemoveMembersError::CreateCommitError(CreateCommitError::InvalidProposal(report))
+            if report.error() == &ProposalValidationError::UnknownMemberRemoval
     ));
 
     // We now have alice create a commit. Then we artificially add an invalid
@@ -1653,9 +1648,8 @@ fn test_valsem108() {
 
     assert!(matches!(
         err,
-        ProcessMessageError::InvalidCommit(StageCommitError::ProposalValidationError(
-            ProposalValidationError::UnknownMemberRemoval
-        ))
+        ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(report))
+            if report.error() == &ProposalValidationError::UnknownMemberRemoval
     ));
 
     let original_update_plaintext =
@@ -1786,11 +1780,8 @@ fn test_valsem110() {
 
     assert!(matches!(
         err,
-        CommitToPendingProposalsError::CreateCommitError(
-            CreateCommitError::ProposalValidationError(
-                ProposalValidationError::DuplicateEncryptionKey
-            )
-        )
+        CommitToPendingProposalsError::CreateCommitError(CreateCommitError::InvalidProposal(report))
+            if report.error() == &ProposalValidationError::DuplicateEncryptionKey
     ));
 
     // Clear commit to see if Bob will process a commit containing two colliding
@@ -2030,9 +2021,8 @@ fn test_valsem111() {
 
     assert!(matches!(
         err,
-        ProcessMessageError::InvalidCommit(StageCommitError::ProposalValidationError(
-            ProposalValidationError::CommitterIncludedOwnUpdate
-        ))
+        ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(report))
+            if report.error() == &ProposalValidationError::CommitterIncludedOwnUpdate
     ));
 
     let original_update_plaintext =
@@ -2221,10 +2211,9 @@ fn valsem113() {
             assert!(matches!(
                 result,
                 Err(CommitToPendingProposalsError::CreateCommitError(
-                    CreateCommitError::ProposalValidationError(
-                        ProposalValidationError::UnsupportedProposalType
-                    )
+                    CreateCommitError::InvalidProposal(report)
                 ))
+                    if report.error() == &ProposalValidationError::UnsupportedProposalType
             ));
             continue;
         } else {
@@ -2254,11 +2243,8 @@ fn valsem113() {
         let _processed_message = if matches!(test_mode, TestMode::Unsupported) {
             assert!(matches!(
                 result,
-                Err(ProcessMessageError::InvalidCommit(
-                    StageCommitError::ProposalValidationError(
-                        ProposalValidationError::UnsupportedProposalType
-                    )
-                ))
+                Err(ProcessMessageError::InvalidCommit(StageCommitError::InvalidProposal(report)))
+                    if report.error() == &ProposalValidationError::UnsupportedProposalType
             ));
             continue;
         } else {
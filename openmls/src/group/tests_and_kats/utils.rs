@@ -6,17 +6,18 @@
 
 use std::{cell::RefCell, collections::HashMap};
 
-use openmls_basic_credential::SignatureKeyPair;
-use openmls_traits::{signatures::Signer, types::SignatureScheme};
+use openmls_traits::signatures::Signer;
 use rand::{rngs::OsRng, RngCore};
 use tls_codec::Serialize;
 
 use crate::{
-    ciphersuite::signable::Signable, credentials::*, framing::*, group::*, key_packages::*,
-    messages::ConfirmationTag, test_utils::*, *,
+    ciphersuite::signable::Signable, framing::*, group::*, key_packages::*,
+    messages::ConfirmationTag, test_utils::*,
 };
 
-use self::storage::OpenMlsProvider;
+pub(crate) use crate::test_utils::{
+    generate_credential_with_key, generate_key_package, CredentialWithKeyAndSigner,
+};
 
 /// Configuration of a client meant to be used in a test setup.
 #[derive(Clone)]
@@ -249,55 +250,6 @@ fn test_setup(provider: &impl crate::storage::OpenMlsProvider) {
     let _test_setup = setup(test_setup_config, provider);
 }
 
-#[derive(Clone)]
-pub(crate) struct CredentialWithKeyAndSigner {
-    pub(crate) credential_with_key: CredentialWithKey,
-    pub(crate) signer: SignatureKeyPair,
-}
-
-// Helper function to generate a CredentialWithKeyAndSigner
-pub(crate) fn generate_credential_with_key<Provider: OpenMlsProvider>(
-    identity: Vec<u8>,
-    signature_scheme: SignatureScheme,
-    provider: &Provider,
-) -> CredentialWithKeyAndSigner {
-    let (credential, signer) = {
-        let credential = BasicCredential::new(identity);
-        let signature_keys = SignatureKeyPair::new(signature_scheme).unwrap();
-        signature_keys.store(provider.storage()).unwrap();
-
-        (credential, signature_keys)
-    };
-    let signature_key =
-        OpenMlsSignaturePublicKey::new(signer.to_public_vec().into(), signature_scheme).unwrap();
-
-    CredentialWithKeyAndSigner {
-        credential_with_key: CredentialWithKey {
-            credential: credential.into(),
-            signature_key: signature_key.into(),
-        },
-        signer,
-    }
-}
-
-// Helper function to generate a KeyPackageBundle
-pub(crate) fn generate_key_package<Provider: OpenMlsProvider>(
-    ciphersuite: Ciphersuite,
-    extensions: Extensions,
-    provider: &Provider,
-    credential_with_keys: CredentialWithKeyAndSigner,
-) -> KeyPackageBundle {
-    KeyPackage::builder()
-        .key_package_extensions(extensions)
-        .build(
-            ciphersuite,
-            provider,
-            &credential_with_keys.signer,
-            credential_with_keys.credential_with_key,
-        )
-        .unwrap()
-}
-
 #[cfg(test)]
 pub(crate) fn resign_message(
     alice_group: &MlsGroup,
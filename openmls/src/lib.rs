@@ -177,7 +177,9 @@ pub mod key_packages;
 pub mod messages;
 #[allow(dead_code)]
 pub mod schedule;
+pub mod sframe;
 pub mod treesync;
+pub mod verification;
 pub mod versions;
 
 // implement storage traits
@@ -186,7 +186,7 @@ impl GroupInfo {
     }
 
     /// Returns the confirmation tag.
-    pub(crate) fn confirmation_tag(&self) -> &ConfirmationTag {
+    pub fn confirmation_tag(&self) -> &ConfirmationTag {
         &self.payload.confirmation_tag
     }
 
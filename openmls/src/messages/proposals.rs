@@ -431,6 +431,43 @@ pub struct ReInitProposal {
     pub(crate) extensions: Extensions,
 }
 
+impl ReInitProposal {
+    /// Create a new [`ReInitProposal`] for a group with the given parameters.
+    pub fn new(
+        group_id: GroupId,
+        version: ProtocolVersion,
+        ciphersuite: Ciphersuite,
+        extensions: Extensions,
+    ) -> Self {
+        Self {
+            group_id,
+            version,
+            ciphersuite,
+            extensions,
+        }
+    }
+
+    /// Get the group ID of the new group.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Get the protocol version of the new group.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Get the ciphersuite of the new group.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
+
+    /// Get the group context extensions of the new group.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+}
+
 /// ExternalInit Proposal.
 ///
 /// An ExternalInit proposal is used by new members that want to join a group by
@@ -23,6 +23,7 @@ pub use crate::extensions::{errors::*, *};
 
 // Framing
 pub use crate::framing::{
+    errors::MessageDecryptionError,
     message_in::{MlsMessageBodyIn, MlsMessageIn, ProtocolMessage},
     message_out::MlsMessageOut,
     sender::Sender,
@@ -34,7 +35,11 @@ pub use crate::framing::{
 pub use crate::key_packages::{errors::*, *};
 
 // Tree
-pub use crate::tree::sender_ratchet::SenderRatchetConfiguration;
+pub use crate::tree::secret_tree::SecretTreeError;
+pub use crate::tree::sender_ratchet::{
+    MessageKeyCacheConfig, MessageKeyEviction, RejectedGeneration, SenderRatchetConfiguration,
+    SenderRatchetState, SenderRatchetType,
+};
 
 // Binary tree
 pub use crate::binary_tree::LeafNodeIndex;
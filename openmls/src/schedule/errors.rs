@@ -47,6 +47,9 @@ pub enum PskError {
         /// Got nonce length.
         got: usize,
     },
+    /// A re-init or branch PSK was used outside of the first commit of a new group.
+    #[error("A re-init or branch PSK was used outside of the first commit of a new group.")]
+    NotFirstCommit,
     /// Duplicate PSK ID.
     #[error("Duplicate PSK ID. First detected duplicate is `{first:?}`.")]
     Duplicate {
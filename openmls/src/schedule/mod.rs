@@ -108,6 +108,7 @@
 // | `sender_data_secret`    | "sender data"   |
 // | `encryption_secret`     | "encryption"    |
 // | `exporter_secret`       | "exporter"      |
+// | `application_export_secret` | "application_export" |
 // | `epoch_authenticator`   | "authentication"|
 // | `external_secret`       | "external"      |
 // | `confirmation_key`      | "confirm"       |
@@ -116,7 +117,7 @@
 // ```
 
 use openmls_traits::{crypto::OpenMlsCrypto, types::*};
-use pprf::Pprf;
+use pprf::{Pprf, PprfError};
 use serde::{Deserialize, Serialize};
 use tls_codec::{TlsDeserialize, TlsDeserializeBytes, TlsSerialize, TlsSize};
 
@@ -793,6 +794,69 @@ impl ExporterSecret {
     }
 }
 
+/// The number of bytes of a component id, i.e. of an input of the exporter
+/// tree.
+const COMPONENT_ID_BYTES: usize = 4;
+
+/// The root of the exporter tree, from which each component can export a
+/// secret exactly once. Afterwards, the tree is punctured at the component's
+/// leaf, so the secret can't be derived again from the stored state.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(PartialEq))]
+pub(crate) struct ApplicationExportSecret {
+    pprf: Pprf,
+}
+
+impl ApplicationExportSecret {
+    /// Derive an `ApplicationExportSecret` from an `EpochSecret`.
+    fn new(
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+        epoch_secret: &EpochSecret,
+    ) -> Result<Self, CryptoError> {
+        let secret =
+            epoch_secret
+                .secret
+                .derive_secret(crypto, ciphersuite, "application_export")?;
+        Ok(Self {
+            pprf: Pprf::with_width(secret, COMPONENT_ID_BYTES),
+        })
+    }
+
+    /// Derive a secret of `key_length` bytes for the `label` and `context`
+    /// from the leaf of the `component_id`, and puncture the tree at that
+    /// leaf. Returns `None` if the leaf was already punctured.
+    pub(crate) fn derive_exported_secret(
+        &mut self,
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+        component_id: u32,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Option<Vec<u8>>, LibraryError> {
+        let leaf = match self
+            .pprf
+            .evaluate(crypto, ciphersuite, &component_id.to_be_bytes())
+        {
+            Ok(leaf) => leaf,
+            Err(PprfError::PuncturedInput) => return Ok(None),
+            Err(PprfError::IndexOutOfBounds) => {
+                return Err(LibraryError::custom(
+                    "The component id is larger than the exporter tree",
+                ))
+            }
+            Err(PprfError::ChildDerivationError(e)) => {
+                return Err(LibraryError::unexpected_crypto_error(e))
+            }
+        };
+        let secret = leaf
+            .kdf_expand_label(crypto, ciphersuite, label, context, key_length)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        Ok(Some(secret.as_slice().to_vec()))
+    }
+}
+
 /// A secret used when joining a group with an external Commit.
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "test-utils"), derive(PartialEq, Clone))]
@@ -952,7 +1016,7 @@ impl MembershipKey {
         Self { secret }
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-utils"))]
     pub(crate) fn as_slice(&self) -> &[u8] {
         self.secret.as_slice()
     }
@@ -1074,6 +1138,7 @@ impl SenderDataSecret {
 /// | `sender_data_secret`    | "sender data"   |
 /// | `encryption_secret`     | "encryption"    |
 /// | `exporter_secret`       | "exporter"      |
+/// | `application_export_secret` | "application_export" |
 /// | `epoch_authenticator`   | "authentication"|
 /// | `external_secret`       | "external"      |
 /// | `confirmation_key`      | "confirm"       |
@@ -1084,6 +1149,7 @@ pub(crate) struct EpochSecrets {
     sender_data_secret: SenderDataSecret,
     encryption_secret: EncryptionSecret,
     exporter_secret: ExporterSecret,
+    application_export_secret: ApplicationExportSecret,
     epoch_authenticator: EpochAuthenticator,
     external_secret: ExternalSecret,
     confirmation_key: ConfirmationKey,
@@ -1191,6 +1257,8 @@ impl EpochSecrets {
         let sender_data_secret = SenderDataSecret::new(crypto, ciphersuite, &epoch_secret)?;
         let encryption_secret = EncryptionSecret::new(crypto, ciphersuite, &epoch_secret)?;
         let exporter_secret = ExporterSecret::new(crypto, ciphersuite, &epoch_secret)?;
+        let application_export_secret =
+            ApplicationExportSecret::new(crypto, ciphersuite, &epoch_secret)?;
         let epoch_authenticator = EpochAuthenticator::new(crypto, ciphersuite, &epoch_secret)?;
         let external_secret = ExternalSecret::new(crypto, ciphersuite, &epoch_secret)?;
         let confirmation_key = ConfirmationKey::new(crypto, ciphersuite, &epoch_secret)?;
@@ -1205,6 +1273,7 @@ impl EpochSecrets {
             sender_data_secret,
             encryption_secret,
             exporter_secret,
+            application_export_secret,
             epoch_authenticator,
             external_secret,
             confirmation_key,
@@ -1247,6 +1316,7 @@ impl EpochSecrets {
             GroupEpochSecrets {
                 init_secret: self.init_secret,
                 exporter_secret: self.exporter_secret,
+                application_export_secret: Some(self.application_export_secret),
                 epoch_authenticator: self.epoch_authenticator,
                 external_secret: self.external_secret,
                 resumption_psk: self.resumption_psk,
@@ -1267,6 +1337,9 @@ impl EpochSecrets {
 pub(crate) struct GroupEpochSecrets {
     init_secret: InitSecret,
     exporter_secret: ExporterSecret,
+    // Epoch secrets stored by earlier versions don't have an exporter tree.
+    #[serde(default)]
+    application_export_secret: Option<ApplicationExportSecret>,
     epoch_authenticator: EpochAuthenticator,
     external_secret: ExternalSecret,
     resumption_psk: ResumptionPskSecret,
@@ -1300,6 +1373,19 @@ impl GroupEpochSecrets {
         &self.exporter_secret
     }
 
+    /// Exporter tree, if available
+    pub(crate) fn application_export_secret_mut(&mut self) -> Option<&mut ApplicationExportSecret> {
+        self.application_export_secret.as_mut()
+    }
+
+    /// Replace the exporter tree, e.g. to undo a puncture.
+    pub(crate) fn set_application_export_secret(
+        &mut self,
+        application_export_secret: ApplicationExportSecret,
+    ) {
+        self.application_export_secret = Some(application_export_secret);
+    }
+
     /// External secret
     pub(crate) fn external_secret(&self) -> &ExternalSecret {
         &self.external_secret
@@ -1310,6 +1396,12 @@ impl GroupEpochSecrets {
         &self.resumption_psk
     }
 
+    /// Consumes the secrets and returns the exporter secret and the epoch
+    /// authenticator, which may be kept for past epochs.
+    pub(crate) fn into_exporter_secrets(self) -> (ExporterSecret, EpochAuthenticator) {
+        (self.exporter_secret, self.epoch_authenticator)
+    }
+
     /// Set the init secret. This should onlye be used to update the PPRF state
     /// when merging a staged commit.
     pub(crate) fn set_init_secret(&mut self, init_secret: InitSecret) {
@@ -99,6 +99,11 @@ fn get_bit(index: &[u8], bit_index: usize) -> bool {
 impl Pprf {
     pub fn new(secret: Secret) -> Self {
         let width = secret.as_slice().len();
+        Self::with_width(secret, width)
+    }
+
+    /// Creates a PPRF whose inputs are `width` bytes long.
+    pub fn with_width(secret: Secret, width: usize) -> Self {
         Pprf {
             // The width of the tree in bytes.
             width,
@@ -459,6 +459,19 @@ pub(crate) fn load_psks<'p, Storage: StorageProvider>(
         log_crypto!(trace, "PSK store {:?}", resumption_psk_store);
 
         match &psk_id.psk {
+            // Re-init and branch PSKs are resumption PSKs of another group. They
+            // have to be put into the storage by the application, e.g. with
+            // [`PreSharedKeyId::store`].
+            Psk::Resumption(resumption) if resumption.usage != ResumptionPskUsage::Application => {
+                let psk_bundle: Option<PskBundle> = storage
+                    .psk(psk_id.psk())
+                    .map_err(|_| PskError::KeyNotFound)?;
+                if let Some(psk_bundle) = psk_bundle {
+                    psk_bundles.push((psk_id, psk_bundle.secret));
+                } else {
+                    return Err(PskError::KeyNotFound);
+                }
+            }
             Psk::Resumption(resumption) => {
                 if let Some(psk_bundle) = resumption_psk_store.get(resumption.psk_epoch()) {
                     psk_bundles.push((psk_id, psk_bundle.secret.clone()));
@@ -0,0 +1,42 @@
+//! # SFrame errors
+//!
+//! `SframeError` is returned by the SFrame key management and encryption.
+
+use thiserror::Error;
+
+use crate::{error::LibraryError, group::ExportSecretError};
+
+/// SFrame error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SframeError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`ExportSecretError`] for more details.
+    #[error(transparent)]
+    ExportSecretError(#[from] ExportSecretError),
+    /// The cipher suite is not supported, e.g. because the crypto provider
+    /// doesn't implement its AEAD.
+    #[error("The cipher suite is not supported.")]
+    UnsupportedCipherSuite,
+    /// The `KID` layout doesn't fit into 64 bits, the window of past epochs
+    /// is too large for the epoch bits, or the own leaf index doesn't fit into
+    /// the index bits.
+    #[error("The SFrame configuration is invalid.")]
+    InvalidConfig,
+    /// There is no key for the epoch.
+    #[error("There is no key for the epoch.")]
+    UnknownEpoch,
+    /// There is no key for the `KID`.
+    #[error("There is no key for the KID.")]
+    UnknownKeyId,
+    /// The SFrame header is malformed.
+    #[error("The SFrame header is malformed.")]
+    MalformedHeader,
+    /// The ciphertext could not be decrypted.
+    #[error("The ciphertext could not be decrypted.")]
+    DecryptionFailed,
+    /// The counter for own frames is exhausted.
+    #[error("The counter for own frames is exhausted.")]
+    CounterExhausted,
+}
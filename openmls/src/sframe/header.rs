@@ -0,0 +1,90 @@
+//! The SFrame header, as defined in Section 4.3 of RFC 9605.
+//!
+//! ```text
+//!  0 1 2 3 4 5 6 7
+//! +-+-+-+-+-+-+-+-+---------------------------+---------------------------+
+//! |X|  K  |Y|  C  |   KID... (length=K+1)     |   CTR... (length=C+1)     |
+//! +-+-+-+-+-+-+-+-+---------------------------+---------------------------+
+//! ```
+//!
+//! Values smaller than 8 are encoded directly in `K` or `C`. Larger values
+//! are encoded in big-endian order in the minimal number of bytes after the
+//! config byte, and `K` or `C` hold that number minus one.
+
+use super::errors::SframeError;
+
+const EXTENDED: u8 = 0b1000;
+
+/// The header of an SFrame ciphertext.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SframeHeader {
+    kid: u64,
+    ctr: u64,
+}
+
+impl SframeHeader {
+    /// Creates a new header with the key id `kid` and the counter `ctr`.
+    pub fn new(kid: u64, ctr: u64) -> Self {
+        Self { kid, ctr }
+    }
+
+    /// Returns the key id.
+    pub fn kid(&self) -> u64 {
+        self.kid
+    }
+
+    /// Returns the counter.
+    pub fn ctr(&self) -> u64 {
+        self.ctr
+    }
+
+    /// Encodes the header.
+    pub fn encode(&self) -> Vec<u8> {
+        let (kid_bits, kid_bytes) = encode_value(self.kid);
+        let (ctr_bits, ctr_bytes) = encode_value(self.ctr);
+        let mut header = vec![(kid_bits << 4) | ctr_bits];
+        header.extend_from_slice(&kid_bytes);
+        header.extend_from_slice(&ctr_bytes);
+        header
+    }
+
+    /// Decodes the header at the start of `bytes` and returns it together with
+    /// its encoded length.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), SframeError> {
+        let config = *bytes.first().ok_or(SframeError::MalformedHeader)?;
+        let mut offset = 1;
+        let kid = decode_value(config >> 4, bytes, &mut offset)?;
+        let ctr = decode_value(config & 0x0f, bytes, &mut offset)?;
+        Ok((Self { kid, ctr }, offset))
+    }
+}
+
+/// Returns the 4 bits for the config byte and the extended encoding of the
+/// `value`, which is empty if the value fits into the config byte.
+fn encode_value(value: u64) -> (u8, Vec<u8>) {
+    if value < EXTENDED as u64 {
+        return (value as u8, vec![]);
+    }
+    let bytes = value.to_be_bytes();
+    let length = bytes.len() - (value.leading_zeros() / 8) as usize;
+    (
+        EXTENDED | (length - 1) as u8,
+        bytes[bytes.len() - length..].to_vec(),
+    )
+}
+
+/// Decodes a value from the 4 `bits` of the config byte and, if the value is
+/// extended, from the `bytes` at the `offset`, which is advanced.
+fn decode_value(bits: u8, bytes: &[u8], offset: &mut usize) -> Result<u64, SframeError> {
+    if bits & EXTENDED == 0 {
+        return Ok(bits as u64);
+    }
+    let length = (bits & !EXTENDED) as usize + 1;
+    let value = bytes
+        .get(*offset..*offset + length)
+        .ok_or(SframeError::MalformedHeader)?
+        .iter()
+        .fold(0u64, |value, byte| (value << 8) | *byte as u64);
+    *offset += length;
+    Ok(value)
+}
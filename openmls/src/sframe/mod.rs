@@ -0,0 +1,489 @@
+//! # SFrame key management
+//!
+//! This module implements the key management for SFrame ([RFC 9605]) on top of
+//! an [`MlsGroup`] as described in [Section 5.2] of the RFC, as well as the
+//! SFrame encryption itself.
+//!
+//! For every epoch, an SFrame epoch secret is exported from the group. The
+//! base key of each sender is derived from it and the sender's leaf index.
+//! The key id (`KID`) of a frame encodes the sender's leaf index and the low
+//! bits of the epoch, so that receivers can find the key for a frame without
+//! any further signalling:
+//!
+//! ```text
+//! KID = (context_id << (index_bits + epoch_bits))
+//!     + (index << epoch_bits)
+//!     + (epoch % (1 << epoch_bits))
+//! ```
+//!
+//! An [`SframeContext`] keeps the secrets of the current epoch and of a
+//! configurable number of past epochs, so that frames sent shortly before an
+//! epoch change can still be decrypted. Call [`SframeContext::update()`]
+//! after every change of the group's epoch.
+//!
+//! Only the AES-GCM cipher suites are supported, because the CTR+HMAC cipher
+//! suites are not provided by [`OpenMlsCrypto`].
+//!
+//! [RFC 9605]: https://www.rfc-editor.org/rfc/rfc9605.html
+//! [Section 5.2]: https://www.rfc-editor.org/rfc/rfc9605.html#section-5.2
+
+use std::collections::VecDeque;
+
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{AeadType, CryptoError, HashType},
+};
+use tls_codec::SecretVLBytes;
+
+use crate::{
+    binary_tree::array_representation::LeafNodeIndex,
+    error::LibraryError,
+    group::{GroupEpoch, MlsGroup},
+};
+
+// Public
+pub mod errors;
+
+mod header;
+
+// Tests
+#[cfg(test)]
+mod tests;
+
+use errors::SframeError;
+pub use header::SframeHeader;
+
+/// The exporter label for the SFrame epoch secret.
+const BASE_KEY_LABEL: &str = "SFrame 1.0 Base Key";
+const SECRET_KEY_LABEL: &[u8] = b"SFrame 1.0 Secret key ";
+const SECRET_SALT_LABEL: &[u8] = b"SFrame 1.0 Secret salt ";
+
+/// SFrame cipher suites that can be used with [`OpenMlsCrypto`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SframeCipherSuite {
+    /// AES_128_GCM_SHA256_128
+    Aes128GcmSha256_128 = 0x0004,
+    /// AES_256_GCM_SHA512_128
+    Aes256GcmSha512_128 = 0x0005,
+}
+
+impl SframeCipherSuite {
+    /// Returns the AEAD algorithm of the cipher suite.
+    pub fn aead_type(&self) -> AeadType {
+        match self {
+            SframeCipherSuite::Aes128GcmSha256_128 => AeadType::Aes128Gcm,
+            SframeCipherSuite::Aes256GcmSha512_128 => AeadType::Aes256Gcm,
+        }
+    }
+
+    /// Returns the hash algorithm of the cipher suite.
+    pub fn hash_type(&self) -> HashType {
+        match self {
+            SframeCipherSuite::Aes128GcmSha256_128 => HashType::Sha2_256,
+            SframeCipherSuite::Aes256GcmSha512_128 => HashType::Sha2_512,
+        }
+    }
+}
+
+impl TryFrom<u16> for SframeCipherSuite {
+    type Error = SframeError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0x0004 => Ok(SframeCipherSuite::Aes128GcmSha256_128),
+            0x0005 => Ok(SframeCipherSuite::Aes256GcmSha512_128),
+            _ => Err(SframeError::UnsupportedCipherSuite),
+        }
+    }
+}
+
+/// The key and salt for a single `KID`.
+pub struct SframeKey {
+    cipher_suite: SframeCipherSuite,
+    kid: u64,
+    key: SecretVLBytes,
+    salt: SecretVLBytes,
+}
+
+impl core::fmt::Debug for SframeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SframeKey")
+            .field("cipher_suite", &self.cipher_suite)
+            .field("kid", &self.kid)
+            .field("key", &"***")
+            .field("salt", &"***")
+            .finish()
+    }
+}
+
+impl SframeKey {
+    /// Derives the key and salt for the `kid` from the `base_key`, as defined
+    /// in Section 4.4.2 of RFC 9605.
+    pub fn derive(
+        crypto: &impl OpenMlsCrypto,
+        cipher_suite: SframeCipherSuite,
+        kid: u64,
+        base_key: &[u8],
+    ) -> Result<Self, SframeError> {
+        let hash_type = cipher_suite.hash_type();
+        let aead_type = cipher_suite.aead_type();
+        let sframe_secret = crypto
+            .hkdf_extract(hash_type, &[], base_key)
+            .map_err(crypto_error)?;
+
+        let label = |label: &[u8]| {
+            [
+                label,
+                &kid.to_be_bytes(),
+                &(cipher_suite as u16).to_be_bytes(),
+            ]
+            .concat()
+        };
+        let key = crypto
+            .hkdf_expand(
+                hash_type,
+                sframe_secret.as_slice(),
+                &label(SECRET_KEY_LABEL),
+                aead_type.key_size(),
+            )
+            .map_err(crypto_error)?;
+        let salt = crypto
+            .hkdf_expand(
+                hash_type,
+                sframe_secret.as_slice(),
+                &label(SECRET_SALT_LABEL),
+                aead_type.nonce_size(),
+            )
+            .map_err(crypto_error)?;
+
+        Ok(Self {
+            cipher_suite,
+            kid,
+            key,
+            salt,
+        })
+    }
+
+    /// Returns the `KID` of the key.
+    pub fn kid(&self) -> u64 {
+        self.kid
+    }
+
+    /// Encrypts the `plaintext` with the counter `ctr` and returns the SFrame
+    /// ciphertext, i.e. the header followed by the encrypted `plaintext`.
+    ///
+    /// The `metadata` is authenticated, but not included in the ciphertext.
+    pub fn encrypt(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        ctr: u64,
+        metadata: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, SframeError> {
+        let mut ciphertext = SframeHeader::new(self.kid, ctr).encode();
+        let aad = [ciphertext.as_slice(), metadata].concat();
+        let encrypted = crypto
+            .aead_encrypt(
+                self.cipher_suite.aead_type(),
+                self.key.as_slice(),
+                plaintext,
+                &self.nonce(ctr),
+                &aad,
+            )
+            .map_err(crypto_error)?;
+        ciphertext.extend_from_slice(&encrypted);
+        Ok(ciphertext)
+    }
+
+    /// Decrypts the SFrame `ciphertext` and returns the plaintext.
+    ///
+    /// Returns an error if the `KID` in the header is not the key's `KID`.
+    pub fn decrypt(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        metadata: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, SframeError> {
+        let (header, header_length) = SframeHeader::decode(ciphertext)?;
+        if header.kid() != self.kid {
+            return Err(SframeError::UnknownKeyId);
+        }
+        let (encoded_header, encrypted) = ciphertext.split_at(header_length);
+        let aad = [encoded_header, metadata].concat();
+        crypto
+            .aead_decrypt(
+                self.cipher_suite.aead_type(),
+                self.key.as_slice(),
+                encrypted,
+                &self.nonce(header.ctr()),
+                &aad,
+            )
+            .map_err(|e| match e {
+                CryptoError::UnsupportedAeadAlgorithm => SframeError::UnsupportedCipherSuite,
+                _ => SframeError::DecryptionFailed,
+            })
+    }
+
+    fn nonce(&self, ctr: u64) -> Vec<u8> {
+        let mut nonce = self.salt.as_slice().to_vec();
+        let offset = nonce.len() - 8;
+        for (nonce_byte, ctr_byte) in nonce[offset..].iter_mut().zip(ctr.to_be_bytes()) {
+            *nonce_byte ^= ctr_byte;
+        }
+        nonce
+    }
+}
+
+/// Maps a crypto error of the provider to an [`SframeError`]. The provider
+/// may not implement the primitives of every SFrame cipher suite.
+fn crypto_error(error: CryptoError) -> SframeError {
+    match error {
+        CryptoError::UnsupportedAeadAlgorithm | CryptoError::UnsupportedHashAlgorithm => {
+            SframeError::UnsupportedCipherSuite
+        }
+        e => LibraryError::unexpected_crypto_error(e).into(),
+    }
+}
+
+/// Configuration of an [`SframeContext`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SframeConfig {
+    cipher_suite: SframeCipherSuite,
+    epoch_bits: u8,
+    index_bits: u8,
+    context_id: u64,
+    max_past_epochs: usize,
+}
+
+impl SframeConfig {
+    /// Creates a new configuration for the `cipher_suite`, with 8 bits for the
+    /// epoch and 16 bits for the leaf index in the `KID`, context id 0 and one
+    /// past epoch.
+    pub fn new(cipher_suite: SframeCipherSuite) -> Self {
+        Self {
+            cipher_suite,
+            epoch_bits: 8,
+            index_bits: 16,
+            context_id: 0,
+            max_past_epochs: 1,
+        }
+    }
+
+    /// Sets the number of bits of the `KID` used for the epoch.
+    pub fn epoch_bits(mut self, epoch_bits: u8) -> Self {
+        self.epoch_bits = epoch_bits;
+        self
+    }
+
+    /// Sets the number of bits of the `KID` used for the leaf index.
+    pub fn index_bits(mut self, index_bits: u8) -> Self {
+        self.index_bits = index_bits;
+        self
+    }
+
+    /// Sets the context id that is encoded in the `KID` of own frames, e.g.
+    /// to distinguish the media streams of a sender.
+    pub fn context_id(mut self, context_id: u64) -> Self {
+        self.context_id = context_id;
+        self
+    }
+
+    /// Sets the number of past epochs whose frames can still be decrypted.
+    pub fn max_past_epochs(mut self, max_past_epochs: usize) -> Self {
+        self.max_past_epochs = max_past_epochs;
+        self
+    }
+
+    /// Returns the cipher suite.
+    pub fn cipher_suite(&self) -> SframeCipherSuite {
+        self.cipher_suite
+    }
+
+    fn validate(&self) -> Result<(), SframeError> {
+        let kid_bits = self.epoch_bits as u32 + self.index_bits as u32;
+        let context_bits = u64::BITS - self.context_id.leading_zeros();
+        if self.epoch_bits == 0
+            || self.index_bits == 0
+            || kid_bits + context_bits > u64::BITS
+            // The epochs in the window must be distinguishable by their low
+            // bits.
+            || (self.epoch_bits < 64 && self.max_past_epochs as u64 >= 1 << self.epoch_bits)
+        {
+            return Err(SframeError::InvalidConfig);
+        }
+        Ok(())
+    }
+}
+
+struct SframeEpoch {
+    epoch: GroupEpoch,
+    secret: SecretVLBytes,
+}
+
+/// The SFrame keys of a member of an [`MlsGroup`].
+pub struct SframeContext {
+    config: SframeConfig,
+    // Oldest first
+    epochs: VecDeque<SframeEpoch>,
+    own_index: LeafNodeIndex,
+    counter: u64,
+}
+
+impl core::fmt::Debug for SframeContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SframeContext")
+            .field("config", &self.config)
+            .field(
+                "epochs",
+                &self.epochs.iter().map(|e| e.epoch).collect::<Vec<_>>(),
+            )
+            .field("own_index", &self.own_index)
+            .field("counter", &self.counter)
+            .finish()
+    }
+}
+
+impl SframeContext {
+    /// Creates a new context for the current epoch of the `group`.
+    pub fn new(
+        crypto: &impl OpenMlsCrypto,
+        config: SframeConfig,
+        group: &MlsGroup,
+    ) -> Result<Self, SframeError> {
+        config.validate()?;
+        let mut context = Self {
+            config,
+            epochs: VecDeque::new(),
+            own_index: group.own_leaf_index(),
+            counter: 0,
+        };
+        context.update(crypto, group)?;
+        Ok(context)
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &SframeConfig {
+        &self.config
+    }
+
+    /// Exports the secret of the current epoch of the `group` if it is new
+    /// and drops the secrets of epochs that are outside the window.
+    pub fn update(
+        &mut self,
+        crypto: &impl OpenMlsCrypto,
+        group: &MlsGroup,
+    ) -> Result<(), SframeError> {
+        let epoch = group.epoch();
+        if group.own_leaf_index().u32() as u64 > mask(self.config.index_bits as u32) {
+            return Err(SframeError::InvalidConfig);
+        }
+        if self.epochs.back().is_some_and(|e| e.epoch == epoch) {
+            return Ok(());
+        }
+
+        let secret = group.export_secret(
+            crypto,
+            BASE_KEY_LABEL,
+            &[],
+            self.config.cipher_suite.hash_type().size(),
+        )?;
+        self.epochs.push_back(SframeEpoch {
+            epoch,
+            secret: secret.into(),
+        });
+        while self.epochs.len() > self.config.max_past_epochs + 1 {
+            self.epochs.pop_front();
+        }
+        self.own_index = group.own_leaf_index();
+        self.counter = 0;
+        Ok(())
+    }
+
+    /// Returns the `KID` of the sender at `index` in the `epoch`, with the
+    /// configured context id.
+    pub fn kid(&self, epoch: GroupEpoch, index: LeafNodeIndex) -> u64 {
+        let epoch_bits = self.config.epoch_bits as u32;
+        let index_bits = self.config.index_bits as u32;
+        let context = self
+            .config
+            .context_id
+            .checked_shl(index_bits + epoch_bits)
+            .unwrap_or(0);
+        let index = (index.u32() as u64) << epoch_bits;
+        context + index + (epoch.as_u64() & mask(epoch_bits))
+    }
+
+    /// Returns the epoch and leaf index of the sender of frames with the
+    /// `kid`, or `None` if the epoch is not in the window.
+    pub fn sender(&self, kid: u64) -> Option<(GroupEpoch, LeafNodeIndex)> {
+        let epoch_bits = self.config.epoch_bits as u32;
+        let index_bits = self.config.index_bits as u32;
+        let epoch_low_bits = kid & mask(epoch_bits);
+        let index = (kid >> epoch_bits) & mask(index_bits);
+        let epoch = self
+            .epochs
+            .iter()
+            .rev()
+            .find(|e| e.epoch.as_u64() & mask(epoch_bits) == epoch_low_bits)?
+            .epoch;
+        Some((epoch, LeafNodeIndex::new(index as u32)))
+    }
+
+    /// Encrypts a frame as the own leaf in the current epoch.
+    pub fn protect(
+        &mut self,
+        crypto: &impl OpenMlsCrypto,
+        metadata: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, SframeError> {
+        let epoch = self.epochs.back().ok_or(SframeError::UnknownEpoch)?.epoch;
+        let key = self.key(crypto, self.kid(epoch, self.own_index))?;
+        let ctr = self.counter;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or(SframeError::CounterExhausted)?;
+        key.encrypt(crypto, ctr, metadata, plaintext)
+    }
+
+    /// Decrypts a frame and returns the plaintext together with the epoch and
+    /// leaf index of the sender.
+    pub fn unprotect(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        metadata: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<(Vec<u8>, GroupEpoch, LeafNodeIndex), SframeError> {
+        let (header, _) = SframeHeader::decode(ciphertext)?;
+        let (epoch, index) = self.sender(header.kid()).ok_or(SframeError::UnknownKeyId)?;
+        let plaintext = self
+            .key(crypto, header.kid())?
+            .decrypt(crypto, metadata, ciphertext)?;
+        Ok((plaintext, epoch, index))
+    }
+
+    /// Derives the key for the `kid`.
+    fn key(&self, crypto: &impl OpenMlsCrypto, kid: u64) -> Result<SframeKey, SframeError> {
+        let (epoch, index) = self.sender(kid).ok_or(SframeError::UnknownKeyId)?;
+        let epoch_secret = self
+            .epochs
+            .iter()
+            .find(|e| e.epoch == epoch)
+            .ok_or(SframeError::UnknownEpoch)?;
+        let hash_type = self.config.cipher_suite.hash_type();
+        let base_key = crypto
+            .hkdf_expand(
+                hash_type,
+                epoch_secret.secret.as_slice(),
+                &index.u32().to_be_bytes(),
+                hash_type.size(),
+            )
+            .map_err(crypto_error)?;
+        SframeKey::derive(crypto, self.config.cipher_suite, kid, base_key.as_slice())
+    }
+}
+
+fn mask(bits: u32) -> u64 {
+    1u64.checked_shl(bits).map_or(u64::MAX, |bit| bit - 1)
+}
@@ -0,0 +1,143 @@
+use serde::Deserialize;
+
+use super::*;
+use crate::test_utils::hex_to_bytes;
+
+/// The test vectors of Appendix C of RFC 9605 for the AES-GCM cipher suites.
+#[derive(Deserialize)]
+struct SframeTestVectors {
+    header: Vec<HeaderTestVector>,
+    key_derivation: Vec<KeyDerivationTestVector>,
+    encryption: Vec<EncryptionTestVector>,
+}
+
+#[derive(Deserialize)]
+struct HeaderTestVector {
+    kid: u64,
+    ctr: u64,
+    header: String,
+}
+
+#[derive(Deserialize)]
+struct KeyDerivationTestVector {
+    cipher_suite: u16,
+    base_key: String,
+    kid: u64,
+    sframe_key: String,
+    sframe_salt: String,
+}
+
+#[derive(Deserialize)]
+struct EncryptionTestVector {
+    cipher_suite: u16,
+    kid: u64,
+    ctr: u64,
+    base_key: String,
+    nonce: String,
+    metadata: String,
+    pt: String,
+    ct: String,
+}
+
+fn test_vectors() -> SframeTestVectors {
+    read_json!("../../test_vectors/sframe.json")
+}
+
+#[test]
+fn header_encoding() {
+    for test in test_vectors().header {
+        let encoded = hex_to_bytes(&test.header);
+        let header = SframeHeader::new(test.kid, test.ctr);
+        assert_eq!(header.encode(), encoded);
+        assert_eq!(SframeHeader::decode(&encoded), Ok((header, encoded.len())));
+    }
+
+    assert_eq!(
+        SframeHeader::decode(&[0x09, 0x01]),
+        Err(SframeError::MalformedHeader)
+    );
+    assert_eq!(SframeHeader::decode(&[]), Err(SframeError::MalformedHeader));
+}
+
+#[openmls_test::openmls_test]
+fn key_derivation() {
+    let crypto = provider.crypto();
+    for test in test_vectors().key_derivation {
+        let cipher_suite = SframeCipherSuite::try_from(test.cipher_suite).unwrap();
+        let key = match SframeKey::derive(
+            crypto,
+            cipher_suite,
+            test.kid,
+            &hex_to_bytes(&test.base_key),
+        ) {
+            Ok(key) => key,
+            // Not every provider implements SHA-512.
+            Err(SframeError::UnsupportedCipherSuite) => continue,
+            Err(e) => panic!("Unexpected error: {e:?}"),
+        };
+        assert_eq!(key.key.as_slice(), hex_to_bytes(&test.sframe_key));
+        assert_eq!(key.salt.as_slice(), hex_to_bytes(&test.sframe_salt));
+    }
+}
+
+#[openmls_test::openmls_test]
+fn encryption() {
+    let crypto = provider.crypto();
+    for test in test_vectors().encryption {
+        let cipher_suite = SframeCipherSuite::try_from(test.cipher_suite).unwrap();
+        let key = match SframeKey::derive(
+            crypto,
+            cipher_suite,
+            test.kid,
+            &hex_to_bytes(&test.base_key),
+        ) {
+            Ok(key) => key,
+            Err(SframeError::UnsupportedCipherSuite) => continue,
+            Err(e) => panic!("Unexpected error: {e:?}"),
+        };
+        assert_eq!(key.nonce(test.ctr), hex_to_bytes(&test.nonce));
+
+        let metadata = hex_to_bytes(&test.metadata);
+        let plaintext = hex_to_bytes(&test.pt);
+        let ciphertext = hex_to_bytes(&test.ct);
+        match key.encrypt(crypto, test.ctr, &metadata, &plaintext) {
+            Ok(encrypted) => assert_eq!(encrypted, ciphertext),
+            // Not every provider implements AES-GCM.
+            Err(SframeError::UnsupportedCipherSuite) => continue,
+            Err(e) => panic!("Unexpected error: {e:?}"),
+        }
+        assert_eq!(
+            key.decrypt(crypto, &metadata, &ciphertext).unwrap(),
+            plaintext
+        );
+    }
+}
+
+#[openmls_test::openmls_test]
+fn key_roundtrip() {
+    let crypto = provider.crypto();
+    for cipher_suite in [
+        SframeCipherSuite::Aes128GcmSha256_128,
+        SframeCipherSuite::Aes256GcmSha512_128,
+    ] {
+        let key = SframeKey::derive(crypto, cipher_suite, 0x0123, &[0x42; 32]).unwrap();
+        let ciphertext = match key.encrypt(crypto, 0x4567, b"metadata", b"plaintext") {
+            Ok(ciphertext) => ciphertext,
+            // Not every provider implements AES-GCM.
+            Err(SframeError::UnsupportedCipherSuite) => continue,
+            Err(e) => panic!("Unexpected error: {e:?}"),
+        };
+        assert_eq!(
+            SframeHeader::decode(&ciphertext).unwrap().0,
+            SframeHeader::new(0x0123, 0x4567)
+        );
+        assert_eq!(
+            key.decrypt(crypto, b"metadata", &ciphertext).unwrap(),
+            b"plaintext"
+        );
+        assert_eq!(
+            key.decrypt(crypto, b"other metadata", &ciphertext),
+            Err(SframeError::DecryptionFailed)
+        );
+    }
+}
@@ -19,7 +19,11 @@ use crate::{
     treesync::{LeafNode, TreeSync},
 };
 use crate::{
-    group::{past_secrets::MessageSecretsStore, GroupEpoch},
+    group::{
+        message_buffer::MessageBuffer,
+        past_secrets::{EpochTree, MessageSecretsStore, PastExporterSecrets},
+        EpochRecord, GroupEpoch, OwnLeafUpdate,
+    },
     prelude::KeyPackageBundle,
     schedule::{
         psk::{store::ResumptionPskStore, PskBundle},
@@ -143,6 +147,21 @@ impl traits::GroupState<CURRENT_VERSION> for MlsGroupState {}
 impl Entity<CURRENT_VERSION> for LeafNode {}
 impl traits::LeafNode<CURRENT_VERSION> for LeafNode {}
 
+impl Entity<CURRENT_VERSION> for MessageBuffer {}
+impl traits::MessageBuffer<CURRENT_VERSION> for MessageBuffer {}
+
+impl Entity<CURRENT_VERSION> for OwnLeafUpdate {}
+impl traits::OwnLeafUpdate<CURRENT_VERSION> for OwnLeafUpdate {}
+
+impl Entity<CURRENT_VERSION> for EpochRecord {}
+impl traits::EpochRecord<CURRENT_VERSION> for EpochRecord {}
+
+impl Entity<CURRENT_VERSION> for PastExporterSecrets {}
+impl traits::PastExporterSecrets<CURRENT_VERSION> for PastExporterSecrets {}
+
+impl Entity<CURRENT_VERSION> for EpochTree {}
+impl traits::ArchivedMessageSecrets<CURRENT_VERSION> for EpochTree {}
+
 // Crypto
 
 impl Key<CURRENT_VERSION> for GroupEpoch {}
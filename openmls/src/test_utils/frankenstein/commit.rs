@@ -5,6 +5,7 @@ use super::{FrankenLeafNode, FrankenProposal};
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenCommit {
     pub proposals: Vec<FrankenProposalOrRef>,
     pub path: Option<FrankenUpdatePathIn>,
@@ -15,6 +16,7 @@ pub struct FrankenCommit {
 )]
 #[repr(u8)]
 #[allow(clippy::large_enum_variant)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenProposalOrRef {
     #[tls_codec(discriminant = 1)]
     Proposal(FrankenProposal),
@@ -24,6 +26,7 @@ pub enum FrankenProposalOrRef {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenUpdatePathIn {
     pub leaf_node: FrankenLeafNode,
     pub nodes: Vec<FrankenUpdatePathNode>,
@@ -32,6 +35,7 @@ pub struct FrankenUpdatePathIn {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenUpdatePathNode {
     pub public_key: VLBytes,
     pub encrypted_path_secrets: Vec<FrankenHpkeCiphertext>,
@@ -40,6 +44,7 @@ pub struct FrankenUpdatePathNode {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenHpkeCiphertext {
     pub kem_output: VLBytes,
     pub ciphertext: VLBytes,
@@ -5,6 +5,7 @@ use crate::credentials::Credential;
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenCredential {
     credential_type: u16,
     serialized_credential_content: VLBytes,
@@ -10,6 +10,7 @@ use crate::{
 use super::{FrankenCredential, FrankenLeafNode};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenExtensionType {
     ApplicationId,
     RatchetTree,
@@ -50,6 +51,7 @@ impl From<FrankenExtensionType> for u16 {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[repr(u16)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenExtension {
     ApplicationId(FrankenApplicationIdExtension),
     RatchetTree(FrankenRatchetTreeExtension),
@@ -84,6 +86,7 @@ impl From<Extension> for FrankenExtension {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenApplicationIdExtension {
     pub key_id: VLBytes,
 }
@@ -91,6 +94,7 @@ pub struct FrankenApplicationIdExtension {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenRatchetTreeExtension {
     pub ratchet_tree: Vec<Option<FrankenNode>>,
 }
@@ -100,6 +104,7 @@ pub struct FrankenRatchetTreeExtension {
 )]
 #[repr(u8)]
 #[allow(clippy::large_enum_variant)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenNode {
     #[tls_codec(discriminant = 1)]
     LeafNode(FrankenLeafNode),
@@ -124,6 +129,7 @@ impl From<NodeIn> for FrankenNode {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenParentNode {
     pub encryption_key: VLBytes,
     pub parent_hash: VLBytes,
@@ -140,6 +146,7 @@ impl From<ParentNode> for FrankenParentNode {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenRequiredCapabilitiesExtension {
     pub extension_types: Vec<u16>,
     pub proposal_types: Vec<u16>,
@@ -156,6 +163,7 @@ impl From<RequiredCapabilitiesExtension> for FrankenRequiredCapabilitiesExtensio
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenExternalPubExtension {
     external_pub: VLBytes,
 }
@@ -163,6 +171,7 @@ pub struct FrankenExternalPubExtension {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenExternalSendersExtension {
     external_senders: Vec<FrankenExternalSender>,
 }
@@ -170,6 +179,7 @@ pub struct FrankenExternalSendersExtension {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenExternalSender {
     pub signature_key: VLBytes,
     pub credential: FrankenCredential,
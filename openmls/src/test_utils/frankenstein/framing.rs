@@ -10,7 +10,7 @@ use crate::{
         MlsMessageIn, MlsMessageOut, PrivateMessage, PrivateMessageIn, PublicMessage,
         PublicMessageIn, Sender, WireFormat,
     },
-    group::GroupContext,
+    group::{GroupContext, MlsGroup},
     messages::{ConfirmationTag, Welcome},
     prelude_test::signable::Signable,
     schedule::{ConfirmationKey, MembershipKey},
@@ -26,6 +26,7 @@ use super::{
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenMlsMessage {
     pub version: u16,
     pub body: FrankenMlsMessageBody,
@@ -36,6 +37,7 @@ pub struct FrankenMlsMessage {
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
 #[repr(u16)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenMlsMessageBody {
     #[tls_codec(discriminant = 1)]
     PublicMessage(FrankenPublicMessage),
@@ -50,6 +52,7 @@ pub enum FrankenMlsMessageBody {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenPublicMessage {
     pub content: FrankenFramedContent,
     pub auth: FrankenFramedContentAuthData,
@@ -190,11 +193,35 @@ impl FrankenPublicMessage {
             membership_tag,
         }
     }
+
+    /// Builds a public message from a member of `group`, like [`Self::auth`]. The group context
+    /// and the membership key are taken from `group`, so that manipulated content can be
+    /// authenticated outside of this crate, e.g., in fuzz targets.
+    pub fn auth_for_group(
+        provider: &impl crate::storage::OpenMlsProvider,
+        group: &MlsGroup,
+        signer: &impl Signer,
+        content: FrankenFramedContent,
+        confirmation_tag: Option<VLBytes>,
+    ) -> Self {
+        let group_context = group.export_group_context().clone().into();
+
+        Self::auth(
+            provider,
+            group.ciphersuite(),
+            signer,
+            content,
+            Some(&group_context),
+            Some(group.message_secrets().membership_key().as_slice()),
+            confirmation_tag,
+        )
+    }
 }
 
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenFramedContent {
     pub group_id: VLBytes,
     pub epoch: u64,
@@ -207,6 +234,7 @@ pub struct FrankenFramedContent {
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenSender {
     #[tls_codec(discriminant = 1)]
     Member(u32),
@@ -219,6 +247,7 @@ pub enum FrankenSender {
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenFramedContentBody {
     #[tls_codec(discriminant = 1)]
     Application(VLBytes),
@@ -231,6 +260,7 @@ pub enum FrankenFramedContentBody {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenPrivateMessage {
     pub group_id: VLBytes,
     pub epoch: VLBytes,
@@ -243,6 +273,7 @@ pub struct FrankenPrivateMessage {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenWelcome {
     pub cipher_suite: u16,
     pub secrets: Vec<FrankenEncryptedGroupSecrets>,
@@ -250,6 +281,7 @@ pub struct FrankenWelcome {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenFramedContentAuthData {
     pub signature: VLBytes,
     pub confirmation_tag: Option<VLBytes>,
@@ -399,6 +431,7 @@ pub struct FrankenAuthenticatedContentTbm<'a> {
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenContentType {
     Application = 1,
     Proposal = 2,
@@ -408,6 +441,7 @@ pub enum FrankenContentType {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenEncryptedGroupSecrets {
     pub new_member: VLBytes,
     pub encrypted_group_secrets: VLBytes,
@@ -19,6 +19,7 @@ use super::extensions::FrankenExtension;
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenGroupInfo {
     pub payload: FrankenGroupInfoTbs,
     pub signature: VLBytes,
@@ -72,6 +73,7 @@ impl Signable for FrankenGroupInfoTbs {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenGroupInfoTbs {
     pub group_context: FrankenGroupContext,
     pub extensions: Vec<FrankenExtension>,
@@ -82,6 +84,7 @@ pub struct FrankenGroupInfoTbs {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenGroupContext {
     protocol_version: u16,
     ciphersuite: u16,
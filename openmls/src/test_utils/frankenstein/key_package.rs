@@ -21,6 +21,7 @@ use crate::{
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenKeyPackage {
     pub payload: FrankenKeyPackageTbs,
     pub signature: VLBytes,
@@ -116,6 +117,7 @@ impl From<FrankenKeyPackage> for KeyPackageIn {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenKeyPackageTbs {
     pub protocol_version: u16,
     pub ciphersuite: u16,
@@ -127,6 +129,7 @@ pub struct FrankenKeyPackageTbs {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenLifetime {
     pub not_before: u64,
     pub not_after: u64,
@@ -21,6 +21,7 @@ use crate::{
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenLeafNode {
     pub payload: FrankenLeafNodePayload,
     pub signature: VLBytes,
@@ -99,6 +100,7 @@ impl From<FrankenLeafNode> for LeafNodeIn {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenLeafNodePayload {
     pub encryption_key: VLBytes,
     pub signature_key: VLBytes,
@@ -111,6 +113,7 @@ pub struct FrankenLeafNodePayload {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenTreePosition {
     pub group_id: VLBytes,
     pub leaf_index: u32,
@@ -136,6 +139,7 @@ impl From<FrankenTreePosition> for TreePosition {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenLeafNodeTbs {
     pub payload: FrankenLeafNodePayload,
     pub tree_position: Option<FrankenTreePosition>,
@@ -221,6 +225,7 @@ impl Serialize for FrankenLeafNodeTbs {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenCapabilities {
     pub versions: Vec<u16>,
     pub ciphersuites: Vec<u16>,
@@ -233,6 +238,7 @@ pub struct FrankenCapabilities {
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenLeafNodeSource {
     #[tls_codec(discriminant = 1)]
     KeyPackage(FrankenLifetime),
@@ -3,6 +3,7 @@ use tls_codec::*;
 use super::{extensions::FrankenExtension, FrankenKeyPackage, FrankenLeafNode};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenProposalType {
     Add,
     Update,
@@ -70,6 +71,7 @@ impl FrankenProposal {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[repr(u16)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenProposal {
     Add(FrankenAddProposal),
     Update(FrankenUpdateProposal),
@@ -85,6 +87,7 @@ pub enum FrankenProposal {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenAddProposal {
     pub key_package: FrankenKeyPackage,
 }
@@ -92,6 +95,7 @@ pub struct FrankenAddProposal {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenUpdateProposal {
     pub leaf_node: FrankenLeafNode,
 }
@@ -99,6 +103,7 @@ pub struct FrankenUpdateProposal {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenRemoveProposal {
     pub removed: u32,
 }
@@ -106,6 +111,7 @@ pub struct FrankenRemoveProposal {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenPreSharedKeyProposal {
     pub psk: FrankenPreSharedKeyId,
 }
@@ -113,6 +119,7 @@ pub struct FrankenPreSharedKeyProposal {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenPreSharedKeyId {
     pub psk: FrankenPsk,
     pub psk_nonce: VLBytes,
@@ -122,6 +129,7 @@ pub struct FrankenPreSharedKeyId {
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenPsk {
     #[tls_codec(discriminant = 1)]
     External(FrankenExternalPsk),
@@ -132,6 +140,7 @@ pub enum FrankenPsk {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenExternalPsk {
     pub psk_id: VLBytes,
 }
@@ -139,6 +148,7 @@ pub struct FrankenExternalPsk {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenResumptionPsk {
     pub usage: FrankenResumptionPskUsage,
     pub psk_group_id: VLBytes,
@@ -149,6 +159,7 @@ pub struct FrankenResumptionPsk {
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrankenResumptionPskUsage {
     Application = 1,
     Reinit = 2,
@@ -158,6 +169,7 @@ pub enum FrankenResumptionPskUsage {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenReInitProposal {
     pub group_id: VLBytes,
     pub version: u16,
@@ -168,6 +180,7 @@ pub struct FrankenReInitProposal {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenExternalInitProposal {
     pub kem_output: VLBytes,
 }
@@ -175,6 +188,7 @@ pub struct FrankenExternalInitProposal {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenAppAckProposal {
     pub received_ranges: Vec<FrankenMessageRange>,
 }
@@ -182,6 +196,7 @@ pub struct FrankenAppAckProposal {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenMessageRange {
     pub sender: VLBytes,
     pub first_generation: u32,
@@ -191,6 +206,7 @@ pub struct FrankenMessageRange {
 #[derive(
     Debug, Clone, PartialEq, Eq, TlsSerialize, TlsDeserialize, TlsDeserializeBytes, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FrankenCustomProposal {
     pub proposal_type: u16,
     pub payload: VLBytes,
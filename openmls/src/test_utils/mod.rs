@@ -6,9 +6,14 @@ use std::{
     fmt::Write as FmtWrite,
     fs::File,
     io::{BufReader, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 use openmls_basic_credential::SignatureKeyPair;
+use openmls_traits::types::SignatureScheme;
 pub use openmls_traits::{
     storage::StorageProvider as StorageProviderTrait,
     types::{Ciphersuite, HpkeKeyPair},
@@ -16,12 +21,12 @@ pub use openmls_traits::{
 };
 use serde::{self, de::DeserializeOwned, Serialize};
 
-#[cfg(test)]
-use crate::group::tests_and_kats::utils::CredentialWithKeyAndSigner;
 pub use crate::utils::*;
 use crate::{
     ciphersuite::{HpkePrivateKey, OpenMlsSignaturePublicKey},
-    credentials::{Credential, CredentialType, CredentialWithKey},
+    credentials::{BasicCredential, Credential, CredentialType, CredentialWithKey},
+    extensions::Extensions,
+    group::Clock,
     key_packages::{KeyPackage, KeyPackageBuilder},
     prelude::KeyPackageBundle,
     treesync::node::encryption_keys::{EncryptionKeyPair, EncryptionPrivateKey},
@@ -99,6 +104,80 @@ pub fn hex_to_bytes_option(hex: Option<String>) -> Vec<u8> {
 
 // === Convenience functions ===
 
+/// A credential with its signature key and the signer for it.
+#[derive(Clone)]
+pub struct CredentialWithKeyAndSigner {
+    pub credential_with_key: CredentialWithKey,
+    pub signer: SignatureKeyPair,
+}
+
+/// Generate a basic credential for `identity` and a new signature key pair.
+/// The signature keys are stored in the storage of the `provider`.
+pub fn generate_credential_with_key<Provider: OpenMlsProvider>(
+    identity: Vec<u8>,
+    signature_scheme: SignatureScheme,
+    provider: &Provider,
+) -> CredentialWithKeyAndSigner {
+    let (credential, signer) = {
+        let credential = BasicCredential::new(identity);
+        let signature_keys = SignatureKeyPair::new(signature_scheme).unwrap();
+        signature_keys.store(provider.storage()).unwrap();
+
+        (credential, signature_keys)
+    };
+    let signature_key =
+        OpenMlsSignaturePublicKey::new(signer.to_public_vec().into(), signature_scheme).unwrap();
+
+    CredentialWithKeyAndSigner {
+        credential_with_key: CredentialWithKey {
+            credential: credential.into(),
+            signature_key: signature_key.into(),
+        },
+        signer,
+    }
+}
+
+/// Generate a [`KeyPackageBundle`] with the given key package extensions.
+pub fn generate_key_package<Provider: OpenMlsProvider>(
+    ciphersuite: Ciphersuite,
+    extensions: Extensions,
+    provider: &Provider,
+    credential_with_keys: CredentialWithKeyAndSigner,
+) -> KeyPackageBundle {
+    KeyPackage::builder()
+        .key_package_extensions(extensions)
+        .build(
+            ciphersuite,
+            provider,
+            &credential_with_keys.signer,
+            credential_with_keys.credential_with_key,
+        )
+        .unwrap()
+}
+
+/// A [`Clock`] that only moves when it is told to. Clones share the same
+/// time.
+#[derive(Clone, Debug, Default)]
+pub struct TestClock(Arc<AtomicU64>);
+
+impl TestClock {
+    /// Create a clock that is set to `now`.
+    pub fn new(now: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(now)))
+    }
+
+    /// Set the clock to `now`.
+    pub fn set(&self, now: u64) {
+        self.0.store(now, Ordering::Relaxed)
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 #[cfg(test)]
 pub(crate) struct GroupCandidate {
     pub identity: Vec<u8>,
@@ -114,8 +193,6 @@ pub(crate) fn generate_group_candidate(
     provider: &impl OpenMlsProvider,
     use_store: bool,
 ) -> GroupCandidate {
-    use crate::{credentials::BasicCredential, prelude::KeyPackageBundle};
-
     let credential_with_key_and_signer = {
         let credential = BasicCredential::new(identity.to_vec());
 
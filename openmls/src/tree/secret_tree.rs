@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, BinaryHeap};
+
 use openmls_traits::crypto::OpenMlsCrypto;
 use openmls_traits::types::{Ciphersuite, CryptoError};
 use thiserror::Error;
@@ -19,18 +21,25 @@ use crate::{
 /// Secret tree error
 #[derive(Error, Debug, Eq, PartialEq, Clone)]
 pub enum SecretTreeError {
-    /// Generation is too old to be processed.
-    #[error("Generation is too old to be processed.")]
+    /// The secrets of the epoch were deleted, because it is too old.
+    #[error("The epoch is too old to be processed.")]
     TooDistantInThePast,
-    /// Generation is too far in the future to be processed.
-    #[error("Generation is too far in the future to be processed.")]
-    TooDistantInTheFuture,
+    /// The generation of the sender is older than the window of its ratchet.
+    #[error("The generation is too old to be processed: {0}.")]
+    GenerationTooOld(RejectedGeneration),
+    /// The generation of the sender is beyond the window of its ratchet.
+    #[error("The generation is too far in the future to be processed: {0}.")]
+    GenerationTooNew(RejectedGeneration),
+    /// The key of the generation of the sender was already used.
+    #[error("The generation was already used to decrypt a message: {0}.")]
+    GenerationReused(RejectedGeneration),
+    /// The key of the generation of the sender was evicted from the cache of
+    /// skipped keys, because the [`MessageKeyCacheConfig`] was exceeded.
+    #[error("The key of the generation was evicted from the cache of skipped keys: {0}.")]
+    GenerationEvicted(RejectedGeneration),
     /// Index out of bounds
     #[error("Index out of bounds")]
     IndexOutOfBounds,
-    /// The requested secret was deleted to preserve forward secrecy.
-    #[error("The requested secret was deleted to preserve forward secrecy.")]
-    SecretReuseError,
     /// Cannot create decryption secrets from own sender ratchet or encryption secrets from the sender ratchets of other members.
     #[error("Cannot create decryption secrets from own sender ratchet or encryption secrets from the sender ratchets of other members.")]
     RatchetTypeError,
@@ -70,6 +79,53 @@ impl From<&PublicMessage> for SecretType {
     }
 }
 
+/// Evicts cached keys of skipped generations from the [`DecryptionRatchet`]s of
+/// the `secret_trees` until at most [`MessageKeyCacheConfig::max_cached_keys()`]
+/// keys remain. Returns the number of evicted keys.
+pub(crate) fn evict_cached_keys<'a>(
+    secret_trees: impl IntoIterator<Item = &'a mut SecretTree>,
+    config: &MessageKeyCacheConfig,
+) -> usize {
+    let mut ratchets: Vec<&mut DecryptionRatchet> = secret_trees
+        .into_iter()
+        .flat_map(|secret_tree| secret_tree.decryption_ratchets_mut())
+        .collect();
+    let cached_keys: usize = ratchets.iter().map(|ratchet| ratchet.cached_keys()).sum();
+    let excess = cached_keys.saturating_sub(config.max_cached_keys());
+    if excess == 0 {
+        return 0;
+    }
+
+    // The ratchet to evict from next is the one with the highest priority.
+    let priority = |ratchet: &DecryptionRatchet| match config.eviction() {
+        MessageKeyEviction::LargestSender => ratchet.cached_keys(),
+        MessageKeyEviction::FarthestBehind => ratchet
+            .oldest_cached_key_distance()
+            .map_or(0, |distance| distance as usize),
+    };
+    let mut candidates: BinaryHeap<(usize, usize)> = ratchets
+        .iter()
+        .enumerate()
+        .filter(|(_, ratchet)| ratchet.cached_keys() > 0)
+        .map(|(index, ratchet)| (priority(ratchet), index))
+        .collect();
+
+    let mut evicted = 0;
+    while evicted < excess {
+        let Some((_, index)) = candidates.pop() else {
+            break;
+        };
+        let ratchet = &mut ratchets[index];
+        if ratchet.evict_oldest_cached_key() {
+            evicted += 1;
+        }
+        if ratchet.cached_keys() > 0 {
+            candidates.push((priority(ratchet), index));
+        }
+    }
+    evicted
+}
+
 /// Derives secrets for inner nodes of a SecretTree. This function corresponds
 /// to the `DeriveTreeSecret` defined in Section 10.1 of the MLS specification.
 #[inline]
@@ -112,18 +168,94 @@ pub(crate) struct SecretTreeNode {
     pub(crate) secret: Secret,
 }
 
-#[derive(Serialize, Deserialize)]
+/// The sender ratchets of a leaf are only materialized once a message of that
+/// leaf is encrypted or decrypted. They are kept in a map, so that large
+/// groups don't pay for the ratchets of silent members. The map is
+/// (de)serialized as a list with an optional ratchet for every leaf of the
+/// tree, which is the encoding of trees stored by earlier versions.
+type SenderRatchets = BTreeMap<LeafNodeIndex, SenderRatchet>;
+
+mod sender_ratchets_serde {
+    use serde::{ser::SerializeSeq, Deserializer, Serializer};
+
+    use super::*;
+
+    /// The [`SenderRatchets`] of a tree with `leaf_count` leaves.
+    pub(super) struct Dense<'a> {
+        pub(super) sender_ratchets: &'a SenderRatchets,
+        pub(super) leaf_count: u32,
+    }
+
+    impl Serialize for Dense<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.leaf_count as usize))?;
+            for index in 0..self.leaf_count {
+                seq.serialize_element(&self.sender_ratchets.get(&LeafNodeIndex::new(index)))?;
+            }
+            seq.end()
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<SenderRatchets, D::Error> {
+        let sender_ratchets = Vec::<Option<SenderRatchet>>::deserialize(deserializer)?;
+        Ok(sender_ratchets
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, sender_ratchet)| {
+                sender_ratchet
+                    .map(|sender_ratchet| (LeafNodeIndex::new(index as u32), sender_ratchet))
+            })
+            .collect())
+    }
+}
+
+#[derive(Deserialize)]
 #[cfg_attr(any(feature = "test-utils", test), derive(PartialEq, Clone))]
 #[cfg_attr(any(feature = "crypto-debug", test), derive(Debug))]
 pub(crate) struct SecretTree {
     own_index: LeafNodeIndex,
     leaf_nodes: Vec<Option<SecretTreeNode>>,
     parent_nodes: Vec<Option<SecretTreeNode>>,
-    handshake_sender_ratchets: Vec<Option<SenderRatchet>>,
-    application_sender_ratchets: Vec<Option<SenderRatchet>>,
+    #[serde(deserialize_with = "sender_ratchets_serde::deserialize")]
+    handshake_sender_ratchets: SenderRatchets,
+    #[serde(deserialize_with = "sender_ratchets_serde::deserialize")]
+    application_sender_ratchets: SenderRatchets,
     size: TreeSize,
 }
 
+// The sender ratchets are serialized with an entry for every leaf, so that
+// readers that index them by leaf don't run out of bounds. That needs the size
+// of the tree, which a field serializer doesn't have.
+impl Serialize for SecretTree {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let leaf_count = self.size.leaf_count();
+        let mut state = serializer.serialize_struct("SecretTree", 6)?;
+        state.serialize_field("own_index", &self.own_index)?;
+        state.serialize_field("leaf_nodes", &self.leaf_nodes)?;
+        state.serialize_field("parent_nodes", &self.parent_nodes)?;
+        state.serialize_field(
+            "handshake_sender_ratchets",
+            &sender_ratchets_serde::Dense {
+                sender_ratchets: &self.handshake_sender_ratchets,
+                leaf_count,
+            },
+        )?;
+        state.serialize_field(
+            "application_sender_ratchets",
+            &sender_ratchets_serde::Dense {
+                sender_ratchets: &self.application_sender_ratchets,
+                leaf_count,
+            },
+        )?;
+        state.serialize_field("size", &self.size)?;
+        state.end()
+    }
+}
+
 impl SecretTree {
     /// Creates a new SecretTree based on an `encryption_secret` and group size
     /// `size`. The inner nodes of the tree and the SenderRatchets only get
@@ -137,16 +269,13 @@ impl SecretTree {
         let leaf_count = size.leaf_count() as usize;
         let leaf_nodes = std::iter::repeat_with(|| None).take(leaf_count).collect();
         let parent_nodes = std::iter::repeat_with(|| None).take(leaf_count).collect();
-        let handshake_sender_ratchets = std::iter::repeat_with(|| None).take(leaf_count).collect();
-        let application_sender_ratchets =
-            std::iter::repeat_with(|| None).take(leaf_count).collect();
 
         let mut secret_tree = SecretTree {
             own_index,
             leaf_nodes,
             parent_nodes,
-            handshake_sender_ratchets,
-            application_sender_ratchets,
+            handshake_sender_ratchets: SenderRatchets::new(),
+            application_sender_ratchets: SenderRatchets::new(),
             size,
         };
 
@@ -281,19 +410,57 @@ impl SecretTree {
             (handshake_sender_ratchet, application_sender_ratchet)
         };
 
-        *self
-            .handshake_sender_ratchets
-            .get_mut(index.usize())
-            .ok_or(SecretTreeError::IndexOutOfBounds)? = Some(handshake_sender_ratchet);
-        *self
-            .application_sender_ratchets
-            .get_mut(index.usize())
-            .ok_or(SecretTreeError::IndexOutOfBounds)? = Some(application_sender_ratchet);
+        self.handshake_sender_ratchets
+            .insert(index, handshake_sender_ratchet);
+        self.application_sender_ratchets
+            .insert(index, application_sender_ratchet);
 
         // Delete leaf node
         self.set_node(index.into(), None)
     }
 
+    /// Returns the state of the [`DecryptionRatchet`]s of the other members
+    /// that were used to decrypt a message. The ratchets of a sender are
+    /// initialized in pairs, so the unused ratchet of the pair is skipped.
+    pub(crate) fn sender_ratchet_states(&self) -> Vec<SenderRatchetState> {
+        let ratchets = [
+            (SecretType::HandshakeSecret, &self.handshake_sender_ratchets),
+            (
+                SecretType::ApplicationSecret,
+                &self.application_sender_ratchets,
+            ),
+        ];
+        let mut states = Vec::new();
+        for (secret_type, sender_ratchets) in ratchets {
+            for (index, sender_ratchet) in sender_ratchets {
+                if let SenderRatchet::DecryptionRatchet(dec_ratchet) = sender_ratchet {
+                    if dec_ratchet.generation() == 0 {
+                        continue;
+                    }
+                    states.push(SenderRatchetState {
+                        sender: *index,
+                        ratchet_type: SenderRatchetType::from_secret_type(secret_type),
+                        generation: dec_ratchet.generation(),
+                        cached_keys: dec_ratchet.cached_keys(),
+                    });
+                }
+            }
+        }
+        states.sort_by_key(|state| state.sender);
+        states
+    }
+
+    /// Returns the [`DecryptionRatchet`]s of the other members.
+    fn decryption_ratchets_mut(&mut self) -> impl Iterator<Item = &mut DecryptionRatchet> {
+        self.handshake_sender_ratchets
+            .values_mut()
+            .chain(self.application_sender_ratchets.values_mut())
+            .filter_map(|sender_ratchet| match sender_ratchet {
+                SenderRatchet::DecryptionRatchet(dec_ratchet) => Some(dec_ratchet),
+                SenderRatchet::EncryptionRatchet(_) => None,
+            })
+    }
+
     /// Return RatchetSecrets for a given index and generation. This should be
     /// called when decrypting an PrivateMessage received from another member.
     /// Returns an error if index or generation are out of bound.
@@ -329,7 +496,14 @@ impl SecretTree {
             }
             SenderRatchet::DecryptionRatchet(dec_ratchet) => {
                 log::trace!("   getting secret for decryption");
-                dec_ratchet.secret_for_decryption(ciphersuite, crypto, generation, configuration)
+                dec_ratchet.secret_for_decryption(
+                    ciphersuite,
+                    crypto,
+                    index,
+                    SenderRatchetType::from_secret_type(secret_type),
+                    generation,
+                    configuration,
+                )
             }
         }
     }
@@ -369,8 +543,7 @@ impl SecretTree {
             SecretType::ApplicationSecret => &mut self.application_sender_ratchets,
         };
         sender_ratchets
-            .get_mut(index.usize())
-            .and_then(|r| r.as_mut())
+            .get_mut(&index)
             .ok_or(SecretTreeError::IndexOutOfBounds)
     }
 
@@ -384,10 +557,10 @@ impl SecretTree {
             SecretType::HandshakeSecret => &self.handshake_sender_ratchets,
             SecretType::ApplicationSecret => &self.application_sender_ratchets,
         };
-        match sender_ratchets.get(index.usize()) {
-            Some(sender_ratchet_option) => Ok(sender_ratchet_option.as_ref()),
-            None => Err(SecretTreeError::IndexOutOfBounds),
+        if index.u32() >= self.size.leaf_count() {
+            return Err(SecretTreeError::IndexOutOfBounds);
         }
+        Ok(sender_ratchets.get(&index))
     }
 
     /// Derives the secrets for the child nodes in a SecretTree and blanks the
@@ -6,13 +6,17 @@
 //! error, will still return a `Result` since they may throw a `LibraryError`.
 
 use openmls_traits::crypto::OpenMlsCrypto;
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, VecDeque};
 
 use openmls_traits::types::Ciphersuite;
 
+use crate::binary_tree::LeafNodeIndex;
 use crate::ciphersuite::{AeadNonce, *};
 use crate::tree::secret_tree::*;
 
+#[cfg(doc)]
+use crate::group::MlsGroup;
+
 use super::*;
 
 /// The generation of a given [`SenderRatchet`].
@@ -60,6 +64,174 @@ impl Default for SenderRatchetConfiguration {
     }
 }
 
+/// Decides which cached key is evicted when a group holds more cached keys
+/// than allowed by its [`MessageKeyCacheConfig`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageKeyEviction {
+    /// Evicts the oldest key of the sender ratchet that caches the most keys,
+    /// so that a single sender can't push out the keys of all other senders.
+    #[default]
+    LargestSender,
+    /// Evicts the key that is the farthest behind the current generation of
+    /// its sender ratchet, across all senders.
+    FarthestBehind,
+}
+
+/// Limits the number of keys of skipped generations a group caches across
+/// all senders and all epochs it keeps message secrets for.
+///
+/// Without a limit, each decryption ratchet caches up to
+/// `out_of_order_tolerance` keys, which adds up in groups with many members.
+/// Once the limit is exceeded, cached keys are evicted according to the
+/// [`MessageKeyEviction`] strategy. Messages that need an evicted key can't be
+/// decrypted anymore.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageKeyCacheConfig {
+    max_cached_keys: usize,
+    eviction: MessageKeyEviction,
+}
+
+impl MessageKeyCacheConfig {
+    /// Create a new configuration that caches at most `max_cached_keys` keys
+    /// and evicts keys with [`MessageKeyEviction::LargestSender`].
+    pub fn new(max_cached_keys: usize) -> Self {
+        Self {
+            max_cached_keys,
+            eviction: MessageKeyEviction::default(),
+        }
+    }
+
+    /// Sets the [`MessageKeyEviction`] strategy.
+    pub fn with_eviction(mut self, eviction: MessageKeyEviction) -> Self {
+        self.eviction = eviction;
+        self
+    }
+
+    /// Returns the maximum number of cached keys.
+    pub fn max_cached_keys(&self) -> usize {
+        self.max_cached_keys
+    }
+
+    /// Returns the [`MessageKeyEviction`] strategy.
+    pub fn eviction(&self) -> MessageKeyEviction {
+        self.eviction
+    }
+}
+
+/// The type of messages a sender ratchet derives key material for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SenderRatchetType {
+    /// The ratchet for handshake messages.
+    Handshake,
+    /// The ratchet for application messages.
+    Application,
+}
+
+impl SenderRatchetType {
+    pub(crate) fn from_secret_type(secret_type: SecretType) -> Self {
+        match secret_type {
+            SecretType::HandshakeSecret => SenderRatchetType::Handshake,
+            SecretType::ApplicationSecret => SenderRatchetType::Application,
+        }
+    }
+}
+
+/// The state of the decryption ratchet of another member, as returned by
+/// [`MlsGroup::sender_ratchet_states()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SenderRatchetState {
+    pub(crate) sender: LeafNodeIndex,
+    pub(crate) ratchet_type: SenderRatchetType,
+    pub(crate) generation: Generation,
+    pub(crate) cached_keys: usize,
+}
+
+impl SenderRatchetState {
+    /// Returns the leaf index of the sender.
+    pub fn sender(&self) -> LeafNodeIndex {
+        self.sender
+    }
+
+    /// Returns the type of the ratchet.
+    pub fn ratchet_type(&self) -> SenderRatchetType {
+        self.ratchet_type
+    }
+
+    /// Returns the next generation the ratchet derives key material for.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the number of keys of skipped generations that are kept for
+    /// messages that arrive out of order.
+    pub fn cached_keys(&self) -> usize {
+        self.cached_keys
+    }
+}
+
+/// Describes a generation that was rejected by the decryption ratchet of a
+/// sender, together with the window of generations the ratchet accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RejectedGeneration {
+    pub(crate) sender: LeafNodeIndex,
+    pub(crate) ratchet_type: SenderRatchetType,
+    pub(crate) generation: Generation,
+    pub(crate) current_generation: Generation,
+    pub(crate) oldest_generation: Generation,
+    pub(crate) newest_generation: Generation,
+}
+
+impl RejectedGeneration {
+    /// Returns the leaf index of the sender.
+    pub fn sender(&self) -> LeafNodeIndex {
+        self.sender
+    }
+
+    /// Returns the type of the ratchet.
+    pub fn ratchet_type(&self) -> SenderRatchetType {
+        self.ratchet_type
+    }
+
+    /// Returns the rejected generation.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the next generation of the ratchet when the generation was
+    /// rejected.
+    pub fn current_generation(&self) -> u32 {
+        self.current_generation
+    }
+
+    /// Returns the oldest generation the ratchet accepted. Keys of older
+    /// generations were deleted according to the
+    /// [`SenderRatchetConfiguration::out_of_order_tolerance()`].
+    pub fn oldest_generation(&self) -> u32 {
+        self.oldest_generation
+    }
+
+    /// Returns the newest generation the ratchet accepted according to the
+    /// [`SenderRatchetConfiguration::maximum_forward_distance()`].
+    pub fn newest_generation(&self) -> u32 {
+        self.newest_generation
+    }
+}
+
+impl std::fmt::Display for RejectedGeneration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "generation {} of the {:?} ratchet of leaf {} (current generation {}, accepted {}..={})",
+            self.generation,
+            self.ratchet_type,
+            self.sender,
+            self.current_generation,
+            self.oldest_generation,
+            self.newest_generation
+        )
+    }
+}
+
 /// The key material derived from a [`RatchetSecret`] meant for use with a
 /// nonce-based symmetric encryption scheme.
 pub(crate) type RatchetKeyMaterial = (AeadKey, AeadNonce);
@@ -179,6 +351,10 @@ impl RatchetSecret {
 pub struct DecryptionRatchet {
     past_secrets: VecDeque<Option<RatchetKeyMaterial>>,
     ratchet_head: RatchetSecret,
+    /// The generations within the window whose keys were evicted from
+    /// `past_secrets` before they were used.
+    #[serde(default)]
+    evicted_generations: BTreeSet<Generation>,
 }
 
 impl DecryptionRatchet {
@@ -187,6 +363,7 @@ impl DecryptionRatchet {
         Self {
             past_secrets: VecDeque::new(),
             ratchet_head: RatchetSecret::initial_ratchet_secret(secret),
+            evicted_generations: BTreeSet::new(),
         }
     }
 
@@ -194,7 +371,9 @@ impl DecryptionRatchet {
     /// bounds determined by the [`SenderRatchetConfiguration`].
     fn prune_past_secrets(&mut self, configuration: &SenderRatchetConfiguration) {
         self.past_secrets
-            .truncate(configuration.out_of_order_tolerance() as usize)
+            .truncate(configuration.out_of_order_tolerance() as usize);
+        let oldest_generation = self.generation() - self.past_secrets.len() as Generation;
+        self.evicted_generations = self.evicted_generations.split_off(&oldest_generation);
     }
 
     /// Get the generation of the ratchet head.
@@ -202,33 +381,92 @@ impl DecryptionRatchet {
         self.ratchet_head.generation()
     }
 
+    /// Returns the number of keys of past generations that weren't used yet.
+    pub(crate) fn cached_keys(&self) -> usize {
+        self.past_secrets
+            .iter()
+            .filter(|secret| secret.is_some())
+            .count()
+    }
+
+    /// Returns how many generations the oldest cached key is behind the
+    /// ratchet head, or `None` if no keys are cached.
+    pub(crate) fn oldest_cached_key_distance(&self) -> Option<Generation> {
+        self.past_secrets
+            .iter()
+            .rposition(|secret| secret.is_some())
+            .map(|index| index as Generation + 1)
+    }
+
+    /// Deletes the oldest cached key. Returns `false` if no keys are cached.
+    pub(crate) fn evict_oldest_cached_key(&mut self) -> bool {
+        match self
+            .past_secrets
+            .iter()
+            .rposition(|secret| secret.is_some())
+        {
+            Some(index) => {
+                self.past_secrets[index] = None;
+                self.evicted_generations
+                    .insert(self.generation() - index as Generation - 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the oldest and the newest generation the ratchet accepts with
+    /// the given [`SenderRatchetConfiguration`].
+    pub(crate) fn window(
+        &self,
+        configuration: &SenderRatchetConfiguration,
+    ) -> (Generation, Generation) {
+        let generation = self.generation();
+        (
+            generation.saturating_sub(configuration.out_of_order_tolerance()),
+            generation.saturating_add(configuration.maximum_forward_distance()),
+        )
+    }
+
     #[cfg(test)]
     pub(crate) fn ratchet_secret_mut(&mut self) -> &mut RatchetSecret {
         &mut self.ratchet_head
     }
 
     /// Gets a secret from the SenderRatchet. Returns an error if the generation
-    /// is out of bound.
+    /// is out of bound. The `sender` and the `ratchet_type` are only used to
+    /// describe a rejected generation.
     pub(crate) fn secret_for_decryption(
         &mut self,
         ciphersuite: Ciphersuite,
         crypto: &impl OpenMlsCrypto,
+        sender: LeafNodeIndex,
+        ratchet_type: SenderRatchetType,
         generation: Generation,
         configuration: &SenderRatchetConfiguration,
     ) -> Result<RatchetKeyMaterial, SecretTreeError> {
         log::debug!("secret_for_decryption");
+        let (oldest_generation, newest_generation) = self.window(configuration);
+        let rejected = RejectedGeneration {
+            sender,
+            ratchet_type,
+            generation,
+            current_generation: self.generation(),
+            oldest_generation,
+            newest_generation,
+        };
         // If generation is too distant in the future
         if self.generation() < u32::MAX - configuration.maximum_forward_distance()
             && generation > self.generation() + configuration.maximum_forward_distance()
         {
-            return Err(SecretTreeError::TooDistantInTheFuture);
+            return Err(SecretTreeError::GenerationTooNew(rejected));
         }
         // If generation id too distant in the past
         if generation < self.generation()
             && (self.generation() - generation) > configuration.out_of_order_tolerance()
         {
             log::error!("  Generation is too far in the past (broke out of order tolerance ({}) {generation} < {}).", configuration.out_of_order_tolerance(), self.generation());
-            return Err(SecretTreeError::TooDistantInThePast);
+            return Err(SecretTreeError::GenerationTooOld(rejected));
         }
         // If generation is the one the ratchet is currently at or in the future
         if generation >= self.generation() {
@@ -262,7 +500,7 @@ impl DecryptionRatchet {
                 window_index as usize
             } else {
                 log::error!("  Generation is too far in the past (not in the window).");
-                return Err(SecretTreeError::TooDistantInThePast);
+                return Err(SecretTreeError::GenerationTooOld(rejected));
             };
             // Get the relevant secrets from the past secrets queue.
             self.past_secrets
@@ -273,8 +511,14 @@ impl DecryptionRatchet {
                 // caller of this function drops it.
                 .take()
                 // If the requested generation was used to decrypt a message
-                // earlier, throw an error.
-                .ok_or(SecretTreeError::SecretReuseError)
+                // earlier or its key was evicted, throw an error.
+                .ok_or_else(|| {
+                    if self.evicted_generations.contains(&generation) {
+                        SecretTreeError::GenerationEvicted(rejected)
+                    } else {
+                        SecretTreeError::GenerationReused(rejected)
+                    }
+                })
         }
     }
 }
@@ -3,7 +3,13 @@ use openmls_traits::random::OpenMlsRand;
 use crate::{
     binary_tree::{array_representation::TreeSize, LeafNodeIndex},
     schedule::EncryptionSecret,
-    tree::{secret_tree::*, sender_ratchet::SenderRatchetConfiguration},
+    tree::{
+        secret_tree::*,
+        sender_ratchet::{
+            MessageKeyCacheConfig, MessageKeyEviction, RejectedGeneration,
+            SenderRatchetConfiguration, SenderRatchetState, SenderRatchetType,
+        },
+    },
 };
 use std::collections::HashMap;
 
@@ -68,7 +74,14 @@ fn test_boundaries() {
             1002,
             configuration,
         ),
-        Err(SecretTreeError::TooDistantInTheFuture)
+        Err(SecretTreeError::GenerationTooNew(RejectedGeneration {
+            sender: LeafNodeIndex::new(1),
+            ratchet_type: SenderRatchetType::Application,
+            generation: 1002,
+            current_generation: 1,
+            oldest_generation: 0,
+            newest_generation: 1001,
+        }))
     );
     assert!(secret_tree
         .secret_for_decryption(
@@ -89,7 +102,49 @@ fn test_boundaries() {
             995,
             configuration,
         ),
-        Err(SecretTreeError::TooDistantInThePast)
+        Err(SecretTreeError::GenerationTooOld(RejectedGeneration {
+            sender: LeafNodeIndex::new(0),
+            ratchet_type: SenderRatchetType::Application,
+            generation: 995,
+            current_generation: 1001,
+            oldest_generation: 996,
+            newest_generation: 2001,
+        }))
+    );
+    assert_eq!(
+        secret_tree.secret_for_decryption(
+            ciphersuite,
+            provider.crypto(),
+            LeafNodeIndex::new(0u32),
+            secret_type,
+            996,
+            configuration,
+        ),
+        Err(SecretTreeError::GenerationReused(RejectedGeneration {
+            sender: LeafNodeIndex::new(0),
+            ratchet_type: SenderRatchetType::Application,
+            generation: 996,
+            current_generation: 1001,
+            oldest_generation: 996,
+            newest_generation: 2001,
+        }))
+    );
+    assert_eq!(
+        secret_tree.sender_ratchet_states(),
+        vec![
+            SenderRatchetState {
+                sender: LeafNodeIndex::new(0),
+                ratchet_type: SenderRatchetType::Application,
+                generation: 1001,
+                cached_keys: 3,
+            },
+            SenderRatchetState {
+                sender: LeafNodeIndex::new(1),
+                ratchet_type: SenderRatchetType::Application,
+                generation: 1,
+                cached_keys: 0,
+            },
+        ]
     );
     assert_eq!(
         secret_tree.secret_for_decryption(
@@ -255,3 +310,125 @@ fn secret_tree() {
         application_secret_nonce.as_slice()
     );
 }
+
+// This tests the eviction of cached keys across the sender ratchets
+#[openmls_test::openmls_test]
+fn test_evict_cached_keys() {
+    let configuration = &SenderRatchetConfiguration::new(10, 100);
+    let new_secret_tree = || {
+        SecretTree::new(
+            EncryptionSecret::random(ciphersuite, provider.rand()),
+            TreeSize::from_leaf_count(4u32),
+            LeafNodeIndex::new(3u32),
+        )
+    };
+    let decrypt =
+        |secret_tree: &mut SecretTree, leaf: u32, secret_type: SecretType, generation: u32| {
+            secret_tree.secret_for_decryption(
+                ciphersuite,
+                provider.crypto(),
+                LeafNodeIndex::new(leaf),
+                secret_type,
+                generation,
+                configuration,
+            )
+        };
+    let cached_keys = |secret_tree: &SecretTree| {
+        secret_tree
+            .sender_ratchet_states()
+            .iter()
+            .map(|state| {
+                (
+                    state.sender().u32(),
+                    state.ratchet_type(),
+                    state.cached_keys(),
+                )
+            })
+            .filter(|(_, _, cached_keys)| *cached_keys > 0)
+            .collect::<Vec<_>>()
+    };
+
+    // The largest sender gives up its oldest keys first.
+    let mut secret_tree = new_secret_tree();
+    decrypt(&mut secret_tree, 0, SecretType::ApplicationSecret, 5).unwrap();
+    decrypt(&mut secret_tree, 1, SecretType::ApplicationSecret, 2).unwrap();
+    decrypt(&mut secret_tree, 2, SecretType::HandshakeSecret, 3).unwrap();
+    assert_eq!(
+        evict_cached_keys([&mut secret_tree], &MessageKeyCacheConfig::new(10)),
+        0
+    );
+    assert_eq!(
+        evict_cached_keys([&mut secret_tree], &MessageKeyCacheConfig::new(6)),
+        4
+    );
+    assert_eq!(
+        cached_keys(&secret_tree),
+        vec![
+            (0, SenderRatchetType::Application, 2),
+            (1, SenderRatchetType::Application, 2),
+            (2, SenderRatchetType::Handshake, 2)
+        ]
+    );
+    assert!(matches!(
+        decrypt(&mut secret_tree, 0, SecretType::ApplicationSecret, 2),
+        Err(SecretTreeError::GenerationEvicted(_))
+    ));
+    assert!(decrypt(&mut secret_tree, 0, SecretType::ApplicationSecret, 3).is_ok());
+    assert!(matches!(
+        decrypt(&mut secret_tree, 0, SecretType::ApplicationSecret, 3),
+        Err(SecretTreeError::GenerationReused(_))
+    ));
+
+    // The keys that are the farthest behind are evicted first.
+    let mut secret_tree = new_secret_tree();
+    decrypt(&mut secret_tree, 0, SecretType::ApplicationSecret, 5).unwrap();
+    decrypt(&mut secret_tree, 1, SecretType::ApplicationSecret, 2).unwrap();
+    let config = MessageKeyCacheConfig::new(4).with_eviction(MessageKeyEviction::FarthestBehind);
+    assert_eq!(evict_cached_keys([&mut secret_tree], &config), 3);
+    assert_eq!(
+        cached_keys(&secret_tree),
+        vec![
+            (0, SenderRatchetType::Application, 2),
+            (1, SenderRatchetType::Application, 2)
+        ]
+    );
+    assert!(decrypt(&mut secret_tree, 1, SecretType::ApplicationSecret, 0).is_ok());
+    assert!(matches!(
+        decrypt(&mut secret_tree, 0, SecretType::ApplicationSecret, 2),
+        Err(SecretTreeError::GenerationEvicted(_))
+    ));
+}
+
+// The sender ratchets are serialized with one entry per leaf of the tree, even
+// though only the ratchets in use are kept.
+#[openmls_test::openmls_test]
+fn test_sender_ratchets_serialization() {
+    let mut secret_tree = SecretTree::new(
+        EncryptionSecret::random(ciphersuite, provider.rand()),
+        TreeSize::from_leaf_count(4u32),
+        LeafNodeIndex::new(0u32),
+    );
+    secret_tree
+        .secret_for_decryption(
+            ciphersuite,
+            provider.crypto(),
+            LeafNodeIndex::new(2u32),
+            SecretType::ApplicationSecret,
+            0,
+            &SenderRatchetConfiguration::default(),
+        )
+        .unwrap();
+
+    let serialized = serde_json::to_value(&secret_tree).unwrap();
+    let application_sender_ratchets = serialized["application_sender_ratchets"]
+        .as_array()
+        .unwrap();
+    let leaf_count = serialized["leaf_nodes"].as_array().unwrap().len();
+    assert_eq!(application_sender_ratchets.len(), leaf_count);
+    for (index, sender_ratchet) in application_sender_ratchets.iter().enumerate() {
+        assert_eq!(sender_ratchet.is_null(), index != 2);
+    }
+
+    let deserialized: SecretTree = serde_json::from_value(serialized).unwrap();
+    assert_eq!(deserialized, secret_tree);
+}
@@ -1,5 +1,6 @@
 use crate::{
-    ciphersuite::Secret, test_utils::*, tree::secret_tree::SecretTreeError, tree::sender_ratchet::*,
+    binary_tree::LeafNodeIndex, ciphersuite::Secret, test_utils::*,
+    tree::secret_tree::SecretTreeError, tree::sender_ratchet::*,
 };
 
 // Test the maximum forward ratcheting
@@ -15,6 +16,8 @@ fn test_max_forward_distance() {
         .secret_for_decryption(
             ciphersuite,
             provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
             configuration.maximum_forward_distance(),
             configuration,
         )
@@ -25,17 +28,26 @@ fn test_max_forward_distance() {
         .secret_for_decryption(
             ciphersuite,
             provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
             configuration.maximum_forward_distance() + 1,
             configuration,
         )
         .expect_err("Expected error.");
 
-    assert_eq!(err, SecretTreeError::TooDistantInTheFuture);
+    assert!(matches!(err, SecretTreeError::GenerationTooNew(_)));
 
     // Test if there's an overflow in the maximum forward distance check.
     ratchet1.ratchet_secret_mut().set_generation(u32::MAX - 5);
     ratchet1
-        .secret_for_decryption(ciphersuite, provider.crypto(), u32::MAX - 1, configuration)
+        .secret_for_decryption(
+            ciphersuite,
+            provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
+            u32::MAX - 1,
+            configuration,
+        )
         .expect("Error ratcheting to very high generation");
 }
 
@@ -49,7 +61,14 @@ fn test_out_of_order_generations() {
     // Ratchet forward twice the size of the window
     for i in 0..configuration.out_of_order_tolerance() * 2 {
         let _secret = ratchet1
-            .secret_for_decryption(ciphersuite, provider.crypto(), i, configuration)
+            .secret_for_decryption(
+                ciphersuite,
+                provider.crypto(),
+                LeafNodeIndex::new(0),
+                SenderRatchetType::Application,
+                i,
+                configuration,
+            )
             .expect("Expected decryption secret.");
     }
 
@@ -58,21 +77,30 @@ fn test_out_of_order_generations() {
         .secret_for_decryption(
             ciphersuite,
             provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
             configuration.out_of_order_tolerance() - 1,
             configuration,
         )
         .expect_err("Expected error.");
 
-    assert_eq!(err, SecretTreeError::TooDistantInThePast);
+    assert!(matches!(err, SecretTreeError::GenerationTooOld(_)));
 
     // All secrets within the window should have been deleted because of FS.
     for i in configuration.out_of_order_tolerance()..configuration.out_of_order_tolerance() * 2 {
-        assert_eq!(
+        assert!(matches!(
             ratchet1
-                .secret_for_decryption(ciphersuite, provider.crypto(), i, configuration)
+                .secret_for_decryption(
+                    ciphersuite,
+                    provider.crypto(),
+                    LeafNodeIndex::new(0),
+                    SenderRatchetType::Application,
+                    i,
+                    configuration
+                )
                 .expect_err("Expected decryption secret."),
-            SecretTreeError::SecretReuseError
-        );
+            SecretTreeError::GenerationReused(_)
+        ));
     }
 }
 
@@ -87,7 +115,14 @@ fn test_forward_secrecy() {
 
     // Let's ratchet once and see if the ratchet keeps any keys around.
     let _ratchet_secrets = ratchet
-        .secret_for_decryption(ciphersuite, provider.crypto(), 0, configuration)
+        .secret_for_decryption(
+            ciphersuite,
+            provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
+            0,
+            configuration,
+        )
         .expect("Error ratcheting forward.");
 
     // The generation should have increased.
@@ -95,35 +130,65 @@ fn test_forward_secrecy() {
 
     // And we should get an error for generation 0.
     let err = ratchet
-        .secret_for_decryption(ciphersuite, provider.crypto(), 0, configuration)
+        .secret_for_decryption(
+            ciphersuite,
+            provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
+            0,
+            configuration,
+        )
         .expect_err("No error when trying to retrieve key outside of tolerance window.");
-    assert_eq!(err, SecretTreeError::SecretReuseError);
+    assert!(matches!(err, SecretTreeError::GenerationReused(_)));
 
     // Let's ratchet forward a few times, making the ratchet keep the secrets round for out-of-order decryption.
     let _ratchet_secrets = ratchet
-        .secret_for_decryption(ciphersuite, provider.crypto(), 10, configuration)
+        .secret_for_decryption(
+            ciphersuite,
+            provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
+            10,
+            configuration,
+        )
         .expect("Error ratcheting forward.");
 
     // First, let's make sure that the window works.
     let err = ratchet
-        .secret_for_decryption(ciphersuite, provider.crypto(), 5, configuration)
+        .secret_for_decryption(
+            ciphersuite,
+            provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
+            5,
+            configuration,
+        )
         .expect_err("No error when trying to retrieve key outside of tolerance window.");
-    assert_eq!(err, SecretTreeError::TooDistantInThePast);
+    assert!(matches!(err, SecretTreeError::GenerationTooOld(_)));
 
-    // Now let's get a few keys. The first time we're trying to get the key of a given generation, it should work. The second time, we should get a SecretReuseError.
+    // Now let's get a few keys. The first time we're trying to get the key of a given generation, it should work. The second time, we should get a GenerationReused error.
     for generation in 10 - configuration.out_of_order_tolerance() + 1..10 {
         let keys = ratchet.secret_for_decryption(
             ciphersuite,
             provider.crypto(),
+            LeafNodeIndex::new(0),
+            SenderRatchetType::Application,
             generation,
             configuration,
         );
         assert!(keys.is_ok());
 
         let err = ratchet
-            .secret_for_decryption(ciphersuite, provider.crypto(), generation, configuration)
+            .secret_for_decryption(
+                ciphersuite,
+                provider.crypto(),
+                LeafNodeIndex::new(0),
+                SenderRatchetType::Application,
+                generation,
+                configuration,
+            )
             .expect_err("No error when trying to retrieve deleted key.");
-        assert_eq!(err, SecretTreeError::SecretReuseError);
+        assert!(matches!(err, SecretTreeError::GenerationReused(_)));
     }
 }
 
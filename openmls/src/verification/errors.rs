@@ -0,0 +1,25 @@
+//! # Verification code errors
+//!
+//! `VerificationCodeError` is returned when deriving or parsing verification
+//! codes.
+
+use thiserror::Error;
+
+use crate::error::LibraryError;
+
+/// Verification code error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum VerificationCodeError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The member is not in the group.
+    #[error("The member is not in the group.")]
+    UnknownMember,
+    /// The QR code payload is malformed.
+    #[error("The QR code payload is malformed.")]
+    MalformedPayload,
+    /// The version of the QR code payload is not supported.
+    #[error("The version of the QR code payload is not supported.")]
+    UnsupportedVersion,
+}
@@ -0,0 +1,336 @@
+//! # Out-of-band verification codes
+//!
+//! Members of a group can detect a malicious Delivery Service or insider by
+//! comparing a [`VerificationCode`] out of band, e.g. in person or over a
+//! call. The codes are derived from the [`EpochAuthenticator`] of an epoch,
+//! so members only get the same code if they agree on the group state, and
+//! from the members that are shown to the user:
+//!
+//! * A group code, derived from all members of the group, can be compared
+//!   by any two members.
+//! * A pairwise code, derived from two members, is compared by exactly these
+//!   members, e.g. when one of them scans a QR code on the other's device.
+//!
+//! The code bytes are derived with `ExpandWithLabel` from the epoch
+//! authenticator:
+//!
+//! ```text
+//! struct {
+//!     uint32 leaf_index;
+//!     Credential credential;
+//!     opaque signature_key<V>;
+//! } MemberCommitment;
+//!
+//! group code    = ExpandWithLabel(epoch_authenticator, "group verification code",
+//!                                 MemberCommitment members<V>, 30)
+//! pairwise code = ExpandWithLabel(epoch_authenticator, "pairwise verification code",
+//!                                 MemberCommitment first || MemberCommitment second, 30)
+//! ```
+//!
+//! The members of the group code are ordered by leaf index, the two members
+//! of a pairwise code by signature key, so that both members derive the same
+//! code.
+//!
+//! A code can be displayed as groups of digits, as words of the PGP word list
+//! or as a payload for a QR code. The test vectors in
+//! `test_vectors/verification-codes.json` cover all encodings.
+//!
+//! [`EpochAuthenticator`]: crate::schedule::EpochAuthenticator
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite};
+use tls_codec::{Serialize as _, VLByteSlice};
+
+use crate::{
+    binary_tree::array_representation::LeafNodeIndex,
+    ciphersuite::Secret,
+    error::LibraryError,
+    group::{GroupEpoch, Member, MlsGroup},
+};
+
+// Public
+pub mod errors;
+
+mod words;
+
+// Tests
+#[cfg(test)]
+mod tests;
+
+use errors::VerificationCodeError;
+
+/// The length of a verification code in bytes.
+pub const VERIFICATION_CODE_LENGTH: usize = 30;
+
+/// The number of digits in a group of the numeric encoding.
+const DIGITS_PER_GROUP: usize = 5;
+
+/// The number of bytes encoded as words.
+const WORDS_LENGTH: usize = 12;
+
+/// The version of the QR code payload.
+const PAYLOAD_VERSION: u8 = 1;
+
+/// The length of the QR code payload in bytes.
+const PAYLOAD_LENGTH: usize = 2 + 8 + VERIFICATION_CODE_LENGTH;
+
+const GROUP_CODE_LABEL: &str = "group verification code";
+const PAIRWISE_CODE_LABEL: &str = "pairwise verification code";
+
+/// The kind of a [`VerificationCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum VerificationCodeKind {
+    /// A code derived from all members of the group.
+    Group = 1,
+    /// A code derived from two members of the group.
+    Pairwise = 2,
+}
+
+impl TryFrom<u8> for VerificationCodeKind {
+    type Error = VerificationCodeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(VerificationCodeKind::Group),
+            2 => Ok(VerificationCodeKind::Pairwise),
+            _ => Err(VerificationCodeError::MalformedPayload),
+        }
+    }
+}
+
+/// A code that members of a group compare out of band to verify that they
+/// agree on the state of the group in an epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationCode {
+    kind: VerificationCodeKind,
+    epoch: GroupEpoch,
+    code: [u8; VERIFICATION_CODE_LENGTH],
+}
+
+impl VerificationCode {
+    /// Derives the group code of the current epoch of the `group`.
+    pub fn for_group(
+        crypto: &impl OpenMlsCrypto,
+        group: &MlsGroup,
+    ) -> Result<Self, VerificationCodeError> {
+        let members: Vec<Member> = group.members().collect();
+        Self::derive_group(
+            crypto,
+            group.ciphersuite(),
+            group.epoch(),
+            group.epoch_authenticator().as_slice(),
+            &members,
+        )
+    }
+
+    /// Derives the pairwise code of the own member of the `group` and the
+    /// member at the leaf `index` in the current epoch.
+    ///
+    /// Returns [`VerificationCodeError::UnknownMember`] if there is no member
+    /// at the leaf `index`.
+    pub fn for_member(
+        crypto: &impl OpenMlsCrypto,
+        group: &MlsGroup,
+        index: LeafNodeIndex,
+    ) -> Result<Self, VerificationCodeError> {
+        let own_member = group
+            .member_at(group.own_leaf_index())
+            .ok_or_else(|| LibraryError::custom("Own leaf is not in the group"))?;
+        let member = group
+            .member_at(index)
+            .ok_or(VerificationCodeError::UnknownMember)?;
+        Self::derive_pairwise(
+            crypto,
+            group.ciphersuite(),
+            group.epoch(),
+            group.epoch_authenticator().as_slice(),
+            &own_member,
+            &member,
+        )
+    }
+
+    /// Derives the group code of the `members` of the `epoch` with the
+    /// `epoch_authenticator`.
+    pub fn derive_group(
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+        epoch: GroupEpoch,
+        epoch_authenticator: &[u8],
+        members: &[Member],
+    ) -> Result<Self, VerificationCodeError> {
+        let mut members: Vec<&Member> = members.iter().collect();
+        members.sort_by_key(|member| member.index);
+
+        let mut commitments = Vec::new();
+        for member in members {
+            member_commitment(member, &mut commitments)?;
+        }
+        let context = VLByteSlice(&commitments)
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+
+        Self::derive(
+            crypto,
+            ciphersuite,
+            VerificationCodeKind::Group,
+            epoch,
+            epoch_authenticator,
+            GROUP_CODE_LABEL,
+            &context,
+        )
+    }
+
+    /// Derives the pairwise code of the members `first` and `second` of the
+    /// `epoch` with the `epoch_authenticator`. The order of the members
+    /// doesn't matter.
+    pub fn derive_pairwise(
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+        epoch: GroupEpoch,
+        epoch_authenticator: &[u8],
+        first: &Member,
+        second: &Member,
+    ) -> Result<Self, VerificationCodeError> {
+        let (first, second) = if first.signature_key <= second.signature_key {
+            (first, second)
+        } else {
+            (second, first)
+        };
+
+        let mut context = Vec::new();
+        member_commitment(first, &mut context)?;
+        member_commitment(second, &mut context)?;
+
+        Self::derive(
+            crypto,
+            ciphersuite,
+            VerificationCodeKind::Pairwise,
+            epoch,
+            epoch_authenticator,
+            PAIRWISE_CODE_LABEL,
+            &context,
+        )
+    }
+
+    fn derive(
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+        kind: VerificationCodeKind,
+        epoch: GroupEpoch,
+        epoch_authenticator: &[u8],
+        label: &str,
+        context: &[u8],
+    ) -> Result<Self, VerificationCodeError> {
+        let secret = Secret::from_slice(epoch_authenticator)
+            .kdf_expand_label(
+                crypto,
+                ciphersuite,
+                label,
+                context,
+                VERIFICATION_CODE_LENGTH,
+            )
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        let code = secret
+            .as_slice()
+            .try_into()
+            .map_err(|_| LibraryError::custom("Unexpected length of the verification code"))?;
+        Ok(Self { kind, epoch, code })
+    }
+
+    /// Returns the kind of the code.
+    pub fn kind(&self) -> VerificationCodeKind {
+        self.kind
+    }
+
+    /// Returns the epoch the code was derived for.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the bytes of the code.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.code
+    }
+
+    /// Encodes the code as six groups of five digits, separated by spaces.
+    ///
+    /// Every group is the big-endian integer of the next five bytes of the
+    /// code modulo 100000.
+    pub fn numeric(&self) -> String {
+        self.code
+            .chunks(DIGITS_PER_GROUP)
+            .map(|chunk| {
+                let value = chunk
+                    .iter()
+                    .fold(0u64, |value, byte| (value << 8) | u64::from(*byte));
+                format!("{:05}", value % 100_000)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Encodes the first twelve bytes of the code as words of the PGP word
+    /// list.
+    pub fn words(&self) -> Vec<&'static str> {
+        self.code[..WORDS_LENGTH]
+            .iter()
+            .enumerate()
+            .map(|(position, byte)| {
+                if position % 2 == 0 {
+                    words::EVEN_WORDS[*byte as usize]
+                } else {
+                    words::ODD_WORDS[*byte as usize]
+                }
+            })
+            .collect()
+    }
+
+    /// Encodes the code as a payload for a QR code.
+    ///
+    /// The payload consists of a version byte, the kind, the epoch as a
+    /// big-endian 64-bit integer and the code.
+    pub fn qr_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(PAYLOAD_LENGTH);
+        payload.push(PAYLOAD_VERSION);
+        payload.push(self.kind as u8);
+        payload.extend_from_slice(&self.epoch.as_u64().to_be_bytes());
+        payload.extend_from_slice(&self.code);
+        payload
+    }
+
+    /// Decodes a code from a [`Self::qr_payload()`], e.g. to compare it with
+    /// the own code.
+    pub fn from_qr_payload(payload: &[u8]) -> Result<Self, VerificationCodeError> {
+        match payload.first() {
+            Some(&PAYLOAD_VERSION) => {}
+            Some(_) => return Err(VerificationCodeError::UnsupportedVersion),
+            None => return Err(VerificationCodeError::MalformedPayload),
+        }
+        if payload.len() != PAYLOAD_LENGTH {
+            return Err(VerificationCodeError::MalformedPayload);
+        }
+
+        let kind = VerificationCodeKind::try_from(payload[1])?;
+        let mut epoch = [0u8; 8];
+        epoch.copy_from_slice(&payload[2..10]);
+        let mut code = [0u8; VERIFICATION_CODE_LENGTH];
+        code.copy_from_slice(&payload[10..]);
+        Ok(Self {
+            kind,
+            epoch: GroupEpoch::from(u64::from_be_bytes(epoch)),
+            code,
+        })
+    }
+}
+
+/// Appends the `MemberCommitment` of the `member` to the `buffer`.
+fn member_commitment(member: &Member, buffer: &mut Vec<u8>) -> Result<(), LibraryError> {
+    member
+        .index
+        .u32()
+        .tls_serialize(buffer)
+        .and_then(|_| member.credential.tls_serialize(buffer))
+        .and_then(|_| VLByteSlice(&member.signature_key).tls_serialize(buffer))
+        .map_err(LibraryError::missing_bound_check)?;
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use serde::Deserialize;
+
+use super::*;
+use crate::{credentials::BasicCredential, test_utils::hex_to_bytes};
+
+#[derive(Deserialize)]
+struct VerificationCodeTestVector {
+    cipher_suite: u16,
+    epoch: u64,
+    epoch_authenticator: String,
+    members: Vec<MemberVector>,
+    group: CodeVector,
+    pairwise: Vec<PairwiseCodeVector>,
+}
+
+#[derive(Deserialize)]
+struct MemberVector {
+    index: u32,
+    identity: String,
+    signature_key: String,
+}
+
+#[derive(Deserialize)]
+struct CodeVector {
+    code: String,
+    numeric: String,
+    words: String,
+    qr_payload: String,
+}
+
+#[derive(Deserialize)]
+struct PairwiseCodeVector {
+    first: usize,
+    second: usize,
+    #[serde(flatten)]
+    code: CodeVector,
+}
+
+fn check_encodings(code: &VerificationCode, kind: VerificationCodeKind, vector: &CodeVector) {
+    assert_eq!(code.kind(), kind);
+    assert_eq!(code.as_slice(), hex_to_bytes(&vector.code));
+    assert_eq!(code.numeric(), vector.numeric);
+    assert_eq!(code.words().join(" "), vector.words);
+    let qr_payload = hex_to_bytes(&vector.qr_payload);
+    assert_eq!(code.qr_payload(), qr_payload);
+    assert_eq!(
+        VerificationCode::from_qr_payload(&qr_payload).as_ref(),
+        Ok(code)
+    );
+}
+
+#[openmls_test::openmls_test]
+fn test_vectors() {
+    let tests: Vec<VerificationCodeTestVector> =
+        read_json!("../../test_vectors/verification-codes.json");
+
+    for test in tests {
+        if test.cipher_suite != ciphersuite as u16 {
+            continue;
+        }
+        let epoch = GroupEpoch::from(test.epoch);
+        let epoch_authenticator = hex_to_bytes(&test.epoch_authenticator);
+        let members: Vec<Member> = test
+            .members
+            .into_iter()
+            .map(|member| {
+                Member::new(
+                    LeafNodeIndex::new(member.index),
+                    vec![],
+                    hex_to_bytes(&member.signature_key),
+                    BasicCredential::new(member.identity.into_bytes()).into(),
+                )
+            })
+            .collect();
+
+        // The order of the members doesn't matter.
+        let mut reversed = members.clone();
+        reversed.reverse();
+        for members in [&members, &reversed] {
+            let code = VerificationCode::derive_group(
+                provider.crypto(),
+                ciphersuite,
+                epoch,
+                &epoch_authenticator,
+                members,
+            )
+            .unwrap();
+            assert_eq!(code.epoch(), epoch);
+            check_encodings(&code, VerificationCodeKind::Group, &test.group);
+        }
+
+        for pairwise in &test.pairwise {
+            let first = &members[pairwise.first];
+            let second = &members[pairwise.second];
+            for (first, second) in [(first, second), (second, first)] {
+                let code = VerificationCode::derive_pairwise(
+                    provider.crypto(),
+                    ciphersuite,
+                    epoch,
+                    &epoch_authenticator,
+                    first,
+                    second,
+                )
+                .unwrap();
+                check_encodings(&code, VerificationCodeKind::Pairwise, &pairwise.code);
+            }
+        }
+    }
+}
+
+#[test]
+fn malformed_payload() {
+    let mut payload = vec![PAYLOAD_VERSION, VerificationCodeKind::Group as u8];
+    payload.extend_from_slice(&[0; 8 + VERIFICATION_CODE_LENGTH]);
+    assert!(VerificationCode::from_qr_payload(&payload).is_ok());
+
+    assert_eq!(
+        VerificationCode::from_qr_payload(&payload[..payload.len() - 1]),
+        Err(VerificationCodeError::MalformedPayload)
+    );
+    assert_eq!(
+        VerificationCode::from_qr_payload(&[]),
+        Err(VerificationCodeError::MalformedPayload)
+    );
+
+    payload[1] = 3;
+    assert_eq!(
+        VerificationCode::from_qr_payload(&payload),
+        Err(VerificationCodeError::MalformedPayload)
+    );
+
+    payload[0] = PAYLOAD_VERSION + 1;
+    assert_eq!(
+        VerificationCode::from_qr_payload(&payload),
+        Err(VerificationCodeError::UnsupportedVersion)
+    );
+}
@@ -0,0 +1,128 @@
+//! Test the changes exposed by staged commits.
+use openmls::prelude::*;
+use openmls::test_utils::{generate_credential_with_key, generate_key_package};
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn key_package<Provider: OpenMlsProvider>(
+    ciphersuite: Ciphersuite,
+    provider: &Provider,
+    identity: &[u8],
+) -> KeyPackage {
+    let credential_with_key = generate_credential_with_key(
+        identity.to_vec(),
+        ciphersuite.signature_algorithm(),
+        provider,
+    );
+    generate_key_package(
+        ciphersuite,
+        Extensions::empty(),
+        provider,
+        credential_with_key,
+    )
+    .key_package()
+    .clone()
+}
+
+#[openmls_test]
+fn staged_commit_changes() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let charlie_provider = &Provider::default();
+    let dave_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential.clone())
+        .unwrap();
+    let charlie_key_package = key_package(ciphersuite, charlie_provider, b"Charlie");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            alice_provider,
+            &alice_signer,
+            &[
+                key_package(ciphersuite, bob_provider, b"Bob"),
+                charlie_key_package.clone(),
+            ],
+        )
+        .unwrap();
+    let changes = alice_group.pending_commit().unwrap().changes();
+    assert_eq!(changes.added().len(), 2);
+    assert!(changes.removed().is_empty());
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::default(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    // Alice replaces Charlie with Dave.
+    let dave_key_package = key_package(ciphersuite, dave_provider, b"Dave");
+    let charlie_index = LeafNodeIndex::new(2);
+    let commit = alice_group
+        .commit_builder()
+        .propose_removals([charlie_index])
+        .propose_adds([dave_key_package.clone()])
+        .load_psks(alice_provider.storage())
+        .unwrap()
+        .build(
+            alice_provider.rand(),
+            alice_provider.crypto(),
+            &alice_signer,
+            |_| true,
+        )
+        .unwrap()
+        .stage_commit(alice_provider)
+        .unwrap()
+        .into_commit();
+
+    let message = MlsMessageIn::from(commit)
+        .try_into_protocol_message()
+        .unwrap();
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = bob_group
+        .process_message(bob_provider, message)
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    let changes = staged_commit.changes();
+
+    // Dave takes Charlie's leaf.
+    assert_eq!(changes.removed().len(), 1);
+    assert_eq!(changes.removed()[0].index, charlie_index);
+    assert_eq!(
+        &changes.removed()[0].credential,
+        charlie_key_package.leaf_node().credential()
+    );
+    assert_eq!(changes.added().len(), 1);
+    assert_eq!(changes.added()[0].index, charlie_index);
+    assert_eq!(
+        &changes.added()[0].credential,
+        dave_key_package.leaf_node().credential()
+    );
+
+    // Alice's leaf is refreshed by the update path, but her identity is
+    // unchanged.
+    assert_eq!(changes.updated().len(), 1);
+    let updated = &changes.updated()[0];
+    assert_eq!(updated.index(), alice_group.own_leaf_index());
+    assert_eq!(updated.new_credential(), &alice_credential.credential);
+    assert!(!updated.credential_changed());
+
+    assert!(changes.extensions().is_none());
+    assert!(changes.psks().is_empty());
+
+    // Alice's pending commit has the same changes.
+    assert_eq!(alice_group.pending_commit().unwrap().changes(), changes);
+}
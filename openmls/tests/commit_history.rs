@@ -0,0 +1,217 @@
+//! Test the commit history and its verification.
+use openmls::prelude::*;
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+use tls_codec::{Deserialize as _, Serialize as _};
+
+#[openmls_test]
+fn commit_history_verifies() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let bob_key_package = KeyPackage::builder()
+        .build(
+            ciphersuite,
+            bob_provider,
+            &bob_signer,
+            bob_credential.clone(),
+        )
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .commit_history_config(CommitHistoryConfig::default())
+        .build(alice_provider, &alice_signer, alice_credential.clone())
+        .unwrap();
+    let start_context = alice_group.export_group_context().clone();
+    let start_confirmation_tag = alice_group.confirmation_tag().clone();
+
+    let (_commit, welcome, group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    // The records don't contain the commits, so they are kept separately.
+    let add_commit = alice_group
+        .pending_commit()
+        .unwrap()
+        .transcript_hash_input()
+        .unwrap()
+        .to_vec();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let group_info = group_info.unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::builder()
+            .commit_history_config(CommitHistoryConfig::default())
+            .build(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    // Bob updates his leaf.
+    let commit = bob_group
+        .self_update(bob_provider, &bob_signer, LeafNodeParameters::default())
+        .unwrap()
+        .into_commit();
+    bob_group.merge_pending_commit(bob_provider).unwrap();
+    let message = MlsMessageIn::from(commit)
+        .try_into_protocol_message()
+        .unwrap();
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = alice_group
+        .process_message(alice_provider, message)
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    let update_commit = staged_commit.transcript_hash_input().unwrap().to_vec();
+    alice_group
+        .merge_staged_commit(alice_provider, *staged_commit)
+        .unwrap();
+
+    let alice_history = alice_group
+        .commit_history(alice_provider.storage())
+        .unwrap();
+    let bob_history = bob_group.commit_history(bob_provider.storage()).unwrap();
+    assert_eq!(alice_history.records().len(), 2);
+    assert_eq!(bob_history.records().len(), 1);
+
+    let record = &alice_history.records()[1];
+    assert_eq!(record, &bob_history.records()[0]);
+    assert_eq!(record.epoch(), GroupEpoch::from(2));
+    assert_eq!(record.committer(), bob_group.own_leaf_index());
+    assert_eq!(record.committer_credential(), &bob_credential.credential);
+    assert_eq!(
+        record.epoch_authenticator(),
+        alice_group.epoch_authenticator().as_slice()
+    );
+    assert_eq!(
+        alice_history.records()[0].committer_credential(),
+        &alice_credential.credential
+    );
+
+    // Alice's history starts at the creation of the group, Bob's at the
+    // epoch he joined.
+    alice_history
+        .verify(
+            alice_provider.crypto(),
+            &start_context,
+            &start_confirmation_tag,
+            &[&add_commit, &update_commit],
+        )
+        .unwrap();
+    bob_history
+        .verify(
+            bob_provider.crypto(),
+            group_info.group_context(),
+            group_info.confirmation_tag(),
+            &[&update_commit],
+        )
+        .unwrap();
+
+    // The exported history verifies after decoding.
+    let exported = alice_history.tls_serialize_detached().unwrap();
+    let imported = CommitHistory::tls_deserialize_exact(exported).unwrap();
+    assert_eq!(imported, alice_history);
+
+    // A history doesn't verify from the wrong starting point, with missing
+    // commits or with the wrong commits.
+    assert_eq!(
+        alice_history.verify(
+            alice_provider.crypto(),
+            group_info.group_context(),
+            group_info.confirmation_tag(),
+            &[&add_commit, &update_commit],
+        ),
+        Err(CommitHistoryError::UnexpectedEpoch)
+    );
+    assert_eq!(
+        alice_history.verify(
+            alice_provider.crypto(),
+            &start_context,
+            &start_confirmation_tag,
+            &[&add_commit],
+        ),
+        Err(CommitHistoryError::CommitCountMismatch)
+    );
+    assert_eq!(
+        alice_history.verify(
+            alice_provider.crypto(),
+            &start_context,
+            &start_confirmation_tag,
+            &[&update_commit, &add_commit],
+        ),
+        Err(CommitHistoryError::CommitHashMismatch)
+    );
+}
+
+#[openmls_test]
+fn commit_history_retention() {
+    let alice_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .commit_history_config(CommitHistoryConfig::new(2))
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+
+    let mut starts = vec![];
+    let mut commits = vec![];
+    for _ in 0..3 {
+        starts.push((
+            alice_group.export_group_context().clone(),
+            alice_group.confirmation_tag().clone(),
+        ));
+        alice_group
+            .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+            .unwrap();
+        commits.push(
+            alice_group
+                .pending_commit()
+                .unwrap()
+                .transcript_hash_input()
+                .unwrap()
+                .to_vec(),
+        );
+        alice_group.merge_pending_commit(alice_provider).unwrap();
+    }
+
+    // Only the records of the last two epochs are kept.
+    let history = alice_group
+        .commit_history(alice_provider.storage())
+        .unwrap();
+    let epochs: Vec<_> = history.records().iter().map(|r| r.epoch()).collect();
+    assert_eq!(epochs, [GroupEpoch::from(2), GroupEpoch::from(3)]);
+
+    let (start_context, start_confirmation_tag) = &starts[1];
+    history
+        .verify(
+            alice_provider.crypto(),
+            start_context,
+            start_confirmation_tag,
+            &commits[1..],
+        )
+        .unwrap();
+
+    // Deleting the group deletes the remaining records.
+    alice_group.delete(alice_provider.storage()).unwrap();
+    assert!(alice_group
+        .commit_history(alice_provider.storage())
+        .unwrap()
+        .records()
+        .is_empty());
+}
@@ -0,0 +1,192 @@
+//! Test automatic commits of pending proposals.
+use openmls::{prelude::*, test_utils::TestClock};
+use openmls_test::openmls_test;
+use openmls_traits::signatures::Signer;
+use test_utils::new_credential;
+
+/// Alice creates a group and adds Bob. Both use the plaintext wire format, so
+/// that SelfRemove proposals can be sent.
+fn setup<Provider: OpenMlsProvider>(
+    ciphersuite: Ciphersuite,
+    alice_provider: &Provider,
+    bob_provider: &Provider,
+) -> (MlsGroup, impl Signer, MlsGroup, impl Signer) {
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+
+    let capabilities = Capabilities::new(
+        None,
+        Some(&[ciphersuite]),
+        None,
+        Some(&[ProposalType::SelfRemove]),
+        None,
+    );
+    let bob_key_package = KeyPackage::builder()
+        .leaf_node_capabilities(capabilities)
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .with_wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::builder()
+            .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+            .build(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    (alice_group, alice_signer, bob_group, bob_signer)
+}
+
+fn store_proposal<Provider: OpenMlsProvider>(
+    group: &mut MlsGroup,
+    provider: &Provider,
+    proposal: MlsMessageOut,
+) {
+    let message = MlsMessageIn::from(proposal)
+        .try_into_protocol_message()
+        .unwrap();
+    let ProcessedMessageContent::ProposalMessage(queued_proposal) = group
+        .process_message(provider, message)
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected a proposal.");
+    };
+    group
+        .store_pending_proposal(provider.storage(), *queued_proposal)
+        .unwrap();
+}
+
+#[openmls_test]
+fn designated_committer_commits_after_delay() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let charlie_provider = &Provider::default();
+
+    let (mut alice_group, alice_signer, mut bob_group, bob_signer) =
+        setup(ciphersuite, alice_provider, bob_provider);
+
+    let policy = CommitPolicy::default()
+        .committer(Committer::Designated(alice_group.own_leaf_index()))
+        .max_pending_proposals(2)
+        .max_delay(60);
+    let clock = TestClock::default();
+    let mut alice_engine = CommitPolicyEngine::with_clock(policy, clock.clone());
+    let mut bob_engine = CommitPolicyEngine::with_clock(policy, clock.clone());
+
+    // Bob proposes to add Charlie, but leaves the commit to Alice.
+    let (charlie_credential, charlie_signer) = new_credential(
+        charlie_provider,
+        b"Charlie",
+        ciphersuite.signature_algorithm(),
+    );
+    let charlie_key_package = KeyPackage::builder()
+        .build(
+            ciphersuite,
+            charlie_provider,
+            &charlie_signer,
+            charlie_credential,
+        )
+        .unwrap()
+        .key_package()
+        .clone();
+    let (proposal, _) = bob_group
+        .propose_add_member(bob_provider, &bob_signer, &charlie_key_package)
+        .unwrap();
+    assert!(bob_engine
+        .poll(&mut bob_group, bob_provider, &bob_signer)
+        .unwrap()
+        .is_none());
+
+    // A single proposal is below the limit, so Alice waits.
+    store_proposal(&mut alice_group, alice_provider, proposal);
+    assert!(alice_engine
+        .poll(&mut alice_group, alice_provider, &alice_signer)
+        .unwrap()
+        .is_none());
+    clock.set(59);
+    assert!(!alice_engine.is_commit_due(&alice_group));
+
+    // Once the delay has passed, Alice commits.
+    clock.set(60);
+    let bundle = alice_engine
+        .poll(&mut alice_group, alice_provider, &alice_signer)
+        .unwrap()
+        .expect("Expected a commit.");
+    assert!(bundle.welcome().is_some());
+    assert_eq!(
+        alice_group
+            .pending_commit()
+            .unwrap()
+            .add_proposals()
+            .count(),
+        1
+    );
+
+    // Nothing is due while the commit is pending.
+    assert!(!alice_engine.is_commit_due(&alice_group));
+}
+
+#[openmls_test]
+fn self_remove_is_committed_by_default() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (mut alice_group, alice_signer, mut bob_group, bob_signer) =
+        setup(ciphersuite, alice_provider, bob_provider);
+    let mut alice_engine = CommitPolicyEngine::new(CommitPolicy::default());
+    let mut bob_engine = CommitPolicyEngine::new(CommitPolicy::default());
+
+    // Bob leaves the group. He can't commit his own proposal.
+    let self_remove = bob_group
+        .leave_group_via_self_remove(bob_provider, &bob_signer)
+        .unwrap();
+    assert!(bob_engine
+        .poll(&mut bob_group, bob_provider, &bob_signer)
+        .unwrap()
+        .is_none());
+
+    // Alice commits it right away.
+    store_proposal(&mut alice_group, alice_provider, self_remove);
+    let bundle = alice_engine
+        .poll(&mut alice_group, alice_provider, &alice_signer)
+        .unwrap()
+        .expect("Expected a commit.");
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    assert_eq!(alice_group.members().count(), 1);
+
+    let commit = MlsMessageIn::from(bundle.into_commit())
+        .try_into_protocol_message()
+        .unwrap();
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = bob_group
+        .process_message(bob_provider, commit)
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    bob_group
+        .merge_staged_commit(bob_provider, *staged_commit)
+        .unwrap();
+    assert!(!bob_group.is_active());
+}
@@ -0,0 +1,282 @@
+//! Test re-creating a pending commit that lost against another commit.
+use openmls::prelude::*;
+use openmls::test_utils::{generate_credential_with_key, generate_key_package};
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn key_package<Provider: OpenMlsProvider>(
+    ciphersuite: Ciphersuite,
+    provider: &Provider,
+    identity: &[u8],
+) -> KeyPackage {
+    let credential_with_key = generate_credential_with_key(
+        identity.to_vec(),
+        ciphersuite.signature_algorithm(),
+        provider,
+    );
+    generate_key_package(
+        ciphersuite,
+        Extensions::empty(),
+        provider,
+        credential_with_key,
+    )
+    .key_package()
+    .clone()
+}
+
+fn staged_commit<Provider: OpenMlsProvider>(
+    group: &mut MlsGroup,
+    provider: &Provider,
+    commit: MlsMessageOut,
+) -> StagedCommit {
+    let message = MlsMessageIn::from(commit)
+        .try_into_protocol_message()
+        .unwrap();
+    match group
+        .process_message(provider, message)
+        .unwrap()
+        .into_content()
+    {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => *staged_commit,
+        _ => panic!("Expected a commit."),
+    }
+}
+
+#[openmls_test]
+fn rebase_losing_commit() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let charlie_provider = &Provider::default();
+    let dave_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    // Alice creates a group with Bob and Charlie.
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            alice_provider,
+            &alice_signer,
+            &[
+                bob_key_package,
+                key_package(ciphersuite, charlie_provider, b"Charlie"),
+            ],
+        )
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::default(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+    let charlie_index = LeafNodeIndex::new(2);
+
+    // Bob wants to remove Charlie and add Dave.
+    let dave_key_package = key_package(ciphersuite, dave_provider, b"Dave");
+    bob_group
+        .commit_builder()
+        .propose_removals([charlie_index])
+        .propose_adds([dave_key_package.clone()])
+        .load_psks(bob_provider.storage())
+        .unwrap()
+        .build(
+            bob_provider.rand(),
+            bob_provider.crypto(),
+            &bob_signer,
+            |_| true,
+        )
+        .unwrap()
+        .stage_commit(bob_provider)
+        .unwrap();
+
+    let intent = bob_group.pending_commit_intent().unwrap();
+    assert_eq!(intent.removed_members().len(), 1);
+    assert_eq!(intent.removed_members()[0].index, charlie_index);
+    assert!(matches!(
+        intent.proposals(),
+        [Proposal::Add(add_proposal)] if add_proposal.key_package() == &dave_key_package
+    ));
+    assert!(!intent.self_update());
+
+    // Alice's removal of Charlie wins the race.
+    let (alice_commit, _welcome, _group_info) = alice_group
+        .remove_members(alice_provider, &alice_signer, &[charlie_index])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    // Bob merges Alice's commit and only adds Dave in the new epoch.
+    let alice_commit = staged_commit(&mut bob_group, bob_provider, alice_commit);
+    let bundle = bob_group
+        .merge_and_rebase_pending_commit(bob_provider, &bob_signer, alice_commit)
+        .unwrap()
+        .expect("Expected a rebased commit.");
+    assert!(bundle.welcome().is_some());
+
+    let pending_commit = bob_group.pending_commit().unwrap();
+    assert_eq!(pending_commit.add_proposals().count(), 1);
+    assert_eq!(pending_commit.remove_proposals().count(), 0);
+
+    // Alice accepts the rebased commit.
+    let bob_commit = staged_commit(&mut alice_group, alice_provider, bundle.into_commit());
+    alice_group
+        .merge_staged_commit(alice_provider, bob_commit)
+        .unwrap();
+    bob_group.merge_pending_commit(bob_provider).unwrap();
+
+    assert_eq!(alice_group.epoch(), bob_group.epoch());
+    assert_eq!(
+        alice_group.members().collect::<Vec<_>>(),
+        bob_group.members().collect::<Vec<_>>()
+    );
+    assert_eq!(bob_group.members().count(), 3);
+}
+
+#[openmls_test]
+fn rebase_removal_of_member_with_new_signature_key() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let charlie_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let (charlie_credential, charlie_signer) = new_credential(
+        charlie_provider,
+        b"Charlie",
+        ciphersuite.signature_algorithm(),
+    );
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+    let charlie_key_package = KeyPackage::builder()
+        .build(
+            ciphersuite,
+            charlie_provider,
+            &charlie_signer,
+            charlie_credential,
+        )
+        .unwrap()
+        .key_package()
+        .clone();
+
+    // Alice creates a group with Bob and Charlie.
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            alice_provider,
+            &alice_signer,
+            &[bob_key_package, charlie_key_package],
+        )
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let join = |provider: &Provider| {
+        StagedWelcome::new_from_welcome(
+            provider,
+            &MlsGroupJoinConfig::default(),
+            welcome.clone(),
+            None,
+        )
+        .unwrap()
+        .into_group(provider)
+        .unwrap()
+    };
+    let mut bob_group = join(bob_provider);
+    let mut charlie_group = join(charlie_provider);
+    let charlie_index = charlie_group.own_leaf_index();
+
+    // Bob wants to remove Charlie.
+    bob_group
+        .commit_builder()
+        .propose_removals([charlie_index])
+        .load_psks(bob_provider.storage())
+        .unwrap()
+        .build(
+            bob_provider.rand(),
+            bob_provider.crypto(),
+            &bob_signer,
+            |_| true,
+        )
+        .unwrap()
+        .stage_commit(bob_provider)
+        .unwrap();
+
+    // Charlie's commit, which changes his signature key, wins the race.
+    let (new_credential, new_signer) = new_credential(
+        charlie_provider,
+        b"Charlie",
+        ciphersuite.signature_algorithm(),
+    );
+    let charlie_commit = charlie_group
+        .self_update_with_new_signer(
+            charlie_provider,
+            &charlie_signer,
+            NewSignerBundle {
+                signer: &new_signer,
+                credential_with_key: new_credential,
+            },
+            LeafNodeParameters::default(),
+        )
+        .unwrap()
+        .into_commit();
+    charlie_group
+        .merge_pending_commit(charlie_provider)
+        .unwrap();
+
+    // Bob still removes Charlie in the new epoch.
+    let alice_charlie_commit =
+        staged_commit(&mut alice_group, alice_provider, charlie_commit.clone());
+    alice_group
+        .merge_staged_commit(alice_provider, alice_charlie_commit)
+        .unwrap();
+    let charlie_commit = staged_commit(&mut bob_group, bob_provider, charlie_commit);
+    let bundle = bob_group
+        .merge_and_rebase_pending_commit(bob_provider, &bob_signer, charlie_commit)
+        .unwrap()
+        .expect("Expected a rebased commit.");
+
+    let pending_commit = bob_group.pending_commit().unwrap();
+    assert!(matches!(
+        pending_commit
+            .remove_proposals()
+            .map(|remove| remove.remove_proposal().removed())
+            .collect::<Vec<_>>()
+            .as_slice(),
+        [removed] if *removed == charlie_index
+    ));
+
+    let bob_commit = staged_commit(&mut alice_group, alice_provider, bundle.into_commit());
+    alice_group
+        .merge_staged_commit(alice_provider, bob_commit)
+        .unwrap();
+    bob_group.merge_pending_commit(bob_provider).unwrap();
+
+    assert_eq!(alice_group.epoch(), bob_group.epoch());
+    assert_eq!(bob_group.members().count(), 2);
+}
@@ -1,10 +1,16 @@
 use openmls::{
-    prelude::*,
+    prelude::{test_utils::new_credential, *},
+    schedule::{
+        errors::PskError,
+        psk::{ResumptionPsk, ResumptionPskUsage},
+        PreSharedKeyId, Psk,
+    },
     test_utils::test_framework::{
         noop_authentication_service, ActionType, CodecUse, MlsGroupTestSetup,
     },
     treesync::LeafNodeParameters,
 };
+use openmls_basic_credential::SignatureKeyPair;
 use openmls_test::openmls_test;
 
 // The following tests correspond to the interop test scenarios detailed here:
@@ -348,3 +354,295 @@ fn large_group_lifecycle() {
     // Check that group members agree on a group state.
     setup.check_group_states(group, noop_authentication_service);
 }
+
+/// A member of a group in the re-init and branch scenarios.
+struct Participant<'a, Provider: OpenMlsProvider> {
+    provider: &'a Provider,
+    credential_with_key: CredentialWithKey,
+    signer: SignatureKeyPair,
+    group: MlsGroup,
+}
+
+/// Create a group of Alice and Bob, each with their own provider.
+fn alice_and_bob<'a, Provider: OpenMlsProvider>(
+    ciphersuite: Ciphersuite,
+    alice_provider: &'a Provider,
+    bob_provider: &'a Provider,
+) -> (Participant<'a, Provider>, Participant<'a, Provider>) {
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential.clone())
+        .expect("Error creating group.");
+    let bob_key_package = KeyPackage::builder()
+        .build(
+            ciphersuite,
+            bob_provider,
+            &bob_signer,
+            bob_credential.clone(),
+        )
+        .expect("Error creating key package.");
+    let (_, welcome, _) = alice_group
+        .add_members(
+            alice_provider,
+            &alice_signer,
+            &[bob_key_package.key_package().clone()],
+        )
+        .expect("Error adding Bob.");
+    alice_group
+        .merge_pending_commit(alice_provider)
+        .expect("Error merging commit.");
+    let bob_group = join(bob_provider, welcome);
+
+    (
+        Participant {
+            provider: alice_provider,
+            credential_with_key: alice_credential,
+            signer: alice_signer,
+            group: alice_group,
+        },
+        Participant {
+            provider: bob_provider,
+            credential_with_key: bob_credential,
+            signer: bob_signer,
+            group: bob_group,
+        },
+    )
+}
+
+fn join<Provider: OpenMlsProvider>(provider: &Provider, welcome: MlsMessageOut) -> MlsGroup {
+    let welcome = MlsMessageIn::from(welcome)
+        .into_welcome()
+        .expect("Expected a welcome.");
+    StagedWelcome::new_from_welcome(
+        provider,
+        &MlsGroupJoinConfig::builder()
+            .use_ratchet_tree_extension(true)
+            .build(),
+        welcome,
+        None,
+    )
+    .expect("Error processing welcome.")
+    .into_group(provider)
+    .expect("Error joining group.")
+}
+
+/// Create a new group with `group_id` and `ciphersuite` for `creator` that
+/// uses the resumption PSK of the creator's current group, and invite
+/// `member` to it. Both store the PSK before, as the RFC requires them to use
+/// the resumption PSK of the old group.
+fn resume_group<Provider: OpenMlsProvider>(
+    creator: &Participant<Provider>,
+    member: &Participant<Provider>,
+    usage: ResumptionPskUsage,
+    group_id: GroupId,
+    ciphersuite: Ciphersuite,
+) -> (MlsGroup, MlsGroup) {
+    let psk_id = PreSharedKeyId::new(
+        ciphersuite,
+        creator.provider.rand(),
+        Psk::Resumption(ResumptionPsk::new(
+            usage,
+            creator.group.group_id().clone(),
+            creator.group.epoch(),
+        )),
+    )
+    .expect("Error creating PSK ID.");
+    psk_id
+        .store(
+            creator.provider,
+            creator.group.resumption_psk_secret().as_slice(),
+        )
+        .expect("Error storing PSK.");
+    psk_id
+        .store(
+            member.provider,
+            member.group.resumption_psk_secret().as_slice(),
+        )
+        .expect("Error storing PSK.");
+
+    let member_key_package = KeyPackage::builder()
+        .build(
+            ciphersuite,
+            member.provider,
+            &member.signer,
+            member.credential_with_key.clone(),
+        )
+        .expect("Error creating key package.");
+
+    let mut creator_group = MlsGroup::builder()
+        .with_group_id(group_id)
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(
+            creator.provider,
+            &creator.signer,
+            creator.credential_with_key.clone(),
+        )
+        .expect("Error creating group.");
+    let welcome = creator_group
+        .commit_builder()
+        .propose_adds(Some(member_key_package.key_package().clone()))
+        .include_resumption_psk(psk_id)
+        .load_psks(creator.provider.storage())
+        .expect("Error loading PSKs.")
+        .build(
+            creator.provider.rand(),
+            creator.provider.crypto(),
+            &creator.signer,
+            |_| true,
+        )
+        .expect("Error building commit.")
+        .stage_commit(creator.provider)
+        .expect("Error staging commit.")
+        .into_welcome_msg()
+        .expect("Expected a welcome.");
+    creator_group
+        .merge_pending_commit(creator.provider)
+        .expect("Error merging commit.");
+
+    let member_group = join(member.provider, welcome);
+    (creator_group, member_group)
+}
+
+// # Re-initialization
+// A:    Create group
+// B->A: KeyPackage
+// A->B: Welcome
+// B->A: ReInit
+// A->B: Commit
+// A:    Create new group
+// B->A: KeyPackage
+// A->B: Welcome (with re-init PSK)
+// ***:  Verify group state
+#[openmls_test]
+fn reinit() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let (mut alice, mut bob) = alice_and_bob(ciphersuite, alice_provider, bob_provider);
+
+    // Bob proposes to re-initialize the group ...
+    let new_group_id = GroupId::from_slice(b"Re-initialized group");
+    let (proposal, _) = bob
+        .group
+        .propose_reinit(
+            bob_provider,
+            &bob.signer,
+            ReInitProposal::new(
+                new_group_id.clone(),
+                ProtocolVersion::Mls10,
+                ciphersuite,
+                Extensions::empty(),
+            ),
+        )
+        .expect("Error proposing re-init.");
+
+    // ... and Alice commits to it.
+    let processed = alice
+        .group
+        .process_message(alice_provider, proposal.into_protocol_message().unwrap())
+        .expect("Error processing proposal.");
+    let ProcessedMessageContent::ProposalMessage(proposal) = processed.into_content() else {
+        panic!("Expected a proposal.");
+    };
+    alice
+        .group
+        .store_pending_proposal(alice_provider.storage(), *proposal)
+        .unwrap();
+    let (commit, _, _) = alice
+        .group
+        .commit_to_pending_proposals(alice_provider, &alice.signer)
+        .expect("Error committing.");
+    alice.group.merge_pending_commit(alice_provider).unwrap();
+
+    let processed = bob
+        .group
+        .process_message(bob_provider, commit.into_protocol_message().unwrap())
+        .expect("Error processing commit.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = processed.into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    let reinit = staged_commit
+        .queued_proposals()
+        .find_map(|queued_proposal| match queued_proposal.proposal() {
+            Proposal::ReInit(reinit) => Some(reinit.clone()),
+            _ => None,
+        })
+        .expect("Expected a ReInit proposal.");
+    bob.group
+        .merge_staged_commit(bob_provider, *staged_commit)
+        .unwrap();
+    assert_eq!(reinit.group_id(), &new_group_id);
+    assert_eq!(reinit.ciphersuite(), ciphersuite);
+
+    // The re-init PSK can only be used in the first commit of the new group.
+    let psk_id = PreSharedKeyId::new(
+        ciphersuite,
+        alice_provider.rand(),
+        Psk::Resumption(ResumptionPsk::new(
+            ResumptionPskUsage::Reinit,
+            alice.group.group_id().clone(),
+            alice.group.epoch(),
+        )),
+    )
+    .unwrap();
+    let result = alice
+        .group
+        .commit_builder()
+        .include_resumption_psk(psk_id)
+        .load_psks(alice_provider.storage());
+    assert!(matches!(
+        result,
+        Err(CreateCommitError::PskError(PskError::NotFirstCommit))
+    ));
+
+    // Alice creates the new group with the parameters of the proposal.
+    let (alice_group, bob_group) = resume_group(
+        &alice,
+        &bob,
+        ResumptionPskUsage::Reinit,
+        reinit.group_id().clone(),
+        reinit.ciphersuite(),
+    );
+    assert_eq!(alice_group.group_id(), &new_group_id);
+    assert_eq!(
+        alice_group.epoch_authenticator().as_slice(),
+        bob_group.epoch_authenticator().as_slice()
+    );
+}
+
+// # Branch
+// A:    Create group
+// B->A: KeyPackage
+// A->B: Welcome
+// A:    Create new group
+// B->A: KeyPackage
+// A->B: Welcome (with branch PSK)
+// ***:  Verify group state
+#[openmls_test]
+fn branch() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let (alice, bob) = alice_and_bob(ciphersuite, alice_provider, bob_provider);
+
+    let (alice_group, bob_group) = resume_group(
+        &alice,
+        &bob,
+        ResumptionPskUsage::Branch,
+        GroupId::from_slice(b"Branched group"),
+        ciphersuite,
+    );
+    assert_eq!(
+        alice_group.epoch_authenticator().as_slice(),
+        bob_group.epoch_authenticator().as_slice()
+    );
+
+    // The old group is still usable.
+    assert_eq!(alice.group.members().count(), 2);
+}
@@ -0,0 +1,151 @@
+//! Test decrypting messages of past epochs from the message archive.
+use openmls::{prelude::*, test_utils::TestClock};
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn process_commit<Provider: OpenMlsProvider>(
+    provider: &Provider,
+    group: &mut MlsGroup,
+    commit: MlsMessageOut,
+) {
+    let commit = MlsMessageIn::from(commit)
+        .try_into_protocol_message()
+        .unwrap();
+    let processed = group.process_message(provider, commit).unwrap();
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = processed.into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    group.merge_staged_commit(provider, *staged_commit).unwrap();
+}
+
+fn process_application_message<Provider: OpenMlsProvider>(
+    provider: &Provider,
+    group: &mut MlsGroup,
+    message: MlsMessageOut,
+) -> Result<Vec<u8>, ProcessMessageError> {
+    let message = MlsMessageIn::from(message)
+        .try_into_protocol_message()
+        .unwrap();
+    let processed = group.process_message(provider, message)?;
+    let ProcessedMessageContent::ApplicationMessage(message) = processed.into_content() else {
+        panic!("Expected an application message.");
+    };
+    Ok(message.into_bytes())
+}
+
+#[openmls_test]
+fn archived_message_secrets() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    // Alice creates the group and adds Bob. Bob keeps no past epochs in
+    // memory, but archives up to two of them.
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let join_config = MlsGroupJoinConfig::builder()
+        .message_archive_config(MessageArchiveConfig::new(2))
+        .build();
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(bob_provider, &join_config, welcome, None)
+        .unwrap()
+        .into_group(bob_provider)
+        .unwrap();
+
+    // Alice sends messages in every epoch and moves to the next one. Bob
+    // only processes the commits.
+    let mut messages = vec![];
+    for epoch in 1..4u64 {
+        assert_eq!(alice_group.epoch(), GroupEpoch::from(epoch));
+        let first = alice_group
+            .create_message(alice_provider, &alice_signer, b"first")
+            .unwrap();
+        let second = alice_group
+            .create_message(alice_provider, &alice_signer, b"second")
+            .unwrap();
+        messages.push((first, second));
+
+        let commit = alice_group
+            .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+            .unwrap()
+            .into_commit();
+        alice_group.merge_pending_commit(alice_provider).unwrap();
+        process_commit(bob_provider, &mut bob_group, commit);
+    }
+    assert_eq!(bob_group.epoch(), GroupEpoch::from(4));
+
+    // Only the two most recent past epochs are archived.
+    assert_eq!(
+        bob_group.archived_epochs(),
+        vec![GroupEpoch::from(2), GroupEpoch::from(3)]
+    );
+    let mut messages = messages.into_iter();
+    let (first, _second) = messages.next().unwrap();
+    assert!(process_application_message(bob_provider, &mut bob_group, first).is_err());
+
+    // Bob decrypts the messages of an archived epoch, also after loading the
+    // group from storage.
+    let (first, second) = messages.next().unwrap();
+    assert_eq!(
+        process_application_message(bob_provider, &mut bob_group, second).unwrap(),
+        b"second"
+    );
+    let mut bob_group = MlsGroup::load(bob_provider.storage(), bob_group.group_id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        process_application_message(bob_provider, &mut bob_group, first.clone()).unwrap(),
+        b"first"
+    );
+
+    // The used secrets were written back, so the message can't be decrypted
+    // again.
+    assert!(process_application_message(bob_provider, &mut bob_group, first).is_err());
+
+    // The archived epochs expire by age.
+    let mut bob_group = MlsGroup::load(bob_provider.storage(), bob_group.group_id())
+        .unwrap()
+        .unwrap();
+    let join_config = MlsGroupJoinConfig::builder()
+        .message_archive_config(MessageArchiveConfig::new(2).with_max_age(60))
+        .build();
+    bob_group
+        .set_configuration(bob_provider.storage(), &join_config)
+        .unwrap();
+    assert_eq!(
+        bob_group
+            .expire_archived_message_secrets(bob_provider.storage(), &TestClock::new(0))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        bob_group
+            .expire_archived_message_secrets(bob_provider.storage(), &TestClock::new(u64::MAX))
+            .unwrap(),
+        2
+    );
+    assert!(bob_group.archived_epochs().is_empty());
+    let (_first, second) = messages.next().unwrap();
+    assert!(process_application_message(bob_provider, &mut bob_group, second).is_err());
+
+    bob_group.delete(bob_provider.storage()).unwrap();
+}
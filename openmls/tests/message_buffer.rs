@@ -0,0 +1,202 @@
+//! Test buffering of messages for future epochs.
+use openmls::prelude::*;
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+// Alice creates a group and adds Bob, who buffers at most two messages.
+fn setup(
+    ciphersuite: Ciphersuite,
+    alice_provider: &impl OpenMlsProvider,
+    bob_provider: &impl OpenMlsProvider,
+) -> (MlsGroup, SignatureKeyPair, MlsGroup, SignatureKeyPair) {
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let join_config = MlsGroupJoinConfig::builder()
+        .message_buffer_config(MessageBufferConfig::new(2, 1))
+        .build();
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let bob_group = StagedWelcome::new_from_welcome(bob_provider, &join_config, welcome, None)
+        .unwrap()
+        .into_group(bob_provider)
+        .unwrap();
+
+    (alice_group, alice_signer, bob_group, bob_signer)
+}
+
+#[openmls_test]
+fn buffered_messages_are_replayed_after_merge() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (mut alice_group, alice_signer, mut bob_group, _bob_signer) =
+        setup(ciphersuite, alice_provider, bob_provider);
+
+    // Alice moves to the next epoch and sends three application messages.
+    let commit = alice_group
+        .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+        .unwrap()
+        .into_commit();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let first = alice_group
+        .create_message(alice_provider, &alice_signer, b"first")
+        .unwrap();
+    let second = alice_group
+        .create_message(alice_provider, &alice_signer, b"second")
+        .unwrap();
+    let third = alice_group
+        .create_message(alice_provider, &alice_signer, b"third")
+        .unwrap();
+
+    // Bob receives the application messages before the commit.
+    for message in [first, second] {
+        let message = MlsMessageIn::from(message)
+            .try_into_protocol_message()
+            .unwrap();
+        let processed = bob_group
+            .process_or_buffer_message(bob_provider, message)
+            .unwrap();
+        assert!(processed.is_none());
+    }
+    let buffered = bob_group.buffered_messages(bob_provider.storage()).unwrap();
+    assert_eq!(buffered.len(), 2);
+    assert!(buffered
+        .iter()
+        .all(|message| message.epoch() == alice_group.epoch()
+            && message.content_type() == ContentType::Application));
+
+    // The buffer is full.
+    let third = MlsMessageIn::from(third)
+        .try_into_protocol_message()
+        .unwrap();
+    let err = bob_group
+        .process_or_buffer_message(bob_provider, third)
+        .unwrap_err();
+    assert!(matches!(err, ProcessOrBufferMessageError::BufferFull));
+
+    // Bob evicts the first message.
+    let mut seen = 0;
+    let evicted = bob_group
+        .evict_buffered_messages(bob_provider.storage(), |_| {
+            seen += 1;
+            seen == 1
+        })
+        .unwrap();
+    assert_eq!(evicted, 1);
+
+    // The buffered message survives reloading the group.
+    let mut bob_group = MlsGroup::load(bob_provider.storage(), bob_group.group_id())
+        .unwrap()
+        .unwrap();
+
+    // Once Bob merges the commit, the remaining message is replayed.
+    let commit = MlsMessageIn::from(commit)
+        .try_into_protocol_message()
+        .unwrap();
+    let processed = bob_group
+        .process_or_buffer_message(bob_provider, commit)
+        .unwrap()
+        .unwrap();
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = processed.into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    bob_group
+        .merge_staged_commit(bob_provider, *staged_commit)
+        .unwrap();
+
+    // The replayed message stays in the buffer until it is taken.
+    assert_eq!(
+        bob_group
+            .buffered_messages(bob_provider.storage())
+            .unwrap()
+            .len(),
+        1
+    );
+    let replayed = bob_group
+        .take_replayed_messages(bob_provider.storage())
+        .unwrap();
+    assert_eq!(replayed.len(), 1);
+    let ProcessedMessageContent::ApplicationMessage(message) = replayed
+        .into_iter()
+        .next()
+        .unwrap()
+        .into_result()
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected an application message.");
+    };
+    assert_eq!(message.into_bytes(), b"second");
+    assert!(bob_group
+        .take_replayed_messages(bob_provider.storage())
+        .unwrap()
+        .is_empty());
+    assert!(bob_group
+        .buffered_messages(bob_provider.storage())
+        .unwrap()
+        .is_empty());
+}
+
+#[openmls_test]
+fn failed_replays_are_reported() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (mut alice_group, alice_signer, mut bob_group, bob_signer) =
+        setup(ciphersuite, alice_provider, bob_provider);
+
+    // Alice moves to the next epoch and sends an application message, which
+    // Bob buffers.
+    alice_group
+        .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let message = alice_group
+        .create_message(alice_provider, &alice_signer, b"lost")
+        .unwrap();
+    let message = MlsMessageIn::from(message)
+        .try_into_protocol_message()
+        .unwrap();
+    assert!(bob_group
+        .process_or_buffer_message(bob_provider, message)
+        .unwrap()
+        .is_none());
+
+    // Bob merges his own commit instead of Alice's, so the buffered message
+    // can't be decrypted.
+    bob_group
+        .self_update(bob_provider, &bob_signer, LeafNodeParameters::default())
+        .unwrap();
+    bob_group.merge_pending_commit(bob_provider).unwrap();
+
+    let replayed = bob_group
+        .take_replayed_messages(bob_provider.storage())
+        .unwrap();
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].message().epoch(), bob_group.epoch());
+    assert!(replayed[0].result().is_err());
+    assert!(bob_group
+        .buffered_messages(bob_provider.storage())
+        .unwrap()
+        .is_empty());
+}
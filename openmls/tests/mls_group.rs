@@ -341,10 +341,8 @@ fn mls_duplicate_signature_key_detection_different_key_package() {
         match alice_group.commit_to_pending_proposals(alice_provider, &alice_signer) {
             // correct case
             Err(CommitToPendingProposalsError::CreateCommitError(
-                CreateCommitError::ProposalValidationError(
-                    ProposalValidationError::DuplicateSignatureKey,
-                ),
-            )) => (),
+                CreateCommitError::InvalidProposal(report),
+            )) if *report.error() == ProposalValidationError::DuplicateSignatureKey => (),
             // incorrect cases
             Err(e) => panic!("Wrong error type returned: {e:?}."),
             Ok(e) => panic!("Creating commit should fail: {e:?}"),
@@ -0,0 +1,99 @@
+//! Test the padding strategies of private messages.
+use openmls::prelude::*;
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+/// A policy that pads every message by a fixed number of bytes.
+struct AddBytes(usize);
+
+impl PaddingPolicy for AddBytes {
+    fn padding_length(&self, _length: usize) -> usize {
+        self.0
+    }
+}
+
+#[openmls_test]
+fn padding_strategies() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .with_wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+        .application_padding(PaddingStrategy::ConstantSize(1024))
+        .handshake_padding(PaddingStrategy::PowerOfTwo)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    assert_eq!(
+        alice_group.configuration().application_padding(),
+        PaddingStrategy::ConstantSize(1024)
+    );
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::builder()
+            .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+            .build(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    // Application messages of different lengths have the same length.
+    let short = alice_group
+        .create_message(alice_provider, &alice_signer, b"short")
+        .unwrap();
+    let long = alice_group
+        .create_message(alice_provider, &alice_signer, &[0x42; 100])
+        .unwrap();
+    let short_length = short.to_bytes().unwrap().len();
+    assert!(short_length > 1024);
+    assert_eq!(short_length, long.to_bytes().unwrap().len());
+
+    // A custom policy can be used for single messages.
+    let custom = alice_group
+        .create_message_with_padding(alice_provider, &alice_signer, b"short", &AddBytes(7))
+        .unwrap();
+    let unpadded = alice_group
+        .create_message_with_padding(
+            alice_provider,
+            &alice_signer,
+            b"short",
+            &PaddingStrategy::default(),
+        )
+        .unwrap();
+    assert_eq!(
+        custom.to_bytes().unwrap().len(),
+        unpadded.to_bytes().unwrap().len() + 7
+    );
+
+    // The receiver doesn't need to know the padding policy.
+    for message in [short, long, custom] {
+        let message = MlsMessageIn::from(message)
+            .try_into_protocol_message()
+            .unwrap();
+        let processed = bob_group.process_message(bob_provider, message).unwrap();
+        assert!(matches!(
+            processed.into_content(),
+            ProcessedMessageContent::ApplicationMessage(_)
+        ));
+    }
+}
@@ -0,0 +1,119 @@
+//! Test exporting secrets from past epochs.
+use openmls::prelude::*;
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+#[openmls_test]
+fn export_secret_for_past_epoch() {
+    let alice_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .max_past_exporter_epochs(2)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+
+    let mut secrets = vec![];
+    let mut authenticators = vec![];
+    for _ in 0..3 {
+        secrets.push(
+            alice_group
+                .export_secret(alice_provider.crypto(), "label", b"context", 32)
+                .unwrap(),
+        );
+        authenticators.push(alice_group.epoch_authenticator().as_slice().to_vec());
+        alice_group
+            .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+            .unwrap();
+        alice_group.merge_pending_commit(alice_provider).unwrap();
+    }
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(3));
+
+    // Only the two most recent past epochs are kept.
+    assert_eq!(
+        alice_group.export_secret_for_epoch(
+            alice_provider.crypto(),
+            GroupEpoch::from(0),
+            "label",
+            b"context",
+            32
+        ),
+        Err(ExportSecretError::UnknownEpoch)
+    );
+    assert!(alice_group
+        .epoch_authenticator_for_epoch(GroupEpoch::from(0))
+        .is_none());
+    for epoch in 1..3 {
+        assert_eq!(
+            alice_group
+                .export_secret_for_epoch(
+                    alice_provider.crypto(),
+                    GroupEpoch::from(epoch),
+                    "label",
+                    b"context",
+                    32
+                )
+                .unwrap(),
+            secrets[epoch as usize]
+        );
+        assert_eq!(
+            alice_group
+                .epoch_authenticator_for_epoch(GroupEpoch::from(epoch))
+                .unwrap()
+                .as_slice(),
+            authenticators[epoch as usize]
+        );
+    }
+
+    // The current epoch is always available.
+    assert_eq!(
+        alice_group
+            .export_secret_for_epoch(
+                alice_provider.crypto(),
+                alice_group.epoch(),
+                "label",
+                b"context",
+                32
+            )
+            .unwrap(),
+        alice_group
+            .export_secret(alice_provider.crypto(), "label", b"context", 32)
+            .unwrap()
+    );
+
+    // Deleted secrets are gone, also after reloading the group.
+    assert!(alice_group
+        .delete_past_exporter_secrets(alice_provider.storage(), GroupEpoch::from(1))
+        .unwrap());
+    assert!(!alice_group
+        .delete_past_exporter_secrets(alice_provider.storage(), GroupEpoch::from(1))
+        .unwrap());
+    let alice_group = MlsGroup::load(alice_provider.storage(), alice_group.group_id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        alice_group.export_secret_for_epoch(
+            alice_provider.crypto(),
+            GroupEpoch::from(1),
+            "label",
+            b"context",
+            32
+        ),
+        Err(ExportSecretError::UnknownEpoch)
+    );
+    assert_eq!(
+        alice_group
+            .export_secret_for_epoch(
+                alice_provider.crypto(),
+                GroupEpoch::from(2),
+                "label",
+                b"context",
+                32
+            )
+            .unwrap(),
+        secrets[2]
+    );
+}
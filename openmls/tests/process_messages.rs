@@ -0,0 +1,184 @@
+//! Test processing of message batches.
+use openmls::prelude::*;
+use openmls::test_utils::{generate_credential_with_key, generate_key_package};
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn key_package<Provider: OpenMlsProvider>(
+    ciphersuite: Ciphersuite,
+    provider: &Provider,
+    identity: &[u8],
+) -> KeyPackage {
+    let credential_with_key = generate_credential_with_key(
+        identity.to_vec(),
+        ciphersuite.signature_algorithm(),
+        provider,
+    );
+    generate_key_package(
+        ciphersuite,
+        Extensions::empty(),
+        provider,
+        credential_with_key,
+    )
+    .key_package()
+    .clone()
+}
+
+fn protocol_message(message: MlsMessageOut) -> ProtocolMessage {
+    MlsMessageIn::from(message)
+        .try_into_protocol_message()
+        .unwrap()
+}
+
+#[openmls_test]
+fn process_batch_with_default_policy() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let charlie_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            alice_provider,
+            &alice_signer,
+            &[key_package(ciphersuite, bob_provider, b"Bob")],
+        )
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::default(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    // While Bob is offline, Alice proposes to add Charlie, commits the
+    // proposal, sends an application message and proposes to add Dave.
+    let (add_charlie, _) = alice_group
+        .propose_add_member(
+            alice_provider,
+            &alice_signer,
+            &key_package(ciphersuite, charlie_provider, b"Charlie"),
+        )
+        .unwrap();
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(alice_provider, &alice_signer)
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let message = alice_group
+        .create_message(alice_provider, &alice_signer, b"hello")
+        .unwrap();
+    let (add_dave, _) = alice_group
+        .propose_add_member(
+            alice_provider,
+            &alice_signer,
+            &key_package(ciphersuite, charlie_provider, b"Dave"),
+        )
+        .unwrap();
+
+    // The application message is delivered twice, which must fail.
+    let batch = [add_charlie, commit, message.clone(), message, add_dave]
+        .into_iter()
+        .map(protocol_message);
+    let outcomes = bob_group
+        .process_messages(bob_provider, batch, ProcessingPolicy::default())
+        .unwrap();
+
+    assert_eq!(outcomes.len(), 5);
+    assert!(matches!(outcomes[0], MessageOutcome::ProposalStored(_)));
+    assert!(matches!(outcomes[1], MessageOutcome::CommitMerged(epoch) if epoch.as_u64() == 2));
+    let MessageOutcome::ApplicationMessage(processed_message) = &outcomes[2] else {
+        panic!("Expected an application message.");
+    };
+    assert_eq!(
+        processed_message.sender(),
+        &Sender::Member(LeafNodeIndex::new(0))
+    );
+    assert!(matches!(outcomes[3], MessageOutcome::Failed(_)));
+    assert!(matches!(outcomes[4], MessageOutcome::ProposalStored(_)));
+
+    assert_eq!(bob_group.epoch(), alice_group.epoch());
+    assert_eq!(bob_group.members().count(), 3);
+
+    // The proposal from the new epoch was written to storage.
+    let bob_group = MlsGroup::load(bob_provider.storage(), bob_group.group_id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(bob_group.pending_proposals().count(), 1);
+}
+
+#[openmls_test]
+fn process_batch_without_merging_commits() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            alice_provider,
+            &alice_signer,
+            &[key_package(ciphersuite, bob_provider, b"Bob")],
+        )
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::default(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    let commit = alice_group
+        .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+        .unwrap()
+        .into_commit();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let message = alice_group
+        .create_message(alice_provider, &alice_signer, b"hello")
+        .unwrap();
+
+    // The commit is left to Bob, so the message for the next epoch fails.
+    let policy = ProcessingPolicy::default().merge_commits(false);
+    let outcomes = bob_group
+        .process_messages(
+            bob_provider,
+            [commit, message].into_iter().map(protocol_message),
+            policy,
+        )
+        .unwrap();
+
+    assert_eq!(outcomes.len(), 2);
+    let MessageOutcome::Processed(processed_message) = &outcomes[0] else {
+        panic!("Expected an unmerged commit.");
+    };
+    assert!(matches!(
+        processed_message.content(),
+        ProcessedMessageContent::StagedCommitMessage(_)
+    ));
+    assert!(matches!(outcomes[1], MessageOutcome::Failed(_)));
+    assert_eq!(bob_group.epoch().as_u64(), 1);
+}
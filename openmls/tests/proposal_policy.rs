@@ -0,0 +1,422 @@
+//! Test the authorization of proposals with a proposal policy.
+use std::sync::{Arc, Mutex};
+
+use openmls::prelude::*;
+use openmls_test::openmls_test;
+use openmls_traits::signatures::Signer;
+use test_utils::new_credential;
+
+/// Rejects all Add proposals.
+struct NoAdds;
+
+impl ProposalPolicy for NoAdds {
+    fn check(
+        &self,
+        _group: &PublicGroup,
+        _sender: &Sender,
+        proposal: &Proposal,
+        _delivery: ProposalDelivery<'_>,
+    ) -> Result<(), ProposalRejection> {
+        if proposal.proposal_type() == ProposalType::Add {
+            return Err(ProposalRejection::new("Adds are not allowed"));
+        }
+        Ok(())
+    }
+}
+
+/// The sender, the proposal type, how the proposal was sent and the committer,
+/// if any.
+type Record = (Sender, ProposalType, &'static str, Option<Sender>);
+
+/// Accepts all proposals and records them. Clones share the records.
+#[derive(Default, Clone)]
+struct Recorder(Arc<Mutex<Vec<Record>>>);
+
+impl Recorder {
+    fn take(&self) -> Vec<Record> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl ProposalPolicy for Recorder {
+    fn check(
+        &self,
+        group: &PublicGroup,
+        sender: &Sender,
+        proposal: &Proposal,
+        delivery: ProposalDelivery<'_>,
+    ) -> Result<(), ProposalRejection> {
+        // The policy sees the group before the message is applied.
+        assert_eq!(group.members().count(), 2);
+        let (delivery, committer) = match delivery {
+            ProposalDelivery::Standalone => ("standalone", None),
+            ProposalDelivery::Inline => ("inline", None),
+            ProposalDelivery::Reference { committer } => ("reference", Some(committer.clone())),
+        };
+        self.0.lock().unwrap().push((
+            sender.clone(),
+            proposal.proposal_type(),
+            delivery,
+            committer,
+        ));
+        Ok(())
+    }
+}
+
+fn into_protocol_message(message: MlsMessageOut) -> ProtocolMessage {
+    MlsMessageIn::from(message)
+        .try_into_protocol_message()
+        .unwrap()
+}
+
+fn assert_add_rejected(err: ProcessMessageError, sender: &Sender) {
+    let ProcessMessageError::ProposalRejected {
+        sender: rejected_sender,
+        proposal_type,
+        rejection,
+    } = err
+    else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(&rejected_sender, sender);
+    assert_eq!(proposal_type, ProposalType::Add);
+    assert_eq!(rejection.reason(), "Adds are not allowed");
+}
+
+/// Alice creates a group and adds Bob. Both use the plaintext wire format, so
+/// that Bob can process the same message more than once. Bob buffers messages
+/// for future epochs. Also returns a key package of Charlie.
+fn setup<Provider: OpenMlsProvider + Default>(
+    ciphersuite: Ciphersuite,
+    alice_provider: &Provider,
+    bob_provider: &Provider,
+) -> (MlsGroup, impl Signer, MlsGroup, KeyPackage) {
+    let charlie_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let (charlie_credential, charlie_signer) = new_credential(
+        charlie_provider,
+        b"Charlie",
+        ciphersuite.signature_algorithm(),
+    );
+
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+    let charlie_key_package = KeyPackage::builder()
+        .build(
+            ciphersuite,
+            charlie_provider,
+            &charlie_signer,
+            charlie_credential,
+        )
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .with_wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::builder()
+            .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+            .message_buffer_config(MessageBufferConfig::default())
+            .build(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    (alice_group, alice_signer, bob_group, charlie_key_package)
+}
+
+#[openmls_test]
+fn proposal_policy() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (mut alice_group, alice_signer, mut bob_group, charlie_key_package) =
+        setup(ciphersuite, alice_provider, bob_provider);
+    let alice_sender = Sender::Member(alice_group.own_leaf_index());
+
+    // A standalone proposal is checked when it is received.
+    let (proposal, _proposal_ref) = alice_group
+        .propose_add_member(alice_provider, &alice_signer, &charlie_key_package)
+        .unwrap();
+    let proposal = into_protocol_message(proposal);
+    bob_group.set_proposal_policy(NoAdds);
+    let err = bob_group
+        .process_message(bob_provider, proposal.clone())
+        .unwrap_err();
+    assert_add_rejected(err, &alice_sender);
+
+    let recorder = Recorder::default();
+    bob_group.set_proposal_policy(recorder.clone());
+    let ProcessedMessageContent::ProposalMessage(queued_proposal) = bob_group
+        .process_message(bob_provider, proposal)
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected a proposal.");
+    };
+    assert_eq!(
+        recorder.take(),
+        vec![(alice_sender.clone(), ProposalType::Add, "standalone", None)]
+    );
+    bob_group
+        .store_pending_proposal(bob_provider.storage(), *queued_proposal)
+        .unwrap();
+
+    // Proposals covered by reference are checked again when committed.
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(alice_provider, &alice_signer)
+        .unwrap();
+    let commit = into_protocol_message(commit);
+    bob_group
+        .process_message(bob_provider, commit.clone())
+        .unwrap();
+    assert_eq!(
+        recorder.take(),
+        vec![(
+            alice_sender.clone(),
+            ProposalType::Add,
+            "reference",
+            Some(alice_sender.clone())
+        )]
+    );
+    bob_group.set_proposal_policy(NoAdds);
+    let err = bob_group.process_message(bob_provider, commit).unwrap_err();
+    assert_add_rejected(err, &alice_sender);
+
+    // Inline proposals are checked with the committer as sender.
+    alice_group
+        .clear_pending_commit(alice_provider.storage())
+        .unwrap();
+    let commit = alice_group
+        .commit_builder()
+        .consume_proposal_store(false)
+        .propose_adds([charlie_key_package])
+        .load_psks(alice_provider.storage())
+        .unwrap()
+        .build(
+            alice_provider.rand(),
+            alice_provider.crypto(),
+            &alice_signer,
+            |_| true,
+        )
+        .unwrap()
+        .stage_commit(alice_provider)
+        .unwrap()
+        .into_commit();
+    let commit = into_protocol_message(commit);
+    let err = bob_group
+        .process_message(bob_provider, commit.clone())
+        .unwrap_err();
+    assert_add_rejected(err, &alice_sender);
+
+    bob_group.set_proposal_policy(recorder.clone());
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = bob_group
+        .process_message(bob_provider, commit)
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    assert_eq!(
+        recorder.take(),
+        vec![(alice_sender, ProposalType::Add, "inline", None)]
+    );
+    bob_group
+        .merge_staged_commit(bob_provider, *staged_commit)
+        .unwrap();
+    assert_eq!(bob_group.members().count(), 3);
+}
+
+#[openmls_test]
+fn missing_referenced_proposal_is_rejected() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (mut alice_group, alice_signer, mut bob_group, charlie_key_package) =
+        setup(ciphersuite, alice_provider, bob_provider);
+    let recorder = Recorder::default();
+    bob_group.set_proposal_policy(recorder.clone());
+
+    // Bob processes the proposal, but doesn't store it.
+    let (proposal, _proposal_ref) = alice_group
+        .propose_add_member(alice_provider, &alice_signer, &charlie_key_package)
+        .unwrap();
+    bob_group
+        .process_message(bob_provider, into_protocol_message(proposal))
+        .unwrap();
+    assert_eq!(recorder.take().len(), 1);
+
+    // The commit covers a proposal the policy can't check.
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(alice_provider, &alice_signer)
+        .unwrap();
+    let err = bob_group
+        .process_message(bob_provider, into_protocol_message(commit))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ProcessMessageError::InvalidCommit(StageCommitError::MissingProposal)
+    ));
+    assert!(recorder.take().is_empty());
+}
+
+#[openmls_test]
+fn buffered_and_batched_messages_are_checked() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (mut alice_group, alice_signer, mut bob_group, charlie_key_package) =
+        setup(ciphersuite, alice_provider, bob_provider);
+    bob_group.set_proposal_policy(NoAdds);
+    assert!(bob_group.has_proposal_policy());
+
+    // Alice moves to the next epoch and proposes to add Charlie there.
+    let commit = alice_group
+        .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+        .unwrap()
+        .into_commit();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let (proposal, _proposal_ref) = alice_group
+        .propose_add_member(alice_provider, &alice_signer, &charlie_key_package)
+        .unwrap();
+    let proposal = into_protocol_message(proposal);
+
+    // Bob receives the proposal before the commit and buffers it.
+    assert!(bob_group
+        .process_or_buffer_message(bob_provider, proposal.clone())
+        .unwrap()
+        .is_none());
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = bob_group
+        .process_or_buffer_message(bob_provider, into_protocol_message(commit))
+        .unwrap()
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    bob_group
+        .merge_staged_commit(bob_provider, *staged_commit)
+        .unwrap();
+
+    // The policy rejects the buffered proposal when it is replayed.
+    let replayed = bob_group
+        .take_replayed_messages(bob_provider.storage())
+        .unwrap();
+    let [replayed] = <[_; 1]>::try_from(replayed).unwrap();
+    assert_add_rejected(
+        replayed.into_result().unwrap_err(),
+        &Sender::Member(alice_group.own_leaf_index()),
+    );
+    assert!(bob_group
+        .buffered_messages(bob_provider.storage())
+        .unwrap()
+        .is_empty());
+
+    // The policy also applies to batches.
+    let outcomes = bob_group
+        .process_messages(bob_provider, [proposal], ProcessingPolicy::default())
+        .unwrap();
+    let [MessageOutcome::Failed(err)] = <[_; 1]>::try_from(outcomes).unwrap() else {
+        panic!("Expected the proposal to fail.");
+    };
+    assert_add_rejected(err, &Sender::Member(alice_group.own_leaf_index()));
+    assert_eq!(bob_group.pending_proposals().count(), 0);
+
+    // Without the policy, the proposal is accepted.
+    bob_group.clear_proposal_policy();
+    assert!(!bob_group.has_proposal_policy());
+    let proposal = alice_group
+        .propose_add_member(alice_provider, &alice_signer, &charlie_key_package)
+        .unwrap()
+        .0;
+    let outcomes = bob_group
+        .process_messages(
+            bob_provider,
+            [into_protocol_message(proposal)],
+            ProcessingPolicy::default(),
+        )
+        .unwrap();
+    assert!(matches!(
+        outcomes.as_slice(),
+        [MessageOutcome::ProposalStored(_)]
+    ));
+}
+
+#[openmls_test]
+fn required_policy_is_not_lost_on_load() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (mut alice_group, alice_signer, mut bob_group, charlie_key_package) =
+        setup(ciphersuite, alice_provider, bob_provider);
+    let alice_sender = Sender::Member(alice_group.own_leaf_index());
+    bob_group
+        .set_configuration(
+            bob_provider.storage(),
+            &MlsGroupJoinConfig::builder()
+                .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+                .message_buffer_config(MessageBufferConfig::default())
+                .require_proposal_policy(true)
+                .build(),
+        )
+        .unwrap();
+    bob_group.set_proposal_policy(NoAdds);
+
+    let message = alice_group
+        .create_message(alice_provider, &alice_signer, b"Hello")
+        .unwrap();
+    let (proposal, _proposal_ref) = alice_group
+        .propose_add_member(alice_provider, &alice_signer, &charlie_key_package)
+        .unwrap();
+    let proposal = into_protocol_message(proposal);
+
+    // The policy isn't persisted, so a loaded group rejects handshake
+    // messages until it is set again.
+    let mut bob_group = MlsGroup::load(bob_provider.storage(), bob_group.group_id())
+        .unwrap()
+        .unwrap();
+    assert!(!bob_group.has_proposal_policy());
+    assert_eq!(
+        bob_group
+            .process_message(bob_provider, proposal.clone())
+            .unwrap_err(),
+        ProcessMessageError::MissingProposalPolicy
+    );
+
+    // Application messages don't contain proposals and are still processed.
+    bob_group
+        .process_message(bob_provider, into_protocol_message(message))
+        .unwrap();
+
+    let mut bob_group =
+        MlsGroup::load_with_policy(bob_provider.storage(), bob_group.group_id(), NoAdds)
+            .unwrap()
+            .unwrap();
+    assert!(bob_group.has_proposal_policy());
+    let err = bob_group
+        .process_message(bob_provider, proposal)
+        .unwrap_err();
+    assert_add_rejected(err, &alice_sender);
+}
@@ -0,0 +1,161 @@
+//! Test the validation reports for proposals and the dry-run validation of
+//! candidate proposals.
+use openmls::prelude::*;
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn invalid_proposal(err: ValidateProposalsError) -> ProposalValidationReport {
+    match err {
+        ValidateProposalsError::InvalidProposal(report) => report,
+        e => panic!("Unexpected error: {e:?}"),
+    }
+}
+
+#[openmls_test]
+fn proposal_validation_report() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let charlie_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let (charlie_credential, charlie_signer) = new_credential(
+        charlie_provider,
+        b"Charlie",
+        ciphersuite.signature_algorithm(),
+    );
+
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+    let charlie_key_package = KeyPackage::builder()
+        .build(
+            ciphersuite,
+            charlie_provider,
+            &charlie_signer,
+            charlie_credential,
+        )
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    alice_group
+        .add_members(
+            alice_provider,
+            &alice_signer,
+            std::slice::from_ref(&bob_key_package),
+        )
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let bob_index = alice_group
+        .members()
+        .find(|member| member.index != alice_group.own_leaf_index())
+        .unwrap()
+        .index;
+
+    // A valid set of proposals passes.
+    alice_group
+        .commit_builder()
+        .propose_adds([charlie_key_package.clone()])
+        .validate_proposals(alice_provider.crypto())
+        .unwrap();
+
+    // Adding Bob again reuses his signature key.
+    let report = invalid_proposal(
+        alice_group
+            .commit_builder()
+            .propose_adds([bob_key_package])
+            .validate_proposals(alice_provider.crypto())
+            .unwrap_err(),
+    );
+    assert_eq!(
+        report.error(),
+        &ProposalValidationError::DuplicateSignatureKey
+    );
+    assert_eq!(report.rule(), ProposalValidationRule::ValSem101);
+    assert_eq!(report.proposal_index(), Some(0));
+    assert!(report.proposal_ref().is_some());
+    assert_eq!(report.leaves(), &[bob_index]);
+
+    // Removing a leaf that isn't in the tree.
+    let unknown_leaf = LeafNodeIndex::new(5);
+    let report = invalid_proposal(
+        alice_group
+            .commit_builder()
+            .propose_removals([unknown_leaf])
+            .validate_proposals(alice_provider.crypto())
+            .unwrap_err(),
+    );
+    assert_eq!(
+        report.error(),
+        &ProposalValidationError::UnknownMemberRemoval
+    );
+    assert_eq!(report.rule(), ProposalValidationRule::ValSem108);
+    assert_eq!(report.leaves(), &[unknown_leaf]);
+
+    // Candidate proposals are validated together with the pending proposals.
+    let (_proposal, pending_ref) = alice_group
+        .propose_add_member(alice_provider, &alice_signer, &charlie_key_package)
+        .unwrap();
+    alice_group
+        .validate_proposals(alice_provider.crypto(), [])
+        .unwrap();
+    let report = invalid_proposal(
+        alice_group
+            .commit_builder()
+            .propose_adds([charlie_key_package.clone()])
+            .validate_proposals(alice_provider.crypto())
+            .unwrap_err(),
+    );
+    assert_eq!(
+        report.error(),
+        &ProposalValidationError::DuplicateSignatureKey
+    );
+    assert_eq!(report.proposal_index(), Some(1));
+    assert_ne!(report.proposal_ref(), Some(&pending_ref));
+    assert!(report.leaves().is_empty());
+    alice_group
+        .commit_builder()
+        .consume_proposal_store(false)
+        .propose_adds([charlie_key_package])
+        .validate_proposals(alice_provider.crypto())
+        .unwrap();
+
+    // Commits that fail validation report the failing proposal, too.
+    let Err(err) = alice_group
+        .commit_builder()
+        .propose_removals([unknown_leaf])
+        .load_psks(alice_provider.storage())
+        .unwrap()
+        .build(
+            alice_provider.rand(),
+            alice_provider.crypto(),
+            &alice_signer,
+            |_| true,
+        )
+    else {
+        panic!("Expected an invalid proposal.");
+    };
+    let CreateCommitError::InvalidProposal(report) = err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert_eq!(
+        report.error(),
+        &ProposalValidationError::UnknownMemberRemoval
+    );
+    assert_eq!(report.rule(), ProposalValidationRule::ValSem108);
+    // The pending proposal to add Charlie comes first.
+    assert_eq!(report.proposal_index(), Some(1));
+    assert_eq!(report.leaves(), &[unknown_leaf]);
+    assert!(report
+        .to_string()
+        .starts_with("Proposal validation failed (ValSem108)"));
+}
@@ -0,0 +1,91 @@
+//! Test the puncturable exporter.
+use openmls::prelude::*;
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+#[openmls_test]
+fn safe_export_secret() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::default(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    // Both members export the same secrets, which differ per component.
+    let alice_secret = alice_group
+        .safe_export_secret(alice_provider, 1, "label", b"context", 32)
+        .unwrap();
+    let bob_secret = bob_group
+        .safe_export_secret(bob_provider, 1, "label", b"context", 32)
+        .unwrap();
+    assert_eq!(alice_secret, bob_secret);
+    assert_eq!(alice_secret.len(), 32);
+    let alice_component_secret = alice_group
+        .safe_export_secret(alice_provider, 2, "label", b"context", 32)
+        .unwrap();
+    assert_ne!(alice_component_secret, alice_secret);
+
+    // The secret depends on the label, the context and the length.
+    let bob_component_secret = bob_group
+        .safe_export_secret(bob_provider, 2, "other label", b"context", 16)
+        .unwrap();
+    assert_eq!(bob_component_secret.len(), 16);
+    assert_ne!(bob_component_secret, alice_component_secret[..16]);
+
+    // Each secret can be exported only once, also after reloading the group.
+    assert_eq!(
+        alice_group.safe_export_secret(alice_provider, 1, "label", b"context", 32),
+        Err(SafeExportSecretError::AlreadyExported)
+    );
+    let mut alice_group = MlsGroup::load(alice_provider.storage(), alice_group.group_id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        alice_group.safe_export_secret(alice_provider, 2, "other label", b"", 32),
+        Err(SafeExportSecretError::AlreadyExported)
+    );
+    assert_eq!(
+        alice_group.safe_export_secret(alice_provider, 3, "label", b"", u16::MAX as usize + 1),
+        Err(SafeExportSecretError::KeyLengthTooLong)
+    );
+
+    // The next epoch has a new exporter tree.
+    alice_group
+        .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    assert_ne!(
+        alice_group
+            .safe_export_secret(alice_provider, 1, "label", b"context", 32)
+            .unwrap(),
+        alice_secret
+    );
+}
@@ -0,0 +1,214 @@
+//! Test the diagnostics of the sender ratchets of MlsGroup.
+use openmls::prelude::*;
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn rejected_generation(err: ProcessMessageError) -> SecretTreeError {
+    match err {
+        ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
+            MessageDecryptionError::SecretTreeError(e),
+        )) => e,
+        e => panic!("Unexpected error: {e:?}"),
+    }
+}
+
+#[openmls_test]
+fn sender_ratchet_diagnostics() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    // Bob tolerates three messages out of order and skips at most three.
+    let join_config = MlsGroupJoinConfig::builder()
+        .sender_ratchet_configuration(SenderRatchetConfiguration::new(3, 3))
+        .build();
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(bob_provider, &join_config, welcome, None)
+        .unwrap()
+        .into_group(bob_provider)
+        .unwrap();
+    assert!(bob_group.sender_ratchet_states().is_empty());
+
+    let messages: Vec<ProtocolMessage> = (0..8)
+        .map(|_| {
+            let message = alice_group
+                .create_message(alice_provider, &alice_signer, b"message")
+                .unwrap();
+            MlsMessageIn::from(message)
+                .try_into_protocol_message()
+                .unwrap()
+        })
+        .collect();
+
+    // Bob receives the third message first and caches the keys of the first
+    // two.
+    bob_group
+        .process_message(bob_provider, messages[2].clone())
+        .unwrap();
+    let states = bob_group.sender_ratchet_states();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].sender(), alice_group.own_leaf_index());
+    assert_eq!(states[0].ratchet_type(), SenderRatchetType::Application);
+    assert_eq!(states[0].generation(), 3);
+    assert_eq!(states[0].cached_keys(), 2);
+
+    bob_group
+        .process_message(bob_provider, messages[0].clone())
+        .unwrap();
+    assert_eq!(bob_group.sender_ratchet_states()[0].cached_keys(), 1);
+    assert_eq!(
+        bob_group.sender_ratchet_states_for_epoch(bob_group.epoch()),
+        Some(bob_group.sender_ratchet_states())
+    );
+
+    // A replayed message is reported with the window of the ratchet.
+    let err = bob_group
+        .process_message(bob_provider, messages[0].clone())
+        .unwrap_err();
+    let SecretTreeError::GenerationReused(rejected) = rejected_generation(err) else {
+        panic!("Expected a reused generation.");
+    };
+    assert_eq!(rejected.sender(), alice_group.own_leaf_index());
+    assert_eq!(rejected.ratchet_type(), SenderRatchetType::Application);
+    assert_eq!(rejected.generation(), 0);
+    assert_eq!(rejected.current_generation(), 3);
+    assert_eq!(rejected.oldest_generation(), 0);
+    assert_eq!(rejected.newest_generation(), 6);
+
+    // Messages beyond the maximum forward distance are rejected.
+    let err = bob_group
+        .process_message(bob_provider, messages[7].clone())
+        .unwrap_err();
+    let SecretTreeError::GenerationTooNew(rejected) = rejected_generation(err) else {
+        panic!("Expected a generation that is too new.");
+    };
+    assert_eq!(rejected.generation(), 7);
+    assert_eq!(rejected.newest_generation(), 6);
+
+    // Once the ratchet moved on, old messages are too old.
+    bob_group
+        .process_message(bob_provider, messages[6].clone())
+        .unwrap();
+    let err = bob_group
+        .process_message(bob_provider, messages[1].clone())
+        .unwrap_err();
+    let SecretTreeError::GenerationTooOld(rejected) = rejected_generation(err) else {
+        panic!("Expected a generation that is too old.");
+    };
+    assert_eq!(rejected.generation(), 1);
+    assert_eq!(rejected.current_generation(), 7);
+    assert_eq!(rejected.oldest_generation(), 4);
+
+    // Past epochs that aren't kept don't report any state.
+    assert!(bob_group
+        .sender_ratchet_states_for_epoch(GroupEpoch::from(0))
+        .is_none());
+}
+
+#[openmls_test]
+fn message_key_cache() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    // Bob configures application messages separately and caches at most two
+    // keys.
+    let join_config = MlsGroupJoinConfig::builder()
+        .application_sender_ratchet_configuration(SenderRatchetConfiguration::new(10, 20))
+        .message_key_cache_config(MessageKeyCacheConfig::new(2))
+        .build();
+    assert_eq!(
+        join_config.application_sender_ratchet_configuration(),
+        &SenderRatchetConfiguration::new(10, 20)
+    );
+    assert_eq!(
+        join_config.handshake_sender_ratchet_configuration(),
+        &SenderRatchetConfiguration::default()
+    );
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(bob_provider, &join_config, welcome, None)
+        .unwrap()
+        .into_group(bob_provider)
+        .unwrap();
+
+    let messages: Vec<ProtocolMessage> = (0..12)
+        .map(|_| {
+            let message = alice_group
+                .create_message(alice_provider, &alice_signer, b"message")
+                .unwrap();
+            MlsMessageIn::from(message)
+                .try_into_protocol_message()
+                .unwrap()
+        })
+        .collect();
+
+    // The application ratchet tolerates ten messages out of order, but only
+    // the keys of the two most recent generations are kept.
+    bob_group
+        .process_message(bob_provider, messages[11].clone())
+        .unwrap();
+    let application_state = |group: &MlsGroup| {
+        group
+            .sender_ratchet_states()
+            .into_iter()
+            .find(|state| state.ratchet_type() == SenderRatchetType::Application)
+            .unwrap()
+    };
+    assert_eq!(application_state(&bob_group).generation(), 12);
+    assert_eq!(application_state(&bob_group).cached_keys(), 2);
+
+    let err = bob_group
+        .process_message(bob_provider, messages[8].clone())
+        .unwrap_err();
+    assert!(matches!(
+        rejected_generation(err),
+        SecretTreeError::GenerationEvicted(_)
+    ));
+    bob_group
+        .process_message(bob_provider, messages[9].clone())
+        .unwrap();
+    bob_group
+        .process_message(bob_provider, messages[10].clone())
+        .unwrap();
+    assert_eq!(application_state(&bob_group).cached_keys(), 0);
+}
@@ -0,0 +1,134 @@
+//! Test the SFrame key management on top of MlsGroup.
+use openmls::{
+    prelude::*,
+    sframe::{errors::SframeError, *},
+};
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn process<Provider: OpenMlsProvider>(
+    group: &mut MlsGroup,
+    provider: &Provider,
+    message: MlsMessageOut,
+) {
+    let message = MlsMessageIn::from(message)
+        .try_into_protocol_message()
+        .unwrap();
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) = group
+        .process_message(provider, message)
+        .unwrap()
+        .into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    group.merge_staged_commit(provider, *staged_commit).unwrap();
+}
+
+#[openmls_test]
+fn sframe_protect_unprotect() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::default(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    let config = SframeConfig::new(SframeCipherSuite::Aes128GcmSha256_128).max_past_epochs(1);
+    let mut alice_sframe =
+        SframeContext::new(alice_provider.crypto(), config, &alice_group).unwrap();
+    let mut bob_sframe = SframeContext::new(bob_provider.crypto(), config, &bob_group).unwrap();
+
+    let frame = match alice_sframe.protect(alice_provider.crypto(), b"metadata", b"audio frame") {
+        Ok(frame) => frame,
+        // Not every provider implements AES-GCM.
+        Err(SframeError::UnsupportedCipherSuite) => return,
+        Err(e) => panic!("Unexpected error: {e:?}"),
+    };
+    let (plaintext, epoch, sender) = bob_sframe
+        .unprotect(bob_provider.crypto(), b"metadata", &frame)
+        .unwrap();
+    assert_eq!(plaintext, b"audio frame");
+    assert_eq!(epoch, alice_group.epoch());
+    assert_eq!(sender, alice_group.own_leaf_index());
+    assert_eq!(
+        bob_sframe.unprotect(bob_provider.crypto(), b"other metadata", &frame),
+        Err(SframeError::DecryptionFailed)
+    );
+
+    // Bob's frames have a different KID and counter.
+    let bob_frame = bob_sframe
+        .protect(bob_provider.crypto(), b"", b"video frame")
+        .unwrap();
+    let (header, _) = SframeHeader::decode(&bob_frame).unwrap();
+    assert_eq!(
+        header.kid(),
+        bob_sframe.kid(bob_group.epoch(), bob_group.own_leaf_index())
+    );
+    assert_eq!(
+        alice_sframe.sender(header.kid()),
+        Some((bob_group.epoch(), bob_group.own_leaf_index()))
+    );
+
+    // Frames of the previous epoch can be decrypted after one epoch change,
+    // but not after two.
+    for epochs in 1..=2 {
+        let commit = bob_group
+            .self_update(bob_provider, &bob_signer, LeafNodeParameters::default())
+            .unwrap()
+            .into_commit();
+        bob_group.merge_pending_commit(bob_provider).unwrap();
+        process(&mut alice_group, alice_provider, commit);
+        alice_sframe
+            .update(alice_provider.crypto(), &alice_group)
+            .unwrap();
+        bob_sframe
+            .update(bob_provider.crypto(), &bob_group)
+            .unwrap();
+
+        let result = bob_sframe.unprotect(bob_provider.crypto(), b"metadata", &frame);
+        if epochs == 1 {
+            assert_eq!(result.unwrap().0, b"audio frame");
+        } else {
+            assert_eq!(result, Err(SframeError::UnknownKeyId));
+        }
+    }
+
+    // Frames of the current epoch still work.
+    let frame = alice_sframe
+        .protect(alice_provider.crypto(), b"", b"audio frame")
+        .unwrap();
+    assert_eq!(
+        bob_sframe
+            .unprotect(bob_provider.crypto(), b"", &frame)
+            .unwrap()
+            .1,
+        bob_group.epoch()
+    );
+}
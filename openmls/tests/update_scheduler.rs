@@ -0,0 +1,154 @@
+//! Test the recording and scheduling of own leaf updates.
+use openmls::{prelude::*, test_utils::TestClock};
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn process<Provider: OpenMlsProvider>(
+    group: &mut MlsGroup,
+    provider: &Provider,
+    message: MlsMessageOut,
+) -> ProcessedMessageContent {
+    let message = MlsMessageIn::from(message)
+        .try_into_protocol_message()
+        .unwrap();
+    group
+        .process_message(provider, message)
+        .unwrap()
+        .into_content()
+}
+
+#[openmls_test]
+fn overdue_updates_are_reported() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+    let (bob_credential, bob_signer) =
+        new_credential(bob_provider, b"Bob", ciphersuite.signature_algorithm());
+    let bob_key_package = KeyPackage::builder()
+        .build(ciphersuite, bob_provider, &bob_signer, bob_credential)
+        .unwrap()
+        .key_package()
+        .clone();
+
+    let clock = TestClock::new(1000);
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .with_clock(clock.clone())
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let own_leaf_update = alice_group.own_leaf_update().unwrap();
+    assert_eq!(own_leaf_update.epoch(), GroupEpoch::from(0));
+    assert_eq!(own_leaf_update.timestamp(), 1000);
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_provider, &alice_signer, &[bob_key_package])
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let mut bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::default(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .with_clock(clock.clone())
+    .into_group(bob_provider)
+    .unwrap();
+    assert_eq!(
+        bob_group.own_leaf_update().unwrap().epoch(),
+        GroupEpoch::from(1)
+    );
+
+    // Bob's updates happen a minute later.
+    clock.set(1060);
+
+    // Bob updates his leaf twice.
+    for _ in 0..2 {
+        let commit = bob_group
+            .self_update(bob_provider, &bob_signer, LeafNodeParameters::default())
+            .unwrap()
+            .into_commit();
+        bob_group.merge_pending_commit(bob_provider).unwrap();
+        let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+            process(&mut alice_group, alice_provider, commit)
+        else {
+            panic!("Expected a commit.");
+        };
+        alice_group
+            .merge_staged_commit(alice_provider, *staged_commit)
+            .unwrap();
+    }
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(3));
+    let own_leaf_update = bob_group.own_leaf_update().unwrap();
+    assert_eq!(own_leaf_update.epoch(), GroupEpoch::from(3));
+    assert_eq!(own_leaf_update.timestamp(), 1060);
+
+    // Only Alice's update is overdue.
+    let scheduler = UpdateScheduler::new().max_epochs(2);
+    assert!(scheduler.is_update_due(&alice_group));
+    assert!(!scheduler.is_update_due(&bob_group));
+    assert_eq!(
+        scheduler.overdue_groups([&alice_group]),
+        vec![alice_group.group_id().clone()]
+    );
+
+    // Bob's Update proposal, committed by Alice, counts as an update of his
+    // leaf and the commit updates Alice's leaf.
+    let (proposal, _) = bob_group
+        .propose_self_update(bob_provider, &bob_signer, LeafNodeParameters::default())
+        .unwrap();
+    let ProcessedMessageContent::ProposalMessage(queued_proposal) =
+        process(&mut alice_group, alice_provider, proposal)
+    else {
+        panic!("Expected a proposal.");
+    };
+    alice_group
+        .store_pending_proposal(alice_provider.storage(), *queued_proposal)
+        .unwrap();
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(alice_provider, &alice_signer)
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        process(&mut bob_group, bob_provider, commit)
+    else {
+        panic!("Expected a commit.");
+    };
+    bob_group
+        .merge_staged_commit(bob_provider, *staged_commit)
+        .unwrap();
+
+    assert_eq!(
+        bob_group.own_leaf_update().unwrap().epoch(),
+        GroupEpoch::from(4)
+    );
+    assert!(!scheduler.is_update_due(&alice_group));
+
+    // The record survives reloading the group.
+    let alice_group = MlsGroup::load(alice_provider.storage(), alice_group.group_id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        alice_group.own_leaf_update().unwrap().epoch(),
+        GroupEpoch::from(4)
+    );
+
+    // An hour after the last updates, both are overdue by age.
+    let scheduler = UpdateScheduler::with_clock(clock.clone()).max_age(3600);
+    assert!(scheduler
+        .overdue_groups([&alice_group, &bob_group])
+        .is_empty());
+    clock.set(1060 + 3599);
+    assert!(scheduler
+        .overdue_groups([&alice_group, &bob_group])
+        .is_empty());
+    clock.set(1060 + 3600);
+    assert_eq!(
+        scheduler.overdue_groups([&alice_group, &bob_group]).len(),
+        2
+    );
+}
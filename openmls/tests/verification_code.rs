@@ -0,0 +1,114 @@
+//! Test the out-of-band verification codes of MlsGroup.
+use openmls::{
+    prelude::*,
+    test_utils::{generate_credential_with_key, generate_key_package},
+    verification::{errors::VerificationCodeError, *},
+};
+use openmls_test::openmls_test;
+use test_utils::new_credential;
+
+fn key_package<Provider: OpenMlsProvider>(
+    ciphersuite: Ciphersuite,
+    provider: &Provider,
+    identity: &[u8],
+) -> KeyPackage {
+    let credential_with_key = generate_credential_with_key(
+        identity.to_vec(),
+        ciphersuite.signature_algorithm(),
+        provider,
+    );
+    generate_key_package(
+        ciphersuite,
+        Extensions::empty(),
+        provider,
+        credential_with_key,
+    )
+    .key_package()
+    .clone()
+}
+
+#[openmls_test]
+fn verification_codes() {
+    let alice_provider = &Provider::default();
+    let bob_provider = &Provider::default();
+    let charlie_provider = &Provider::default();
+
+    let (alice_credential, alice_signer) =
+        new_credential(alice_provider, b"Alice", ciphersuite.signature_algorithm());
+
+    let mut alice_group = MlsGroup::builder()
+        .ciphersuite(ciphersuite)
+        .use_ratchet_tree_extension(true)
+        .build(alice_provider, &alice_signer, alice_credential)
+        .unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            alice_provider,
+            &alice_signer,
+            &[
+                key_package(ciphersuite, bob_provider, b"Bob"),
+                key_package(ciphersuite, charlie_provider, b"Charlie"),
+            ],
+        )
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+
+    let welcome = MlsMessageIn::from(welcome).into_welcome().unwrap();
+    let bob_group = StagedWelcome::new_from_welcome(
+        bob_provider,
+        &MlsGroupJoinConfig::default(),
+        welcome,
+        None,
+    )
+    .unwrap()
+    .into_group(bob_provider)
+    .unwrap();
+
+    // All members derive the same group code.
+    let alice_code = VerificationCode::for_group(alice_provider.crypto(), &alice_group).unwrap();
+    let bob_code = VerificationCode::for_group(bob_provider.crypto(), &bob_group).unwrap();
+    assert_eq!(alice_code, bob_code);
+    assert_eq!(alice_code.kind(), VerificationCodeKind::Group);
+    assert_eq!(alice_code.epoch(), alice_group.epoch());
+    assert_eq!(alice_code.numeric(), bob_code.numeric());
+    assert_eq!(alice_code.words(), bob_code.words());
+    assert_eq!(
+        VerificationCode::from_qr_payload(&alice_code.qr_payload()).unwrap(),
+        bob_code
+    );
+
+    // Alice and Bob derive the same pairwise code, which differs from the
+    // code of Alice and Charlie.
+    let alice_bob_code = VerificationCode::for_member(
+        alice_provider.crypto(),
+        &alice_group,
+        bob_group.own_leaf_index(),
+    )
+    .unwrap();
+    let bob_alice_code = VerificationCode::for_member(
+        bob_provider.crypto(),
+        &bob_group,
+        alice_group.own_leaf_index(),
+    )
+    .unwrap();
+    assert_eq!(alice_bob_code, bob_alice_code);
+    assert_eq!(alice_bob_code.kind(), VerificationCodeKind::Pairwise);
+    assert_ne!(alice_bob_code, alice_code);
+    let alice_charlie_code =
+        VerificationCode::for_member(alice_provider.crypto(), &alice_group, LeafNodeIndex::new(2))
+            .unwrap();
+    assert_ne!(alice_bob_code, alice_charlie_code);
+    assert_eq!(
+        VerificationCode::for_member(alice_provider.crypto(), &alice_group, LeafNodeIndex::new(3)),
+        Err(VerificationCodeError::UnknownMember)
+    );
+
+    // The codes change with the epoch.
+    alice_group
+        .self_update(alice_provider, &alice_signer, LeafNodeParameters::default())
+        .unwrap();
+    alice_group.merge_pending_commit(alice_provider).unwrap();
+    let new_code = VerificationCode::for_group(alice_provider.crypto(), &alice_group).unwrap();
+    assert_ne!(new_code.as_slice(), alice_code.as_slice());
+    assert_ne!(new_code, bob_code);
+}
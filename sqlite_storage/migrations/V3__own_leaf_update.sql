@@ -0,0 +1,32 @@
+-- SQLite can't alter a CHECK constraint, so the group data table is rebuilt
+-- with the extended list of data types.
+CREATE TABLE openmls_group_data_new (
+    provider_version INTEGER NOT NULL,
+    group_id BLOB NOT NULL,
+    dmls_epoch_id BLOB NOT NULL,
+    data_type TEXT NOT NULL CHECK (data_type IN (
+        'join_group_config', 
+        'tree', 
+        'interim_transcript_hash',
+        'context', 
+        'confirmation_tag', 
+        'group_state', 
+        'message_secrets', 
+        'resumption_psk_store',
+        'own_leaf_index',
+        'use_ratchet_tree_extension',
+        'group_epoch_secrets',
+        'message_buffer',
+        'own_leaf_update'
+    )),
+    group_data BLOB NOT NULL,
+    PRIMARY KEY (group_id, data_type, dmls_epoch_id)
+);
+
+INSERT INTO openmls_group_data_new
+    SELECT provider_version, group_id, dmls_epoch_id, data_type, group_data
+    FROM openmls_group_data;
+
+DROP TABLE openmls_group_data;
+
+ALTER TABLE openmls_group_data_new RENAME TO openmls_group_data;
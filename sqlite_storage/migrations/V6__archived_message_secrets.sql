@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS openmls_archived_message_secrets (
+    provider_version INTEGER NOT NULL,
+    group_id BLOB NOT NULL,
+    epoch_id BLOB NOT NULL,
+    dmls_epoch_id BLOB NOT NULL,
+    message_secrets BLOB NOT NULL,
+    PRIMARY KEY (group_id, epoch_id, dmls_epoch_id)
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS openmls_epoch_records (
+    provider_version INTEGER NOT NULL,
+    group_id BLOB NOT NULL,
+    epoch_id BLOB NOT NULL,
+    dmls_epoch_id BLOB NOT NULL,
+    epoch_record BLOB NOT NULL,
+    PRIMARY KEY (group_id, epoch_id, dmls_epoch_id)
+);
+
+-- The commit history used to be stored as a single entry per group.
+DELETE FROM openmls_group_data WHERE data_type = 'commit_history';
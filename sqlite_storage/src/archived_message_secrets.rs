@@ -0,0 +1,117 @@
+use std::marker::PhantomData;
+
+use openmls_traits::storage::{Entity, Key};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{
+    codec::Codec,
+    storage_provider::StorableGroupIdRef,
+    wrappers::{EntityRefWrapper, EntityWrapper, KeyRefWrapper},
+    STORAGE_PROVIDER_VERSION,
+};
+
+pub(crate) struct StorableArchivedMessageSecrets<
+    ArchivedMessageSecrets: Entity<STORAGE_PROVIDER_VERSION>,
+>(pub ArchivedMessageSecrets);
+
+impl<ArchivedMessageSecrets: Entity<STORAGE_PROVIDER_VERSION>>
+    StorableArchivedMessageSecrets<ArchivedMessageSecrets>
+{
+    fn from_row<C: Codec>(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        let EntityWrapper::<C, _>(message_secrets, ..) = row.get(0)?;
+        Ok(Self(message_secrets))
+    }
+
+    pub(super) fn load<
+        C: Codec,
+        GroupId: Key<STORAGE_PROVIDER_VERSION>,
+        EpochKey: Key<STORAGE_PROVIDER_VERSION>,
+    >(
+        connection: &Connection,
+        group_id: &GroupId,
+        epoch_id: &EpochKey,
+        dmls_epoch_id: &[u8],
+    ) -> Result<Option<ArchivedMessageSecrets>, rusqlite::Error> {
+        let mut stmt = connection.prepare(
+            "SELECT message_secrets 
+            FROM openmls_archived_message_secrets 
+            WHERE group_id = ?1 
+                AND epoch_id = ?2 
+                AND provider_version = ?3
+                AND dmls_epoch_id = ?4",
+        )?;
+        stmt.query_row(
+            params![
+                KeyRefWrapper::<C, _>(group_id, PhantomData),
+                KeyRefWrapper::<C, _>(epoch_id, PhantomData),
+                STORAGE_PROVIDER_VERSION,
+                dmls_epoch_id,
+            ],
+            Self::from_row::<C>,
+        )
+        .map(|x| x.0)
+        .optional()
+    }
+}
+
+pub(super) struct StorableArchivedMessageSecretsRef<
+    'a,
+    ArchivedMessageSecrets: Entity<STORAGE_PROVIDER_VERSION>,
+>(pub &'a ArchivedMessageSecrets);
+
+impl<ArchivedMessageSecrets: Entity<STORAGE_PROVIDER_VERSION>>
+    StorableArchivedMessageSecretsRef<'_, ArchivedMessageSecrets>
+{
+    pub(super) fn store<
+        C: Codec,
+        GroupId: Key<STORAGE_PROVIDER_VERSION>,
+        EpochKey: Key<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        connection: &Connection,
+        group_id: &GroupId,
+        epoch_id: &EpochKey,
+        dmls_epoch_id: &[u8],
+    ) -> Result<(), rusqlite::Error> {
+        connection.execute(
+            "INSERT OR REPLACE INTO openmls_archived_message_secrets 
+                    (group_id, epoch_id, message_secrets, provider_version, dmls_epoch_id) 
+                    VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                KeyRefWrapper::<C, _>(group_id, PhantomData),
+                KeyRefWrapper::<C, _>(epoch_id, PhantomData),
+                EntityRefWrapper::<C, _>(self.0, PhantomData),
+                STORAGE_PROVIDER_VERSION,
+                dmls_epoch_id,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl<GroupId: Key<STORAGE_PROVIDER_VERSION>> StorableGroupIdRef<'_, GroupId> {
+    pub(super) fn delete_archived_message_secrets<
+        C: Codec,
+        EpochKey: Key<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        connection: &Connection,
+        epoch_id: &EpochKey,
+        dmls_epoch_id: &[u8],
+    ) -> Result<(), rusqlite::Error> {
+        connection.execute(
+            "DELETE FROM openmls_archived_message_secrets 
+            WHERE group_id = ?1 
+                AND epoch_id = ?2 
+                AND provider_version = ?3
+                AND dmls_epoch_id = ?4",
+            params![
+                KeyRefWrapper::<C, _>(self.0, PhantomData),
+                KeyRefWrapper::<C, _>(epoch_id, PhantomData),
+                STORAGE_PROVIDER_VERSION,
+                dmls_epoch_id,
+            ],
+        )?;
+        Ok(())
+    }
+}
@@ -20,6 +20,8 @@ impl<C: Codec, ConnectionRef: Borrow<Connection>> DmlsStorageProvider<STORAGE_PR
         clone_epoch_key_pairs(connection, self.epoch(), destination_epoch)?;
         clone_own_leaf_nodes(connection, self.epoch(), destination_epoch)?;
         clone_proposals(connection, self.epoch(), destination_epoch)?;
+        clone_archived_message_secrets(connection, self.epoch(), destination_epoch)?;
+        clone_epoch_records(connection, self.epoch(), destination_epoch)?;
 
         Ok(())
     }
@@ -34,6 +36,8 @@ impl<C: Codec, ConnectionRef: Borrow<Connection>> DmlsStorageProvider<STORAGE_PR
         delete_epoch_key_pairs(connection, epoch_id)?;
         delete_own_leaf_nodes(connection, epoch_id)?;
         delete_proposals(connection, epoch_id)?;
+        delete_archived_message_secrets(connection, epoch_id)?;
+        delete_epoch_records(connection, epoch_id)?;
 
         Ok(())
     }
@@ -160,3 +164,52 @@ fn delete_proposals(connection: &Connection, epoch_id: &[u8]) -> Result<(), rusq
     )?;
     Ok(())
 }
+
+fn clone_archived_message_secrets(
+    connection: &Connection,
+    origin_epoch_id: &[u8],
+    destination_epoch_id: &[u8],
+) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "INSERT INTO openmls_archived_message_secrets (group_id, epoch_id, message_secrets, provider_version, dmls_epoch_id) 
+        SELECT group_id, epoch_id, message_secrets, provider_version, ?1 
+        FROM openmls_archived_message_secrets 
+        WHERE dmls_epoch_id = ?2",
+        params![destination_epoch_id, origin_epoch_id],
+    )?;
+    Ok(())
+}
+
+fn delete_archived_message_secrets(
+    connection: &Connection,
+    epoch_id: &[u8],
+) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "DELETE FROM openmls_archived_message_secrets WHERE dmls_epoch_id = ?1",
+        params![epoch_id],
+    )?;
+    Ok(())
+}
+
+fn clone_epoch_records(
+    connection: &Connection,
+    origin_epoch_id: &[u8],
+    destination_epoch_id: &[u8],
+) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "INSERT INTO openmls_epoch_records (group_id, epoch_id, epoch_record, provider_version, dmls_epoch_id) 
+        SELECT group_id, epoch_id, epoch_record, provider_version, ?1 
+        FROM openmls_epoch_records 
+        WHERE dmls_epoch_id = ?2",
+        params![destination_epoch_id, origin_epoch_id],
+    )?;
+    Ok(())
+}
+
+fn delete_epoch_records(connection: &Connection, epoch_id: &[u8]) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "DELETE FROM openmls_epoch_records WHERE dmls_epoch_id = ?1",
+        params![epoch_id],
+    )?;
+    Ok(())
+}
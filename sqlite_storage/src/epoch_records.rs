@@ -0,0 +1,109 @@
+use std::marker::PhantomData;
+
+use openmls_traits::storage::{Entity, Key};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{
+    codec::Codec,
+    storage_provider::StorableGroupIdRef,
+    wrappers::{EntityRefWrapper, EntityWrapper, KeyRefWrapper},
+    STORAGE_PROVIDER_VERSION,
+};
+
+pub(crate) struct StorableEpochRecord<EpochRecord: Entity<STORAGE_PROVIDER_VERSION>>(
+    pub EpochRecord,
+);
+
+impl<EpochRecord: Entity<STORAGE_PROVIDER_VERSION>> StorableEpochRecord<EpochRecord> {
+    fn from_row<C: Codec>(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        let EntityWrapper::<C, _>(epoch_record, ..) = row.get(0)?;
+        Ok(Self(epoch_record))
+    }
+
+    pub(super) fn load<
+        C: Codec,
+        GroupId: Key<STORAGE_PROVIDER_VERSION>,
+        EpochKey: Key<STORAGE_PROVIDER_VERSION>,
+    >(
+        connection: &Connection,
+        group_id: &GroupId,
+        epoch_id: &EpochKey,
+        dmls_epoch_id: &[u8],
+    ) -> Result<Option<EpochRecord>, rusqlite::Error> {
+        let mut stmt = connection.prepare(
+            "SELECT epoch_record 
+            FROM openmls_epoch_records 
+            WHERE group_id = ?1 
+                AND epoch_id = ?2 
+                AND provider_version = ?3
+                AND dmls_epoch_id = ?4",
+        )?;
+        stmt.query_row(
+            params![
+                KeyRefWrapper::<C, _>(group_id, PhantomData),
+                KeyRefWrapper::<C, _>(epoch_id, PhantomData),
+                STORAGE_PROVIDER_VERSION,
+                dmls_epoch_id,
+            ],
+            Self::from_row::<C>,
+        )
+        .map(|x| x.0)
+        .optional()
+    }
+}
+
+pub(super) struct StorableEpochRecordRef<'a, EpochRecord: Entity<STORAGE_PROVIDER_VERSION>>(
+    pub &'a EpochRecord,
+);
+
+impl<EpochRecord: Entity<STORAGE_PROVIDER_VERSION>> StorableEpochRecordRef<'_, EpochRecord> {
+    pub(super) fn store<
+        C: Codec,
+        GroupId: Key<STORAGE_PROVIDER_VERSION>,
+        EpochKey: Key<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        connection: &Connection,
+        group_id: &GroupId,
+        epoch_id: &EpochKey,
+        dmls_epoch_id: &[u8],
+    ) -> Result<(), rusqlite::Error> {
+        connection.execute(
+            "INSERT OR REPLACE INTO openmls_epoch_records 
+                    (group_id, epoch_id, epoch_record, provider_version, dmls_epoch_id) 
+                    VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                KeyRefWrapper::<C, _>(group_id, PhantomData),
+                KeyRefWrapper::<C, _>(epoch_id, PhantomData),
+                EntityRefWrapper::<C, _>(self.0, PhantomData),
+                STORAGE_PROVIDER_VERSION,
+                dmls_epoch_id,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl<GroupId: Key<STORAGE_PROVIDER_VERSION>> StorableGroupIdRef<'_, GroupId> {
+    pub(super) fn delete_epoch_record<C: Codec, EpochKey: Key<STORAGE_PROVIDER_VERSION>>(
+        &self,
+        connection: &Connection,
+        epoch_id: &EpochKey,
+        dmls_epoch_id: &[u8],
+    ) -> Result<(), rusqlite::Error> {
+        connection.execute(
+            "DELETE FROM openmls_epoch_records 
+            WHERE group_id = ?1 
+                AND epoch_id = ?2 
+                AND provider_version = ?3
+                AND dmls_epoch_id = ?4",
+            params![
+                KeyRefWrapper::<C, _>(self.0, PhantomData),
+                KeyRefWrapper::<C, _>(epoch_id, PhantomData),
+                STORAGE_PROVIDER_VERSION,
+                dmls_epoch_id,
+            ],
+        )?;
+        Ok(())
+    }
+}
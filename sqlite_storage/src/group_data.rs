@@ -23,6 +23,9 @@ pub(super) enum GroupDataType {
     OwnLeafIndex,
     UseRatchetTreeExtension,
     GroupEpochSecrets,
+    MessageBuffer,
+    OwnLeafUpdate,
+    PastExporterSecrets,
 }
 
 impl ToSql for GroupDataType {
@@ -39,6 +42,9 @@ impl ToSql for GroupDataType {
             GroupDataType::OwnLeafIndex => "own_leaf_index".to_sql(),
             GroupDataType::UseRatchetTreeExtension => "use_ratchet_tree_extension".to_sql(),
             GroupDataType::GroupEpochSecrets => "group_epoch_secrets".to_sql(),
+            GroupDataType::MessageBuffer => "message_buffer".to_sql(),
+            GroupDataType::OwnLeafUpdate => "own_leaf_update".to_sql(),
+            GroupDataType::PastExporterSecrets => "past_exporter_secrets".to_sql(),
         }
     }
 }
@@ -58,6 +64,9 @@ impl FromSql for GroupDataType {
             "own_leaf_index" => Ok(GroupDataType::OwnLeafIndex),
             "use_ratchet_tree_extension" => Ok(GroupDataType::UseRatchetTreeExtension),
             "group_epoch_secrets" => Ok(GroupDataType::GroupEpochSecrets),
+            "message_buffer" => Ok(GroupDataType::MessageBuffer),
+            "own_leaf_update" => Ok(GroupDataType::OwnLeafUpdate),
+            "past_exporter_secrets" => Ok(GroupDataType::PastExporterSecrets),
             _ => Err(rusqlite::types::FromSqlError::InvalidType),
         }
     }
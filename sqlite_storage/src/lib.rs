@@ -27,10 +27,12 @@ use openmls_traits::storage::StorageProvider;
 #[cfg(doc)]
 use serde::{de::DeserializeOwned, Serialize};
 
+mod archived_message_secrets;
 mod codec;
 pub mod dmls;
 mod encryption_key_pairs;
 mod epoch_key_pairs;
+mod epoch_records;
 mod group_data;
 mod key_packages;
 mod own_leaf_nodes;
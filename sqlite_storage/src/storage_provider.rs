@@ -13,11 +13,13 @@ use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    archived_message_secrets::{StorableArchivedMessageSecrets, StorableArchivedMessageSecretsRef},
     codec::Codec,
     encryption_key_pairs::{
         StorableEncryptionKeyPair, StorableEncryptionKeyPairRef, StorableEncryptionPublicKeyRef,
     },
     epoch_key_pairs::{StorableEpochKeyPairs, StorableEpochKeyPairsRef},
+    epoch_records::{StorableEpochRecord, StorableEpochRecordRef},
     group_data::{GroupDataType, StorableGroupData, StorableGroupDataRef},
     key_packages::{StorableHashRef, StorableKeyPackage, StorableKeyPackageRef},
     own_leaf_nodes::{StorableLeafNode, StorableLeafNodeRef},
@@ -287,6 +289,103 @@ impl<C: Codec, ConnectionRef: Borrow<Connection>> StorageProvider<STORAGE_PROVID
         Ok(())
     }
 
+    fn write_message_buffer<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        MessageBuffer: openmls_traits::storage::traits::MessageBuffer<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        message_buffer: &MessageBuffer,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupDataRef(message_buffer).store::<C, _>(
+            connection,
+            group_id,
+            GroupDataType::MessageBuffer,
+            &self.epoch,
+        )?;
+        Ok(())
+    }
+
+    fn write_own_leaf_update<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        OwnLeafUpdate: openmls_traits::storage::traits::OwnLeafUpdate<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        own_leaf_update: &OwnLeafUpdate,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupDataRef(own_leaf_update).store::<C, _>(
+            connection,
+            group_id,
+            GroupDataType::OwnLeafUpdate,
+            &self.epoch,
+        )?;
+        Ok(())
+    }
+
+    fn write_epoch_record<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        EpochKey: openmls_traits::storage::traits::EpochKey<STORAGE_PROVIDER_VERSION>,
+        EpochRecord: openmls_traits::storage::traits::EpochRecord<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        epoch_record: &EpochRecord,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableEpochRecordRef(epoch_record).store::<C, _, _>(
+            connection,
+            group_id,
+            epoch,
+            &self.epoch,
+        )
+    }
+
+    fn write_past_exporter_secrets<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        PastExporterSecrets: openmls_traits::storage::traits::PastExporterSecrets<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        past_exporter_secrets: &PastExporterSecrets,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupDataRef(past_exporter_secrets).store::<C, _>(
+            connection,
+            group_id,
+            GroupDataType::PastExporterSecrets,
+            &self.epoch,
+        )?;
+        Ok(())
+    }
+
+    fn write_archived_message_secrets<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        EpochKey: openmls_traits::storage::traits::EpochKey<STORAGE_PROVIDER_VERSION>,
+        ArchivedMessageSecrets: openmls_traits::storage::traits::ArchivedMessageSecrets<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        archived_message_secrets: &ArchivedMessageSecrets,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableArchivedMessageSecretsRef(archived_message_secrets).store::<C, _, _>(
+            connection,
+            group_id,
+            epoch,
+            &self.epoch,
+        )
+    }
+
     fn write_signature_key_pair<
         SignaturePublicKey: openmls_traits::storage::traits::SignaturePublicKey<STORAGE_PROVIDER_VERSION>,
         SignatureKeyPair: openmls_traits::storage::traits::SignatureKeyPair<STORAGE_PROVIDER_VERSION>,
@@ -558,6 +657,85 @@ impl<C: Codec, ConnectionRef: Borrow<Connection>> StorageProvider<STORAGE_PROVID
         )
     }
 
+    fn message_buffer<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        MessageBuffer: openmls_traits::storage::traits::MessageBuffer<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<MessageBuffer>, Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupData::load::<C, _>(
+            connection,
+            group_id,
+            &self.epoch,
+            GroupDataType::MessageBuffer,
+        )
+    }
+
+    fn own_leaf_update<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        OwnLeafUpdate: openmls_traits::storage::traits::OwnLeafUpdate<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<OwnLeafUpdate>, Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupData::load::<C, _>(
+            connection,
+            group_id,
+            &self.epoch,
+            GroupDataType::OwnLeafUpdate,
+        )
+    }
+
+    fn epoch_record<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        EpochKey: openmls_traits::storage::traits::EpochKey<STORAGE_PROVIDER_VERSION>,
+        EpochRecord: openmls_traits::storage::traits::EpochRecord<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<Option<EpochRecord>, Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableEpochRecord::load::<C, _, _>(connection, group_id, epoch, &self.epoch)
+    }
+
+    fn past_exporter_secrets<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        PastExporterSecrets: openmls_traits::storage::traits::PastExporterSecrets<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<PastExporterSecrets>, Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupData::load::<C, _>(
+            connection,
+            group_id,
+            &self.epoch,
+            GroupDataType::PastExporterSecrets,
+        )
+    }
+
+    fn archived_message_secrets<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        EpochKey: openmls_traits::storage::traits::EpochKey<STORAGE_PROVIDER_VERSION>,
+        ArchivedMessageSecrets: openmls_traits::storage::traits::ArchivedMessageSecrets<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<Option<ArchivedMessageSecrets>, Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableArchivedMessageSecrets::load::<C, _, _>(connection, group_id, epoch, &self.epoch)
+    }
+
     fn signature_key_pair<
         SignaturePublicKey: openmls_traits::storage::traits::SignaturePublicKey<STORAGE_PROVIDER_VERSION>,
         SignatureKeyPair: openmls_traits::storage::traits::SignatureKeyPair<STORAGE_PROVIDER_VERSION>,
@@ -793,6 +971,81 @@ impl<C: Codec, ConnectionRef: Borrow<Connection>> StorageProvider<STORAGE_PROVID
         )
     }
 
+    fn delete_message_buffer<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupIdRef(group_id).delete_group_data::<C>(
+            connection,
+            GroupDataType::MessageBuffer,
+            &self.epoch,
+        )
+    }
+
+    fn delete_own_leaf_update<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupIdRef(group_id).delete_group_data::<C>(
+            connection,
+            GroupDataType::OwnLeafUpdate,
+            &self.epoch,
+        )
+    }
+
+    fn delete_epoch_record<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        EpochKey: openmls_traits::storage::traits::EpochKey<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupIdRef(group_id).delete_epoch_record::<C, _>(connection, epoch, &self.epoch)
+    }
+
+    fn delete_past_exporter_secrets<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupIdRef(group_id).delete_group_data::<C>(
+            connection,
+            GroupDataType::PastExporterSecrets,
+            &self.epoch,
+        )
+    }
+
+    fn delete_archived_message_secrets<
+        GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
+        EpochKey: openmls_traits::storage::traits::EpochKey<STORAGE_PROVIDER_VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<(), Self::Error> {
+        let connection_guard = self.connection.lock().unwrap();
+        let connection = connection_guard.deref().borrow();
+        StorableGroupIdRef(group_id).delete_archived_message_secrets::<C, _>(
+            connection,
+            epoch,
+            &self.epoch,
+        )
+    }
+
     fn clear_proposal_queue<
         GroupId: openmls_traits::storage::traits::GroupId<STORAGE_PROVIDER_VERSION>,
         ProposalRef: openmls_traits::storage::traits::ProposalRef<STORAGE_PROVIDER_VERSION>,
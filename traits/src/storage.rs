@@ -161,6 +161,64 @@ pub trait StorageProvider<const VERSION: u16> {
         group_epoch_secrets: &GroupEpochSecrets,
     ) -> Result<(), Self::Error>;
 
+    /// Writes the buffer of messages for future epochs for the group with the
+    /// given id.
+    fn write_message_buffer<
+        GroupId: traits::GroupId<VERSION>,
+        MessageBuffer: traits::MessageBuffer<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        message_buffer: &MessageBuffer,
+    ) -> Result<(), Self::Error>;
+
+    /// Writes the epoch and time of the last update of the own leaf in the
+    /// group with the given id.
+    fn write_own_leaf_update<
+        GroupId: traits::GroupId<VERSION>,
+        OwnLeafUpdate: traits::OwnLeafUpdate<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        own_leaf_update: &OwnLeafUpdate,
+    ) -> Result<(), Self::Error>;
+
+    /// Writes the commit history record of the given epoch of the group with
+    /// the given id.
+    fn write_epoch_record<
+        GroupId: traits::GroupId<VERSION>,
+        EpochKey: traits::EpochKey<VERSION>,
+        EpochRecord: traits::EpochRecord<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        epoch_record: &EpochRecord,
+    ) -> Result<(), Self::Error>;
+
+    /// Writes the past exporter secrets of the group with the given id.
+    fn write_past_exporter_secrets<
+        GroupId: traits::GroupId<VERSION>,
+        PastExporterSecrets: traits::PastExporterSecrets<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        past_exporter_secrets: &PastExporterSecrets,
+    ) -> Result<(), Self::Error>;
+
+    /// Writes the archived message secrets of the given epoch of the group
+    /// with the given id.
+    fn write_archived_message_secrets<
+        GroupId: traits::GroupId<VERSION>,
+        EpochKey: traits::EpochKey<VERSION>,
+        ArchivedMessageSecrets: traits::ArchivedMessageSecrets<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+        archived_message_secrets: &ArchivedMessageSecrets,
+    ) -> Result<(), Self::Error>;
+
     //
     //    ---   setters/writers/enqueuers for crypto objects  ---
     //
@@ -356,6 +414,59 @@ pub trait StorageProvider<const VERSION: u16> {
         group_id: &GroupId,
     ) -> Result<Option<GroupEpochSecrets>, Self::Error>;
 
+    /// Returns the buffer of messages for future epochs for the group with
+    /// the given id.
+    fn message_buffer<
+        GroupId: traits::GroupId<VERSION>,
+        MessageBuffer: traits::MessageBuffer<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<MessageBuffer>, Self::Error>;
+
+    /// Returns the epoch and time of the last update of the own leaf in the
+    /// group with the given id.
+    fn own_leaf_update<
+        GroupId: traits::GroupId<VERSION>,
+        OwnLeafUpdate: traits::OwnLeafUpdate<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<OwnLeafUpdate>, Self::Error>;
+
+    /// Returns the commit history record of the given epoch of the group with
+    /// the given id.
+    fn epoch_record<
+        GroupId: traits::GroupId<VERSION>,
+        EpochKey: traits::EpochKey<VERSION>,
+        EpochRecord: traits::EpochRecord<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<Option<EpochRecord>, Self::Error>;
+
+    /// Returns the past exporter secrets of the group with the given id.
+    fn past_exporter_secrets<
+        GroupId: traits::GroupId<VERSION>,
+        PastExporterSecrets: traits::PastExporterSecrets<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Option<PastExporterSecrets>, Self::Error>;
+
+    /// Returns the archived message secrets of the given epoch of the group
+    /// with the given id.
+    fn archived_message_secrets<
+        GroupId: traits::GroupId<VERSION>,
+        EpochKey: traits::EpochKey<VERSION>,
+        ArchivedMessageSecrets: traits::ArchivedMessageSecrets<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<Option<ArchivedMessageSecrets>, Self::Error>;
+
     //
     //    ---   getter for crypto objects  ---
     //
@@ -493,6 +604,45 @@ pub trait StorageProvider<const VERSION: u16> {
         group_id: &GroupId,
     ) -> Result<(), Self::Error>;
 
+    /// Deletes the buffer of messages for future epochs for the group with
+    /// the given id.
+    fn delete_message_buffer<GroupId: traits::GroupId<VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error>;
+
+    /// Deletes the epoch and time of the last update of the own leaf in the
+    /// group with the given id.
+    fn delete_own_leaf_update<GroupId: traits::GroupId<VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error>;
+
+    /// Deletes the commit history record of the given epoch of the group with
+    /// the given id.
+    fn delete_epoch_record<GroupId: traits::GroupId<VERSION>, EpochKey: traits::EpochKey<VERSION>>(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<(), Self::Error>;
+
+    /// Deletes the past exporter secrets of the group with the given id.
+    fn delete_past_exporter_secrets<GroupId: traits::GroupId<VERSION>>(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<(), Self::Error>;
+
+    /// Deletes the archived message secrets of the given epoch of the group
+    /// with the given id.
+    fn delete_archived_message_secrets<
+        GroupId: traits::GroupId<VERSION>,
+        EpochKey: traits::EpochKey<VERSION>,
+    >(
+        &self,
+        group_id: &GroupId,
+        epoch: &EpochKey,
+    ) -> Result<(), Self::Error>;
+
     /// Clear the proposal queue for the group with the given id.
     fn clear_proposal_queue<
         GroupId: traits::GroupId<VERSION>,
@@ -605,6 +755,11 @@ pub mod traits {
     pub trait KeyPackage<const VERSION: u16>: Entity<VERSION> {}
     pub trait MlsGroupJoinConfig<const VERSION: u16>: Entity<VERSION> {}
     pub trait LeafNode<const VERSION: u16>: Entity<VERSION> {}
+    pub trait MessageBuffer<const VERSION: u16>: Entity<VERSION> {}
+    pub trait OwnLeafUpdate<const VERSION: u16>: Entity<VERSION> {}
+    pub trait EpochRecord<const VERSION: u16>: Entity<VERSION> {}
+    pub trait PastExporterSecrets<const VERSION: u16>: Entity<VERSION> {}
+    pub trait ArchivedMessageSecrets<const VERSION: u16>: Entity<VERSION> {}
 
     // traits for types that implement both
     pub trait ProposalRef<const VERSION: u16>: Entity<VERSION> + Key<VERSION> {}